@@ -0,0 +1,51 @@
+# Install the latest NEXUS AI Forge binary on Windows.
+#
+#   irm https://raw.githubusercontent.com/mrsarac/NEXUS-AI-Forge/main/install.ps1 | iex
+#
+# $env:NEXUS_VERSION picks a release (default: latest) and
+# $env:NEXUS_INSTALL_DIR the directory (default: %LOCALAPPDATA%\nexus\bin,
+# added to the user PATH). The download is checked against SHA256SUMS.
+# For a system-wide install, use the MSI from the release page instead.
+
+$ErrorActionPreference = 'Stop'
+
+$Repo = 'mrsarac/NEXUS-AI-Forge'
+$Version = if ($env:NEXUS_VERSION) { $env:NEXUS_VERSION } else { 'latest' }
+$Asset = 'nexus-windows-x64.exe'
+
+if ($Version -eq 'latest') {
+    $Base = "https://github.com/$Repo/releases/latest/download"
+} else {
+    $Base = "https://github.com/$Repo/releases/download/v$($Version.TrimStart('v'))"
+}
+
+$Dir = if ($env:NEXUS_INSTALL_DIR) { $env:NEXUS_INSTALL_DIR } else { Join-Path $env:LOCALAPPDATA 'nexus\bin' }
+New-Item -ItemType Directory -Force -Path $Dir | Out-Null
+
+$Tmp = Join-Path ([System.IO.Path]::GetTempPath()) ([System.Guid]::NewGuid())
+New-Item -ItemType Directory -Path $Tmp | Out-Null
+try {
+    Write-Host "Downloading $Asset ($Version)..."
+    Invoke-WebRequest -Uri "$Base/$Asset" -OutFile (Join-Path $Tmp $Asset) -UseBasicParsing
+    Invoke-WebRequest -Uri "$Base/SHA256SUMS" -OutFile (Join-Path $Tmp 'SHA256SUMS') -UseBasicParsing
+
+    $Line = Get-Content (Join-Path $Tmp 'SHA256SUMS') | Where-Object { $_ -match "\s\*?$([regex]::Escape($Asset))$" }
+    $Expected = if ($Line) { ($Line -split '\s+')[0].ToLower() } else { '' }
+    $Actual = (Get-FileHash (Join-Path $Tmp $Asset) -Algorithm SHA256).Hash.ToLower()
+    if (-not $Expected -or $Expected -ne $Actual) {
+        throw "Checksum mismatch for $Asset; not installing."
+    }
+
+    Move-Item -Force (Join-Path $Tmp $Asset) (Join-Path $Dir 'nexus.exe')
+} finally {
+    Remove-Item -Recurse -Force $Tmp -ErrorAction SilentlyContinue
+}
+
+$UserPath = [Environment]::GetEnvironmentVariable('Path', 'User')
+if (-not ($UserPath -split ';' | Where-Object { $_ -eq $Dir })) {
+    [Environment]::SetEnvironmentVariable('Path', "$UserPath;$Dir", 'User')
+    Write-Host "Added $Dir to your PATH; open a new terminal to use it."
+}
+
+Write-Host "Installed nexus to $Dir\nexus.exe"
+& (Join-Path $Dir 'nexus.exe') --version
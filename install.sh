@@ -0,0 +1,70 @@
+#!/bin/sh
+# Install the latest NEXUS AI Forge binary on macOS or Linux.
+#
+#   curl -fsSL https://raw.githubusercontent.com/mrsarac/NEXUS-AI-Forge/main/install.sh | sh
+#
+# NEXUS_VERSION picks a release (default: latest) and NEXUS_INSTALL_DIR the
+# directory (default: /usr/local/bin when writable, else ~/.local/bin). The
+# download is checked against the release's SHA256SUMS.
+
+set -eu
+
+REPO="mrsarac/NEXUS-AI-Forge"
+VERSION="${NEXUS_VERSION:-latest}"
+
+case "$(uname -s)" in
+    Darwin) os="darwin" ;;
+    Linux) os="linux" ;;
+    *) echo "Unsupported OS: $(uname -s). On Windows, use install.ps1." >&2; exit 1 ;;
+esac
+
+case "$(uname -m)" in
+    x86_64 | amd64) arch="x64" ;;
+    arm64 | aarch64) arch="arm64" ;;
+    *) echo "Unsupported architecture: $(uname -m)" >&2; exit 1 ;;
+esac
+
+asset="nexus-${os}-${arch}"
+if [ "$VERSION" = "latest" ]; then
+    base="https://github.com/${REPO}/releases/latest/download"
+else
+    base="https://github.com/${REPO}/releases/download/v${VERSION#v}"
+fi
+
+if [ -n "${NEXUS_INSTALL_DIR:-}" ]; then
+    dir="$NEXUS_INSTALL_DIR"
+elif [ -w /usr/local/bin ]; then
+    dir="/usr/local/bin"
+else
+    dir="$HOME/.local/bin"
+fi
+mkdir -p "$dir"
+
+tmp="$(mktemp -d)"
+trap 'rm -rf "$tmp"' EXIT
+
+echo "Downloading ${asset} (${VERSION})..."
+curl -fsSL "${base}/${asset}" -o "${tmp}/${asset}"
+curl -fsSL "${base}/SHA256SUMS" -o "${tmp}/SHA256SUMS"
+
+expected="$(grep " \*\{0,1\}${asset}\$" "${tmp}/SHA256SUMS" | cut -d ' ' -f 1)"
+if command -v sha256sum >/dev/null 2>&1; then
+    actual="$(sha256sum "${tmp}/${asset}" | cut -d ' ' -f 1)"
+else
+    actual="$(shasum -a 256 "${tmp}/${asset}" | cut -d ' ' -f 1)"
+fi
+if [ -z "$expected" ] || [ "$expected" != "$actual" ]; then
+    echo "Checksum mismatch for ${asset}; not installing." >&2
+    exit 1
+fi
+
+chmod +x "${tmp}/${asset}"
+mv "${tmp}/${asset}" "${dir}/nexus"
+echo "Installed nexus to ${dir}/nexus"
+
+case ":$PATH:" in
+    *":$dir:"*) ;;
+    *) echo "Add ${dir} to your PATH to run nexus." ;;
+esac
+
+"${dir}/nexus" --version
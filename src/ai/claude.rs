@@ -10,6 +10,11 @@ use reqwest::{Client, header};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use super::router::{estimate_tokens, AiRouter};
+use crate::core::hooks::{self, Prompt, PromptMessage};
+use crate::core::memory;
+use crate::core::permissions::{self, Permission};
+
 const CLAUDE_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const ANTHROPIC_VERSION: &str = "2023-06-01";
 const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
@@ -17,6 +22,7 @@ const DEFAULT_MAX_TOKENS: u32 = 4096;
 const REQUEST_TIMEOUT_SECS: u64 = 120;
 
 /// Claude API Client
+#[derive(Clone)]
 pub struct ClaudeClient {
     client: Client,
     api_key: String,
@@ -51,6 +57,14 @@ struct ClaudeRequest {
     temperature: Option<f32>,
 }
 
+impl ClaudeRequest {
+    /// Rough size of everything sent: system prompt and every message
+    fn context_tokens(&self) -> usize {
+        let system = self.system.as_deref().map(estimate_tokens).unwrap_or(0);
+        system + self.messages.iter().map(|m| estimate_tokens(&m.content)).sum::<usize>()
+    }
+}
+
 /// Response from Claude API
 #[derive(Debug, Deserialize)]
 pub struct ClaudeResponse {
@@ -159,6 +173,9 @@ impl ClaudeClient {
         system: Option<String>,
         temperature: Option<f32>,
     ) -> Result<String> {
+        permissions::request(Permission::Network, "send this request to Claude")?;
+        let prompt = hooks::pre_prompt("claude", memory::inject(to_prompt(&messages, system)))?;
+        let (messages, system) = from_prompt(&prompt);
         let request = ClaudeRequest {
             model: self.model.clone(),
             max_tokens: self.max_tokens,
@@ -166,6 +183,7 @@ impl ClaudeClient {
             system,
             temperature,
         };
+        AiRouter::admit("claude", request.context_tokens())?;
 
         let response = self.client
             .post(CLAUDE_API_URL)
@@ -184,6 +202,11 @@ impl ClaudeClient {
                 .json()
                 .await
                 .context("Failed to parse Claude response")?;
+            AiRouter::record(
+                "claude",
+                claude_response.usage.input_tokens as usize,
+                claude_response.usage.output_tokens as usize,
+            );
 
             // Extract text from content blocks
             let text = claude_response
@@ -194,7 +217,7 @@ impl ClaudeClient {
                 .collect::<Vec<String>>()
                 .join("");
 
-            Ok(text)
+            hooks::post_response("claude", &prompt, text)
         } else {
             let error_text = response.text().await.unwrap_or_default();
 
@@ -218,6 +241,9 @@ impl ClaudeClient {
         system: Option<String>,
         temperature: Option<f32>,
     ) -> Result<ClaudeResponse> {
+        permissions::request(Permission::Network, "send this request to Claude")?;
+        let prompt = hooks::pre_prompt("claude", memory::inject(to_prompt(&messages, system)))?;
+        let (messages, system) = from_prompt(&prompt);
         let request = ClaudeRequest {
             model: self.model.clone(),
             max_tokens: self.max_tokens,
@@ -225,6 +251,7 @@ impl ClaudeClient {
             system,
             temperature,
         };
+        AiRouter::admit("claude", request.context_tokens())?;
 
         let response = self.client
             .post(CLAUDE_API_URL)
@@ -239,10 +266,21 @@ impl ClaudeClient {
         let status = response.status();
 
         if status.is_success() {
-            response
+            let mut claude_response: ClaudeResponse = response
                 .json()
                 .await
-                .context("Failed to parse Claude response")
+                .context("Failed to parse Claude response")?;
+            AiRouter::record(
+                "claude",
+                claude_response.usage.input_tokens as usize,
+                claude_response.usage.output_tokens as usize,
+            );
+            let text: String = claude_response.content.iter().filter_map(|block| block.text.as_deref()).collect();
+            let checked = hooks::post_response("claude", &prompt, text.clone())?;
+            if checked != text {
+                claude_response.content = vec![ContentBlock { block_type: "text".to_string(), text: Some(checked) }];
+            }
+            Ok(claude_response)
         } else {
             let error_text = response.text().await.unwrap_or_default();
             anyhow::bail!("Claude API error ({}): {}", status, error_text);
@@ -250,11 +288,43 @@ impl ClaudeClient {
     }
 }
 
+/// A request as hooks see it
+fn to_prompt(messages: &[Message], system: Option<String>) -> Prompt {
+    Prompt {
+        system,
+        messages: messages
+            .iter()
+            .map(|m| PromptMessage {
+                role: match m.role {
+                    Role::User => "user".to_string(),
+                    Role::Assistant => "assistant".to_string(),
+                },
+                content: m.content.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Messages and system prompt of a request after the hooks
+fn from_prompt(prompt: &Prompt) -> (Vec<Message>, Option<String>) {
+    let messages = prompt
+        .messages
+        .iter()
+        .map(|m| Message {
+            role: if m.role == "assistant" { Role::Assistant } else { Role::User },
+            content: m.content.clone(),
+        })
+        .collect();
+    (messages, prompt.system.clone())
+}
+
 /// Conversation manager for multi-turn chats
 pub struct Conversation {
     client: ClaudeClient,
     messages: Vec<Message>,
     system: Option<String>,
+    /// Context kept on every turn, outside the message history
+    pinned: Option<String>,
 }
 
 impl Conversation {
@@ -264,6 +334,7 @@ impl Conversation {
             client,
             messages: Vec::new(),
             system: None,
+            pinned: None,
         }
     }
 
@@ -273,6 +344,19 @@ impl Conversation {
         self
     }
 
+    /// Set context sent with every turn, such as pinned files
+    pub fn set_pinned(&mut self, pinned: Option<String>) {
+        self.pinned = pinned;
+    }
+
+    /// System prompt with the pinned context appended
+    fn system_prompt(&self) -> Option<String> {
+        match (&self.system, &self.pinned) {
+            (Some(system), Some(pinned)) => Some(format!("{}\n\n{}", system, pinned)),
+            (system, pinned) => system.clone().or_else(|| pinned.clone()),
+        }
+    }
+
     /// Send a message and get response
     pub async fn send(&mut self, content: &str) -> Result<String> {
         // Add user message
@@ -285,7 +369,7 @@ impl Conversation {
         let response = self.client
             .complete(
                 self.messages.clone(),
-                self.system.clone(),
+                self.system_prompt(),
                 None,
             )
             .await?;
@@ -299,6 +383,13 @@ impl Conversation {
         Ok(response)
     }
 
+    /// Add an exchange made outside the conversation, so later turns can
+    /// build on it
+    pub fn record(&mut self, content: &str, response: &str) {
+        self.messages.push(Message { role: Role::User, content: content.to_string() });
+        self.messages.push(Message { role: Role::Assistant, content: response.to_string() });
+    }
+
     /// Get conversation history
     pub fn history(&self) -> &[Message] {
         &self.messages
@@ -308,6 +399,32 @@ impl Conversation {
     pub fn clear(&mut self) {
         self.messages.clear();
     }
+
+    /// Replace the history with a summary written by the model, keeping the
+    /// system prompt and pinned context
+    pub async fn compact(&mut self, instruction: &str) -> Result<String> {
+        let mut messages = self.messages.clone();
+        messages.push(Message {
+            role: Role::User,
+            content: instruction.to_string(),
+        });
+
+        let summary = self.client
+            .complete(messages, self.system_prompt(), None)
+            .await?;
+
+        self.messages = vec![
+            Message {
+                role: Role::User,
+                content: format!("Summary of our conversation so far:\n\n{}", summary),
+            },
+            Message {
+                role: Role::Assistant,
+                content: "Understood. I'll continue from this summary.".to_string(),
+            },
+        ];
+        Ok(summary)
+    }
 }
 
 /// System prompts for different coding tasks
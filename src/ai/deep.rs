@@ -0,0 +1,282 @@
+//! Time-boxed deep analysis
+//!
+//! `--deep` replaces a single prompt with several passes: an outline that
+//! picks the areas worth a close look, one deep dive per area, and a
+//! synthesis that writes the usual report from the dive notes. Dives stop
+//! when the next one would leave no room for the synthesis within the
+//! `[deep]` time and token budget, so the report always arrives.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use super::router::estimate_tokens;
+use crate::config::DeepConfig;
+
+/// Response size assumed before any pass has finished
+const INITIAL_RESPONSE_TOKENS: usize = 1500;
+
+/// An area picked by the outline pass
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Area {
+    pub area: String,
+    /// What to look for there
+    #[serde(default)]
+    pub focus: String,
+}
+
+/// What a deep analysis asks about
+pub struct DeepTask<'a> {
+    /// The command's usual system prompt; the synthesis answers in its format
+    pub system: &'a str,
+    /// The code under analysis, already delimited
+    pub code: &'a str,
+    /// What the analysis is for, e.g. "security review"
+    pub goal: &'a str,
+}
+
+/// Progress reported between passes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stage {
+    Outline,
+    DeepDive { index: usize, total: usize, area: String },
+    Synthesis,
+}
+
+/// The final report and how the budget was spent
+#[derive(Debug, Clone)]
+pub struct DeepResult {
+    pub response: String,
+    /// Areas that got a deep dive
+    pub explored: Vec<String>,
+    /// Areas left out when the budget ran short
+    pub skipped: Vec<String>,
+    pub passes: usize,
+    pub tokens: usize,
+    pub elapsed: Duration,
+}
+
+/// Time and tokens left for the passes of one analysis
+#[derive(Debug, Clone)]
+pub struct Budget {
+    started: Instant,
+    max_time: Duration,
+    max_tokens: usize,
+    used_tokens: usize,
+    passes: usize,
+    largest_response: usize,
+}
+
+impl Budget {
+    pub fn new(config: &DeepConfig) -> Self {
+        Self {
+            started: Instant::now(),
+            max_time: Duration::from_secs(config.max_seconds),
+            max_tokens: config.max_tokens,
+            used_tokens: 0,
+            passes: 0,
+            largest_response: INITIAL_RESPONSE_TOKENS,
+        }
+    }
+
+    /// Count a finished pass
+    pub fn charge(&mut self, prompt: &str, response: &str) {
+        let response_tokens = estimate_tokens(response);
+        self.used_tokens += estimate_tokens(prompt) + response_tokens;
+        self.largest_response = self.largest_response.max(response_tokens);
+        self.passes += 1;
+    }
+
+    /// Whether a pass sending `prompt` still leaves room for a synthesis
+    /// sending `synthesis_prompt` afterwards
+    pub fn allows(&self, prompt: &str, synthesis_prompt: &str) -> bool {
+        let tokens = estimate_tokens(prompt) + estimate_tokens(synthesis_prompt) + 2 * self.largest_response;
+        if self.used_tokens + tokens > self.max_tokens {
+            return false;
+        }
+        match self.average_pass() {
+            Some(pass) => self.started.elapsed() + 2 * pass <= self.max_time,
+            None => true,
+        }
+    }
+
+    pub fn used_tokens(&self) -> usize {
+        self.used_tokens
+    }
+
+    fn average_pass(&self) -> Option<Duration> {
+        (self.passes > 0).then(|| self.started.elapsed() / self.passes as u32)
+    }
+}
+
+/// Run the outline, deep dive and synthesis passes. `ask` sends a system
+/// prompt and a user prompt to the provider.
+pub async fn run<F, Fut>(
+    task: &DeepTask<'_>,
+    config: &DeepConfig,
+    mut ask: F,
+    mut progress: impl FnMut(&Stage),
+) -> Result<DeepResult>
+where
+    F: FnMut(String, String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let mut budget = Budget::new(config);
+
+    progress(&Stage::Outline);
+    let outline = outline_prompt(task, config.max_areas);
+    let response = ask(outline_system(task), outline.clone()).await?;
+    budget.charge(&outline, &response);
+    let mut areas = parse_areas(&response);
+    areas.truncate(config.max_areas);
+
+    let mut notes: Vec<(String, String)> = Vec::new();
+    let mut skipped = Vec::new();
+    let total = areas.len();
+    for (index, area) in areas.into_iter().enumerate() {
+        let prompt = dive_prompt(task, &area);
+        if !budget.allows(&prompt, &synthesis_prompt(task, &notes)) {
+            skipped.push(area.area);
+            continue;
+        }
+        progress(&Stage::DeepDive { index: index + 1, total, area: area.area.clone() });
+        let response = ask(outline_system(task), prompt.clone()).await?;
+        budget.charge(&prompt, &response);
+        notes.push((area.area, response));
+    }
+
+    progress(&Stage::Synthesis);
+    let prompt = synthesis_prompt(task, &notes);
+    let response = ask(task.system.to_string(), prompt.clone()).await?;
+    budget.charge(&prompt, &response);
+
+    Ok(DeepResult {
+        response,
+        explored: notes.into_iter().map(|(area, _)| area).collect(),
+        skipped,
+        passes: budget.passes,
+        tokens: budget.used_tokens(),
+        elapsed: budget.started.elapsed(),
+    })
+}
+
+/// Areas from the outline's JSON array, fenced or bare
+pub fn parse_areas(response: &str) -> Vec<Area> {
+    let Some(start) = response.find('[') else {
+        return Vec::new();
+    };
+    let Some(end) = response.rfind(']') else {
+        return Vec::new();
+    };
+    if end < start {
+        return Vec::new();
+    }
+    serde_json::from_str::<Vec<Area>>(&response[start..=end])
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|a| !a.area.trim().is_empty())
+        .collect()
+}
+
+/// Outline and dive passes take notes rather than writing the report
+fn outline_system(task: &DeepTask) -> String {
+    format!(
+        "{}\n\nYou are working in several passes. Until you are asked for the final report, \
+         answer only what each pass asks for.",
+        task.system
+    )
+}
+
+fn outline_prompt(task: &DeepTask, max_areas: usize) -> String {
+    format!(
+        "{}\n\nThis is the outline pass of a deep {}. Do not analyze yet. Pick at most {} areas \
+         of this code (functions, modules or flows) that most deserve a close look, most \
+         critical first, and answer only with a JSON array:\n\n\
+         [{{\"area\": \"name of the function, module or flow\", \"focus\": \"what to check there\"}}]",
+        task.code, task.goal, max_areas
+    )
+}
+
+fn dive_prompt(task: &DeepTask, area: &Area) -> String {
+    format!(
+        "{}\n\nThis is a deep-dive pass of a deep {}. Examine only `{}`{}. Trace its data flow, \
+         edge cases and callers, and list each concrete issue with its file and line, how sure \
+         you are, and the fix. Mention briefly the suspicions you ruled out. These are notes \
+         for the final report, not the report itself.",
+        task.code,
+        task.goal,
+        area.area,
+        if area.focus.is_empty() { String::new() } else { format!(" ({})", area.focus) }
+    )
+}
+
+fn synthesis_prompt(task: &DeepTask, notes: &[(String, String)]) -> String {
+    let notes = if notes.is_empty() {
+        "(no deep-dive notes; analyze the code directly)".to_string()
+    } else {
+        notes
+            .iter()
+            .map(|(area, note)| format!("### {}\n\n{}", area, note))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+    format!(
+        "{}\n\n## Deep-Dive Notes\n\n{}\n\nThis is the final pass of a deep {}. Write the full \
+         report in your usual output format, using the notes above: keep the issues they \
+         confirm, drop the ones they ruled out, and cover anything important outside those areas.",
+        task.code, notes, task.goal
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_areas() {
+        let response = "Here is the outline:\n```json\n[{\"area\": \"login\", \"focus\": \"timing-safe comparison\"},\n {\"area\": \"session_store\"}]\n```";
+        assert_eq!(
+            parse_areas(response),
+            [
+                Area { area: "login".to_string(), focus: "timing-safe comparison".to_string() },
+                Area { area: "session_store".to_string(), focus: String::new() },
+            ]
+        );
+        assert!(parse_areas("No JSON here").is_empty());
+    }
+
+    #[test]
+    fn test_deep_analysis_stays_within_budget() {
+        let task = DeepTask { system: "Review code.", code: "fn login() {}", goal: "security review" };
+        let outline = "[{\"area\": \"login\"}, {\"area\": \"logout\"}, {\"area\": \"reset\"}]";
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let analyze = |config: DeepConfig| {
+            let mut stages = Vec::new();
+            let result = runtime
+                .block_on(run(
+                    &task,
+                    &config,
+                    |_, prompt: String| async move {
+                        Ok(if prompt.contains("outline pass") { outline.to_string() } else { "x".repeat(400) })
+                    },
+                    |stage| stages.push(stage.clone()),
+                ))
+                .unwrap();
+            (result, stages)
+        };
+
+        let (result, stages) = analyze(DeepConfig { max_seconds: 60, max_tokens: 100_000, max_areas: 2 });
+        assert_eq!(result.explored, ["login", "logout"]);
+        assert_eq!(result.passes, 4);
+        assert_eq!(stages.last(), Some(&Stage::Synthesis));
+
+        // A tight token budget skips the dives but still writes the report
+        let (result, _) = analyze(DeepConfig { max_seconds: 60, max_tokens: 3000, max_areas: 5 });
+        assert!(result.explored.is_empty());
+        assert_eq!(result.skipped, ["login", "logout", "reset"]);
+        assert_eq!(result.response.len(), 400);
+    }
+}
@@ -0,0 +1,274 @@
+//! Local intent classification of chat messages
+//!
+//! A small fastText-style model, a naive Bayes classifier over word and
+//! word-pair features, decides whether a chat message asks about the
+//! codebase, requests an edit, names a chat command, looks for code, or is
+//! ordinary conversation. It is trained at startup on the examples below,
+//! runs in microseconds and never leaves the machine. Messages it is unsure
+//! about are treated as conversation.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+/// Probability below which a message is treated as conversation
+const MIN_CONFIDENCE: f64 = 0.6;
+
+/// What a chat message asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intent {
+    /// A question about the code in the project
+    Question,
+    /// A change to a file in the project
+    Edit,
+    /// One of the chat's own commands, in words
+    Command,
+    /// Looking for where something is in the code
+    Search,
+    /// Anything else: follow-ups, general programming, small talk
+    Chat,
+}
+
+impl Intent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Intent::Question => "question",
+            Intent::Edit => "edit",
+            Intent::Command => "command",
+            Intent::Search => "search",
+            Intent::Chat => "chat",
+        }
+    }
+}
+
+/// Training examples. `<path>` and `<code>` features come from the shape
+/// of words, so file names and identifiers here are placeholders.
+const EXAMPLES: &[(Intent, &str)] = &[
+    (Intent::Question, "how does authentication work in this project?"),
+    (Intent::Question, "where is the config file loaded in this codebase"),
+    (Intent::Question, "what does parse_config do"),
+    (Intent::Question, "how is the database connection handled here?"),
+    (Intent::Question, "why does our app retry failed requests"),
+    (Intent::Question, "which modules depend on the cache"),
+    (Intent::Question, "what calls SessionStore::refresh in our code?"),
+    (Intent::Question, "how do we handle errors in the api layer"),
+    (Intent::Question, "explain how src/router.rs dispatches requests"),
+    (Intent::Question, "what happens when a user logs in"),
+    (Intent::Question, "how does the indexer pick files in this repo?"),
+    (Intent::Question, "what is the purpose of the journal module here"),
+    (Intent::Question, "explain the Config struct"),
+    (Intent::Question, "what does the Parser class do"),
+    (Intent::Edit, "add error handling to src/main.rs"),
+    (Intent::Edit, "rename load_config to read_config in config.rs"),
+    (Intent::Edit, "change the timeout in client.rs to 30 seconds"),
+    (Intent::Edit, "fix the off by one bug in parser.py"),
+    (Intent::Edit, "refactor handle_request in server.go to use early returns"),
+    (Intent::Edit, "add a doc comment to every public function in lib.rs"),
+    (Intent::Edit, "replace the unwrap calls in utils.rs with proper errors"),
+    (Intent::Edit, "update app.ts so the port comes from the environment"),
+    (Intent::Edit, "remove the unused imports from index.js"),
+    (Intent::Edit, "make retry_count configurable in worker.rs"),
+    (Intent::Edit, "insert logging at the start of process_batch in jobs.py"),
+    (Intent::Edit, "edit models.py to add a created_at field"),
+    (Intent::Command, "clear the conversation"),
+    (Intent::Command, "clear the chat history"),
+    (Intent::Command, "export the chat as markdown"),
+    (Intent::Command, "save this conversation to a file"),
+    (Intent::Command, "pin src/config.rs"),
+    (Intent::Command, "unpin main.rs"),
+    (Intent::Command, "show my pinned files"),
+    (Intent::Command, "switch to the api project"),
+    (Intent::Command, "switch project to web"),
+    (Intent::Command, "compact the conversation"),
+    (Intent::Command, "which model are you using"),
+    (Intent::Command, "show the help"),
+    (Intent::Command, "list the available commands"),
+    (Intent::Command, "quit the chat"),
+    (Intent::Command, "exit"),
+    (Intent::Search, "find all usages of parse_config"),
+    (Intent::Search, "search for TODO comments"),
+    (Intent::Search, "find where HttpClient is defined"),
+    (Intent::Search, "show me every function that calls send_request"),
+    (Intent::Search, "locate the definition of UserService"),
+    (Intent::Search, "search the code for unsafe blocks"),
+    (Intent::Search, "find functions named validate"),
+    (Intent::Search, "grep for api_key"),
+    (Intent::Search, "list all structs in the project"),
+    (Intent::Search, "find references to retry_count"),
+    (Intent::Search, "search for database queries"),
+    (Intent::Search, "find files that mention the payment gateway"),
+    (Intent::Chat, "what is a closure in rust"),
+    (Intent::Chat, "thanks, that helps"),
+    (Intent::Chat, "can you explain that again more simply"),
+    (Intent::Chat, "why?"),
+    (Intent::Chat, "write a function that reverses a string"),
+    (Intent::Chat, "what's the difference between a mutex and a rwlock"),
+    (Intent::Chat, "hello"),
+    (Intent::Chat, "ok and what about the second option"),
+    (Intent::Chat, "give me an example of a builder pattern in go"),
+    (Intent::Chat, "is async better than threads for this"),
+    (Intent::Chat, "tell me more"),
+    (Intent::Chat, "how would you name a function that merges two maps"),
+    (Intent::Chat, "what are best practices for error messages"),
+    (Intent::Chat, "sounds good, go on"),
+    (Intent::Chat, "how do I use generics in typescript"),
+    (Intent::Chat, "how do i write a unit test in python"),
+];
+
+/// The classifier's decision on one message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Classification {
+    pub intent: Intent,
+    /// Probability of `intent` among all intents
+    pub confidence: f64,
+}
+
+/// Feature counts of one intent
+#[derive(Debug, Default)]
+struct IntentModel {
+    counts: HashMap<String, f64>,
+    total: f64,
+    examples: usize,
+}
+
+/// Naive Bayes classifier of chat messages
+#[derive(Debug)]
+pub struct IntentClassifier {
+    models: Vec<(Intent, IntentModel)>,
+    vocabulary: usize,
+    examples: usize,
+}
+
+impl IntentClassifier {
+    /// A classifier trained on the built-in examples
+    pub fn new() -> Self {
+        Self::train(EXAMPLES)
+    }
+
+    pub fn train(examples: &[(Intent, &str)]) -> Self {
+        let mut models: Vec<(Intent, IntentModel)> = Vec::new();
+        let mut vocabulary = std::collections::HashSet::new();
+        for (intent, text) in examples {
+            let index = match models.iter().position(|(i, _)| i == intent) {
+                Some(index) => index,
+                None => {
+                    models.push((*intent, IntentModel::default()));
+                    models.len() - 1
+                }
+            };
+            let model = &mut models[index].1;
+            model.examples += 1;
+            for feature in features(text) {
+                *model.counts.entry(feature.clone()).or_default() += 1.0;
+                model.total += 1.0;
+                vocabulary.insert(feature);
+            }
+        }
+        Self { models, vocabulary: vocabulary.len(), examples: examples.len() }
+    }
+
+    /// The most likely intent, or `Chat` when no intent is likely enough
+    pub fn classify(&self, text: &str) -> Classification {
+        let features = features(text);
+        let scores: Vec<(Intent, f64)> = self
+            .models
+            .iter()
+            .map(|(intent, model)| {
+                let prior = (model.examples as f64 / self.examples as f64).ln();
+                let likelihood: f64 = features
+                    .iter()
+                    .map(|f| {
+                        let count = model.counts.get(f).copied().unwrap_or(0.0);
+                        ((count + 1.0) / (model.total + self.vocabulary as f64)).ln()
+                    })
+                    .sum();
+                (*intent, prior + likelihood)
+            })
+            .collect();
+
+        // Softmax over log scores
+        let best = scores.iter().map(|(_, s)| *s).fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = scores.iter().map(|(_, s)| (s - best).exp()).sum();
+        let (intent, score) = scores
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap_or((Intent::Chat, best));
+        let confidence = (score - best).exp() / sum;
+
+        if confidence < MIN_CONFIDENCE {
+            Classification { intent: Intent::Chat, confidence }
+        } else {
+            Classification { intent, confidence }
+        }
+    }
+}
+
+impl Default for IntentClassifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Words, word pairs, the first word, and markers for paths, identifiers
+/// and a closing question mark
+fn features(text: &str) -> Vec<String> {
+    let words: Vec<String> = text.split_whitespace().filter_map(word_feature).collect();
+    let mut features = words.clone();
+    if let Some(first) = words.first() {
+        features.push(format!("^{}", first));
+    }
+    for pair in words.windows(2) {
+        features.push(format!("{} {}", pair[0], pair[1]));
+    }
+    if text.trim_end().ends_with('?') {
+        features.push("<?>".to_string());
+    }
+    features
+}
+
+/// The feature of one word: `<path>` for file names, `<code>` for
+/// identifiers, otherwise the lowercased word
+fn word_feature(raw: &str) -> Option<String> {
+    let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+    if word.is_empty() {
+        return None;
+    }
+    let is_path = word.contains('/')
+        || word
+            .rsplit_once('.')
+            .is_some_and(|(stem, ext)| !stem.is_empty() && (1..=4).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_lowercase()));
+    if is_path {
+        return Some("<path>".to_string());
+    }
+    let is_code = word.contains("::")
+        || raw.contains('`')
+        || raw.contains("()")
+        || word.trim_matches('_').contains('_')
+        || word.chars().skip(1).any(|c| c.is_ascii_uppercase());
+    if is_code {
+        return Some("<code>".to_string());
+    }
+    Some(word.to_lowercase().replace('\'', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_intents() {
+        let classifier = IntentClassifier::new();
+        let intent = |text: &str| classifier.classify(text).intent;
+
+        assert_eq!(intent("how does the payment flow work in this codebase?"), Intent::Question);
+        assert_eq!(intent("add input validation to handlers/user.rs"), Intent::Edit);
+        assert_eq!(intent("clear the history"), Intent::Command);
+        assert_eq!(intent("find all callers of send_email"), Intent::Search);
+        assert_eq!(intent("thanks!"), Intent::Chat);
+
+        // Features do not depend on the particular names used
+        assert_eq!(features("rename `foo` in a/b.rs"), features("rename `bar` in c.py"));
+        assert!(classifier.classify("clear the history").confidence >= MIN_CONFIDENCE);
+    }
+}
@@ -2,10 +2,14 @@
 
 pub mod claude;
 pub mod context;
+pub mod deep;
+pub mod intent;
 pub mod ollama;
+pub mod postprocess;
 pub mod providers;
 pub mod proxy_client;
 pub mod router;
+pub mod verify;
 
 pub use claude::{ClaudeClient, Conversation};
 #[allow(unused_imports)]
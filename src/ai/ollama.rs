@@ -8,6 +8,10 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use super::router::{estimate_tokens, AiRouter};
+use crate::core::hooks::{self, Prompt, PromptMessage};
+use crate::core::memory;
+
 /// Default Ollama server URL
 const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
 
@@ -206,6 +210,21 @@ impl OllamaClient {
             content: message.to_string(),
         });
 
+        // The system prompt reaches hooks as the first message; project
+        // memory, when on, as `system`
+        let prompt = hooks::pre_prompt("local", memory::inject(Prompt {
+            system: None,
+            messages: messages.into_iter().map(|m| PromptMessage { role: m.role, content: m.content }).collect(),
+        }))?;
+        let messages: Vec<Message> = prompt
+            .system
+            .iter()
+            .map(|system| Message { role: "system".to_string(), content: system.clone() })
+            .chain(prompt.messages.iter().map(|m| Message { role: m.role.clone(), content: m.content.clone() }))
+            .collect();
+
+        let input_tokens = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+        AiRouter::admit("local", input_tokens)?;
         let request = ChatRequest {
             model: self.model.clone(),
             messages,
@@ -236,18 +255,40 @@ impl OllamaClient {
             .await
             .context("Failed to parse chat response")?;
 
-        Ok(chat_response.message.content)
+        let output_tokens = chat_response
+            .eval_count
+            .map(|count| count as usize)
+            .unwrap_or_else(|| estimate_tokens(&chat_response.message.content));
+        AiRouter::record("local", input_tokens, output_tokens);
+        hooks::post_response("local", &prompt, chat_response.message.content)
+    }
+
+    /// Send a message with `context` as an extra system message, the way
+    /// `ProxyClient::chat` takes it
+    pub async fn ask(&self, message: &str, context: Option<&str>) -> Result<String> {
+        let history = context
+            .map(|context| vec![Message { role: "system".to_string(), content: context.to_string() }])
+            .unwrap_or_default();
+        self.chat_with_history(message, history).await
     }
 
     /// Simple text generation (non-chat)
     pub async fn generate(&self, prompt: &str) -> Result<String> {
         let url = format!("{}/api/generate", self.base_url);
+        let hooked = hooks::pre_prompt("local", memory::inject(Prompt {
+            system: self.system_prompt.clone(),
+            messages: vec![PromptMessage { role: "user".to_string(), content: prompt.to_string() }],
+        }))?;
+        let prompt = hooked.messages.iter().map(|m| m.content.as_str()).collect::<Vec<_>>().join("\n\n");
+
+        let input_tokens = estimate_tokens(&prompt) + hooked.system.as_deref().map(estimate_tokens).unwrap_or(0);
+        AiRouter::admit("local", input_tokens)?;
 
         let request = GenerateRequest {
             model: self.model.clone(),
-            prompt: prompt.to_string(),
+            prompt: prompt.clone(),
             stream: Some(false),
-            system: self.system_prompt.clone(),
+            system: hooked.system.clone(),
         };
 
         let response = self
@@ -267,7 +308,8 @@ impl OllamaClient {
             .await
             .context("Failed to parse generate response")?;
 
-        Ok(gen_response.response)
+        AiRouter::record("local", input_tokens, estimate_tokens(&gen_response.response));
+        hooks::post_response("local", &hooked, gen_response.response)
     }
 
     /// Get the current model name
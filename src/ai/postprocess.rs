@@ -0,0 +1,273 @@
+//! AI response post-processing
+//!
+//! A pipeline of steps applied to AI output before it is shown or written:
+//! stripping banned phrases from prose, trimming trailing whitespace, and
+//! running the language's formatter (rustfmt, black, prettier) on code.
+//! Commands that write code format it by default; the others only clean up
+//! prose. `[postprocess.commands]` overrides the steps per command.
+
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::PostprocessConfig;
+use crate::core::parser::Language;
+use crate::core::permissions::{self, Permission};
+use crate::core::query;
+
+/// Step names accepted in `[postprocess.commands]`
+pub const STEPS: &[&str] = &["strip_phrases", "trim_whitespace", "format_code"];
+
+/// Commands whose output is mostly code
+const CODE_COMMANDS: &[&str] = &["generate", "fix", "refactor", "convert", "test", "optimize"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    StripPhrases,
+    TrimWhitespace,
+    FormatCode,
+}
+
+impl Step {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "strip_phrases" => Some(Step::StripPhrases),
+            "trim_whitespace" => Some(Step::TrimWhitespace),
+            "format_code" => Some(Step::FormatCode),
+            _ => None,
+        }
+    }
+}
+
+/// The steps for one command
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    steps: Vec<Step>,
+    banned: Vec<String>,
+    config: PostprocessConfig,
+}
+
+impl Pipeline {
+    /// Steps configured for `command`, or its defaults
+    pub fn for_command(config: &PostprocessConfig, command: &str) -> Self {
+        let steps = match config.commands.get(command) {
+            Some(names) => names
+                .iter()
+                .filter_map(|name| {
+                    let step = Step::parse(name);
+                    if step.is_none() {
+                        tracing::warn!("Unknown post-processing step `{}` (expected one of: {})", name, STEPS.join(", "));
+                    }
+                    step
+                })
+                .collect(),
+            None if CODE_COMMANDS.contains(&command) => vec![Step::StripPhrases, Step::TrimWhitespace, Step::FormatCode],
+            None => vec![Step::StripPhrases, Step::TrimWhitespace],
+        };
+        let banned = config.banned_phrases.iter().map(|p| p.to_lowercase()).filter(|p| !p.is_empty()).collect();
+        Self { steps, banned, config: config.clone() }
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Process a Markdown response: prose steps outside code fences, the
+    /// formatter on each fenced block whose language is known
+    pub fn apply(&self, response: &str) -> String {
+        let mut out: Vec<String> = Vec::new();
+        let mut block: Option<(Option<Language>, Vec<&str>)> = None;
+
+        for line in response.lines() {
+            let fence = line.trim_start().starts_with("```");
+            match block.take() {
+                Some((language, code)) if fence => {
+                    out.extend(self.format_block(language, &code.join("\n")).lines().map(str::to_string));
+                    out.push(line.to_string());
+                }
+                Some((language, mut code)) => {
+                    code.push(line);
+                    block = Some((language, code));
+                }
+                None if fence => {
+                    let tag = line.trim_start().trim_start_matches('`').trim();
+                    block = Some((query::parse_language(tag), Vec::new()));
+                    out.push(line.to_string());
+                }
+                None => {
+                    if let Some(line) = self.process_prose(line) {
+                        out.push(line);
+                    }
+                }
+            }
+        }
+        // An unterminated block is left as it is
+        if let Some((_, code)) = block {
+            out.extend(code.iter().map(|l| l.to_string()));
+        }
+
+        let mut text = out.join("\n");
+        if response.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Process bare code in `language`
+    pub fn apply_code(&self, code: &str, language: Language) -> String {
+        let code = if self.steps.contains(&Step::TrimWhitespace) {
+            code.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+        } else {
+            code.to_string()
+        };
+        if self.steps.contains(&Step::FormatCode) {
+            format(&code, language, &self.config).unwrap_or(code)
+        } else {
+            code
+        }
+    }
+
+    fn format_block(&self, language: Option<Language>, code: &str) -> String {
+        match language {
+            Some(language) => self.apply_code(code, language),
+            None => code.to_string(),
+        }
+    }
+
+    /// A prose line with banned phrases removed, or `None` when nothing is left
+    fn process_prose(&self, line: &str) -> Option<String> {
+        let mut line = line.to_string();
+        if self.steps.contains(&Step::StripPhrases) {
+            let original = line.clone();
+            for phrase in &self.banned {
+                line = remove_phrase(&line, phrase);
+            }
+            if line != original {
+                if !line.chars().any(char::is_alphanumeric) {
+                    return None;
+                }
+                // Keep the indentation and list marker, restore the capital
+                let body = line.trim_start();
+                let body = ["- ", "* ", "+ "].iter().find_map(|m| body.strip_prefix(m)).unwrap_or(body);
+                let prefix = &line[..line.len() - body.len()];
+                let kept = body.trim_start_matches([',', '.', '!', ';', ':', ' ']);
+                let mut chars = kept.chars();
+                line = match chars.next() {
+                    Some(first) => format!("{}{}{}", prefix, first.to_uppercase(), chars.as_str()),
+                    None => prefix.to_string(),
+                };
+            }
+        }
+        if self.steps.contains(&Step::TrimWhitespace) {
+            line.truncate(line.trim_end().len());
+        }
+        Some(line)
+    }
+}
+
+/// `line` without any case-insensitive occurrence of `phrase` (lowercase)
+fn remove_phrase(line: &str, phrase: &str) -> String {
+    let lower = line.to_lowercase();
+    // Lowercasing can change byte lengths; only strip when offsets still line up
+    if lower.len() != line.len() {
+        return line.to_string();
+    }
+    let mut out = String::new();
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find(phrase) {
+        out.push_str(&line[pos..pos + found]);
+        pos += found + phrase.len();
+        // Close the gap the phrase leaves; other spacing stays as written
+        if out.ends_with(char::is_whitespace) {
+            pos = line.len() - line[pos..].trim_start().len();
+        }
+    }
+    if pos > 0 && line[pos..].trim().is_empty() {
+        out.truncate(out.trim_end().len());
+    } else {
+        out.push_str(&line[pos..]);
+    }
+    out
+}
+
+/// Formatter reading code on stdin and printing it formatted, from
+/// `[postprocess.formatters]` or the default for the language
+pub fn formatter_command(language: Language, config: &PostprocessConfig) -> Option<Vec<String>> {
+    let key = match language {
+        Language::Rust => "rust",
+        Language::Python => "python",
+        Language::JavaScript => "javascript",
+        Language::TypeScript => "typescript",
+        Language::Unknown => return None,
+    };
+    if let Some(command) = config.formatters.get(key) {
+        let args: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+        return (!args.is_empty()).then_some(args);
+    }
+    let default: &[&str] = match language {
+        Language::Rust => &["rustfmt", "--edition", "2021"],
+        Language::Python => &["black", "-q", "-"],
+        Language::JavaScript => &["prettier", "--stdin-filepath", "answer.js"],
+        Language::TypeScript => &["prettier", "--stdin-filepath", "answer.ts"],
+        Language::Unknown => return None,
+    };
+    Some(default.iter().map(|a| a.to_string()).collect())
+}
+
+/// `code` run through the formatter, or `None` when there is none, it is
+/// not installed, or it rejects the code (e.g. a fragment that does not parse)
+fn format(code: &str, language: Language, config: &PostprocessConfig) -> Option<String> {
+    let args = formatter_command(language, config)?;
+    permissions::request(Permission::Run, &format!("run `{}` on the answer's code", args.join(" "))).ok()?;
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let mut stdin = child.stdin.take()?;
+    let input = code.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()).ok());
+    let output = child.wait_with_output().ok()?;
+    writer.join().ok();
+
+    let formatted = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !formatted.trim().is_empty()).then(|| formatted.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline_steps_and_phrases() {
+        let mut config = PostprocessConfig {
+            banned_phrases: vec!["As an AI language model".to_string(), "I hope this helps".to_string()],
+            ..Default::default()
+        };
+        config.commands.insert("chat".to_string(), vec!["strip_phrases".to_string(), "bogus".to_string()]);
+
+        assert_eq!(Pipeline::for_command(&config, "chat").steps(), [Step::StripPhrases]);
+        assert!(Pipeline::for_command(&config, "fix").steps().contains(&Step::FormatCode));
+        assert!(!Pipeline::for_command(&config, "explain").steps().contains(&Step::FormatCode));
+
+        let pipeline = Pipeline::for_command(&config, "explain");
+        let response = "As an AI language model, the function parses input.   \n\n```\nlet x = 1;   \n// I hope this helps\n```\n- I hope this helps!\nDone.\n";
+        assert_eq!(
+            pipeline.apply(response),
+            "The function parses input.\n\n```\nlet x = 1;   \n// I hope this helps\n```\nDone.\n"
+        );
+        // Only lines with a phrase change, and only where it was
+        assert_eq!(pipeline.apply("|  a  |  b  |\n"), "|  a  |  b  |\n");
+        assert_eq!(pipeline.apply("Call `f(a,  b)`. I hope this helps\n"), "Call `f(a,  b)`.\n");
+        assert_eq!(pipeline.apply("It works and I hope this helps  a lot.\n"), "It works and a lot.\n");
+
+        // A fragment the formatter rejects, or a missing formatter, leaves code as it is
+        config.formatters.insert("python".to_string(), "false".to_string());
+        let pipeline = Pipeline::for_command(&config, "generate");
+        assert_eq!(pipeline.apply_code("x  =  1   \n", Language::Python), "x  =  1");
+    }
+}
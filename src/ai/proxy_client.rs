@@ -8,6 +8,11 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use super::router::{estimate_tokens, AiRouter};
+use crate::core::hooks::{self, Prompt, PromptMessage};
+use crate::core::memory;
+use crate::core::permissions::{self, Permission};
+
 /// Default proxy server URL
 const DEFAULT_PROXY_URL: &str = "https://api-nexus.mustafasarac.com";
 
@@ -115,9 +120,17 @@ impl ProxyClient {
     /// Generate code using the proxy
     pub async fn generate(&self, description: &str, language: &str) -> Result<String> {
         let url = format!("{}/api/generate", self.base_url);
+        permissions::request(Permission::Network, "send this request to the NEXUS proxy")?;
+        let prompt = hooks::pre_prompt("proxy", memory::inject(to_prompt(description, None)))?;
+        // The generate endpoint takes no system prompt, so it leads the description
+        let description = match &prompt.system {
+            Some(system) => format!("{}\n\n{}", system, user_message(&prompt)),
+            None => user_message(&prompt),
+        };
+        AiRouter::admit("proxy", estimate_tokens(&description))?;
 
         let request = GenerateRequest {
-            description: description.to_string(),
+            description: description.clone(),
             language: language.to_string(),
         };
 
@@ -140,17 +153,22 @@ impl ProxyClient {
             anyhow::bail!("Code generation failed: {}", error_msg);
         }
 
-        body.code.ok_or_else(|| anyhow::anyhow!("No code in response"))
+        let code = body.code.ok_or_else(|| anyhow::anyhow!("No code in response"))?;
+        AiRouter::record("proxy", estimate_tokens(&description), estimate_tokens(&code));
+        hooks::post_response("proxy", &prompt, code)
     }
 
     /// Send a chat/ask request
     pub async fn chat(&self, message: &str, context: Option<&str>) -> Result<String> {
         let url = format!("{}/api/chat", self.base_url);
+        permissions::request(Permission::Network, "send this request to the NEXUS proxy")?;
+        let prompt = hooks::pre_prompt("proxy", memory::inject(to_prompt(message, context)))?;
+        let message = user_message(&prompt);
+        let context = prompt.system.clone();
+        let input_tokens = estimate_tokens(&message) + context.as_deref().map(estimate_tokens).unwrap_or(0);
+        AiRouter::admit("proxy", input_tokens)?;
 
-        let request = ChatRequest {
-            message: message.to_string(),
-            context: context.map(|s| s.to_string()),
-        };
+        let request = ChatRequest { message, context };
 
         let response = self
             .client
@@ -171,10 +189,32 @@ impl ProxyClient {
             anyhow::bail!("Chat request failed: {}", error_msg);
         }
 
-        body.response.ok_or_else(|| anyhow::anyhow!("No response in body"))
+        let answer = body.response.ok_or_else(|| anyhow::anyhow!("No response in body"))?;
+        AiRouter::record("proxy", input_tokens, estimate_tokens(&answer));
+        hooks::post_response("proxy", &prompt, answer)
+    }
+}
+
+/// A request as hooks see it; the project context takes the place of the
+/// system prompt
+fn to_prompt(message: &str, context: Option<&str>) -> Prompt {
+    Prompt {
+        system: context.map(String::from),
+        messages: vec![PromptMessage { role: "user".to_string(), content: message.to_string() }],
     }
 }
 
+/// The message of a request after the hooks
+fn user_message(prompt: &Prompt) -> String {
+    prompt
+        .messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 impl Default for ProxyClient {
     fn default() -> Self {
         Self::new()
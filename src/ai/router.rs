@@ -1,12 +1,30 @@
 //! Intelligent AI model routing
 //!
 //! Routes requests to the optimal AI model based on task type,
-//! context length, and user preferences.
+//! context length, and user preferences, and holds requests to the
+//! per-provider quotas in `[ai.quotas]`. Commands send their requests to
+//! the local model instead of Claude or the proxy when it is the default
+//! provider.
 
 #![allow(dead_code)]
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::ollama::OllamaClient;
 use crate::config::Config;
+use crate::core::activity::{ActivityLog, Interaction};
+
+/// Requests made per provider today
+const USAGE_FILE: &str = "usage.json";
+
+/// Router whose quotas apply to every request of this process
+static ACTIVE: OnceLock<AiRouter> = OnceLock::new();
 
 /// Task types for intelligent routing
 #[derive(Debug, Clone, Copy)]
@@ -28,11 +46,114 @@ pub struct AiRouter {
     config: Config,
 }
 
+/// Requests counted per provider, reset each day
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageLedger {
+    date: Option<NaiveDate>,
+    requests: BTreeMap<String, u32>,
+}
+
+/// Rough token count of `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len() / 4
+}
+
 impl AiRouter {
     pub fn new(config: Config) -> Self {
         Self { config }
     }
 
+    /// Apply `config`'s quotas to every AI request of this process
+    pub fn install(config: Config) {
+        ACTIVE.set(Self::new(config)).ok();
+    }
+
+    /// Hold a request of `context_tokens` to `provider` to the installed
+    /// quotas, counting it toward the daily limit when it is allowed
+    pub fn admit(provider: &str, context_tokens: usize) -> Result<()> {
+        match ACTIVE.get() {
+            Some(router) => router.check_quota(provider, context_tokens, &usage_path()?),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the installed config makes the local model the default
+    /// provider (`ai.default_provider = "local"`)
+    pub fn local_default() -> bool {
+        ACTIVE.get().is_some_and(|router| router.config.ai.default_provider == "local")
+    }
+
+    /// Client for the local provider of the installed config
+    pub fn local_client() -> OllamaClient {
+        match ACTIVE.get() {
+            Some(router) => router.local(),
+            None => OllamaClient::from_env(),
+        }
+    }
+
+    /// Client for the model and endpoint in `[ai.providers.local]`, else
+    /// the `OLLAMA_HOST` and `OLLAMA_MODEL` defaults
+    fn local(&self) -> OllamaClient {
+        let Some(local) = &self.config.ai.providers.local else {
+            return OllamaClient::from_env();
+        };
+        let client = OllamaClient::with_model(&local.model);
+        match &local.endpoint {
+            Some(endpoint) => client.with_url(endpoint),
+            None => client,
+        }
+    }
+
+    /// Log a completed request to the project's activity log. Only requests
+    /// of a process with an installed router are logged.
+    pub fn record(provider: &str, input_tokens: usize, output_tokens: usize) {
+        if ACTIVE.get().is_none() {
+            return;
+        }
+        let interaction = Interaction::new(provider, input_tokens as u64, output_tokens as u64);
+        if let Err(e) = ActivityLog::current().record(&interaction) {
+            tracing::debug!("Activity not logged: {}", e);
+        }
+    }
+
+    /// Check a request against the quota for `provider`, recording it in
+    /// the usage ledger at `usage` when allowed
+    pub fn check_quota(&self, provider: &str, context_tokens: usize, usage: &Path) -> Result<()> {
+        let Some(quota) = self.config.ai.quotas.get(provider) else {
+            return Ok(());
+        };
+
+        if let Some(max) = quota.max_context_tokens {
+            if context_tokens > max {
+                bail!(
+                    "This request needs about {} tokens of context, over the {} quota of {} (ai.quotas.{}.max_context_tokens).\n\
+                     Narrow the scope: pass a single file or directory instead of the project, lower --limit, \
+                     or use /compact and /unpin in chat.",
+                    context_tokens, provider, max, provider
+                );
+            }
+        }
+
+        let Some(max) = quota.max_requests_per_day else {
+            return Ok(());
+        };
+        let today = Utc::now().date_naive();
+        let mut ledger = UsageLedger::load(usage);
+        if ledger.date != Some(today) {
+            ledger = UsageLedger { date: Some(today), requests: BTreeMap::new() };
+        }
+        let used = ledger.requests.entry(provider.to_string()).or_default();
+        if *used >= max {
+            bail!(
+                "The daily quota of {} {} requests is used up (ai.quotas.{}.max_requests_per_day); it resets at midnight UTC.\n\
+                 {}",
+                max, provider, provider, other_provider_hint(provider)
+            );
+        }
+        *used += 1;
+        ledger.save(usage)
+    }
+
     /// Select the best provider for a given task
     pub fn select_provider(&self, task: TaskType, context_tokens: usize) -> String {
         // Intelligent routing logic
@@ -79,10 +200,88 @@ impl AiRouter {
 
     /// Generate completion from the selected provider
     pub async fn complete(&self, prompt: &str, task: TaskType) -> Result<String> {
-        let provider = self.select_provider(task, prompt.len() / 4); // Rough token estimate
+        let provider = self.select_provider(task, estimate_tokens(prompt));
         tracing::info!("Using provider: {}", provider);
 
         // TODO: Implement actual API calls
         Ok(format!("[{}] Response placeholder for: {}...", provider, &prompt[..50.min(prompt.len())]))
     }
 }
+
+/// Where to turn when a provider's daily quota runs out
+fn other_provider_hint(provider: &str) -> &'static str {
+    match provider {
+        "proxy" => "Set ANTHROPIC_API_KEY to use your own Claude account, or raise the quota in the config.",
+        "claude" => "Unset ANTHROPIC_API_KEY to use the free NEXUS proxy, or raise the quota in the config.",
+        _ => "Raise the quota in the config or switch providers.",
+    }
+}
+
+fn usage_path() -> Result<PathBuf> {
+    let dir = directories::ProjectDirs::from("com", "nexus", "forge")
+        .context("Failed to determine data directory")?
+        .data_dir()
+        .to_path_buf();
+    Ok(dir.join(USAGE_FILE))
+}
+
+impl UsageLedger {
+    fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QuotaConfig;
+
+    #[test]
+    fn test_quota_limits_context_and_daily_requests() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let usage = temp_dir.path().join(USAGE_FILE);
+        let mut config = Config::default();
+        config.ai.quotas.insert(
+            "proxy".to_string(),
+            QuotaConfig { max_context_tokens: Some(1000), max_requests_per_day: Some(2) },
+        );
+        let router = AiRouter::new(config);
+
+        let too_large = router.check_quota("proxy", 1500, &usage).unwrap_err().to_string();
+        assert!(too_large.contains("max_context_tokens") && too_large.contains("Narrow the scope"));
+
+        router.check_quota("proxy", 500, &usage).unwrap();
+        router.check_quota("proxy", 500, &usage).unwrap();
+        let used_up = router.check_quota("proxy", 500, &usage).unwrap_err().to_string();
+        assert!(used_up.contains("daily quota of 2 proxy requests") && used_up.contains("ANTHROPIC_API_KEY"));
+        // Providers without a quota are not limited
+        router.check_quota("claude", 1_000_000, &usage).unwrap();
+
+        // A new day starts from zero
+        fs::write(&usage, r#"{"date": "2000-01-01", "requests": {"proxy": 2}}"#).unwrap();
+        router.check_quota("proxy", 500, &usage).unwrap();
+    }
+
+    #[test]
+    fn test_local_client_uses_configured_model() {
+        let mut config = Config::default();
+        config.ai.providers.local = Some(crate::config::LocalProviderConfig {
+            enabled: true,
+            backend: "ollama".to_string(),
+            model: "coder:7b".to_string(),
+            endpoint: Some("http://gpu-box:11434".to_string()),
+        });
+        assert_eq!(AiRouter::new(config).local().model(), "coder:7b");
+    }
+}
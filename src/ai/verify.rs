@@ -0,0 +1,168 @@
+//! Verification pass for AI answers
+//!
+//! With `--verify`, a second request, optionally to another provider or
+//! model, critiques an answer against the context it was given and reports
+//! a confidence score plus the claims the context does not support.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::router::AiRouter;
+use super::{ClaudeClient, ProxyClient};
+use crate::config::VerifyConfig;
+
+/// System prompt of the verifier
+const VERIFIER_PROMPT: &str = r#"You are NEXUS AI acting as a strict fact checker.
+
+You get the context an assistant was given, the question, and its answer.
+Check every claim in the answer against the context only:
+- Names of functions, types, files and lines must exist in the context
+- Described behavior must follow from the code shown
+- Claims the context cannot confirm count as unsupported, even if plausible
+
+Reply only with JSON:
+
+```json
+{"score": 0-100, "summary": "One sentence on how well the answer holds up",
+ "discrepancies": [{"claim": "What the answer says", "issue": "Why the context contradicts or does not support it",
+                    "evidence": "Quote or location from the context, if any"}]}
+```
+
+`score` is how confident you are that the answer is correct and grounded in the context."#;
+
+/// Score at or above which confidence is high
+const HIGH_SCORE: u8 = 80;
+/// Score at or above which confidence is medium
+const MEDIUM_SCORE: u8 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    pub fn from_score(score: u8) -> Self {
+        if score >= HIGH_SCORE {
+            Confidence::High
+        } else if score >= MEDIUM_SCORE {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Confidence::High => "high",
+            Confidence::Medium => "medium",
+            Confidence::Low => "low",
+        }
+    }
+}
+
+/// A claim the verifier could not match to the context
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Discrepancy {
+    pub claim: String,
+    pub issue: String,
+    #[serde(default)]
+    pub evidence: String,
+}
+
+/// The verifier's judgment of one answer
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Verdict {
+    pub score: u8,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub discrepancies: Vec<Discrepancy>,
+    /// Provider that verified the answer
+    #[serde(skip)]
+    pub verifier: String,
+}
+
+impl Verdict {
+    pub fn confidence(&self) -> Confidence {
+        Confidence::from_score(self.score)
+    }
+}
+
+/// Provider of the verifier: the configured one, else the one that answered
+pub fn provider<'a>(config: &'a VerifyConfig, answered_by: &'a str) -> &'a str {
+    if config.provider.is_empty() {
+        answered_by
+    } else {
+        &config.provider
+    }
+}
+
+/// Ask the verifier to check `answer` against `context`. `answered_by` is
+/// `claude`, `proxy` or `local`.
+pub async fn verify(config: &VerifyConfig, answered_by: &str, context: &str, question: &str, answer: &str) -> Result<Verdict> {
+    let provider = provider(config, answered_by);
+    let prompt = format!(
+        "## Context Given to the Assistant\n\n{}\n\n## Question\n\n{}\n\n## Answer to Check\n\n{}",
+        context, question, answer
+    );
+
+    let response = match provider {
+        "claude" => {
+            let mut client = ClaudeClient::from_env()?;
+            if let Some(model) = &config.model {
+                client = client.with_model(model);
+            }
+            client.send_with_system(&prompt, VERIFIER_PROMPT).await?
+        }
+        "local" => {
+            let mut client = AiRouter::local_client().with_system(VERIFIER_PROMPT);
+            if let Some(model) = &config.model {
+                client.set_model(model);
+            }
+            client.chat(&prompt).await?
+        }
+        _ => ProxyClient::from_env().chat(&format!("{}\n\n{}", VERIFIER_PROMPT, prompt), None).await?,
+    };
+
+    let mut verdict = parse_verdict(&response).context("The verifier did not return a verdict")?;
+    verdict.verifier = match &config.model {
+        Some(model) if provider != "proxy" => format!("{} ({})", provider, model),
+        _ => provider.to_string(),
+    };
+    Ok(verdict)
+}
+
+/// The verdict in a verifier response, fenced or bare
+pub fn parse_verdict(response: &str) -> Option<Verdict> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    let mut verdict: Verdict = serde_json::from_str(&response[start..=end]).ok()?;
+    verdict.score = verdict.score.min(100);
+    Some(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verdict() {
+        let response = "```json\n{\"score\": 62, \"summary\": \"Mostly grounded.\", \"discrepancies\": [{\"claim\": \"load_config caches the result\", \"issue\": \"No cache in the code shown\"}]}\n```";
+        let verdict = parse_verdict(response).unwrap();
+        assert_eq!(verdict.score, 62);
+        assert_eq!(verdict.confidence(), Confidence::Medium);
+        assert_eq!(verdict.discrepancies[0].claim, "load_config caches the result");
+        assert!(verdict.discrepancies[0].evidence.is_empty());
+
+        assert_eq!(parse_verdict("{\"score\": 95}").unwrap().confidence(), Confidence::High);
+        assert_eq!(Confidence::from_score(10), Confidence::Low);
+        assert!(parse_verdict("Looks right to me.").is_none());
+    }
+}
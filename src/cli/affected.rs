@@ -0,0 +1,144 @@
+//! Affected command - packages and tests impacted by a change
+//!
+//! Compares the working tree with a base branch and reports which packages
+//! and test files need to run, as text or as JSON for CI pipelines.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Result};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::affected::{self, Affected, Reason, TestReason};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const AFFECTED: &str = "󰙨";
+    pub const PACKAGE: &str = "󰏗";
+    pub const TEST: &str = "󰙨";
+    pub const SUCCESS: &str = "󰄂";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Report what changed since `base` affects
+pub async fn run(_config: Config, base: &str, json: bool) -> Result<()> {
+    let root = Path::new(".");
+
+    let packages = affected::discover_packages(root)?;
+    let changed = match affected::changed_files(root, base) {
+        Ok(files) => files,
+        // CI consumers need a failing exit code, not an empty result
+        Err(e) if json => return Err(e),
+        Err(e) => {
+            print_header(base);
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+
+    if packages.is_empty() && json {
+        bail!("No Cargo.toml, package.json or pyproject.toml found");
+    }
+
+    let result = affected::analyze(root, base, &packages, changed);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    print_header(base);
+    print_result(&result, packages.len());
+    Ok(())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(base: &str) {
+    println!();
+    println!(
+        "{}{}  {} Affected{}",
+        colors::PRIMARY, colors::BOLD, symbols::AFFECTED, colors::RESET
+    );
+    println!(
+        "{}  │ {}changes since {}{}",
+        colors::MUTED, colors::FG, base, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_result(result: &Affected, package_count: usize) {
+    println!(
+        "{}  {} files changed · {} of {} packages affected{}",
+        colors::MUTED, result.changed_files.len(), result.packages.len(), package_count, colors::RESET
+    );
+    println!();
+
+    if result.packages.is_empty() && result.tests.is_empty() {
+        println!(
+            "{}{}  {} Nothing to build or test{}",
+            colors::SUCCESS, colors::BOLD, symbols::SUCCESS, colors::RESET
+        );
+        println!();
+        return;
+    }
+
+    for package in &result.packages {
+        let reason = match &package.reason {
+            Reason::Changed => "changed".to_string(),
+            Reason::Dependency(via) => format!("depends on {}", via),
+            Reason::Global(file) => format!("shared file {}", file),
+        };
+        println!(
+            "{}  {} {}{}{} {} · {}{}",
+            colors::MUTED, symbols::PACKAGE, colors::FG, package.name, colors::MUTED,
+            package.path.display(), reason, colors::RESET
+        );
+    }
+    println!();
+
+    for test in &result.tests {
+        let reason = match &test.reason {
+            TestReason::Changed => "changed".to_string(),
+            TestReason::References(name) => format!("uses {}", name),
+            TestReason::Package => "package affected".to_string(),
+        };
+        println!(
+            "{}  {} {}{}{} · {}{}",
+            colors::WARNING, symbols::TEST, colors::FG, test.path.display(), colors::MUTED,
+            reason, colors::RESET
+        );
+    }
+    if !result.tests.is_empty() {
+        println!();
+    }
+    println!(
+        "{}  Use --json for CI pipelines{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -0,0 +1,232 @@
+//! API command - public API changes between revisions
+//!
+//! Compares the public items of a Rust crate at two git revisions and
+//! advises the semver bump the next release needs.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Result};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::api::{self, ApiChange, ApiDiff, ApiSurface, Bump, ChangeKind};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const API: &str = "󰘦";
+    pub const ADDED: &str = "+";
+    pub const REMOVED: &str = "-";
+    pub const CHANGED: &str = "~";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Diff the public API over a revision range (`v1.4.0..HEAD`). With
+/// `deny = Some("breaking")`, fails when breaking changes are not matched
+/// by a major version bump in the manifest, for use as a CI gate.
+pub async fn diff(_config: Config, range: &str, deny: Option<&str>) -> Result<()> {
+    let (from, to) = api::parse_range(range);
+    let to_label = to.clone().unwrap_or_else(|| "working tree".to_string());
+
+    print_header(&from, &to_label);
+
+    let gate = deny == Some("breaking");
+
+    let root = Path::new(".");
+    if !root.join("Cargo.toml").exists() {
+        print_error("No Cargo.toml here; run from the root of a Rust crate");
+        if gate {
+            bail!("No Rust crate to check");
+        }
+        return Ok(());
+    }
+
+    let old = match ApiSurface::at_revision(root, &from) {
+        Ok(surface) => surface,
+        Err(e) if gate => return Err(e),
+        Err(e) => {
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+    let new = match &to {
+        Some(revision) => ApiSurface::at_revision(root, revision),
+        None => ApiSurface::working_tree(root),
+    };
+    let new = match new {
+        Ok(surface) => surface,
+        Err(e) if gate => return Err(e),
+        Err(e) => {
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+
+    let diff = api::diff(&old, &new);
+    print_changes(&diff, old.items.len(), new.items.len());
+    print_advice(&diff, old.version.as_deref(), new.version.as_deref());
+
+    if gate {
+        check_gate(&diff, old.version.as_deref(), new.version.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Fail when breaking changes ship without a major bump
+fn check_gate(diff: &ApiDiff, old_version: Option<&str>, new_version: Option<&str>) -> Result<()> {
+    let breaking = diff.breaking().count();
+    if breaking == 0 {
+        print_success("No breaking changes");
+        return Ok(());
+    }
+
+    match (old_version, new_version) {
+        (Some(old), Some(new)) if api::bump_satisfied(old, new, Bump::Major) => {
+            print_success(&format!("{} breaking changes covered by the bump {} → {}", breaking, old, new));
+            Ok(())
+        }
+        (Some(old), Some(new)) => {
+            let required = api::next_version(old, Bump::Major).unwrap_or_else(|| "a major release".to_string());
+            print_error(&format!("{} breaking changes but the version is {} (needs {})", breaking, new, required));
+            bail!("Breaking API changes without a major version bump")
+        }
+        _ => {
+            print_error("Could not read package.version from Cargo.toml at both revisions");
+            bail!("Breaking API changes and no version to check against")
+        }
+    }
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(from: &str, to: &str) {
+    println!();
+    println!(
+        "{}{}  {} API Diff{}",
+        colors::PRIMARY, colors::BOLD, symbols::API, colors::RESET
+    );
+    println!(
+        "{}  │ {}{} → {}{}",
+        colors::MUTED, colors::FG, from, to, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_change(change: &ApiChange) {
+    let (symbol, color) = match change.kind {
+        ChangeKind::Added => (symbols::ADDED, colors::SUCCESS),
+        ChangeKind::Removed => (symbols::REMOVED, colors::ERROR),
+        ChangeKind::Changed => (symbols::CHANGED, colors::WARNING),
+    };
+    println!(
+        "{}  {} {}{}{} {}{}",
+        color, symbol, colors::FG, change.path, colors::MUTED, change.item.label(), colors::RESET
+    );
+    if change.kind == ChangeKind::Changed {
+        if let (Some(before), Some(after)) = (&change.before, &change.after) {
+            println!("{}      was: {}{}", colors::MUTED, before, colors::RESET);
+            println!("{}      now: {}{}", colors::MUTED, after, colors::RESET);
+        }
+    }
+}
+
+fn print_changes(diff: &ApiDiff, old_count: usize, new_count: usize) {
+    println!(
+        "{}  {} → {} public items{}",
+        colors::MUTED, old_count, new_count, colors::RESET
+    );
+    println!();
+
+    if diff.changes.is_empty() {
+        println!(
+            "{}{}  {} No public API changes{}",
+            colors::SUCCESS, colors::BOLD, symbols::SUCCESS, colors::RESET
+        );
+        println!();
+        return;
+    }
+
+    let (breaking, compatible): (Vec<&ApiChange>, Vec<&ApiChange>) = diff.changes.iter().partition(|c| c.breaking);
+
+    if !breaking.is_empty() {
+        println!("{}{}  Breaking ({}){}", colors::ERROR, colors::BOLD, breaking.len(), colors::RESET);
+        breaking.into_iter().for_each(print_change);
+        println!();
+    }
+    if !compatible.is_empty() {
+        println!("{}{}  Compatible ({}){}", colors::SUCCESS, colors::BOLD, compatible.len(), colors::RESET);
+        compatible.into_iter().for_each(print_change);
+        println!();
+    }
+}
+
+fn print_advice(diff: &ApiDiff, old_version: Option<&str>, new_version: Option<&str>) {
+    let color = match diff.bump {
+        Bump::Major => colors::ERROR,
+        Bump::Minor => colors::WARNING,
+        Bump::Patch => colors::SUCCESS,
+    };
+
+    let next = old_version.and_then(|v| api::next_version(v, diff.bump).map(|n| format!(" ({} → {})", v, n)));
+    println!(
+        "{}{}  Recommended release: {}{}{}",
+        color, colors::BOLD, diff.bump.label(), next.unwrap_or_default(), colors::RESET
+    );
+
+    if old_version.is_some_and(|v| v.starts_with("0.")) && diff.bump != Bump::Patch {
+        println!(
+            "{}  Below 1.0, Cargo treats a minor bump as breaking and a patch as compatible{}",
+            colors::MUTED, colors::RESET
+        );
+    }
+    if let (Some(old), Some(new)) = (old_version, new_version) {
+        if old != new {
+            println!(
+                "{}  Manifest version already moved {} → {}{}",
+                colors::MUTED, old, new, colors::RESET
+            );
+        }
+    }
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
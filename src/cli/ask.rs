@@ -1,22 +1,30 @@
 //! Ask command - query your codebase with AI assistance
 //!
 //! Indexes the codebase, finds relevant context, and uses Claude to answer questions.
+//! With `--roots`, several registered repositories are searched together and
+//! the HTTP calls between them are traced.
 
 #![allow(dead_code)]
 
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::io::{self, Write};
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::postprocess::Pipeline;
+use crate::ai::verify::{self, Confidence, Verdict};
 use crate::config::Config;
 use crate::core::parser::{CodeParser, Language, ParsedFile, Symbol, SymbolKind};
+use crate::core::references::{self, ReferenceIndex, ReferenceKind, UnknownReference};
+use crate::index::endpoints::{self, CallSite, Endpoint};
 
 /// AI Provider mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes from design system
@@ -37,6 +45,7 @@ mod symbols {
     pub const SUCCESS: &str = "󰄂";
     pub const ERROR: &str = "󰅚";
     pub const CODE: &str = "";
+    pub const WARNING: &str = "󰀦";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
@@ -60,16 +69,37 @@ When explaining code:
 - Explain the "why" not just the "what"
 "#;
 
+/// Extra instructions when several repositories are searched together
+const FEDERATION_RULES: &str = r#"
+The context comes from several repositories, each under a `## Repository` heading.
+- Cite code as `root:path:line`, e.g. `web:src/api/payments.ts:42`
+- Say which repository each piece of the answer lives in
+- Use the cross-service calls to explain how the repositories talk to each other
+"#;
+
+/// Routes and cross-service calls listed per question
+const MAX_ROUTES: usize = 25;
+const MAX_CALLS: usize = 30;
+
+/// A repository searched by a federated question
+struct Repository {
+    name: String,
+    root: PathBuf,
+    files: Vec<ParsedFile>,
+}
+
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, question: &str) -> Result<()> {
+pub async fn run(config: Config, question: &str, roots: &[String], verify: bool) -> Result<()> {
     // Print header
     print_header(question);
 
@@ -78,65 +108,114 @@ pub async fn run(_config: Config, question: &str) -> Result<()> {
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
-    // Index codebase
-    print_status("Scanning codebase...");
-    let parsed_files = index_codebase(Path::new("."))?;
+    let (context, known) = if roots.is_empty() {
+        // Index codebase
+        print_status("Scanning codebase...");
+        let parsed_files = index_codebase(Path::new("."))?;
 
-    if parsed_files.is_empty() {
-        print_warning("No supported files found in current directory");
-        return Ok(());
-    }
+        if parsed_files.is_empty() {
+            print_warning("No supported files found in current directory");
+            return Ok(());
+        }
 
-    // Find relevant context based on question
-    print_status("Finding relevant context...");
-    let context = build_context(&parsed_files, question);
+        // Find relevant context based on question
+        print_status("Finding relevant context...");
+        (build_context(&parsed_files, Path::new("."), question), ReferenceIndex::build(Path::new("."))?)
+    } else {
+        let resolved = match resolve_roots(&config, roots) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                print_error(&e.to_string());
+                return Ok(());
+            }
+        };
+
+        let mut repos = Vec::new();
+        let mut known = ReferenceIndex::default();
+        for (name, root) in resolved {
+            print_status(&format!("Scanning {} ({})...", name, root.display()));
+            let files = index_codebase(&root)?;
+            known.add_root(&root, Some(&name))?;
+            repos.push(Repository { name, root, files });
+        }
+
+        if repos.iter().all(|r| r.files.is_empty()) {
+            print_warning("No supported files found in the selected roots");
+            return Ok(());
+        }
+
+        print_status("Tracing calls between repositories...");
+        (build_federated_context(&repos, question), known)
+    };
+
+    let system = if roots.is_empty() {
+        CODEBASE_ASSISTANT.to_string()
+    } else {
+        format!("{}{}", CODEBASE_ASSISTANT, FEDERATION_RULES)
+    };
 
     // Build prompt with context
     let full_prompt = format!(
         "{}\n\n## Codebase Context\n\n{}\n\n## Question\n\n{}",
-        CODEBASE_ASSISTANT, context, question
+        system, context, question
     );
 
+    let pipeline = Pipeline::for_command(&config.postprocess, "ask");
+
     // Send to AI
     print_thinking_with_provider(provider_name);
 
-    match ai_mode {
+    let result = match ai_mode {
         AiMode::Claude => {
             let client = ClaudeClient::from_env()?;
             let mut conversation = Conversation::new(client)
-                .with_system(CODEBASE_ASSISTANT);
+                .with_system(&system);
 
             let prompt = format!(
                 "## Codebase Context\n\n{}\n\n## Question\n\n{}",
                 context, question
             );
+            conversation.send(&prompt).await
+        }
+        AiMode::Proxy => ProxyClient::from_env().chat(&full_prompt, None).await,
+        AiMode::Local => {
+            let prompt = format!(
+                "## Codebase Context\n\n{}\n\n## Question\n\n{}",
+                context, question
+            );
+            AiRouter::local_client().with_system(&system).chat(&prompt).await
+        }
+    };
 
-            match conversation.send(&prompt).await {
-                Ok(response) => {
-                    clear_line();
-                    print_response(&response);
-                }
-                Err(e) => {
-                    clear_line();
-                    print_error(&format!("AI error: {}", e));
-                }
-            }
+    let response = match result {
+        Ok(response) => {
+            clear_line();
+            let response = pipeline.apply(&response);
+            let unknown = known.check(&response);
+            print_response(&references::annotate(&response, &unknown));
+            print_unknown_references(&unknown);
+            response
+        }
+        Err(e) => {
+            clear_line();
+            print_error(&format!("AI error: {}", e));
+            return Ok(());
         }
-        AiMode::Proxy => {
-            let proxy = ProxyClient::from_env();
+    };
 
-            match proxy.chat(&full_prompt, None).await {
-                Ok(response) => {
-                    clear_line();
-                    print_response(&response);
-                }
-                Err(e) => {
-                    clear_line();
-                    print_error(&format!("AI error: {}", e));
-                }
-            }
+    if verify || config.verify.always {
+        let answered_by = match ai_mode {
+            AiMode::Claude => "claude",
+            AiMode::Proxy => "proxy",
+            AiMode::Local => "local",
+        };
+        print_status(&format!("Verifying with {}...", verify::provider(&config.verify, answered_by)));
+        match verify::verify(&config.verify, answered_by, &context, question, &response).await {
+            Ok(verdict) => print_verdict(&verdict),
+            Err(e) => print_warning(&format!("Verification failed: {:#}", e)),
         }
     }
 
@@ -182,8 +261,78 @@ fn index_codebase(path: &Path) -> Result<Vec<ParsedFile>> {
     Ok(parsed_files)
 }
 
+/// Directories for `--roots` names; names not in `[roots]` may be paths
+fn resolve_roots(config: &Config, names: &[String]) -> Result<Vec<(String, PathBuf)>> {
+    names.iter().map(|name| config.resolve_root(name)).collect()
+}
+
+/// `root:path:line` citation for a file in a repository
+fn cite(repo: &Repository, path: &Path, line: usize) -> String {
+    let relative = path.strip_prefix(&repo.root).unwrap_or(path);
+    format!("{}:{}:{}", repo.name, relative.display(), line)
+}
+
+/// Per-repository context plus the routes each serves and the HTTP calls
+/// that cross from one repository into another
+fn build_federated_context(repos: &[Repository], question: &str) -> String {
+    let mut parts = Vec::new();
+    let mut routes: Vec<(usize, Endpoint)> = Vec::new();
+    let mut calls: Vec<(usize, CallSite)> = Vec::new();
+
+    for (i, repo) in repos.iter().enumerate() {
+        parts.push(format!(
+            "## Repository `{}`\n\n{}",
+            repo.name,
+            build_context(&repo.files, &repo.root, question)
+        ));
+
+        let paths: Vec<PathBuf> = repo.files.iter().map(|f| f.path.clone()).collect();
+        routes.extend(endpoints::inventory(&paths).into_iter().map(|e| (i, e)));
+        calls.extend(endpoints::call_sites(&paths).into_iter().map(|c| (i, c)));
+    }
+
+    if !routes.is_empty() {
+        parts.push("## Routes Served\n".to_string());
+        for (i, repo) in repos.iter().enumerate() {
+            for (_, route) in routes.iter().filter(|(r, _)| *r == i).take(MAX_ROUTES) {
+                parts.push(format!(
+                    "- `{} {}` → `{}` in `{}`",
+                    route.method, route.path, route.handler, cite(repo, &route.file, route.line)
+                ));
+            }
+        }
+    }
+
+    let crossing: Vec<String> = calls
+        .iter()
+        .flat_map(|(from, call)| {
+            routes
+                .iter()
+                .filter(move |(to, route)| to != from && call.reaches(route))
+                .map(move |(to, route)| {
+                    format!(
+                        "- `{}` {} {} → `{}` ({})",
+                        cite(&repos[*from], &call.file, call.line),
+                        call.method.as_deref().unwrap_or("?"),
+                        call.path,
+                        cite(&repos[*to], &route.file, route.line),
+                        route.handler
+                    )
+                })
+        })
+        .take(MAX_CALLS)
+        .collect();
+
+    if !crossing.is_empty() {
+        parts.push("\n## Cross-Service Calls\n".to_string());
+        parts.extend(crossing);
+    }
+
+    parts.join("\n")
+}
+
 /// Build context string from parsed files based on the question
-fn build_context(files: &[ParsedFile], question: &str) -> String {
+fn build_context(files: &[ParsedFile], root: &Path, question: &str) -> String {
     let question_lower = question.to_lowercase();
     let mut context_parts = Vec::new();
 
@@ -222,7 +371,7 @@ fn build_context(files: &[ParsedFile], question: &str) -> String {
         context_parts.push("### Relevant Symbols\n".to_string());
 
         for (file, symbol) in relevant_symbols.iter().take(10) {
-            let rel_path = file.path.strip_prefix(".").unwrap_or(&file.path);
+            let rel_path = file.path.strip_prefix(root).unwrap_or(&file.path);
             let kind_str = match symbol.kind {
                 SymbolKind::Function => "fn",
                 SymbolKind::Struct => "struct",
@@ -255,7 +404,8 @@ fn build_context(files: &[ParsedFile], question: &str) -> String {
     // Group by directory
     let mut dirs: std::collections::HashMap<String, Vec<&ParsedFile>> = std::collections::HashMap::new();
     for file in files {
-        let dir = file.path.parent()
+        let dir = file.path.strip_prefix(root).unwrap_or(&file.path).parent()
+            .filter(|p| !p.as_os_str().is_empty())
             .map(|p| p.display().to_string())
             .unwrap_or_else(|| ".".to_string());
         dirs.entry(dir).or_default().push(file);
@@ -361,6 +511,54 @@ fn print_response(response: &str) {
     println!();
 }
 
+/// List the paths and names the answer mentions that the codebase lacks
+fn print_unknown_references(unknown: &[UnknownReference]) {
+    if unknown.is_empty() {
+        return;
+    }
+    println!(
+        "{}  {} {} reference{} not found in the codebase{}",
+        colors::AI_ACCENT, references::MARKER, unknown.len(),
+        if unknown.len() == 1 { "" } else { "s" }, colors::RESET
+    );
+    for reference in unknown {
+        let kind = match reference.kind {
+            ReferenceKind::Path => "file",
+            ReferenceKind::Symbol => "symbol",
+        };
+        println!("{}  │ {} {}{}", colors::MUTED, kind, reference.name, colors::RESET);
+    }
+    println!();
+}
+
+/// Print the confidence badge and the discrepancies the verifier found
+fn print_verdict(verdict: &Verdict) {
+    let (color, symbol) = match verdict.confidence() {
+        Confidence::High => (colors::SUCCESS, symbols::SUCCESS),
+        Confidence::Medium => (colors::AI_ACCENT, symbols::WARNING),
+        Confidence::Low => (colors::ERROR, symbols::ERROR),
+    };
+    println!(
+        "{}{}  {} Confidence: {} ({}/100){}{}  verified by {}{}",
+        color, colors::BOLD, symbol, verdict.confidence().label(), verdict.score, colors::RESET,
+        colors::MUTED, verdict.verifier, colors::RESET
+    );
+    if !verdict.summary.is_empty() {
+        println!("{}  │ {}{}", colors::MUTED, verdict.summary, colors::RESET);
+    }
+    for discrepancy in &verdict.discrepancies {
+        println!(
+            "{}  │ {}{} {}{}",
+            colors::MUTED, color, symbols::WARNING, discrepancy.claim, colors::RESET
+        );
+        println!("{}  │   {}{}", colors::MUTED, discrepancy.issue, colors::RESET);
+        if !discrepancy.evidence.is_empty() {
+            println!("{}  │   {}{}", colors::MUTED, discrepancy.evidence, colors::RESET);
+        }
+    }
+    println!();
+}
+
 /// Print error message
 fn print_error(message: &str) {
     println!(
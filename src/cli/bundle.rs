@@ -0,0 +1,211 @@
+//! Bundle command - offline installs for air-gapped machines
+//!
+//! `create` packs this binary, Ollama models and the documentation into one
+//! archive on a connected machine. `install` unpacks it where neither GitHub
+//! nor the proxy is reachable and switches the configuration to the local
+//! provider. Ollama itself must already be installed there.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, Config, LocalProviderConfig};
+use crate::core::bundle::{self, BundleManifest, BundleSpec, InstallTargets};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const PACKAGE: &str = "󰏗";
+    pub const SUCCESS: &str = "󰄂";
+    pub const MODEL: &str = "󰧑";
+    pub const INFO: &str = "󰋼";
+}
+
+const README: &str = include_str!("../../README.md");
+
+/// Write a bundle to `output`. Without `models`, the configured local model
+/// is bundled; `reference` is the generated command reference.
+pub fn create(
+    config: &Config,
+    output: &str,
+    models: &[String],
+    binary: Option<&str>,
+    no_docs: bool,
+    reference: String,
+) -> Result<()> {
+    let binary = match binary {
+        Some(path) => PathBuf::from(path),
+        None => env::current_exe().context("Failed to locate the nexus binary")?,
+    };
+    let models = if models.is_empty() {
+        config.ai.providers.local.iter().map(|local| local.model.clone()).collect()
+    } else {
+        models.to_vec()
+    };
+    let models_dir = bundle::ollama_models_dir().context("Failed to locate the Ollama model store")?;
+    let docs = if no_docs {
+        Vec::new()
+    } else {
+        vec![
+            ("README.md".to_string(), README.to_string()),
+            ("COMMANDS.md".to_string(), reference),
+        ]
+    };
+
+    print_header("Creating offline bundle");
+    let spec = BundleSpec { binary, models_dir, models, docs };
+    let manifest = bundle::create(&spec, Path::new(output))?;
+    print_contents(&manifest);
+
+    let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    println!(
+        "\n{}  {} Bundle written to {} ({}){}",
+        colors::SUCCESS, symbols::SUCCESS, output, format_size(size), colors::RESET
+    );
+    println!(
+        "{}  On the offline machine: tar xzf {} {} && ./{} bundle install {}{}",
+        colors::MUTED, output, manifest.binary, manifest.binary, output, colors::RESET
+    );
+    Ok(())
+}
+
+/// Unpack a bundle and, unless `no_config`, configure nexus for offline use
+pub fn install(
+    config: &Config,
+    archive: &str,
+    bin_dir: Option<&str>,
+    models_dir: Option<&str>,
+    no_config: bool,
+) -> Result<()> {
+    let dirs = directories::ProjectDirs::from("com", "nexus", "forge")
+        .context("Failed to determine data directory")?;
+    let bin_dir = match bin_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => default_bin_dir()?,
+    };
+    let models_dir = match models_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => bundle::ollama_models_dir().context("Failed to locate the Ollama model store")?,
+    };
+    let targets = InstallTargets {
+        bin_dir,
+        models_dir,
+        docs_dir: dirs.data_dir().join("docs"),
+    };
+
+    print_header("Installing offline bundle");
+    let manifest = bundle::install(Path::new(archive), &targets)?;
+    print_contents(&manifest);
+    println!();
+    print_installed("Binary", &targets.bin_dir);
+    if !manifest.models.is_empty() {
+        print_installed("Models", &targets.models_dir);
+    }
+    if !manifest.docs.is_empty() {
+        print_installed("Docs", &targets.docs_dir);
+    }
+
+    if !no_config {
+        let path = config::save_config(&offline_config(config, &manifest))?;
+        println!(
+            "{}  {} Configured for offline use: {}{}",
+            colors::SUCCESS, symbols::SUCCESS, path.display(), colors::RESET
+        );
+        println!(
+            "{}  Local provider, no cloud requests{}",
+            colors::MUTED, colors::RESET
+        );
+    }
+    Ok(())
+}
+
+/// `config` switched to the local provider with the first bundled model,
+/// without cloud requests or update checks
+fn offline_config(config: &Config, manifest: &BundleManifest) -> Config {
+    let mut config = config.clone();
+    config.ai.default_provider = "local".to_string();
+    config.ai.local_fallback = true;
+    config.general.auto_update = false;
+    config.general.telemetry = false;
+    config.privacy.send_code_to_cloud = false;
+
+    let mut local = config.ai.providers.local.take().unwrap_or_else(|| LocalProviderConfig {
+        enabled: true,
+        backend: "ollama".to_string(),
+        model: "codellama".to_string(),
+        endpoint: None,
+    });
+    local.enabled = true;
+    if let Some(model) = manifest.models.first() {
+        local.model = model.name.clone();
+    }
+    config.ai.providers.local = Some(local);
+    config
+}
+
+/// `~/.local/bin` on Unix, `%LOCALAPPDATA%\nexus\bin` on Windows, matching
+/// the install scripts
+fn default_bin_dir() -> Result<PathBuf> {
+    if cfg!(windows) {
+        let local = env::var("LOCALAPPDATA").context("LOCALAPPDATA is not set")?;
+        return Ok(PathBuf::from(local).join("nexus").join("bin"));
+    }
+    match directories::BaseDirs::new() {
+        Some(dirs) => Ok(dirs.home_dir().join(".local").join("bin")),
+        None => bail!("Failed to determine home directory; pass --bin-dir"),
+    }
+}
+
+fn print_header(title: &str) {
+    println!(
+        "\n{}{}  {} {}{}\n",
+        colors::PRIMARY, colors::BOLD, symbols::PACKAGE, title, colors::RESET
+    );
+}
+
+fn print_contents(manifest: &BundleManifest) {
+    println!(
+        "{}  {} nexus v{} ({}-{}){}",
+        colors::FG, symbols::INFO, manifest.version, manifest.os, manifest.arch, colors::RESET
+    );
+    for model in &manifest.models {
+        println!(
+            "{}  {} {} {}({}){}",
+            colors::FG, symbols::MODEL, model.name, colors::MUTED, format_size(model.size), colors::RESET
+        );
+    }
+    println!(
+        "{}  Grammars: {} (built in){}",
+        colors::MUTED, manifest.grammars.join(", "), colors::RESET
+    );
+    if !manifest.docs.is_empty() {
+        println!("{}  Docs: {}{}", colors::MUTED, manifest.docs.join(", "), colors::RESET);
+    }
+}
+
+fn print_installed(what: &str, dir: &Path) {
+    println!(
+        "{}  {} {} {}{}{}",
+        colors::SUCCESS, symbols::SUCCESS, what, colors::FG, dir.display(), colors::RESET
+    );
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1 << 30 {
+        format!("{:.1} GB", bytes as f64 / (1u64 << 30) as f64)
+    } else if bytes >= 1 << 20 {
+        format!("{:.1} MB", bytes as f64 / (1u64 << 20) as f64)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
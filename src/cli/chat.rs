@@ -4,18 +4,501 @@
 
 #![allow(dead_code)]
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::fs;
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Editor, Helper};
 use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
 
-use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::{ClaudeClient, Conversation, OllamaClient, ProxyClient};
+use crate::ai::router::AiRouter;
 use crate::ai::claude::prompts;
-use crate::config::Config;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::intent::{Intent, IntentClassifier};
+use crate::config::{Config, ProjectConfig};
+use crate::core::edits;
+use crate::core::formatter;
+use crate::core::hooks;
+use crate::core::import_organizer;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions::{self, Permission};
+use crate::core::pins::Pins;
+use crate::core::transcript::{ExportFormat, Speaker, Transcript};
+use crate::index;
+use crate::ui::NexusForm;
+use super::{ask, memory, search};
 
 /// AI Provider mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AiMode {
     Claude,
     Proxy,
+    Local,
+}
+
+/// A message read at the prompt
+enum Input {
+    /// Typed lines, which may be a slash command
+    Typed(String),
+    /// Pasted or composed with `/paste` / `/editor`; always sent as is
+    Composed(String),
+}
+
+impl Input {
+    /// The text when it may be a command
+    fn command(&self) -> Option<&str> {
+        match self {
+            Input::Typed(text) => Some(text),
+            Input::Composed(_) => None,
+        }
+    }
+
+    fn into_text(self) -> String {
+        match self {
+            Input::Typed(text) | Input::Composed(text) => text,
+        }
+    }
+}
+
+/// Line that ends a `/paste` message unless another is given
+const PASTE_SENTINEL: &str = "/end";
+
+/// Editor for `/editor` when neither $VISUAL nor $EDITOR is set
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Slash commands offered by Tab completion
+const COMMANDS: &[&str] = &[
+    "/help", "/clear", "/model", "/export", "/pin", "/pins", "/unpin", "/file", "/project", "/compact", "/paste",
+    "/editor", "/route", "/exit",
+];
+
+/// Input history kept across sessions, in the data directory
+const INPUT_HISTORY_FILE: &str = "chat_history.txt";
+const INPUT_HISTORY_SIZE: usize = 1000;
+
+/// Tab completion for slash commands, file paths after `/pin` and
+/// `/file`, and registered roots after `/project`
+struct ChatHelper {
+    files: FilenameCompleter,
+    projects: Vec<String>,
+}
+
+impl Completer for ChatHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, ctx: &rustyline::Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        if before.starts_with('/') && !before.contains(char::is_whitespace) {
+            let matches = COMMANDS
+                .iter()
+                .filter(|c| c.starts_with(before))
+                .map(|c| Pair { display: c.to_string(), replacement: format!("{} ", c) })
+                .collect();
+            return Ok((0, matches));
+        }
+        if is_command(before, "/pin") || is_command(before, "/file") {
+            return self.files.complete(line, pos, ctx);
+        }
+        if is_command(before, "/project") {
+            let start = before.find(char::is_whitespace).map_or(pos, |i| i + 1);
+            let matches = self
+                .projects
+                .iter()
+                .filter(|p| p.starts_with(before[start..].trim_start()))
+                .map(|p| Pair { display: p.clone(), replacement: p.clone() })
+                .collect();
+            return Ok((start, matches));
+        }
+        Ok((pos, Vec::new()))
+    }
+}
+
+impl Hinter for ChatHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ChatHelper {}
+
+impl Validator for ChatHelper {}
+
+impl Helper for ChatHelper {}
+
+/// Line editor for the chat prompt: arrow keys, Ctrl+R history search,
+/// Tab completion and bracketed paste
+struct ChatInput {
+    editor: Editor<ChatHelper, FileHistory>,
+    history: Option<PathBuf>,
+}
+
+impl ChatInput {
+    fn new(projects: Vec<String>) -> Result<Self> {
+        let config = rustyline::Config::builder()
+            .max_history_size(INPUT_HISTORY_SIZE)?
+            .history_ignore_dups(true)?
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor = Editor::with_config(config)?;
+        editor.set_helper(Some(ChatHelper { files: FilenameCompleter::new(), projects }));
+
+        let history = directories::ProjectDirs::from("com", "nexus", "forge")
+            .map(|p| p.data_dir().join(INPUT_HISTORY_FILE));
+        if let Some(path) = &history {
+            // Missing on first run
+            editor.load_history(path).ok();
+        }
+        Ok(Self { editor, history })
+    }
+
+    /// Add a sent message to the history and save it
+    fn remember(&mut self, message: &str) {
+        if message.is_empty() || self.editor.add_history_entry(message).is_err() {
+            return;
+        }
+        if let Some(path) = &self.history {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).ok();
+            }
+            self.editor.append_history(path).ok();
+        }
+    }
+}
+
+/// The project `/pin` and `/file` read from, switched with `/project`
+struct Workspace {
+    /// Shown in the prompt; `None` while in the directory chat started in
+    name: Option<String>,
+    root: PathBuf,
+    /// Files attached with `/file` for the next message: label and path
+    attached: Vec<(String, PathBuf)>,
+    /// Cloud exclusions of each project visited, the active one last
+    exclusions: Vec<CloudExclusions>,
+}
+
+impl Workspace {
+    fn new(config: &Config) -> Result<Self> {
+        let root = PathBuf::from(".");
+        let exclusions = vec![CloudExclusions::for_root(&config.privacy, &root)?];
+        Ok(Self { name: None, root, attached: Vec::new(), exclusions })
+    }
+
+    /// Switch to a registered root or a directory; later pins resolve there
+    /// and its own exclusions apply
+    fn switch(&mut self, config: &Config, name: &str, pins: &mut Pins) -> Result<()> {
+        let (label, root) = config.resolve_root(name)?;
+        if !self.exclusions.iter().any(|e| e.root() == root) {
+            self.exclusions.push(CloudExclusions::for_root(&config.privacy, &root)?);
+        }
+        pins.set_root(&root);
+        self.name = Some(label);
+        self.root = root;
+        Ok(())
+    }
+
+    /// Exclusions of the active project
+    fn active_exclusions(&self) -> &CloudExclusions {
+        self.exclusions
+            .iter()
+            .rev()
+            .find(|e| e.root() == self.root)
+            .unwrap_or(&self.exclusions[0])
+    }
+
+    /// Attach a file of the active project to the next message
+    fn attach(&mut self, path: &str) -> Result<&str> {
+        let inside = Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside {
+            anyhow::bail!("{} is outside the project; give a path relative to {}", path, self.root.display());
+        }
+        let full = self.root.join(path);
+        if !full.is_file() {
+            anyhow::bail!("No file {} in {}", path, self.root.display());
+        }
+        let label = match &self.name {
+            Some(name) => format!("{}:{}", name, path),
+            None => path.to_string(),
+        };
+        if !self.attached.iter().any(|(l, _)| *l == label) {
+            self.attached.push((label, full));
+        }
+        Ok(&self.attached[self.attached.len() - 1].0)
+    }
+
+    /// The message with attached files before it; attachments are sent once
+    fn take_message(&mut self, input: &str) -> String {
+        if self.attached.is_empty() {
+            return input.to_string();
+        }
+        let mut message = format!("## Attached Files\n\n{}\n", context::REPOSITORY_CONTENT_RULES);
+        for (label, path) in std::mem::take(&mut self.attached) {
+            let body = match self.active_exclusions().read_file(&path) {
+                Ok(content) => context::delimit(&label, &context::sanitize(&content)),
+                Err(e) => format!("({})", e),
+            };
+            message.push_str(&format!("\n### {}\n\n{}\n", label, body));
+        }
+        message.push_str(&format!("\n{}", input));
+        message
+    }
+}
+
+/// Results shown when a message is routed to search
+const SEARCH_LIMIT: usize = 10;
+
+/// Where a typed message goes
+enum Route {
+    /// To the AI as usual; with a path, edits to that file are applied
+    Chat(Input, Option<String>),
+    /// Already handled by another pipeline
+    Done,
+}
+
+/// Sends typed messages to the pipeline their intent calls for: searches
+/// to `search`, questions about the code to `ask`, edit requests to a
+/// patch of the file named, and commands in words to the slash command
+struct IntentRouter {
+    classifier: IntentClassifier,
+    enabled: bool,
+}
+
+impl IntentRouter {
+    fn new(config: &Config) -> Self {
+        Self { classifier: IntentClassifier::new(), enabled: config.chat.route }
+    }
+
+    async fn route(&mut self, input: Input, config: &Config, workspace: &mut Workspace) -> Route {
+        let Input::Typed(text) = &input else {
+            return Route::Chat(input, None);
+        };
+        if is_command(text, "/route") {
+            self.toggle(command_argument(text));
+            return Route::Done;
+        }
+        if !self.enabled || text.starts_with('/') {
+            return Route::Chat(input, None);
+        }
+
+        // Attached files mean the message is about them, so it stays in the chat
+        let free = workspace.attached.is_empty();
+        match self.classifier.classify(text).intent {
+            Intent::Command => match slash_command(text, config) {
+                // A misread message must not drop the conversation or switch projects
+                Some(command) if is_destructive(&command) => {
+                    print_route(Intent::Command, &command);
+                    if NexusForm::ask_confirm(&format!("Run {}?", command), false).unwrap_or(false) {
+                        Route::Chat(Input::Typed(command), None)
+                    } else {
+                        Route::Chat(input, None)
+                    }
+                }
+                Some(command) => {
+                    print_route(Intent::Command, &command);
+                    Route::Chat(Input::Typed(command), None)
+                }
+                None => Route::Chat(input, None),
+            },
+            Intent::Search if free && workspace.name.is_none() => {
+                let query = search_query(text);
+                print_route(Intent::Search, &query);
+                if let Err(e) = search::run(config.clone(), &query, SEARCH_LIMIT, None).await {
+                    print_error(&e.to_string());
+                }
+                Route::Done
+            }
+            Intent::Question if free => {
+                let project = workspace.name.as_deref().unwrap_or("the current directory");
+                print_route(Intent::Question, &format!("answered from {}", project));
+                let roots: Vec<String> = workspace.name.iter().cloned().collect();
+                if let Err(e) = ask::run(config.clone(), text, &roots, false).await {
+                    print_error(&e.to_string());
+                }
+                Route::Done
+            }
+            Intent::Edit => match mentioned_file(text, &workspace.root)
+                .filter(|path| permissions::request(Permission::Read, &format!("read {} into the conversation", path)).is_ok())
+            {
+                Some(path) => match workspace.attach(&path) {
+                    Ok(label) => {
+                        print_route(Intent::Edit, label);
+                        Route::Chat(input, Some(path))
+                    }
+                    Err(_) => Route::Chat(input, None),
+                },
+                None => Route::Chat(input, None),
+            },
+            _ => Route::Chat(input, None),
+        }
+    }
+
+    /// Handle `/route [on|off]`
+    fn toggle(&mut self, argument: &str) {
+        self.enabled = match argument {
+            "on" => true,
+            "off" => false,
+            _ => !self.enabled,
+        };
+        if self.enabled {
+            print_success("Messages are routed to search, ask, edits and commands by intent");
+        } else {
+            print_success("Every message goes to the chat");
+        }
+    }
+}
+
+/// The slash command a message in words stands for, e.g. `/export html`
+/// for "save the chat as html"
+fn slash_command(text: &str, config: &Config) -> Option<String> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || ",!?`'\"".contains(c))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let has = |name: &str| words.iter().any(|w| w.trim_end_matches('.').eq_ignore_ascii_case(name));
+    let path = words.iter().map(|w| w.trim_end_matches('.')).find(|w| w.contains('/') || w.contains('.'));
+
+    if has("unpin") {
+        return match path {
+            Some(path) => Some(format!("/unpin {}", path)),
+            None if has("all") || has("everything") => Some("/unpin all".to_string()),
+            None => None,
+        };
+    }
+    if has("pin") {
+        return path.map(|path| format!("/pin {}", path));
+    }
+    if has("pins") || has("pinned") {
+        return Some("/pins".to_string());
+    }
+    if has("export") || (has("save") && (has("chat") || has("conversation") || has("transcript"))) {
+        let format = ["html", "json"].into_iter().find(|f| has(f)).unwrap_or("markdown");
+        return Some(format!("/export {}", format));
+    }
+    if has("compact") || has("summarize") {
+        return Some("/compact".to_string());
+    }
+    if has("clear") || has("reset") {
+        return Some("/clear".to_string());
+    }
+    if has("switch") || has("project") || has("projects") {
+        let name = config.roots.keys().find(|name| has(name));
+        return Some(name.map_or("/project".to_string(), |name| format!("/project {}", name)));
+    }
+    if has("model") {
+        return Some("/model".to_string());
+    }
+    if has("help") || has("commands") {
+        return Some("/help".to_string());
+    }
+    if has("exit") || has("quit") {
+        return Some("/exit".to_string());
+    }
+    None
+}
+
+/// Slash commands that lose the conversation, the pins or the project,
+/// confirmed before a message in words runs them
+fn is_destructive(command: &str) -> bool {
+    ["/clear", "/exit", "/unpin all"].contains(&command) || command.starts_with("/project ")
+}
+
+/// Search terms of a message: its identifiers, else its words without the
+/// request around them
+fn search_query(text: &str) -> String {
+    const FILLER: &[&str] = &[
+        "find", "search", "for", "show", "me", "all", "every", "the", "a", "an", "of", "to", "in", "where", "is",
+        "are", "defined", "locate", "definition", "usages", "uses", "references", "grep", "code", "project",
+        "codebase", "that", "which", "please", "list",
+    ];
+    let identifiers: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || ",?!`'\"".contains(c))
+        .map(|w| w.trim_end_matches("()").trim_end_matches('.'))
+        .filter(|w| w.contains('_') || w.contains("::") || w.chars().skip(1).any(|c| c.is_ascii_uppercase()))
+        .collect();
+    if !identifiers.is_empty() {
+        return identifiers.join(" ");
+    }
+    text.split(|c: char| c.is_whitespace() || ",.?!`'\"".contains(c))
+        .filter(|w| !w.is_empty() && !FILLER.contains(&w.to_lowercase().as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The project file a message names: an exact relative path, or a unique
+/// file whose path ends with the name given
+fn mentioned_file(text: &str, root: &Path) -> Option<String> {
+    let names: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || ",?!`'\"()".contains(c))
+        .map(|w| w.trim_end_matches(['.', ':']).trim_start_matches("./"))
+        .filter(|w| w.contains('.') && !w.starts_with('.'))
+        .collect();
+    for name in names {
+        if root.join(name).is_file() {
+            return Some(name.to_string());
+        }
+        let suffix = format!("/{}", name);
+        let matches = index::collect_matching(root, |p| p.to_string_lossy().replace('\\', "/").ends_with(&suffix)).ok()?;
+        if let [only] = matches.as_slice() {
+            return Some(only.strip_prefix(root).unwrap_or(only).to_string_lossy().replace('\\', "/"));
+        }
+    }
+    None
+}
+
+/// Apply the edits in a response to `path` of the active project after
+/// showing them and asking. Responses without edits are left alone.
+fn apply_edits(response: &str, workspace: &Workspace, path: &str) {
+    let patch = edits::parse(response);
+    if patch.is_empty() {
+        return;
+    }
+    let full = workspace.root.join(path);
+    let patched = match fs::read_to_string(&full)
+        .with_context(|| format!("Failed to read {}", path))
+        .and_then(|content| edits::apply(&content, &patch))
+    {
+        Ok(patched) => patched,
+        Err(e) => {
+            print_error(&format!("Edits not applied: {:#}", e));
+            return;
+        }
+    };
+
+    print_patch(path, &patch);
+    if let Err(e) = permissions::request(Permission::Write, &format!("apply these edits to {}", path)) {
+        print_error(&format!("{:#}", e));
+        return;
+    }
+    let patched = match hooks::pre_apply(path, patched) {
+        Ok(patched) => patched,
+        Err(e) => {
+            print_error(&format!("{:#}", e));
+            return;
+        }
+    };
+    if let Err(e) = fs::write(&full, &patched) {
+        print_error(&format!("Failed to write {}: {}", path, e));
+        return;
+    }
+    Journal::current()
+        .record("chat", Change::FileWritten { path: full.display().to_string(), provenance: None })
+        .ok();
+    if let Ok(project) = ProjectConfig::load(&workspace.root) {
+        import_organizer::organize_file(&workspace.root, Path::new(path), &project.imports).ok();
+        formatter::format_file(&workspace.root, Path::new(path), &project.format);
+    }
+    // post_apply hooks see the file as it ended up, formatted
+    hooks::post_apply(path, &fs::read_to_string(&full).unwrap_or(patched));
+    print_success(&format!("Patched {}", path));
 }
 
 // ANSI color codes from design system
@@ -41,8 +524,16 @@ mod symbols {
     pub const SUCCESS: &str = "󰄂";
     pub const ERROR: &str = "󰅚";
     pub const DIVIDER: &str = "─";
+    pub const PIN: &str = "󰐃";
+    pub const FILE: &str = "󰈙";
+    pub const ROUTE: &str = "↳";
 }
 
+/// Instruction for `/compact`
+const COMPACT_PROMPT: &str = "Summarize our conversation so far for your own future reference: \
+the goal, decisions made, open questions, and any code or names we settled on. \
+Be complete but concise; this summary will replace the conversation history.";
+
 /// Print a horizontal divider
 fn print_divider() {
     println!(
@@ -113,6 +604,83 @@ fn print_success(message: &str) {
     );
 }
 
+/// Print where a message was routed
+fn print_route(intent: Intent, detail: &str) {
+    println!(
+        "\n{}  {} {}: {}{}{}  (/route off to chat instead){}",
+        colors::MUTED, symbols::ROUTE, intent.label(), colors::FG, detail, colors::MUTED, colors::RESET
+    );
+}
+
+/// Print the edits of a patch as removed and added lines
+fn print_patch(path: &str, patch: &[edits::Edit]) {
+    println!();
+    println!(
+        "{}{}  {} {}{}",
+        colors::PRIMARY, colors::BOLD, symbols::FILE, path, colors::RESET
+    );
+    for edit in patch {
+        println!("{}  ╭{}─{}", colors::MUTED, symbols::DIVIDER.repeat(50), colors::RESET);
+        for line in edit.search.lines() {
+            println!("{}  │ {}- {}{}", colors::MUTED, colors::ERROR, line, colors::RESET);
+        }
+        for line in edit.replace.lines() {
+            println!("{}  │ {}+ {}{}", colors::MUTED, colors::SUCCESS, line, colors::RESET);
+        }
+        println!("{}  ╰{}─{}", colors::MUTED, symbols::DIVIDER.repeat(50), colors::RESET);
+    }
+}
+
+/// Print pinned items
+fn print_pins(pins: &Pins) {
+    if pins.is_empty() {
+        println!(
+            "\n{}  Nothing pinned. Use /pin <file|symbol|note: text>{}",
+            colors::MUTED, colors::RESET
+        );
+        return;
+    }
+    println!();
+    for pin in pins.iter() {
+        println!(
+            "{}  {} #{} {}{}{}",
+            colors::MUTED, symbols::PIN, pin.id, colors::FG, pin.label(), colors::RESET
+        );
+    }
+}
+
+/// Print the registered roots, marking the active project
+fn print_projects(config: &Config, workspace: &Workspace) {
+    println!();
+    let current = workspace.name.as_deref().unwrap_or("(current directory)");
+    println!(
+        "{}  Project: {}{}{} ({}){}",
+        colors::MUTED, colors::FG, current, colors::MUTED, workspace.root.display(), colors::RESET
+    );
+    if config.roots.is_empty() {
+        println!(
+            "{}  No registered roots; add them under [roots] in the config file, or /project <dir>{}",
+            colors::MUTED, colors::RESET
+        );
+        return;
+    }
+    for (name, path) in &config.roots {
+        let marker = if workspace.name.as_deref() == Some(name.as_str()) { symbols::SUCCESS } else { " " };
+        println!(
+            "{}  {} {}{}{}  {}{}",
+            colors::MUTED, marker, colors::FG, name, colors::MUTED, path.display(), colors::RESET
+        );
+    }
+}
+
+/// Print the summary that replaced the history
+fn print_compacted(summary: &str, pins: &Pins) {
+    print_ai_message(summary);
+    let kept = pins.iter().count();
+    let note = if kept > 0 { format!(" ({} pins kept)", kept) } else { String::new() };
+    print_success(&format!("History replaced with this summary{}", note));
+}
+
 /// Print help information
 fn print_help() {
     println!();
@@ -136,6 +704,46 @@ fn print_help() {
         "{}  /model{}   - Show current AI model",
         colors::FG, colors::MUTED
     );
+    println!(
+        "{}  /export{}  - Save the transcript: /export markdown|html|json [file]",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /pin{}     - Keep a file, symbol or note in context: /pin src/models.rs",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /pins{}    - List pinned items",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /unpin{}   - Remove a pin by number or name, or /unpin all",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /file{}    - Send a file with the next message: /file src/api.rs",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /project{} - Switch the project /pin and /file read from: /project api",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /compact{} - Replace the history with a summary (pins are kept)",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /paste{}   - Compose a message with blank lines; end it with /end",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /editor{}  - Write the message in $EDITOR",
+        colors::FG, colors::MUTED
+    );
+    println!(
+        "{}  /route{}   - Turn routing of messages to search, ask, edits and commands on or off",
+        colors::FG, colors::MUTED
+    );
     println!();
     println!(
         "{}  Tips:{}",
@@ -150,62 +758,171 @@ fn print_help() {
         colors::MUTED
     );
     println!(
-        "{}  • Paste code directly - I'll understand it",
+        "{}  • Paste code directly - blank lines in a paste don't send it",
+        colors::MUTED
+    );
+    println!(
+        "{}  • Up arrow recalls earlier messages, Ctrl+R searches them, Tab completes commands",
         colors::MUTED
     );
     println!();
 }
 
-/// Read multi-line input from user
-fn read_input() -> Option<String> {
-    print!(
-        "\n{}  {} {}",
-        colors::PRIMARY, symbols::USER_ICON, colors::RESET
-    );
-    io::stdout().flush().ok();
-
-    let mut lines = Vec::new();
-    let mut empty_count = 0;
+/// Read multi-line input from user. A blank line sends; a paste arrives
+/// whole, blank lines included. `/paste` and `/editor` compose longer
+/// messages.
+fn read_input(reader: &mut ChatInput, project: Option<&str>) -> Option<Input> {
+    println!();
+    let mut lines: Vec<String> = Vec::new();
+    let mut pasted = false;
 
     loop {
-        let mut line = String::new();
-        match io::stdin().read_line(&mut line) {
-            Ok(0) => return None, // EOF
-            Ok(_) => {
-                let trimmed = line.trim_end();
-
-                if trimmed.is_empty() {
-                    empty_count += 1;
-                    if empty_count >= 1 && !lines.is_empty() {
-                        // Double enter = send
-                        break;
-                    }
-                } else {
-                    empty_count = 0;
-                    lines.push(trimmed.to_string());
-                    // Continue prompt
-                    print!(
-                        "{}  {} {}",
-                        colors::MUTED, ".", colors::RESET
-                    );
-                    io::stdout().flush().ok();
-                }
+        let marker = if lines.is_empty() {
+            match project {
+                Some(name) => format!(
+                    "{}  {} {}{} {}",
+                    colors::MUTED, name, colors::PRIMARY, symbols::USER_ICON, colors::RESET
+                ),
+                None => format!("{}  {} {}", colors::PRIMARY, symbols::USER_ICON, colors::RESET),
+            }
+        } else {
+            // Continue prompt
+            format!("{}  . {}", colors::MUTED, colors::RESET)
+        };
+        let line = match reader.editor.readline(&marker) {
+            Ok(line) => line,
+            // Ctrl+C drops the message being typed
+            Err(ReadlineError::Interrupted) => {
+                lines.clear();
+                pasted = false;
+                continue;
             }
-            Err(_) => return None,
+            Err(_) => return None, // EOF
+        };
+        let trimmed = line.trim_end();
+
+        if trimmed.trim().is_empty() {
+            if !lines.is_empty() {
+                // Double enter = send
+                break;
+            }
+        } else if line.contains('\n') {
+            pasted = true;
+            lines.push(trimmed.to_string());
+        } else if lines.is_empty() && is_compose_command(trimmed) {
+            reader.remember(trimmed);
+            if let Some(text) = compose(trimmed, reader) {
+                return Some(Input::Composed(text));
+            }
+            println!();
+        } else {
+            lines.push(trimmed.to_string());
         }
     }
 
     let input = lines.join("\n").trim().to_string();
-    if input.is_empty() {
-        None
+    reader.remember(&input);
+    if pasted {
+        Some(Input::Composed(input))
     } else {
-        Some(input)
+        Some(Input::Typed(input))
+    }
+}
+
+/// Whether a line starts `/paste` or `/editor`
+fn is_compose_command(line: &str) -> bool {
+    ["/paste", "/editor", "/e"].iter().any(|c| is_command(line, c))
+}
+
+/// Compose a message with `/paste [SENTINEL]` or `/editor`. `None` when
+/// the result is empty or the editor failed.
+fn compose(line: &str, reader: &mut ChatInput) -> Option<String> {
+    let text = if is_command(line, "/paste") {
+        let sentinel = line.split_whitespace().nth(1).unwrap_or(PASTE_SENTINEL);
+        read_until(sentinel, reader)
+    } else {
+        match compose_in_editor() {
+            Ok(text) => text,
+            Err(e) => {
+                print_error(&e.to_string());
+                return None;
+            }
+        }
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        println!("{}  Empty message, nothing sent{}", colors::MUTED, colors::RESET);
+        return None;
+    }
+    Some(text.to_string())
+}
+
+/// Read lines verbatim, blank ones included, until `sentinel` on its own
+/// line or end of input. Ctrl+C discards the message.
+fn read_until(sentinel: &str, reader: &mut ChatInput) -> String {
+    println!(
+        "{}  Paste or type your message, then {} on its own line to send{}",
+        colors::MUTED, sentinel, colors::RESET
+    );
+
+    let mut lines: Vec<String> = Vec::new();
+    loop {
+        let chunk = match reader.editor.readline("") {
+            Ok(chunk) => chunk,
+            Err(ReadlineError::Interrupted) => return String::new(),
+            Err(_) => break,
+        };
+        // A paste is one chunk of several lines
+        for line in chunk.split('\n') {
+            if line.trim() == sentinel {
+                return lines.join("\n");
+            }
+            lines.push(line.trim_end_matches('\r').to_string());
+        }
     }
+    lines.join("\n")
+}
+
+/// Open `$VISUAL` / `$EDITOR` on a scratch file and return what was saved
+fn compose_in_editor() -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+    // `code --wait` and similar carry their own arguments
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    permissions::request(Permission::Run, &format!("open `{}` to write a message", editor))?;
+
+    // Removed when dropped; the suffix gets editors into Markdown mode
+    let scratch = tempfile::Builder::new()
+        .prefix("nexus-message-")
+        .suffix(".md")
+        .tempfile()
+        .context("Failed to create a file for the message")?;
+
+    println!(
+        "{}  Waiting for {} to close...{}",
+        colors::MUTED, program, colors::RESET
+    );
+    let status = Command::new(program)
+        .args(parts)
+        .arg(scratch.path())
+        .status()
+        .with_context(|| format!("Failed to start editor `{}` (set $EDITOR)", editor));
+    let text = fs::read_to_string(scratch.path()).unwrap_or_default();
+
+    if !status?.success() {
+        anyhow::bail!("Editor exited with an error; message discarded");
+    }
+    Ok(text)
 }
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
@@ -213,20 +930,29 @@ fn determine_ai_mode() -> AiMode {
 }
 
 /// Main chat loop
-pub async fn run(_config: Config, initial_prompt: Option<String>) -> Result<()> {
+pub async fn run(config: Config, initial_prompt: Option<String>) -> Result<()> {
     let ai_mode = determine_ai_mode();
+    let mut reader = ChatInput::new(config.roots.keys().cloned().collect())?;
 
     match ai_mode {
-        AiMode::Claude => run_with_claude(initial_prompt).await,
-        AiMode::Proxy => run_with_proxy(initial_prompt).await,
+        AiMode::Claude => run_with_claude(&config, initial_prompt, &mut reader).await,
+        AiMode::Proxy | AiMode::Local => run_with_context(&config, ai_mode, initial_prompt, &mut reader).await,
     }
 }
 
 /// Run chat with Claude (requires API key)
-async fn run_with_claude(initial_prompt: Option<String>) -> Result<()> {
+async fn run_with_claude(
+    config: &Config,
+    initial_prompt: Option<String>,
+    reader: &mut ChatInput,
+) -> Result<()> {
     let client = ClaudeClient::from_env()?;
-    let mut conversation = Conversation::new(client)
+    let mut conversation = Conversation::new(client.clone())
         .with_system(prompts::CODING_ASSISTANT);
+    let mut transcript = Transcript::new("Claude");
+    let mut pins = Pins::new(Path::new("."));
+    let mut workspace = Workspace::new(config)?;
+    let mut router = IntentRouter::new(config);
 
     print_banner_with_provider("Claude");
 
@@ -235,10 +961,12 @@ async fn run_with_claude(initial_prompt: Option<String>) -> Result<()> {
         print_user_message(&prompt);
         print_thinking();
 
+        conversation.set_pinned(pins.render(&workspace.exclusions));
         match conversation.send(&prompt).await {
             Ok(response) => {
                 clear_thinking();
                 print_ai_message(&response);
+                record(&mut transcript, &prompt, &response);
             }
             Err(e) => {
                 clear_thinking();
@@ -249,7 +977,7 @@ async fn run_with_claude(initial_prompt: Option<String>) -> Result<()> {
 
     // Main chat loop
     loop {
-        let input = match read_input() {
+        let input = match read_input(reader, workspace.name.as_deref()) {
             Some(i) => i,
             None => {
                 println!();
@@ -257,22 +985,54 @@ async fn run_with_claude(initial_prompt: Option<String>) -> Result<()> {
             }
         };
 
+        let (input, edit) = match router.route(input, config, &mut workspace).await {
+            Route::Chat(input, edit) => (input, edit),
+            Route::Done => continue,
+        };
+
+        if input.command().is_some_and(|c| is_command(c, "/compact")) {
+            print_thinking();
+            conversation.set_pinned(pins.render(&workspace.exclusions));
+            let result = conversation.compact(COMPACT_PROMPT).await;
+            clear_thinking();
+            match result {
+                Ok(summary) => print_compacted(&summary, &pins),
+                Err(e) => print_error(&format!("AI error: {}", e)),
+            }
+            continue;
+        }
+
         // Handle commands
-        if let Some(should_break) = handle_command(&input, Some(&mut conversation), AiMode::Claude) {
+        if let Some(should_break) = input
+            .command()
+            .and_then(|c| {
+                handle_command(c, Some(&mut conversation), AiMode::Claude, &transcript, &mut pins, config, &mut workspace)
+            })
+        {
             if should_break {
                 break;
             }
             continue;
         }
+        let input = input.into_text();
+        let mut message = workspace.take_message(&input);
+        if let Some(path) = &edit {
+            message = format!("{}\n\nChange only {}.\n\n{}", message, path, edits::EDIT_FORMAT);
+        }
 
         // Send message to AI
         print_user_message(&input);
         print_thinking();
 
-        match conversation.send(&input).await {
+        conversation.set_pinned(pins.render(&workspace.exclusions));
+        match conversation.send(&message).await {
             Ok(response) => {
                 clear_thinking();
                 print_ai_message(&response);
+                record(&mut transcript, &input, &response);
+                if let Some(path) = &edit {
+                    apply_edits(&response, &workspace, path);
+                }
             }
             Err(e) => {
                 clear_thinking();
@@ -281,27 +1041,57 @@ async fn run_with_claude(initial_prompt: Option<String>) -> Result<()> {
         }
     }
 
+    let session = transcript.render(ExportFormat::Markdown).unwrap_or_default();
+    memory::remember(&session, |system, message| async move {
+        client.send_with_system(&message, &system).await
+    })
+    .await;
     println!();
     Ok(())
 }
 
-/// Run chat with NEXUS Proxy (free tier, Gemini-powered)
-async fn run_with_proxy(initial_prompt: Option<String>) -> Result<()> {
-    let proxy = ProxyClient::from_env();
+/// Client of a chat that sends the conversation so far as context
+enum ContextClient {
+    /// NEXUS proxy (free tier, Gemini-powered)
+    Proxy(ProxyClient),
+    /// Local model
+    Local(OllamaClient),
+}
+
+impl ContextClient {
+    async fn chat(&self, message: &str, context: Option<&str>) -> Result<String> {
+        match self {
+            ContextClient::Proxy(proxy) => proxy.chat(message, context).await,
+            ContextClient::Local(local) => local.ask(message, context).await,
+        }
+    }
+}
+
+/// Run chat with NEXUS Proxy (free tier, Gemini-powered) or the local model
+async fn run_with_context(
+    config: &Config,
+    mode: AiMode,
+    initial_prompt: Option<String>,
+    reader: &mut ChatInput,
+) -> Result<()> {
+    let (proxy, provider) = match mode {
+        AiMode::Local => (ContextClient::Local(AiRouter::local_client()), "Local model"),
+        _ => (ContextClient::Proxy(ProxyClient::from_env()), "NEXUS AI (Free)"),
+    };
     let mut history: Vec<String> = Vec::new();
+    let mut transcript = Transcript::new(provider);
+    let mut pins = Pins::new(Path::new("."));
+    let mut workspace = Workspace::new(config)?;
+    let mut router = IntentRouter::new(config);
 
-    print_banner_with_provider("NEXUS AI (Free)");
+    print_banner_with_provider(provider);
 
     // Handle initial prompt
     if let Some(prompt) = initial_prompt {
         print_user_message(&prompt);
         print_thinking();
 
-        let context = if history.is_empty() {
-            None
-        } else {
-            Some(history.join("\n\n"))
-        };
+        let context = proxy_context(&history, &pins, &workspace);
 
         match proxy.chat(&prompt, context.as_deref()).await {
             Ok(response) => {
@@ -309,6 +1099,7 @@ async fn run_with_proxy(initial_prompt: Option<String>) -> Result<()> {
                 print_ai_message(&response);
                 history.push(format!("User: {}", prompt));
                 history.push(format!("Assistant: {}", response));
+                record(&mut transcript, &prompt, &response);
             }
             Err(e) => {
                 clear_thinking();
@@ -319,7 +1110,7 @@ async fn run_with_proxy(initial_prompt: Option<String>) -> Result<()> {
 
     // Main chat loop
     loop {
-        let input = match read_input() {
+        let input = match read_input(reader, workspace.name.as_deref()) {
             Some(i) => i,
             None => {
                 println!();
@@ -327,30 +1118,61 @@ async fn run_with_proxy(initial_prompt: Option<String>) -> Result<()> {
             }
         };
 
+        let (input, edit) = match router.route(input, config, &mut workspace).await {
+            Route::Chat(input, edit) => (input, edit),
+            Route::Done => continue,
+        };
+
+        if input.command().is_some_and(|c| is_command(c, "/compact")) {
+            if history.is_empty() {
+                print_success("Nothing to compact yet");
+                continue;
+            }
+            print_thinking();
+            let result = proxy.chat(COMPACT_PROMPT, Some(&history.join("\n\n"))).await;
+            clear_thinking();
+            match result {
+                Ok(summary) => {
+                    history = vec![format!("Summary of the conversation so far: {}", summary)];
+                    print_compacted(&summary, &pins);
+                }
+                Err(e) => print_error(&format!("AI error: {}", e)),
+            }
+            continue;
+        }
+
         // Handle commands
-        if let Some(should_break) = handle_command_proxy(&input, &mut history) {
+        if let Some(should_break) = input
+            .command()
+            .and_then(|c| handle_command_proxy(c, mode, &mut history, &transcript, &mut pins, config, &mut workspace))
+        {
             if should_break {
                 break;
             }
             continue;
         }
+        let input = input.into_text();
+        let mut message = workspace.take_message(&input);
+        if let Some(path) = &edit {
+            message = format!("{}\n\nChange only {}.\n\n{}", message, path, edits::EDIT_FORMAT);
+        }
 
         // Send message to AI
         print_user_message(&input);
         print_thinking();
 
-        let context = if history.is_empty() {
-            None
-        } else {
-            Some(history.join("\n\n"))
-        };
+        let context = proxy_context(&history, &pins, &workspace);
 
-        match proxy.chat(&input, context.as_deref()).await {
+        match proxy.chat(&message, context.as_deref()).await {
             Ok(response) => {
                 clear_thinking();
                 print_ai_message(&response);
-                history.push(format!("User: {}", input));
+                history.push(format!("User: {}", message));
                 history.push(format!("Assistant: {}", response));
+                record(&mut transcript, &input, &response);
+                if let Some(path) = &edit {
+                    apply_edits(&response, &workspace, path);
+                }
             }
             Err(e) => {
                 clear_thinking();
@@ -359,17 +1181,145 @@ async fn run_with_proxy(initial_prompt: Option<String>) -> Result<()> {
         }
     }
 
+    let session = transcript.render(ExportFormat::Markdown).unwrap_or_default();
+    memory::remember(&session, |system, message| async move {
+        proxy.chat(&system, Some(&message)).await
+    })
+    .await;
     println!();
     Ok(())
 }
 
+/// Pinned context followed by the conversation so far
+fn proxy_context(history: &[String], pins: &Pins, workspace: &Workspace) -> Option<String> {
+    let parts: Vec<String> = pins
+        .render(&workspace.exclusions)
+        .into_iter()
+        .chain((!history.is_empty()).then(|| history.join("\n\n")))
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// Whether the input is the slash command `name`, with or without arguments
+fn is_command(input: &str, name: &str) -> bool {
+    input.split_whitespace().next().is_some_and(|w| w.eq_ignore_ascii_case(name))
+}
+
+/// Text after the command word
+fn command_argument(input: &str) -> &str {
+    input.trim().split_once(char::is_whitespace).map(|(_, rest)| rest.trim()).unwrap_or("")
+}
+
+/// Handle `/pin`, `/pins` and `/unpin`
+fn handle_pin_command(command: &str, argument: &str, pins: &mut Pins) {
+    match command {
+        "/pin" if argument.is_empty() => {
+            print_error("Usage: /pin <file|symbol|note: text>");
+        }
+        "/pin" => match pins.add(argument) {
+            Ok(pin) => print_success(&format!("Pinned #{} {}", pin.id, pin.label())),
+            Err(e) => print_error(&e.to_string()),
+        },
+        "/unpin" if argument.eq_ignore_ascii_case("all") => {
+            pins.clear();
+            print_success("All pins removed");
+        }
+        "/unpin" => match pins.remove(argument) {
+            Some(pin) => print_success(&format!("Unpinned #{} {}", pin.id, pin.label())),
+            None => print_error(&format!("No pin `{}` (see /pins)", argument)),
+        },
+        _ => print_pins(pins),
+    }
+}
+
+/// Handle `/project [name]`: list the registered roots, or switch to one
+fn handle_project_command(argument: &str, config: &Config, workspace: &mut Workspace, pins: &mut Pins) {
+    if argument.is_empty() {
+        print_projects(config, workspace);
+        return;
+    }
+    match workspace.switch(config, argument, pins) {
+        Ok(()) => print_success(&format!(
+            "Now in {} ({}); /pin and /file read from here",
+            workspace.name.as_deref().unwrap_or(argument),
+            workspace.root.display()
+        )),
+        Err(e) => print_error(&e.to_string()),
+    }
+}
+
+/// Handle `/file <path>`
+fn handle_file_command(argument: &str, workspace: &mut Workspace) {
+    if argument.is_empty() {
+        print_error("Usage: /file <path>");
+        return;
+    }
+    match workspace.attach(argument) {
+        Ok(label) => print_success(&format!("{} will be sent with your next message", label)),
+        Err(e) => print_error(&e.to_string()),
+    }
+}
+
+/// Add an exchange to the transcript and save it, so it survives a crash
+fn record(transcript: &mut Transcript, prompt: &str, response: &str) {
+    transcript.push(Speaker::User, prompt);
+    transcript.push(Speaker::Assistant, response);
+    transcript.save(Path::new(".")).ok();
+}
+
+/// Handle `/export [format] [file]`
+fn export_transcript(transcript: &Transcript, args: &[&str]) {
+    if transcript.is_empty() {
+        print_error("Nothing to export yet");
+        return;
+    }
+
+    let format = match args.first() {
+        Some(f) => match ExportFormat::parse(f) {
+            Some(format) => format,
+            None => {
+                print_error(&format!("Unknown format `{}` (expected markdown, html or json)", f));
+                return;
+            }
+        },
+        None => ExportFormat::Markdown,
+    };
+    let path = args.get(1).map(|p| p.to_string()).unwrap_or_else(|| transcript.file_name(format));
+    if Path::new(&path).exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", path), false).unwrap_or(false) {
+        print_error("Export cancelled");
+        return;
+    }
+
+    let written = transcript
+        .render(format)
+        .and_then(|content| permissions::write_file(&path, content));
+    match written {
+        Ok(()) => {
+            Journal::current()
+                .record("chat export", Change::FileWritten { path: path.clone(), provenance: None })
+                .ok();
+            print_success(&format!("Transcript saved to {}", path));
+        }
+        Err(e) => print_error(&e.to_string()),
+    }
+}
+
 /// Handle slash commands for Claude mode
-fn handle_command(input: &str, conversation: Option<&mut Conversation>, mode: AiMode) -> Option<bool> {
+fn handle_command(
+    input: &str,
+    conversation: Option<&mut Conversation>,
+    mode: AiMode,
+    transcript: &Transcript,
+    pins: &mut Pins,
+    config: &Config,
+    workspace: &mut Workspace,
+) -> Option<bool> {
     if !input.starts_with('/') {
         return None;
     }
 
-    match input.to_lowercase().as_str() {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words[0].to_lowercase().as_str() {
         "/exit" | "/quit" | "/q" => {
             print_success("Goodbye! Happy coding!");
             Some(true)
@@ -389,6 +1339,7 @@ fn handle_command(input: &str, conversation: Option<&mut Conversation>, mode: Ai
             let model_name = match mode {
                 AiMode::Claude => "Claude (claude-sonnet-4-20250514)",
                 AiMode::Proxy => "NEXUS AI Free (Gemini 2.0 Flash)",
+                AiMode::Local => "Local model",
             };
             println!(
                 "\n{}  Current model: {}{}",
@@ -396,6 +1347,22 @@ fn handle_command(input: &str, conversation: Option<&mut Conversation>, mode: Ai
             );
             Some(false)
         }
+        "/export" => {
+            export_transcript(transcript, &words[1..]);
+            Some(false)
+        }
+        command @ ("/pin" | "/pins" | "/unpin") => {
+            handle_pin_command(command, command_argument(input), pins);
+            Some(false)
+        }
+        "/project" => {
+            handle_project_command(command_argument(input), config, workspace, pins);
+            Some(false)
+        }
+        "/file" => {
+            handle_file_command(command_argument(input), workspace);
+            Some(false)
+        }
         _ => {
             print_error(&format!("Unknown command: {}", input));
             println!("{}  Type /help for available commands{}", colors::MUTED, colors::RESET);
@@ -404,13 +1371,22 @@ fn handle_command(input: &str, conversation: Option<&mut Conversation>, mode: Ai
     }
 }
 
-/// Handle slash commands for Proxy mode
-fn handle_command_proxy(input: &str, history: &mut Vec<String>) -> Option<bool> {
+/// Handle slash commands for Proxy and Local mode
+fn handle_command_proxy(
+    input: &str,
+    mode: AiMode,
+    history: &mut Vec<String>,
+    transcript: &Transcript,
+    pins: &mut Pins,
+    config: &Config,
+    workspace: &mut Workspace,
+) -> Option<bool> {
     if !input.starts_with('/') {
         return None;
     }
 
-    match input.to_lowercase().as_str() {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    match words[0].to_lowercase().as_str() {
         "/exit" | "/quit" | "/q" => {
             print_success("Goodbye! Happy coding!");
             Some(true)
@@ -425,12 +1401,32 @@ fn handle_command_proxy(input: &str, history: &mut Vec<String>) -> Option<bool>
             Some(false)
         }
         "/model" | "/m" => {
+            let model_name = match mode {
+                AiMode::Local => format!("Local model ({})", AiRouter::local_client().model()),
+                _ => "NEXUS AI Free (Gemini 2.0 Flash)".to_string(),
+            };
             println!(
-                "\n{}  Current model: NEXUS AI Free (Gemini 2.0 Flash){}",
-                colors::MUTED, colors::RESET
+                "\n{}  Current model: {}{}",
+                colors::MUTED, model_name, colors::RESET
             );
             Some(false)
         }
+        "/export" => {
+            export_transcript(transcript, &words[1..]);
+            Some(false)
+        }
+        command @ ("/pin" | "/pins" | "/unpin") => {
+            handle_pin_command(command, command_argument(input), pins);
+            Some(false)
+        }
+        "/project" => {
+            handle_project_command(command_argument(input), config, workspace, pins);
+            Some(false)
+        }
+        "/file" => {
+            handle_file_command(command_argument(input), workspace);
+            Some(false)
+        }
         _ => {
             print_error(&format!("Unknown command: {}", input));
             println!("{}  Type /help for available commands{}", colors::MUTED, colors::RESET);
@@ -484,7 +1480,7 @@ fn print_banner_with_provider(provider: &str) {
     );
     println!();
     println!(
-        "{}  Commands: /help, /clear, /exit{}",
+        "{}  Commands: /help, /pin, /compact, /export, /exit{}",
         colors::MUTED, colors::RESET
     );
     println!(
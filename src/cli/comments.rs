@@ -0,0 +1,335 @@
+//! Comments command - find comments that no longer match their code
+//!
+//! Pairs doc comments and docstrings with the symbols they describe and has
+//! the AI flag the ones that contradict the implementation.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::comments::{self, Confidence, DocComment, Finding};
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::core::parser::CodeParser;
+use crate::index;
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const COMMENT: &str = "󰅺";
+    pub const AI_ICON: &str = "󰌤";
+    pub const FILE: &str = "󰈙";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for auditing comments
+const AUDIT_PROMPT: &str = r#"You are NEXUS AI, a meticulous code reviewer.
+
+You are given numbered pairs of a doc comment and the code it documents.
+Find comments that contradict the code or no longer describe it: wrong
+parameters or return values, behaviour the code does not have, missing
+important behaviour, outdated names, wrong units or defaults.
+
+Do not report style, grammar or comments that are merely brief.
+
+For each problem give:
+- "id": the pair number
+- "issue": one sentence saying what the comment gets wrong
+- "suggestion": a corrected comment, or "" if unsure
+- "confidence": "high" when the code clearly contradicts the comment,
+  "medium" when it likely does, "low" when it might
+
+Respond with only a JSON array, [] if every comment is accurate:
+[{"id": 1, "issue": "...", "suggestion": "...", "confidence": "high"}]"#;
+
+/// Pairs sent per request
+const BATCH_SIZE: usize = 12;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+/// Audit the comments of a file or directory
+pub async fn audit(
+    config: Config,
+    path: &str,
+    min_confidence: &str,
+    limit: usize,
+    output: Option<&str>,
+) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    print_header(path, provider_name);
+
+    let Some(min_confidence) = Confidence::parse(min_confidence) else {
+        print_error(&format!("Unknown confidence `{}` (expected low, medium or high)", min_confidence));
+        return Ok(());
+    };
+
+    let root = Path::new(path);
+    let files: Vec<PathBuf> = if root.is_file() {
+        vec![root.to_path_buf()]
+    } else if root.is_dir() {
+        index::collect_files(root, false)?
+    } else {
+        print_error(&format!("Path not found: {}", path));
+        return Ok(());
+    };
+
+    // Excluded files are never sent, so their comments are not audited
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let (allowed, excluded): (Vec<PathBuf>, Vec<PathBuf>) =
+        files.into_iter().partition(|f| !exclusions.is_excluded(f));
+
+    let mut parser = CodeParser::new().context("Failed to initialize code parser")?;
+    let mut pairs: Vec<DocComment> = allowed
+        .iter()
+        .filter_map(|f| parser.parse_file(f).ok())
+        .flat_map(|f| comments::pair(&f))
+        .collect();
+
+    let total = pairs.len();
+    pairs.truncate(limit);
+    print_stats(total, pairs.len(), excluded.len());
+
+    if pairs.is_empty() {
+        print_warning("No documented symbols found");
+        return Ok(());
+    }
+
+    let mut findings: Vec<Finding> = Vec::new();
+    let batches = pairs.chunks(BATCH_SIZE).count();
+
+    for (n, batch) in pairs.chunks(BATCH_SIZE).enumerate() {
+        let listing: Vec<String> = batch
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let source = format!("{} · {}", p.path.display(), p.symbol);
+                format!(
+                    "### Pair {}\n\nComment:\n{}\n\nCode:\n{}",
+                    i + 1,
+                    context::delimit(&source, &context::sanitize(&p.comment)),
+                    context::delimit(&source, &context::sanitize(&p.code))
+                )
+            })
+            .collect();
+
+        let prompt = format!(
+            "{}\n\n{}\n\n## Task\n\nReport comments that no longer match their code.",
+            context::REPOSITORY_CONTENT_RULES,
+            listing.join("\n\n")
+        );
+
+        print_thinking(provider_name, n + 1, batches);
+
+        let response = match ai_mode {
+            AiMode::Claude => {
+                let client = ClaudeClient::from_env()?;
+                let mut conversation = Conversation::new(client)
+                    .with_system(AUDIT_PROMPT);
+
+                conversation.send(&prompt).await?
+            }
+            AiMode::Proxy => {
+                let proxy = ProxyClient::from_env();
+                let prompt_with_system = format!("{}\n\n{}", AUDIT_PROMPT, prompt);
+                proxy.chat(&prompt_with_system, None).await?
+            }
+            AiMode::Local => {
+                AiRouter::local_client().with_system(AUDIT_PROMPT).chat(&prompt).await?
+            }
+        };
+
+        clear_line();
+        findings.extend(comments::parse_findings(&response, batch));
+    }
+
+    findings.retain(|f| f.confidence >= min_confidence);
+    findings.sort_by(|a, b| b.confidence.cmp(&a.confidence).then(a.path.cmp(&b.path)).then(a.line.cmp(&b.line)));
+
+    if findings.is_empty() {
+        print_success(&format!("All {} comments match their code", pairs.len()));
+    } else {
+        print_findings(&findings);
+    }
+
+    if let Some(output) = output {
+        let content = if output.ends_with(".json") {
+            serde_json::to_string_pretty(&findings)?
+        } else {
+            render_markdown(&findings)
+        };
+        permissions::write_file(output, content)?;
+        Journal::current()
+            .record("comments audit", Change::FileWritten { path: output.to_string(), provenance: None })
+            .ok();
+        print_success(&format!("Findings saved to {}", output));
+    }
+
+    Ok(())
+}
+
+/// Findings as a Markdown report
+fn render_markdown(findings: &[Finding]) -> String {
+    let mut out = String::from("# Comment Audit\n");
+    for f in findings {
+        out.push_str(&format!(
+            "\n## `{}` — {}:{} ({} confidence)\n\n> {}\n\n{}\n",
+            f.symbol,
+            f.path.display(),
+            f.line,
+            f.confidence.label(),
+            f.comment.replace('\n', "\n> "),
+            f.issue
+        ));
+        if let Some(suggestion) = &f.suggestion {
+            out.push_str(&format!("\nSuggested: {}\n", suggestion));
+        }
+    }
+    out
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(path: &str, provider: &str) {
+    println!();
+    println!(
+        "{}{}  {} Comment Audit{}",
+        colors::PRIMARY, colors::BOLD, symbols::COMMENT, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, path, colors::RESET
+    );
+    println!(
+        "{}  │ {}via {}{}",
+        colors::MUTED, colors::MUTED, provider, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_stats(total: usize, audited: usize, excluded: usize) {
+    println!(
+        "{}  {} documented symbols, auditing {}{}",
+        colors::MUTED, total, audited, colors::RESET
+    );
+    if excluded > 0 {
+        println!(
+            "{}  {} files skipped by privacy.cloud_exclude{}",
+            colors::MUTED, excluded, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_thinking(provider: &str, batch: usize, batches: usize) {
+    print!(
+        "\r{}  {} {} is reading comments ({}/{}) {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        batch,
+        batches,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_findings(findings: &[Finding]) {
+    for f in findings {
+        let color = match f.confidence {
+            Confidence::High => colors::ERROR,
+            Confidence::Medium => colors::WARNING,
+            Confidence::Low => colors::MUTED,
+        };
+        println!(
+            "{}  {} {}{}{} {}:{} {}[{}]{}",
+            colors::MUTED, symbols::FILE, colors::FG, f.symbol, colors::MUTED,
+            f.path.display(), f.line, color, f.confidence.label(), colors::RESET
+        );
+        println!("{}    {}{}", colors::FG, f.issue, colors::RESET);
+        if let Some(suggestion) = &f.suggestion {
+            println!("{}    → {}{}", colors::SUCCESS, suggestion, colors::RESET);
+        }
+        println!();
+    }
+    println!(
+        "{}  {} comments out of date{}",
+        colors::MUTED, findings.len(), colors::RESET
+    );
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -6,10 +6,15 @@
 
 use anyhow::{Context, Result};
 use std::io::{self, Write};
+use std::path::Path;
 use std::process::Command;
 
+use crate::ai::context::CloudExclusions;
+use crate::ai::router::AiRouter;
 use crate::ai::ProxyClient;
 use crate::config::Config;
+use crate::core::git;
+use crate::core::permissions::{self, Permission};
 
 // ANSI color codes
 mod colors {
@@ -64,7 +69,7 @@ Based on the git diff provided, generate a semantic commit message following the
 ## Output
 Provide ONLY the commit message, no explanations or markdown formatting."#;
 
-pub async fn run(_config: Config, execute: bool) -> Result<()> {
+pub async fn run(config: Config, execute: bool) -> Result<()> {
     print_header();
 
     // Check if we're in a git repository
@@ -88,15 +93,20 @@ pub async fn run(_config: Config, execute: bool) -> Result<()> {
     // Generate commit message
     print_thinking();
 
-    let proxy = ProxyClient::from_env();
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let shared = exclusions.redact_diff(&diff, &git::repo_root(Path::new("."))?);
     let prompt = format!(
         "{}\n\n## Git Diff\n\n```diff\n{}\n```\n\n## Changed Files\n{}\n\nGenerate a commit message:",
         COMMIT_PROMPT,
-        truncate_diff(&diff, 4000),
+        truncate_diff(&shared, 4000),
         files.join("\n")
     );
 
-    let response = proxy.chat(&prompt, None).await?;
+    let response = if AiRouter::local_default() {
+        AiRouter::local_client().chat(&prompt).await?
+    } else {
+        ProxyClient::from_env().chat(&prompt, None).await?
+    };
     clear_line();
 
     let commit_msg = response.trim();
@@ -104,6 +114,7 @@ pub async fn run(_config: Config, execute: bool) -> Result<()> {
 
     if execute {
         // Execute the commit
+        permissions::request(Permission::Run, "run `git commit` with this message")?;
         print_committing();
         execute_commit(commit_msg)?;
         print_success();
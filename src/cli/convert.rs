@@ -10,7 +10,14 @@ use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
-use crate::config::Config;
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
+use crate::config::{Config, ProjectConfig};
+use crate::core::formatter::{self, FormatOutcome};
+use crate::core::permissions;
+use crate::core::hooks;
+use crate::core::import_organizer::{self, ImportChanges};
 use crate::core::parser::Language;
 
 /// AI Provider mode
@@ -18,6 +25,7 @@ use crate::core::parser::Language;
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -37,6 +45,7 @@ mod symbols {
     pub const AI_ICON: &str = "󰌤";
     pub const FILE: &str = "󰈙";
     pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
     pub const ERROR: &str = "󰅚";
     pub const ARROW: &str = "→";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
@@ -67,7 +76,9 @@ If something cannot be directly translated, add a TODO comment explaining the li
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
@@ -135,7 +146,7 @@ fn extract_code_from_response(response: &str) -> String {
 }
 
 pub async fn run(
-    _config: Config,
+    config: Config,
     file: &str,
     target_lang: &str,
     output: Option<&str>,
@@ -149,24 +160,27 @@ pub async fn run(
     }
 
     // Read source file
-    let source_code = fs::read_to_string(path)?;
+    let source_code = CloudExclusions::from_config(&config.privacy)?.read_file(path)?;
     let source_lang = detect_language(file, None);
     let target = target_lang.to_lowercase();
 
     print_header(file, &source_lang, &target);
     print_file_info(file, source_code.lines().count());
+    context::print_injection_warnings(file, &source_code);
 
     let ai_mode = determine_ai_mode();
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Prepare prompt
     let prompt = format!(
-        "## Source Code ({source_lang})\n\n```{source_lang}\n{source_code}\n```\n\n## Target Language\nConvert this code to {target}.\n\nFollow {target} best practices and idioms.",
+        "{rules}\n\n## Source Code ({source_lang})\n\n{source_code}\n\n## Target Language\nConvert this code to {target}.\n\nFollow {target} best practices and idioms.",
         source_lang = source_lang,
-        source_code = source_code,
+        rules = context::REPOSITORY_CONTENT_RULES,
+        source_code = context::delimit(file, &source_code),
         target = target
     );
 
@@ -186,29 +200,57 @@ pub async fn run(
             let prompt_with_system = format!("{}\n\n{}", CONVERT_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(CONVERT_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "convert").apply(&response);
 
     // Extract code from response
     let converted_code = extract_code_from_response(&response);
 
     // Save or print
     if let Some(out_path) = output {
-        fs::write(out_path, &converted_code)?;
+        let converted_code = hooks::pre_apply(out_path, converted_code)?;
+        permissions::write_file(out_path, &converted_code)?;
         print_saved(out_path);
+        tidy_written(out_path);
+        hooks::post_apply(out_path, &fs::read_to_string(out_path).unwrap_or(converted_code));
     } else {
         // Generate default output filename
         let stem = path.file_stem().unwrap_or_default().to_string_lossy();
         let ext = get_extension_for_language(&target);
         let default_output = format!("{}_converted.{}", stem, ext);
-        fs::write(&default_output, &converted_code)?;
+        let converted_code = hooks::pre_apply(&default_output, converted_code)?;
+        permissions::write_file(&default_output, &converted_code)?;
         print_saved(&default_output);
+        tidy_written(&default_output);
+        hooks::post_apply(&default_output, &fs::read_to_string(&default_output).unwrap_or(converted_code));
     }
 
     Ok(())
 }
 
+/// Organize the imports of a file just written, then run the project's
+/// formatter on it
+fn tidy_written(path: &str) {
+    let root = Path::new(".");
+    let project = match ProjectConfig::load(root) {
+        Ok(project) => project,
+        Err(e) => return print_tidy_warning(&format!("{:#}", e)),
+    };
+    match import_organizer::organize_file(root, Path::new(path), &project.imports) {
+        Ok(Some(changes)) => print_import_changes(&changes),
+        Ok(None) => {}
+        Err(e) => print_tidy_warning(&format!("Imports not organized: {:#}", e)),
+    }
+    if let Some(outcome) = formatter::format_file(root, Path::new(path), &project.format) {
+        print_format_outcome(&outcome);
+    }
+}
+
 // ============================================
 // UI Functions
 // ============================================
@@ -220,8 +262,8 @@ fn print_header(file: &str, source: &str, target: &str) {
         colors::PRIMARY, colors::BOLD, symbols::CONVERT, colors::RESET
     );
     println!(
-        "{}  │ {} {} {} {}{}",
-        colors::MUTED, source, symbols::ARROW, target, colors::RESET, ""
+        "{}  │ {} {} {} {}",
+        colors::MUTED, source, symbols::ARROW, target, colors::RESET
     );
     println!(
         "{}  │ Source: {}{}",
@@ -271,6 +313,60 @@ fn print_saved(path: &str) {
     println!();
 }
 
+fn print_import_changes(changes: &ImportChanges) {
+    if changes.is_empty() {
+        return;
+    }
+    let mut parts = Vec::new();
+    if !changes.added.is_empty() {
+        parts.push(format!("{} added", changes.added.len()));
+    }
+    if !changes.removed.is_empty() {
+        parts.push(format!("{} unused removed", changes.removed.len()));
+    }
+    if changes.duplicates > 0 {
+        parts.push(format!("{} duplicate removed", changes.duplicates));
+    }
+    if changes.sorted {
+        parts.push("sorted".to_string());
+    }
+    if let Some(tool) = &changes.tool {
+        parts.push(format!("ran {}", tool));
+    }
+    println!(
+        "{}  {} Imports: {}{}",
+        colors::SUCCESS, symbols::SUCCESS, parts.join(", "), colors::RESET
+    );
+    for import in changes.added.iter().map(|i| format!("+ {}", i)).chain(changes.removed.iter().map(|i| format!("- {}", i))) {
+        println!("{}      {}{}", colors::MUTED, import, colors::RESET);
+    }
+}
+
+fn print_format_outcome(outcome: &FormatOutcome) {
+    let (color, symbol, message) = match outcome {
+        FormatOutcome::Formatted(formatter) => {
+            (colors::SUCCESS, symbols::SUCCESS, format!("Formatted with {}", formatter))
+        }
+        FormatOutcome::Unchanged(formatter) => {
+            (colors::MUTED, symbols::SUCCESS, format!("Already formatted ({})", formatter))
+        }
+        FormatOutcome::NotInstalled(formatter) => {
+            (colors::WARNING, symbols::WARNING, format!("{} is not installed; file left unformatted", formatter))
+        }
+        FormatOutcome::Failed { formatter, message } => {
+            (colors::WARNING, symbols::WARNING, format!("{} failed: {}", formatter, message))
+        }
+    };
+    println!("{}  {} {}{}", color, symbol, message, colors::RESET);
+}
+
+fn print_tidy_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
 fn print_error(message: &str) {
     println!(
         "\n{}  {} Error: {}{}",
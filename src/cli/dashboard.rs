@@ -0,0 +1,218 @@
+//! Dashboard command - how NEXUS is used on this project
+//!
+//! One screen with index health, recent AI requests, written files awaiting
+//! commit, open review findings and this week's token spend. `--html`
+//! writes the same summary as a standalone page.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::dashboard::Dashboard;
+use crate::core::permissions;
+use crate::core::graders::Severity;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const DASHBOARD: &str = "󰕮";
+    pub const INDEX: &str = "󰈙";
+    pub const AI: &str = "󰌤";
+    pub const PENDING: &str = "󰏫";
+    pub const FINDING: &str = "󰀦";
+    pub const TOKENS: &str = "󰆙";
+    pub const SUCCESS: &str = "󰄂";
+}
+
+/// Most files listed under each heading in the terminal
+const MAX_LISTED: usize = 5;
+
+/// Show the dashboard, or write it as HTML to `html`
+pub async fn run(_config: Config, html: Option<&str>) -> Result<()> {
+    let dashboard = Dashboard::gather(Path::new("."))?;
+
+    if let Some(path) = html {
+        permissions::write_file(path, dashboard.render_html())?;
+        println!(
+            "\n{}  {} Dashboard written to {}{}\n",
+            colors::SUCCESS, symbols::SUCCESS, path, colors::RESET
+        );
+        return Ok(());
+    }
+
+    print_header();
+    print_index(&dashboard);
+    print_recent(&dashboard);
+    print_pending(&dashboard);
+    print_findings(&dashboard);
+    print_spend(&dashboard);
+    Ok(())
+}
+
+fn print_header() {
+    println!();
+    println!(
+        "{}{}  {} Dashboard{}",
+        colors::PRIMARY, colors::BOLD, symbols::DASHBOARD, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+}
+
+fn print_section(symbol: &str, title: &str) {
+    println!();
+    println!("{}{}  {} {}{}", colors::FG, colors::BOLD, symbol, title, colors::RESET);
+}
+
+fn print_muted(message: &str) {
+    println!("{}     {}{}", colors::MUTED, message, colors::RESET);
+}
+
+fn print_index(dashboard: &Dashboard) {
+    print_section(symbols::INDEX, "Index");
+    let Some(health) = &dashboard.index else {
+        print_muted("Not indexed yet; run `nexus index`");
+        return;
+    };
+    println!(
+        "{}     {} files · {} lines · {} symbols · indexed {}{}",
+        colors::FG,
+        health.snapshot.files.len(),
+        health.snapshot.total_lines,
+        health.snapshot.symbols,
+        local_time(&health.snapshot.indexed_at),
+        colors::RESET
+    );
+    if health.is_fresh() {
+        println!("{}     Up to date{}", colors::SUCCESS, colors::RESET);
+        return;
+    }
+    println!(
+        "{}     {} changed, {} added, {} removed since; run `nexus index` to refresh{}",
+        colors::WARNING, health.stale.len(), health.added.len(), health.removed.len(), colors::RESET
+    );
+    for path in health.stale.iter().chain(&health.added).take(MAX_LISTED) {
+        print_muted(path);
+    }
+}
+
+fn print_recent(dashboard: &Dashboard) {
+    print_section(symbols::AI, &format!("Recent AI requests ({} total)", dashboard.total_interactions));
+    if dashboard.recent.is_empty() {
+        print_muted("None yet");
+        return;
+    }
+    for interaction in &dashboard.recent {
+        println!(
+            "{}     {}  {}{:<10}{} {:<8} {:>8} tokens{}",
+            colors::MUTED,
+            local_time(&interaction.timestamp),
+            colors::FG,
+            interaction.command,
+            colors::MUTED,
+            interaction.provider,
+            interaction.tokens(),
+            colors::RESET
+        );
+    }
+}
+
+fn print_pending(dashboard: &Dashboard) {
+    print_section(symbols::PENDING, "Awaiting commit");
+    let Some(pending) = &dashboard.pending else {
+        print_muted("Not a git repository");
+        return;
+    };
+    if pending.is_empty() {
+        print_muted("Nothing NEXUS wrote is uncommitted");
+        return;
+    }
+    for change in pending.iter().take(MAX_LISTED) {
+        println!(
+            "{}     {}{}  {} · {}{}",
+            colors::FG, change.path, colors::MUTED, change.command, local_time(&change.timestamp), colors::RESET
+        );
+    }
+    if pending.len() > MAX_LISTED {
+        print_muted(&format!("and {} more", pending.len() - MAX_LISTED));
+    }
+}
+
+fn print_findings(dashboard: &Dashboard) {
+    print_section(symbols::FINDING, &format!("Open findings ({})", dashboard.findings.len()));
+    if dashboard.findings.is_empty() {
+        print_muted("None; `nexus review` records them");
+        return;
+    }
+    let counts: Vec<String> = dashboard
+        .findings_by_severity()
+        .iter()
+        .rev()
+        .map(|(severity, count)| format!("{}{} {}{}", severity_color(*severity), count, severity.label(), colors::RESET))
+        .collect();
+    println!("     {}", counts.join(&format!("{} · ", colors::MUTED)));
+    for open in dashboard.findings.iter().take(MAX_LISTED) {
+        let finding = &open.finding;
+        let location = match finding.line {
+            Some(line) => format!("{}:{}", finding.path, line),
+            None => finding.path.clone(),
+        };
+        println!(
+            "{}     {:<8}{} {} {}{}{}",
+            severity_color(finding.severity), finding.severity.label(),
+            colors::FG, finding.title, colors::MUTED, location, colors::RESET
+        );
+    }
+}
+
+fn print_spend(dashboard: &Dashboard) {
+    print_section(
+        symbols::TOKENS,
+        &format!("Tokens this week (since {})", dashboard.week_start.with_timezone(&Local).format("%a %Y-%m-%d")),
+    );
+    if dashboard.spend.is_empty() {
+        print_muted("None");
+        println!();
+        return;
+    }
+    for (provider, spend) in &dashboard.spend {
+        println!(
+            "{}     {:<8} {:>4} requests {:>10} in {:>10} out{}",
+            colors::FG, provider, spend.requests, spend.input_tokens, spend.output_tokens, colors::RESET
+        );
+    }
+    let total = dashboard.week_total();
+    println!(
+        "{}{}     {:<8} {:>4} requests {:>10} in {:>10} out{}",
+        colors::FG, colors::BOLD, "total", total.requests, total.input_tokens, total.output_tokens, colors::RESET
+    );
+    print_muted("Claude counts are exact; proxy and local counts are estimates");
+    println!();
+}
+
+fn severity_color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => colors::ERROR,
+        Severity::High | Severity::Medium => colors::WARNING,
+        Severity::Low => colors::MUTED,
+    }
+}
+
+fn local_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%m-%d %H:%M").to_string()
+}
@@ -0,0 +1,407 @@
+//! Diagram command - Mermaid diagrams of the codebase
+//!
+//! Renders the data model (from SQL schemas and ORM models) as an ER
+//! diagram, the import structure as a module dependency graph, and call
+//! paths from an entry point as sequence diagrams or flowcharts.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::callgraph::{CallGraph, Step};
+use crate::core::permissions;
+use crate::core::diagram::{self, ErModel, ModuleGraph};
+use crate::core::journal::{Change, Journal};
+use crate::index;
+use crate::ui::{FormOption, FormResult, NexusForm};
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const DIAGRAM: &str = "󰙅";
+    pub const AI_ICON: &str = "󰌤";
+    pub const FILE: &str = "󰈙";
+    pub const FUNCTION: &str = "󰊕";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for labelling call flow steps
+const FLOW_PROMPT: &str = r#"You are NEXUS AI, an expert at explaining code.
+
+You are given the numbered calls along a code path, each with the calling
+function, the called function and the line that makes the call.
+
+For each step write a short, human-readable label (at most 8 words) that
+says what the step does in domain terms, e.g. "Validate the session token"
+rather than "call validate()".
+
+Respond with only a JSON object mapping step numbers to labels:
+{"1": "Load settings from disk", "2": "..."}"#;
+
+/// Longest label kept from the AI, in characters
+const MAX_LABEL_CHARS: usize = 60;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+/// ER diagram of the project's data model
+pub async fn er(_config: Config, output: Option<&str>) -> Result<()> {
+    print_header("Data Model", "SQL schemas and ORM models");
+
+    let model = diagram::project_er_model(Path::new("."))?;
+    if model.is_empty() {
+        print_warning("No tables or ORM models found (looked for .sql, .prisma, Diesel, sqlx/SeaORM, SQLAlchemy and Django)");
+        return Ok(());
+    }
+
+    print_er_summary(&model);
+    emit(&model.render_mermaid(), output, "diagram er")
+}
+
+/// Module dependency graph of the project
+pub async fn modules(_config: Config, depth: usize, output: Option<&str>) -> Result<()> {
+    print_header("Module Dependencies", &format!("grouped {} directories deep", depth));
+
+    let graph = diagram::project_module_graph(Path::new("."), depth)?;
+    if graph.modules.is_empty() {
+        print_warning("No source files found");
+        return Ok(());
+    }
+
+    print_graph_summary(&graph);
+    emit(&graph.render_mermaid(), output, "diagram modules")
+}
+
+/// Call flow from an entry point, optionally labelled by the AI
+pub async fn flow(
+    config: Config,
+    entry: &str,
+    file: Option<&str>,
+    depth: usize,
+    format: &str,
+    labels: bool,
+    output: Option<&str>,
+) -> Result<()> {
+    print_header("Call Flow", &format!("{} · depth {}", entry, depth));
+
+    if format != "sequence" && format != "flowchart" {
+        print_error(&format!("Unknown format `{}` (expected sequence or flowchart)", format));
+        return Ok(());
+    }
+
+    let root = Path::new(".");
+    let files = index::collect_files(root, false)?;
+    let graph = CallGraph::build(&files)?;
+
+    let candidates: Vec<usize> = graph
+        .find(entry)
+        .into_iter()
+        .filter(|&i| file.is_none_or(|f| graph.functions[i].path.ends_with(f.trim_start_matches("./"))))
+        .collect();
+
+    let Some(entry_index) = choose_entry(&graph, candidates)? else {
+        print_error(&format!("No function named `{}` found", entry));
+        return Ok(());
+    };
+
+    let mut steps = graph.walk(entry_index, depth);
+    print_flow_summary(&graph, entry_index, &steps);
+    if steps.is_empty() {
+        print_warning("The entry point makes no calls to project functions");
+        return Ok(());
+    }
+
+    if labels {
+        label_steps(&config, &graph, &mut steps).await?;
+    }
+
+    let mermaid = match format {
+        "flowchart" => graph.render_flowchart(entry_index, &steps, root),
+        _ => graph.render_sequence(entry_index, &steps, root),
+    };
+    emit(&mermaid, output, "diagram flow")
+}
+
+/// Pick the entry function, asking when the name is ambiguous
+fn choose_entry(graph: &CallGraph, candidates: Vec<usize>) -> Result<Option<usize>> {
+    if candidates.len() <= 1 {
+        return Ok(candidates.first().copied());
+    }
+
+    let options: Vec<FormOption> = candidates
+        .iter()
+        .map(|&i| {
+            let f = &graph.functions[i];
+            FormOption::new(format!("{}:{}", f.path.display(), f.line_start), f.qualified_name())
+        })
+        .collect();
+
+    match NexusForm::new().select("Which function is the entry point?", &options)? {
+        FormResult::Single(idx) => Ok(Some(candidates[idx])),
+        _ => Ok(None),
+    }
+}
+
+/// Ask the AI for human-readable step labels
+async fn label_steps(config: &Config, graph: &CallGraph, steps: &mut [Step]) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    // Call lines from excluded paths are not sent; names and signatures are
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let listing: Vec<String> = steps
+        .iter()
+        .enumerate()
+        .map(|(n, step)| {
+            let caller = &graph.functions[step.caller];
+            let callee = &graph.functions[step.callee];
+            let call = if exclusions.is_excluded(&caller.path) {
+                String::new()
+            } else {
+                format!(" | call: `{}`", context::sanitize(&step.text))
+            };
+            format!(
+                "{}. {} → {}{} | signature: `{}`",
+                n + 1,
+                caller.qualified_name(),
+                callee.qualified_name(),
+                call,
+                callee.signature.as_deref().unwrap_or(&callee.name)
+            )
+        })
+        .collect();
+
+    let prompt = format!(
+        "{}\n\n## Call Steps\n\n{}\n\n## Task\n\nLabel each step.",
+        context::REPOSITORY_CONTENT_RULES,
+        listing.join("\n")
+    );
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(FLOW_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", FLOW_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(FLOW_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+
+    let labels = parse_labels(&response);
+    if labels.is_empty() {
+        print_warning("Could not read step labels from the response; using function names");
+    }
+    for (n, step) in steps.iter_mut().enumerate() {
+        step.label = labels.get(&(n + 1)).cloned();
+    }
+
+    Ok(())
+}
+
+/// Step labels from a JSON object in the response
+fn parse_labels(response: &str) -> HashMap<usize, String> {
+    let (Some(start), Some(end)) = (response.find('{'), response.rfind('}')) else {
+        return HashMap::new();
+    };
+    let Ok(map) = serde_json::from_str::<HashMap<String, String>>(&response[start..=end]) else {
+        return HashMap::new();
+    };
+
+    map.into_iter()
+        .filter_map(|(k, v)| {
+            let label: String = v.trim().chars().take(MAX_LABEL_CHARS).collect();
+            Some((k.trim().parse().ok()?, label))
+        })
+        .collect()
+}
+
+/// Print the diagram, or write it as raw Mermaid (.mmd) or a Markdown block
+fn emit(mermaid: &str, output: Option<&str>, command: &str) -> Result<()> {
+    let Some(path) = output else {
+        print_diagram(mermaid);
+        return Ok(());
+    };
+
+    let content = if path.ends_with(".md") || path.ends_with(".markdown") {
+        diagram::fenced(mermaid)
+    } else {
+        mermaid.to_string()
+    };
+    permissions::write_file(path, content)?;
+    Journal::current()
+        .record(command, Change::FileWritten { path: path.to_string(), provenance: None })
+        .ok();
+
+    print_saved(path);
+    Ok(())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(title: &str, detail: &str) {
+    println!();
+    println!(
+        "{}{}  {} {}{}",
+        colors::PRIMARY, colors::BOLD, symbols::DIAGRAM, title, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, detail, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_er_summary(model: &ErModel) {
+    for entity in &model.entities {
+        println!(
+            "{}  {} {}{}{} ({} fields, {}){}",
+            colors::MUTED, symbols::FILE, colors::FG, entity.name, colors::MUTED,
+            entity.fields.len(), entity.source.display(), colors::RESET
+        );
+    }
+    println!(
+        "{}  {} entities, {} relations{}",
+        colors::MUTED, model.entities.len(), model.relations.len(), colors::RESET
+    );
+    println!();
+}
+
+fn print_graph_summary(graph: &ModuleGraph) {
+    println!(
+        "{}  {} modules, {} dependencies{}",
+        colors::MUTED, graph.modules.len(), graph.edges.len(), colors::RESET
+    );
+    println!();
+}
+
+fn print_flow_summary(graph: &CallGraph, entry: usize, steps: &[Step]) {
+    let f = &graph.functions[entry];
+    println!(
+        "{}  {} {}{}{} ({}:{}){}",
+        colors::MUTED, symbols::FUNCTION, colors::FG, f.qualified_name(), colors::MUTED,
+        f.path.display(), f.line_start, colors::RESET
+    );
+
+    let files: std::collections::HashSet<&PathBuf> = steps.iter().map(|s| &graph.functions[s.callee].path).collect();
+    println!(
+        "{}  {} calls across {} files{}",
+        colors::MUTED, steps.len(), files.len(), colors::RESET
+    );
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is labelling the steps {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_diagram(mermaid: &str) {
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    for line in mermaid.lines() {
+        println!("{}  │ {}{}{}", colors::MUTED, colors::FG, line, colors::RESET);
+    }
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!(
+        "{}  Paste into a ```mermaid block, or use -o diagram.md / -o diagram.mmd{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_saved(path: &str) {
+    println!(
+        "{}{}  {} Diagram saved to {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, path, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
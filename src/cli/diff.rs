@@ -6,16 +6,21 @@
 
 use anyhow::Result;
 use std::io::{self, Write};
+use std::path::Path;
 use std::process::Command;
 
+use crate::ai::context::CloudExclusions;
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
 use crate::config::Config;
+use crate::core::git;
 
 /// AI Provider mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -74,7 +79,9 @@ Keep the analysis concise but thorough."#;
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
@@ -142,7 +149,7 @@ fn get_diff_stats(staged: bool) -> Result<(usize, usize, usize)> {
     Ok((files, additions, deletions))
 }
 
-pub async fn run(_config: Config, staged: bool, file: Option<&str>) -> Result<()> {
+pub async fn run(config: Config, staged: bool, file: Option<&str>) -> Result<()> {
     print_header(staged, file);
 
     // Check if in git repo
@@ -158,6 +165,7 @@ pub async fn run(_config: Config, staged: bool, file: Option<&str>) -> Result<()
         print_no_changes(staged);
         return Ok(());
     }
+    let diff = CloudExclusions::from_config(&config.privacy)?.redact_diff(&diff, &git::repo_root(Path::new("."))?);
 
     // Get stats
     let (files, additions, deletions) = get_diff_stats(staged)?;
@@ -167,6 +175,7 @@ pub async fn run(_config: Config, staged: bool, file: Option<&str>) -> Result<()
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Prepare prompt
@@ -191,6 +200,9 @@ pub async fn run(_config: Config, staged: bool, file: Option<&str>) -> Result<()
             let prompt_with_system = format!("{}\n\n{}", DIFF_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(DIFF_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
@@ -235,10 +247,10 @@ fn print_diff_stats(files: usize, additions: usize, deletions: usize) {
         colors::MUTED, symbols::FILE, files
     );
     println!(
-        "{}  {} {} insertion(s)  {}{}  {} {} deletion(s){}",
+        "{}  {} {} insertion(s)  {}{} {} deletion(s){}",
         colors::ADDED, symbols::ADDED, additions,
         colors::REMOVED, symbols::REMOVED, deletions,
-        colors::RESET, ""
+        colors::RESET
     );
     println!();
 }
@@ -5,19 +5,26 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
 use crate::config::Config;
+use crate::core::diagram;
+use crate::core::permissions;
+use crate::core::hooks;
 use crate::core::parser::{CodeParser, Language, SymbolKind};
+use crate::index;
 
 /// AI Provider mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -37,6 +44,7 @@ mod symbols {
     pub const AI_ICON: &str = "󰌤";
     pub const FILE: &str = "󰈙";
     pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
     pub const ERROR: &str = "󰅚";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
@@ -71,22 +79,63 @@ Your task is to generate comprehensive documentation for the provided code.
 Generate documentation comments that can be added directly to the code.
 Format as markdown with appropriate code blocks."#;
 
+/// System prompt for project-level documentation
+const PROJECT_DOC_PROMPT: &str = r#"You are NEXUS AI, an expert technical documentation writer.
+
+Your task is to write architecture documentation for a whole project from
+its file and symbol overview.
+
+## Sections
+- Overview: what the project does
+- Architecture: the main modules and how they depend on each other
+- Data Model: the main entities, when the project has any
+- Getting Started: how to build, run and test
+
+## Diagrams
+Diagrams are generated separately. Place the line
+<!-- nexus:diagram modules --> in the Architecture section and
+<!-- nexus:diagram er --> in the Data Model section, exactly as written.
+Do not draw your own diagrams.
+
+## Output
+Markdown only, ready to save as a file."#;
+
+/// Files listed in the project overview sent to the AI
+const MAX_PROJECT_FILES: usize = 200;
+
+/// Symbol names listed per file in the project overview
+const MAX_SYMBOLS_PER_FILE: usize = 15;
+
+/// Module grouping depth for the embedded dependency graph
+const DIAGRAM_DEPTH: usize = 2;
+
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool) -> Result<()> {
+pub async fn run(config: Config, file: Option<&str>, output: Option<&str>, inline: bool, project: bool) -> Result<()> {
+    if project {
+        return run_project(config, output).await;
+    }
+    let Some(file) = file else {
+        print_error("Specify a file to document, or use --project");
+        return Ok(());
+    };
+
     print_header(file);
 
     let ai_mode = determine_ai_mode();
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Read the file
@@ -96,7 +145,7 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool
         return Ok(());
     }
 
-    let content = fs::read_to_string(path)?;
+    let content = CloudExclusions::from_config(&config.privacy)?.read_file(path)?;
     let lang = Language::from_path(path);
     let lines = content.lines().count();
 
@@ -125,6 +174,7 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool
         .collect();
 
     print_file_info(file, lang, lines, symbols_summary.len());
+    context::print_injection_warnings(file, &content);
 
     let doc_style = if inline {
         "Generate inline documentation comments to add directly to the code."
@@ -133,12 +183,12 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool
     };
 
     let prompt = format!(
-        "## Code to Document\n\n**File:** `{}`\n**Language:** {}\n\n### Symbols:\n{}\n\n```{}\n{}\n```\n\n## Task\n\n{}",
+        "{}\n\n## Code to Document\n\n**File:** `{}`\n**Language:** {}\n\n### Symbols:\n{}\n\n{}\n\n## Task\n\n{}",
+        context::REPOSITORY_CONTENT_RULES,
         file,
         lang,
         symbols_summary.join("\n"),
-        lang.to_string().to_lowercase(),
-        content,
+        context::delimit(file, &content),
         doc_style
     );
 
@@ -158,13 +208,19 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool
             let prompt_with_system = format!("{}\n\n{}", DOC_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(DOC_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "doc").apply(&response);
 
     // Save to file if output specified
     if let Some(out_path) = output {
-        fs::write(out_path, &response)?;
+        let response = hooks::pre_apply(out_path, response)?;
+        permissions::write_file(out_path, &response)?;
+        hooks::post_apply(out_path, &response);
         print_saved(out_path);
     } else {
         print_response(&response);
@@ -173,6 +229,108 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>, inline: bool
     Ok(())
 }
 
+/// Document the whole project, embedding module and data model diagrams
+async fn run_project(config: Config, output: Option<&str>) -> Result<()> {
+    print_header("project");
+
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    let root = Path::new(".");
+    let files = index::collect_files(root, false)?;
+    if files.is_empty() {
+        print_error("No source files found");
+        return Ok(());
+    }
+
+    let mut parser = CodeParser::new()?;
+    let mut overview = Vec::new();
+    for path in files.iter().take(MAX_PROJECT_FILES) {
+        let Ok(parsed) = parser.parse_file(path) else {
+            continue;
+        };
+        let names: Vec<&str> = parsed.symbols
+            .iter()
+            .filter(|s| s.kind != SymbolKind::Impl)
+            .take(MAX_SYMBOLS_PER_FILE)
+            .map(|s| s.name.as_str())
+            .collect();
+        overview.push(format!("- `{}` ({} lines): {}", path.display(), parsed.line_count, names.join(", ")));
+    }
+
+    let modules = diagram::project_module_graph(root, DIAGRAM_DEPTH)?;
+    let er = diagram::project_er_model(root)?;
+
+    print_project_info(files.len(), modules.modules.len(), er.entities.len());
+
+    let dependencies: Vec<String> = modules.edges
+        .keys()
+        .map(|(from, to)| format!("- {} → {}", from, to))
+        .collect();
+    let entities: Vec<String> = er.entities
+        .iter()
+        .map(|e| format!("- {} ({})", e.name, e.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>().join(", ")))
+        .collect();
+
+    let mut prompt = format!(
+        "{}\n\n## Project Files\n\n{}\n\n## Module Dependencies\n\n{}\n",
+        context::REPOSITORY_CONTENT_RULES,
+        overview.join("\n"),
+        dependencies.join("\n")
+    );
+    if !entities.is_empty() {
+        prompt.push_str(&format!("\n## Data Model\n\n{}\n", entities.join("\n")));
+    }
+
+    let readme = root.join("README.md");
+    if readme.exists() {
+        let content = CloudExclusions::from_config(&config.privacy)?.read_file(&readme)?;
+        let excerpt: String = content.chars().take(4000).collect();
+        prompt.push_str(&format!("\n## Existing README (excerpt)\n\n{}\n", context::delimit("README.md", &excerpt)));
+    }
+    prompt.push_str("\n## Task\n\nWrite the project documentation.");
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(PROJECT_DOC_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", PROJECT_DOC_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(PROJECT_DOC_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "doc").apply(&response);
+
+    let document = diagram::embed(&response, &modules, &er);
+
+    if let Some(out_path) = output {
+        let document = hooks::pre_apply(out_path, document)?;
+        permissions::write_file(out_path, &document)?;
+        hooks::post_apply(out_path, &document);
+        print_saved(out_path);
+    } else {
+        print_response(&document);
+    }
+
+    Ok(())
+}
+
 // ============================================
 // UI Functions
 // ============================================
@@ -202,6 +360,14 @@ fn print_file_info(file: &str, lang: Language, lines: usize, symbols: usize) {
     println!();
 }
 
+fn print_project_info(files: usize, modules: usize, entities: usize) {
+    println!(
+        "{}  {} {} files, {} modules, {} data model entities{}",
+        colors::MUTED, symbols::FILE, files, modules, entities, colors::RESET
+    );
+    println!();
+}
+
 fn print_thinking(provider: &str) {
     print!(
         "\r{}  {} {} is generating documentation {}{}",
@@ -0,0 +1,337 @@
+//! Eval command - score providers on an evaluation suite
+//!
+//! Runs every case of one or more YAML suites against each provider,
+//! grades the answers automatically, and prints a scoreboard, so a team can
+//! check a provider or model change before rolling it out.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::ai::{ClaudeClient, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::config::Config;
+use crate::core::eval::{self, CaseResult, ProviderScore, Report, Suite, Verdict};
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::ui::{text, NexusForm};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const EVAL: &str = "󰙨";
+    pub const PASS: &str = "󰄬";
+    pub const FAIL: &str = "󰅖";
+    pub const SKIP: &str = "󰒭";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// Providers an evaluation can target
+pub const PROVIDERS: &[&str] = &["claude", "proxy", "local"];
+
+/// System prompt for every case
+const EVAL_PROMPT: &str = r#"You are being evaluated on a coding task.
+
+Answer with complete, self-contained code in a single fenced code block.
+Do not explain outside the code block."#;
+
+/// Run suites against providers and print the scoreboard
+pub async fn run(_config: Config, suites: &[String], providers: &[String], output: Option<&str>) -> Result<()> {
+    let paths = match suite_paths(suites) {
+        Ok(paths) => paths,
+        Err(e) => {
+            print_header(0, 0, &[]);
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+    let mut loaded: Vec<Suite> = Vec::new();
+    for path in &paths {
+        match eval::load_suite(path) {
+            Ok(suite) => loaded.push(suite),
+            Err(e) => {
+                print_header(paths.len(), 0, &[]);
+                print_error(&format!("{:#}", e));
+                return Ok(());
+            }
+        }
+    }
+
+    if let Some(unknown) = providers.iter().find(|p| !PROVIDERS.contains(&p.as_str())) {
+        print_header(loaded.len(), 0, &[]);
+        print_error(&format!("Unknown provider `{}` (expected one of: {})", unknown, PROVIDERS.join(", ")));
+        return Ok(());
+    }
+    let available = available_providers().await;
+
+    let case_count: usize = loaded.iter().map(|s| s.cases.len()).sum();
+    print_header(loaded.len(), case_count, &available);
+
+    let mut results: Vec<CaseResult> = Vec::new();
+    for suite in &loaded {
+        // Explicit providers win over the suite's, which win over all available
+        let wanted: Vec<String> = if !providers.is_empty() {
+            providers.to_vec()
+        } else if !suite.providers.is_empty() {
+            suite.providers.clone()
+        } else {
+            available.clone()
+        };
+        let system = match &suite.system {
+            Some(extra) => format!("{}\n\n{}", EVAL_PROMPT, extra),
+            None => EVAL_PROMPT.to_string(),
+        };
+
+        for provider in &wanted {
+            if !available.contains(provider) {
+                print_warning(&format!("{}: skipping {}, not available here", suite.name, provider));
+                continue;
+            }
+            for case in &suite.cases {
+                print_running(&suite.name, &case.name, provider);
+                let started = Instant::now();
+                let answer = ask(provider, &system, &case.prompt).await;
+                let latency_ms = started.elapsed().as_millis() as u64;
+
+                let result = match answer {
+                    Ok(answer) => CaseResult {
+                        suite: suite.name.clone(),
+                        case: case.name.clone(),
+                        provider: provider.clone(),
+                        latency_ms,
+                        grades: eval::grade(case, &eval::extract_code(&answer)),
+                        error: None,
+                        failed_request: false,
+                    },
+                    Err(e) => CaseResult {
+                        suite: suite.name.clone(),
+                        case: case.name.clone(),
+                        provider: provider.clone(),
+                        latency_ms,
+                        grades: Vec::new(),
+                        error: Some(e.to_string()),
+                        failed_request: true,
+                    },
+                };
+                clear_line();
+                print_result(&result);
+                results.push(result);
+            }
+        }
+    }
+
+    if results.is_empty() {
+        print_error("No case ran: none of the requested providers is available");
+        return Ok(());
+    }
+
+    let scores = eval::scoreboard(&results);
+    print_scoreboard(&scores);
+
+    if let Some(output) = output {
+        if Path::new(output).exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", output), false)? {
+            print_warning("Report not written");
+            return Ok(());
+        }
+        let report = Report {
+            generated: Utc::now(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            scoreboard: scores,
+            results,
+        };
+        if let Some(parent) = Path::new(output).parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        permissions::write_file(output, serde_json::to_string_pretty(&report)?)?;
+        Journal::current()
+            .record("eval", Change::FileWritten { path: output.to_string(), provenance: None })
+            .ok();
+        print_saved(output);
+    }
+    Ok(())
+}
+
+/// Suite files from arguments: files, directories of `.yaml`/`.yml` files,
+/// or globs the shell did not expand
+fn suite_paths(args: &[String]) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for arg in args {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            let mut found: Vec<PathBuf> = fs::read_dir(path)?
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| is_suite(p))
+                .collect();
+            found.sort();
+            paths.extend(found);
+        } else if path.is_file() {
+            paths.push(path.to_path_buf());
+        } else if arg.contains(['*', '?', '[']) {
+            let glob = globset::Glob::new(arg).with_context(|| format!("Invalid pattern `{}`", arg))?.compile_matcher();
+            let mut found: Vec<PathBuf> = ignore::Walk::new(".")
+                .filter_map(|e| e.ok())
+                .map(|e| e.path().strip_prefix(".").unwrap_or(e.path()).to_path_buf())
+                .filter(|p| p.is_file() && glob.is_match(p))
+                .collect();
+            found.sort();
+            paths.extend(found);
+        } else {
+            bail!("No suite at {}", arg);
+        }
+    }
+    if paths.is_empty() {
+        bail!("No suite files found in {}", args.join(", "));
+    }
+    Ok(paths)
+}
+
+fn is_suite(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "yaml" || e == "yml")
+}
+
+/// Providers that can answer from this machine
+async fn available_providers() -> Vec<String> {
+    let mut available = Vec::new();
+    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        available.push("claude".to_string());
+    }
+    available.push("proxy".to_string());
+    if AiRouter::local_client().is_available().await {
+        available.push("local".to_string());
+    }
+    available
+}
+
+async fn ask(provider: &str, system: &str, prompt: &str) -> Result<String> {
+    match provider {
+        "claude" => ClaudeClient::from_env()?.send_with_system(prompt, system).await,
+        "local" => AiRouter::local_client().with_system(system).chat(prompt).await,
+        _ => ProxyClient::from_env().chat(&format!("{}\n\n{}", system, prompt), None).await,
+    }
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(suites: usize, cases: usize, providers: &[String]) {
+    println!();
+    println!(
+        "{}{}  {} Model Evaluation{}",
+        colors::PRIMARY, colors::BOLD, symbols::EVAL, colors::RESET
+    );
+    println!(
+        "{}  │ {}{} suites · {} cases · available: {}{}",
+        colors::MUTED, colors::FG, suites, cases, providers.join(", "), colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_running(suite: &str, case: &str, provider: &str) {
+    print!(
+        "\r{}  {} {}/{} on {}{}",
+        colors::WARNING, symbols::SPINNER[0], suite, case, provider, colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_result(result: &CaseResult) {
+    let (color, symbol) = if result.passed() {
+        (colors::SUCCESS, symbols::PASS)
+    } else {
+        (colors::ERROR, symbols::FAIL)
+    };
+    println!(
+        "{}  {} {}{}  {}{}  {:.1}s{}",
+        color, symbol, colors::FG,
+        text::cell(&format!("{}/{}", result.suite, result.case), 40),
+        colors::MUTED, text::cell(&result.provider, 8),
+        result.latency_ms as f64 / 1000.0, colors::RESET
+    );
+
+    if let Some(error) = &result.error {
+        println!("{}      request failed: {}{}", colors::ERROR, text::truncate(error, 70), colors::RESET);
+    }
+    for grade in result.grades.iter().filter(|g| g.verdict != Verdict::Pass) {
+        let (color, symbol) = match grade.verdict {
+            Verdict::Skipped => (colors::MUTED, symbols::SKIP),
+            _ => (colors::ERROR, symbols::FAIL),
+        };
+        let detail = grade.detail.as_deref().map(|d| format!(": {}", text::truncate(d, 60))).unwrap_or_default();
+        println!("{}      {} {}{}{}", color, symbol, grade.grader, detail, colors::RESET);
+    }
+}
+
+fn print_scoreboard(scores: &[ProviderScore]) {
+    println!();
+    println!(
+        "{}{}  {}  {}  {}  Avg latency{}",
+        colors::FG, colors::BOLD,
+        text::cell("Provider", 10), text::cell("Cases", 12), text::cell("Checks", 12), colors::RESET
+    );
+    println!("{}  {}{}", colors::MUTED, "─".repeat(52), colors::RESET);
+    for score in scores {
+        let color = match score.percent() {
+            80.. => colors::SUCCESS,
+            50..=79 => colors::WARNING,
+            _ => colors::ERROR,
+        };
+        println!(
+            "  {}{}  {}{}  {}{}  {:.1}s{}",
+            colors::FG, text::cell(&score.provider, 10),
+            color, text::cell(&format!("{}/{} {:>3}%", score.cases_passed, score.cases, score.percent()), 12),
+            colors::FG, text::cell(&format!("{}/{}", score.checks_passed, score.checks), 12),
+            score.avg_latency_ms as f64 / 1000.0, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_saved(path: &str) {
+    println!(
+        "{}{}  {} Report saved to {} (verdicts and timings only){}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, path, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
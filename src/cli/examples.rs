@@ -0,0 +1,309 @@
+//! Examples command - how a symbol is used in this codebase
+//!
+//! Collects real call sites of a function or references to a type, picks a
+//! few that differ from each other, and has the AI turn them into a short
+//! usage guide that cites the examples by `path:line`.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::examples::{self, Examples, Usage};
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::core::parser::{CodeParser, ParsedFile};
+use crate::index;
+use crate::ui::{text, NexusForm};
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+    pub const AI_ACCENT: &str = "\x1b[38;2;179;157;219m";    // #B39DDB
+}
+
+mod symbols {
+    pub const EXAMPLES: &str = "󰘦";
+    pub const AI_ICON: &str = "󰌤";
+    pub const USAGE: &str = "󰁔";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for usage guides
+const EXAMPLES_PROMPT: &str = r#"You are NEXUS AI, an expert at onboarding developers to a codebase.
+
+You are given the definition of a function or type and real places where
+this codebase uses it, each labelled with its `path:line`.
+
+Write a short Markdown guide titled "How to use <name> in this codebase":
+1. One or two sentences on what it is for, as the examples show it
+2. The typical usage pattern, with a small code block adapted from the examples
+3. Variations and gotchas the examples reveal (error handling, setup,
+   arguments that change behavior)
+4. Cite every pattern with the `path:line` of the example it comes from
+
+Base everything on the examples given; do not invent parameters, methods or
+call sites. Keep it under 40 lines."#;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+/// Write a usage guide for `symbol` from its call sites
+pub async fn run(config: Config, symbol: &str, max: usize, output: Option<&str>) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    print_header(symbol, provider_name);
+
+    let root = Path::new(".");
+    let mut parser = CodeParser::new().context("Failed to initialize code parser")?;
+    let files: Vec<ParsedFile> = index::collect_files(root, false)?
+        .iter()
+        .filter_map(|f| parser.parse_file(f.strip_prefix(root).unwrap_or(f)).ok())
+        .collect();
+
+    let Some(found) = examples::collect(&files, symbol) else {
+        print_error(&format!("No function or type named `{}`", symbol));
+        return Ok(());
+    };
+    if found.usages.is_empty() {
+        print_warning(&format!("`{}` is defined but not used anywhere", symbol));
+        println!();
+        return Ok(());
+    }
+
+    // Examples from excluded paths stay local
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let usable: Vec<Usage> = found.usages.iter().filter(|u| !exclusions.is_excluded(&u.path)).cloned().collect();
+    let picked = examples::pick(&usable, max);
+    print_usages(&found, &picked);
+    if picked.is_empty() {
+        print_error("Every usage is in a path excluded from the cloud");
+        return Ok(());
+    }
+
+    if let Some(output) = output {
+        if Path::new(output).exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", output), false)? {
+            print_warning("Guide not written");
+            return Ok(());
+        }
+    }
+
+    let prompt = build_prompt(symbol, &found, &picked, &exclusions);
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(EXAMPLES_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", EXAMPLES_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(EXAMPLES_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+
+    match output {
+        Some(output) => {
+            if let Some(parent) = Path::new(output).parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            permissions::write_file(output, format!("{}\n", response.trim_end()))?;
+            Journal::current()
+                .record("examples", Change::FileWritten { path: output.to_string(), provenance: None })
+                .ok();
+            print_saved(output);
+        }
+        None => print_response(&response),
+    }
+    Ok(())
+}
+
+fn build_prompt(symbol: &str, found: &Examples, picked: &[Usage], exclusions: &CloudExclusions) -> String {
+    let definitions: Vec<String> = found
+        .definitions
+        .iter()
+        .filter(|d| !exclusions.is_excluded(&d.path))
+        .map(|d| {
+            format!(
+                "{}:{}  {}",
+                d.path.display(), d.line_start, d.signature.as_deref().unwrap_or(symbol)
+            )
+        })
+        .collect();
+
+    let mut sections = vec![context::delimit("definition", &context::sanitize(&definitions.join("\n")))];
+    for usage in picked {
+        let label = match &usage.caller {
+            Some(caller) => format!("{}:{} in {}", usage.path.display(), usage.line, caller),
+            None => format!("{}:{}", usage.path.display(), usage.line),
+        };
+        sections.push(context::delimit(&label, &context::sanitize(&numbered(usage))));
+    }
+
+    format!(
+        "{}\n\n{}\n\n## Task\n\nWrite the guide for `{}`. It is used in {} places; these {} were picked to show different ways of using it.",
+        context::REPOSITORY_CONTENT_RULES,
+        sections.join("\n\n"),
+        symbol,
+        found.usages.len(),
+        picked.len()
+    )
+}
+
+/// Context lines prefixed with their line numbers, so citations are exact
+fn numbered(usage: &Usage) -> String {
+    usage
+        .context
+        .lines()
+        .enumerate()
+        .map(|(i, line)| format!("{:>5}  {}", usage.context_start + i, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(symbol: &str, provider: &str) {
+    println!();
+    println!(
+        "{}{}  {} Usage Examples{}",
+        colors::PRIMARY, colors::BOLD, symbols::EXAMPLES, colors::RESET
+    );
+    println!(
+        "{}  │ {}{} via {}{}",
+        colors::MUTED, colors::FG, symbol, provider, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_usages(found: &Examples, picked: &[Usage]) {
+    let mut files: Vec<&Path> = found.usages.iter().map(|u| u.path.as_path()).collect();
+    files.sort();
+    files.dedup();
+    println!(
+        "{}  {} usages in {} files, {} picked as examples{}",
+        colors::MUTED, found.usages.len(), files.len(), picked.len(), colors::RESET
+    );
+    for usage in picked {
+        println!(
+            "{}  {} {}{}:{}{}  {}{}",
+            colors::MUTED, symbols::USAGE, colors::FG,
+            text::truncate_start(&usage.path.display().to_string(), 40), usage.line, colors::MUTED,
+            text::truncate_code(&usage.text, 60), colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is writing the guide {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_response(response: &str) {
+    println!(
+        "{}{}  {} Guide {}",
+        colors::AI_ACCENT, colors::BOLD, symbols::AI_ICON, colors::RESET
+    );
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+
+    for line in response.lines() {
+        println!("{}  │ {}{}", colors::MUTED, colors::FG, line);
+    }
+
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_saved(path: &str) {
+    println!(
+        "{}{}  {} Guide saved to {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, path, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
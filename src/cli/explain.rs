@@ -6,10 +6,12 @@
 
 use anyhow::{Context, Result};
 use std::path::Path;
-use std::fs;
 use std::io::{self, Write};
 
 use crate::ai::{ClaudeClient, Conversation};
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
+use crate::ai::verify::{self, Confidence, Verdict};
 use crate::config::Config;
 use crate::core::parser::{CodeParser, Language, SymbolKind};
 
@@ -19,6 +21,7 @@ mod colors {
     pub const BOLD: &str = "\x1b[1m";
     pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
     pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
     pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
     pub const AI_ACCENT: &str = "\x1b[38;2;255;202;40m";     // #FFCA28
     pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
@@ -28,7 +31,9 @@ mod colors {
 mod symbols {
     pub const AI_ICON: &str = "󰌤";
     pub const FILE: &str = "󰈙";
+    pub const WARNING: &str = "󰀦";
     pub const ERROR: &str = "󰅚";
+    pub const SUCCESS: &str = "󰄂";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
@@ -67,7 +72,7 @@ Guidelines:
     }
 }
 
-pub async fn run(_config: Config, target: &str, depth: &str) -> Result<()> {
+pub async fn run(config: Config, target: &str, depth: &str, verify: bool) -> Result<()> {
     let path = Path::new(target);
 
     // Check if target exists
@@ -97,9 +102,12 @@ pub async fn run(_config: Config, target: &str, depth: &str) -> Result<()> {
     };
 
     // Read file content
-    let content = fs::read_to_string(path)
+    let content = CloudExclusions::from_config(&config.privacy)?
+        .read_file(path)
         .with_context(|| format!("Failed to read file: {}", target))?;
 
+    context::print_injection_warnings(target, &content);
+
     // Parse the file to get structure info
     let mut parser = CodeParser::new()
         .context("Failed to initialize parser")?;
@@ -143,8 +151,8 @@ pub async fn run(_config: Config, target: &str, depth: &str) -> Result<()> {
 
     // Build prompt
     let prompt = format!(
-        "## File: {}\n\n## Structure\n{}\n## Code\n```\n{}\n```\n\nPlease explain this code.",
-        target, structure_info, content
+        "{}\n\n## File: {}\n\n## Structure\n{}\n## Code\n{}\n\nPlease explain this code.",
+        context::REPOSITORY_CONTENT_RULES, target, structure_info, context::delimit(target, &content)
     );
 
     // Send to Claude
@@ -153,14 +161,27 @@ pub async fn run(_config: Config, target: &str, depth: &str) -> Result<()> {
     let mut conversation = Conversation::new(client)
         .with_system(get_system_prompt(depth));
 
-    match conversation.send(&prompt).await {
+    let response = match conversation.send(&prompt).await {
         Ok(response) => {
             clear_line();
+            let response = Pipeline::for_command(&config.postprocess, "explain").apply(&response);
             print_response(&response, depth);
+            response
         }
         Err(e) => {
             clear_line();
             print_error(&format!("AI error: {}", e));
+            return Ok(());
+        }
+    };
+
+    if verify || config.verify.always {
+        print_verifying(verify::provider(&config.verify, "claude"));
+        let result = verify::verify(&config.verify, "claude", &prompt, "Explain this code.", &response).await;
+        clear_line();
+        match result {
+            Ok(verdict) => print_verdict(&verdict),
+            Err(e) => print_error(&format!("Verification failed: {:#}", e)),
         }
     }
 
@@ -238,6 +259,43 @@ fn print_response(response: &str, depth: &str) {
     println!();
 }
 
+/// Print the verification indicator
+fn print_verifying(verifier: &str) {
+    print!(
+        "\r{}  {} Verifying with {} {}{}",
+        colors::MUTED, symbols::AI_ICON, verifier, symbols::SPINNER[0], colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+/// Print the confidence badge and the discrepancies the verifier found
+fn print_verdict(verdict: &Verdict) {
+    let (color, symbol) = match verdict.confidence() {
+        Confidence::High => (colors::SUCCESS, symbols::SUCCESS),
+        Confidence::Medium => (colors::WARNING, symbols::WARNING),
+        Confidence::Low => (colors::ERROR, symbols::ERROR),
+    };
+    println!(
+        "{}{}  {} Confidence: {} ({}/100){}{}  verified by {}{}",
+        color, colors::BOLD, symbol, verdict.confidence().label(), verdict.score, colors::RESET,
+        colors::MUTED, verdict.verifier, colors::RESET
+    );
+    if !verdict.summary.is_empty() {
+        println!("{}  │ {}{}", colors::MUTED, verdict.summary, colors::RESET);
+    }
+    for discrepancy in &verdict.discrepancies {
+        println!(
+            "{}  │ {}{} {}{}",
+            colors::MUTED, color, symbols::WARNING, discrepancy.claim, colors::RESET
+        );
+        println!("{}  │   {}{}", colors::MUTED, discrepancy.issue, colors::RESET);
+        if !discrepancy.evidence.is_empty() {
+            println!("{}  │   {}{}", colors::MUTED, discrepancy.evidence, colors::RESET);
+        }
+    }
+    println!();
+}
+
 /// Print error message
 fn print_error(message: &str) {
     println!(
@@ -5,12 +5,17 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
 use crate::config::Config;
+use crate::core::environment::Environment;
+use crate::core::imports::{self, MissingDependency};
+use crate::core::permissions::{self, Permission};
 use crate::core::parser::Language;
 
 /// AI Provider mode
@@ -18,6 +23,7 @@ use crate::core::parser::Language;
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -38,6 +44,7 @@ mod symbols {
     pub const FILE: &str = "󰈙";
     pub const SUCCESS: &str = "󰄂";
     pub const ERROR: &str = "󰅚";
+    pub const WARNING: &str = "󰀦";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
@@ -64,20 +71,23 @@ Use markdown code blocks with the appropriate language tag for code."#;
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, file: &str, error_msg: Option<&str>) -> Result<()> {
+pub async fn run(config: Config, file: &str, error_msg: Option<&str>) -> Result<()> {
     print_header(file);
 
     let ai_mode = determine_ai_mode();
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Read the file
@@ -87,19 +97,20 @@ pub async fn run(_config: Config, file: &str, error_msg: Option<&str>) -> Result
         return Ok(());
     }
 
-    let content = fs::read_to_string(path)?;
+    let content = CloudExclusions::from_config(&config.privacy)?.read_file(path)?;
     let lang = Language::from_path(path);
     let lines = content.lines().count();
 
     print_file_info(file, lang, lines);
+    context::print_injection_warnings(file, &content);
 
     // Build prompt
     let mut prompt = format!(
-        "## Code to Fix\n\n**File:** `{}`\n**Language:** {}\n\n```{}\n{}\n```\n",
+        "{}\n\n## Code to Fix\n\n**File:** `{}`\n**Language:** {}\n\n{}\n",
+        context::REPOSITORY_CONTENT_RULES,
         file,
         lang,
-        lang.to_string().to_lowercase(),
-        content
+        context::delimit(file, &content)
     );
 
     if let Some(err) = error_msg {
@@ -109,6 +120,9 @@ pub async fn run(_config: Config, file: &str, error_msg: Option<&str>) -> Result
         ));
     }
 
+    let request = format!("{}\n{}", content, error_msg.unwrap_or_default());
+    prompt.push_str(&format!("\n{}", Environment::session().prompt_context(&request)));
+
     prompt.push_str("\n## Task\n\nAnalyze the code and provide a fix for the bug.");
 
     // Send to AI
@@ -127,14 +141,64 @@ pub async fn run(_config: Config, file: &str, error_msg: Option<&str>) -> Result
             let prompt_with_system = format!("{}\n\n{}", FIX_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(FIX_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "fix").apply(&response);
     print_response(&response);
 
+    let missing = new_dependencies(&response, lang);
+    print_dependency_warnings(&missing);
+    offer_dependency_install(&missing);
+
     Ok(())
 }
 
+/// Offer to add each missing dependency through the package manager
+fn offer_dependency_install(missing: &[MissingDependency]) {
+    for dep in missing {
+        let action = format!("run `{}`", dep.add_command().join(" "));
+        if let Err(e) = permissions::request(Permission::Run, &action) {
+            print_error(&format!("{:#}", e));
+            continue;
+        }
+
+        match dep.install("fix") {
+            Ok(()) => println!(
+                "{}  {} Added {} to {}{}",
+                colors::SUCCESS, symbols::SUCCESS, dep.name, dep.kind.file_name(), colors::RESET
+            ),
+            Err(e) => print_error(&format!("Failed to add {}: {}", dep.name, e)),
+        }
+    }
+}
+
+/// Imports in the suggested fix that the project manifest does not declare
+fn new_dependencies(response: &str, lang: Language) -> Vec<MissingDependency> {
+    let code = extract_code_blocks(response);
+    imports::missing_dependencies(&code, lang, &Environment::session().manifests)
+}
+
+/// Concatenate the contents of all markdown code blocks in a response
+fn extract_code_blocks(response: &str) -> String {
+    let mut code = String::new();
+    let mut in_block = false;
+
+    for line in response.lines() {
+        if line.trim_start().starts_with("```") {
+            in_block = !in_block;
+        } else if in_block {
+            code.push_str(line);
+            code.push('\n');
+        }
+    }
+
+    code
+}
+
 // ============================================
 // UI Functions
 // ============================================
@@ -203,6 +267,19 @@ fn print_response(response: &str) {
     println!();
 }
 
+fn print_dependency_warnings(missing: &[MissingDependency]) {
+    for dep in missing {
+        println!(
+            "{}  {} New dependency not in {}: {}{}{}",
+            colors::WARNING, symbols::WARNING, dep.kind.file_name(),
+            colors::FG, dep.name, colors::RESET
+        );
+    }
+    if !missing.is_empty() {
+        println!();
+    }
+}
+
 fn print_error(message: &str) {
     println!(
         "\n{}  {} Error: {}{}",
@@ -0,0 +1,331 @@
+//! Fuzz command - fuzzing harness generation
+//!
+//! Generates a cargo-fuzz (libFuzzer) or atheris harness for a single
+//! function from its parsed signature and wires up the fuzz workspace.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::core::harness::{self, FuzzTarget, ParamInput};
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::core::manifest::{Manifest, ManifestKind};
+use crate::core::parser::{CodeParser, Language, Symbol, SymbolKind};
+use crate::index;
+use crate::ui::{FormOption, FormResult, NexusForm};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const FUZZ: &str = "󰒡";
+    pub const FILE: &str = "󰈙";
+    pub const FUNCTION: &str = "󰊕";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Directory holding fuzz targets, as cargo-fuzz lays it out
+const FUZZ_DIR: &str = "fuzz";
+
+/// A function definition matching the requested name
+struct Candidate {
+    path: PathBuf,
+    content: String,
+    symbol: Symbol,
+}
+
+/// Generate a harness for `target` and wire up the fuzz workspace
+pub async fn init(_config: Config, target: &str, file: Option<&str>) -> Result<()> {
+    print_header(target);
+
+    let root = Path::new(".");
+    let candidates = find_function(root, target, file)?;
+
+    let Some(candidate) = choose_candidate(candidates)? else {
+        print_error(&format!("No function named `{}` found", target));
+        return Ok(());
+    };
+
+    let crate_name = Manifest::discover(root)
+        .into_iter()
+        .find(|m| m.kind == ManifestKind::Cargo)
+        .and_then(|m| m.name);
+
+    let fuzz_target = match FuzzTarget::from_symbol(
+        &candidate.symbol, &candidate.content, &candidate.path, root, crate_name.as_deref(),
+    ) {
+        Ok(t) => t,
+        Err(e) => {
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+
+    print_target_info(&candidate, &fuzz_target);
+
+    let written = match fuzz_target.language {
+        Language::Python => write_atheris(&fuzz_target)?,
+        _ => write_cargo_fuzz(&fuzz_target, crate_name.as_deref().unwrap_or("crate"))?,
+    };
+
+    let journal = Journal::current();
+    for path in &written {
+        print_file_written(path);
+        journal
+            .record("fuzz init", Change::FileWritten { path: path.display().to_string(), provenance: None })
+            .ok();
+    }
+
+    if fuzz_target.language == Language::Rust && !root.join("src/lib.rs").exists() {
+        print_warning("cargo-fuzz links against a library crate; move the function into src/lib.rs (or a module of it)");
+    }
+
+    print_run_instructions(&fuzz_target);
+    Ok(())
+}
+
+/// Find function definitions named `target`
+fn find_function(root: &Path, target: &str, file: Option<&str>) -> Result<Vec<Candidate>> {
+    let files = match file {
+        Some(f) => vec![PathBuf::from(f)],
+        None => index::collect_files(root, false)?,
+    };
+
+    let mut parser = CodeParser::new().context("Failed to initialize parser")?;
+    let mut candidates = Vec::new();
+
+    for path in files {
+        let Ok(parsed) = parser.parse_file(&path) else {
+            continue;
+        };
+
+        for symbol in &parsed.symbols {
+            if symbol.kind == SymbolKind::Function && symbol.name == target {
+                candidates.push(Candidate {
+                    path: path.clone(),
+                    content: parsed.content.clone(),
+                    symbol: symbol.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Pick one candidate, asking when the name is ambiguous
+fn choose_candidate(mut candidates: Vec<Candidate>) -> Result<Option<Candidate>> {
+    if candidates.len() <= 1 {
+        return Ok(candidates.pop());
+    }
+
+    let options: Vec<FormOption> = candidates
+        .iter()
+        .map(|c| FormOption::new(
+            format!("{}:{}", c.path.display(), c.symbol.line_start),
+            c.symbol.signature.clone().unwrap_or_default(),
+        ))
+        .collect();
+
+    match NexusForm::new().select("Which function should be fuzzed?", &options)? {
+        FormResult::Single(idx) => Ok(Some(candidates.swap_remove(idx))),
+        _ => Ok(None),
+    }
+}
+
+/// Write the cargo-fuzz workspace and target
+fn write_cargo_fuzz(target: &FuzzTarget, crate_name: &str) -> Result<Vec<PathBuf>> {
+    let fuzz_dir = Path::new(FUZZ_DIR);
+    let harness_name = target.harness_name();
+    let mut written = Vec::new();
+
+    fs::create_dir_all(fuzz_dir.join("fuzz_targets"))
+        .context("Failed to create fuzz/fuzz_targets")?;
+
+    let manifest_path = fuzz_dir.join("Cargo.toml");
+    let mut manifest = if manifest_path.exists() {
+        fs::read_to_string(&manifest_path).context("Failed to read fuzz/Cargo.toml")?
+    } else {
+        harness::fuzz_manifest(crate_name, target.needs_arbitrary())
+    };
+
+    if target.needs_arbitrary() && !manifest.contains("arbitrary") {
+        print_warning("Add `arbitrary = { version = \"1\", features = [\"derive\"] }` to fuzz/Cargo.toml");
+    }
+
+    if !manifest.contains(&format!("name = \"{}\"", harness_name)) {
+        manifest.push_str(&harness::fuzz_bin_entry(&harness_name));
+        permissions::write_file(&manifest_path, &manifest)?;
+        written.push(manifest_path);
+    }
+
+    let gitignore = fuzz_dir.join(".gitignore");
+    if !gitignore.exists() {
+        permissions::write_file(&gitignore, "target\ncorpus\nartifacts\ncoverage\n")?;
+        written.push(gitignore);
+    }
+
+    let harness_path = fuzz_dir.join("fuzz_targets").join(format!("{}.rs", harness_name));
+    if confirm_overwrite(&harness_path)? {
+        permissions::write_file(&harness_path, target.render())?;
+        written.push(harness_path);
+    }
+
+    Ok(written)
+}
+
+/// Write the atheris harness and its requirements
+fn write_atheris(target: &FuzzTarget) -> Result<Vec<PathBuf>> {
+    let fuzz_dir = Path::new(FUZZ_DIR);
+    let mut written = Vec::new();
+
+    fs::create_dir_all(fuzz_dir).context("Failed to create fuzz directory")?;
+
+    let requirements = fuzz_dir.join("requirements.txt");
+    let existing = fs::read_to_string(&requirements).unwrap_or_default();
+    if let Some(updated) = harness::requirements_with_atheris(&existing) {
+        permissions::write_file(&requirements, updated)?;
+        written.push(requirements);
+    }
+
+    let harness_path = fuzz_dir.join(format!("{}.py", target.harness_name()));
+    if confirm_overwrite(&harness_path)? {
+        permissions::write_file(&harness_path, target.render())?;
+        written.push(harness_path);
+    }
+
+    Ok(written)
+}
+
+fn confirm_overwrite(path: &Path) -> Result<bool> {
+    if !path.exists() {
+        return Ok(true);
+    }
+    NexusForm::ask_confirm(&format!("{} already exists. Overwrite?", path.display()), false)
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(target: &str) {
+    println!();
+    println!(
+        "{}{}  {} Fuzz Harness{}",
+        colors::PRIMARY, colors::BOLD, symbols::FUZZ, colors::RESET
+    );
+    println!(
+        "{}  │ Target: {}{}{}",
+        colors::MUTED, colors::FG, target, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_target_info(candidate: &Candidate, target: &FuzzTarget) {
+    println!(
+        "{}  {} {}{}:{}{}",
+        colors::MUTED, symbols::FILE, colors::FG,
+        candidate.path.display(), candidate.symbol.line_start, colors::RESET
+    );
+    println!(
+        "{}  {} {}{}{}",
+        colors::MUTED, symbols::FUNCTION, colors::FG,
+        harness::full_signature(&candidate.content, &candidate.symbol), colors::RESET
+    );
+
+    for param in &target.params {
+        let source = match &param.input {
+            ParamInput::Bytes => "raw fuzz input".to_string(),
+            ParamInput::Str => "fuzz input as UTF-8".to_string(),
+            ParamInput::Arbitrary { ty, .. } => format!("arbitrary {}", ty),
+            ParamInput::Provided { consume } => format!("fdp.{}", consume),
+        };
+        println!(
+            "{}     {} ← {}{}",
+            colors::MUTED, param.name, source, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_file_written(path: &Path) {
+    println!(
+        "{}  {} Wrote {}{}{}",
+        colors::SUCCESS, symbols::SUCCESS, colors::FG, path.display(), colors::RESET
+    );
+}
+
+fn print_run_instructions(target: &FuzzTarget) {
+    let name = target.harness_name();
+    let steps: Vec<String> = match target.language {
+        Language::Python => vec![
+            "pip install -r fuzz/requirements.txt".to_string(),
+            format!("python fuzz/{}.py -max_total_time=60", name),
+            format!("python fuzz/{}.py crash-<hash>    # reproduce a crash", name),
+        ],
+        _ => vec![
+            "cargo install cargo-fuzz".to_string(),
+            format!("cargo +nightly fuzz run {} -- -max_total_time=60", name),
+            format!("cargo +nightly fuzz run {} fuzz/artifacts/{}/crash-<hash>    # reproduce", name, name),
+        ],
+    };
+
+    println!();
+    println!(
+        "{}{}  How to run{}",
+        colors::PRIMARY, colors::BOLD, colors::RESET
+    );
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    for step in steps {
+        println!("{}  │ {}{}{}", colors::MUTED, colors::FG, step, colors::RESET);
+    }
+    println!(
+        "{}  │{}",
+        colors::MUTED, colors::RESET
+    );
+    println!(
+        "{}  │ Seed a corpus with real inputs (e.g. sample files) for faster coverage.{}",
+        colors::MUTED, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
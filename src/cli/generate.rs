@@ -5,13 +5,26 @@
 
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::path::Path;
 use std::fs;
 use std::io::{self, Write};
 
+use crate::ai::postprocess::Pipeline;
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
-use crate::config::Config;
+use crate::ai::router::AiRouter;
+use crate::config::{ComplianceConfig, Config, ProjectConfig};
+use crate::core::environment::Environment;
+use crate::core::formatter::{self, FormatOutcome};
+use crate::core::hooks;
+use crate::core::import_organizer::{self, ImportChanges};
+use crate::core::imports::{self, MissingDependency};
+use crate::core::permissions::{self, Permission};
+use crate::core::journal::{Change, Journal};
+use crate::core::parser;
+use crate::core::provenance::Provenance;
+use crate::core::query;
+use crate::core::script_safety::{self, SafetyFinding, ScriptKind, Severity};
 use crate::ui::{FormOption, NexusForm, FormResult};
 
 /// AI Provider mode
@@ -21,6 +34,8 @@ enum AiMode {
     Claude,
     /// Use NEXUS proxy (free tier, no API key needed)
     Proxy,
+    /// Use the local model (`ai.default_provider = "local"`)
+    Local,
 }
 
 // ANSI color codes from design system
@@ -31,6 +46,7 @@ mod colors {
     pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
     pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
     pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
     pub const AI_ACCENT: &str = "\x1b[38;2;255;202;40m";     // #FFCA28
     pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
     pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
@@ -41,6 +57,7 @@ mod symbols {
     pub const FILE: &str = "󰈙";
     pub const CODE: &str = "";
     pub const ERROR: &str = "󰅚";
+    pub const WARNING: &str = "󰀦";
     pub const SUCCESS: &str = "󰄂";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
@@ -58,6 +75,9 @@ enum Language {
     Ruby,
     Swift,
     Kotlin,
+    Shell,
+    PowerShell,
+    Yaml,
     Unknown,
 }
 
@@ -74,6 +94,9 @@ impl Language {
             "rb" => Language::Ruby,
             "swift" => Language::Swift,
             "kt" | "kts" => Language::Kotlin,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "ps1" | "psm1" => Language::PowerShell,
+            "yml" | "yaml" => Language::Yaml,
             _ => Language::Unknown,
         }
     }
@@ -90,6 +113,9 @@ impl Language {
             "ruby" | "rb" => Language::Ruby,
             "swift" => Language::Swift,
             "kotlin" | "kt" => Language::Kotlin,
+            "shell" | "bash" | "sh" => Language::Shell,
+            "powershell" | "pwsh" | "ps1" => Language::PowerShell,
+            "yaml" | "yml" => Language::Yaml,
             _ => Language::Unknown,
         }
     }
@@ -106,6 +132,9 @@ impl Language {
             Language::Ruby => "Ruby",
             Language::Swift => "Swift",
             Language::Kotlin => "Kotlin",
+            Language::Shell => "Shell",
+            Language::PowerShell => "PowerShell",
+            Language::Yaml => "YAML",
             Language::Unknown => "Unknown",
         }
     }
@@ -122,6 +151,9 @@ impl Language {
             Language::Ruby => "rb",
             Language::Swift => "swift",
             Language::Kotlin => "kt",
+            Language::Shell => "sh",
+            Language::PowerShell => "ps1",
+            Language::Yaml => "yml",
             Language::Unknown => "txt",
         }
     }
@@ -138,9 +170,28 @@ impl Language {
             Language::Ruby => "ruby",
             Language::Swift => "swift",
             Language::Kotlin => "kotlin",
+            Language::Shell => "bash",
+            Language::PowerShell => "powershell",
+            Language::Yaml => "yaml",
             Language::Unknown => "",
         }
     }
+
+    /// Generated code run through the command's post-processing steps
+    fn postprocess(&self, pipeline: &Pipeline, code: &str) -> String {
+        let language = query::parse_language(self.code_fence()).unwrap_or(parser::Language::Unknown);
+        pipeline.apply_code(code, language)
+    }
+
+    /// Script kind for the static safety pass, if this is a script language
+    fn script_kind(&self) -> Option<ScriptKind> {
+        match self {
+            Language::Shell => Some(ScriptKind::Shell),
+            Language::PowerShell => Some(ScriptKind::PowerShell),
+            Language::Yaml => Some(ScriptKind::CiConfig),
+            _ => None,
+        }
+    }
 }
 
 /// Get system prompt for code generation
@@ -170,11 +221,16 @@ The user will save this directly to a file, so it must be valid, compilable/runn
 }
 
 pub async fn run(
-    _config: Config,
+    config: Config,
     description: &str,
     output: Option<&str>,
     language: Option<&str>,
+    license_check: bool,
 ) -> Result<()> {
+    let mut compliance = config.compliance;
+    compliance.similarity_check |= license_check;
+    let pipeline = Pipeline::for_command(&config.postprocess, "generate");
+
     // Determine language
     let lang = determine_language(output, language)?;
 
@@ -186,16 +242,24 @@ pub async fn run(
 
     match ai_mode {
         AiMode::Claude => {
-            run_with_claude(description, lang, output).await
+            run_with_claude(description, lang, output, &compliance, &pipeline).await
         }
         AiMode::Proxy => {
-            run_with_proxy(description, lang, output).await
+            run_with_proxy(description, lang, output, &compliance, &pipeline).await
+        }
+        AiMode::Local => {
+            run_with_local(description, lang, output, &compliance, &pipeline).await
         }
     }
 }
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
+    // The local model, when it is the configured default
+    if AiRouter::local_default() {
+        return AiMode::Local;
+    }
+
     // Check for Claude API key
     if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         return AiMode::Claude;
@@ -206,12 +270,12 @@ fn determine_ai_mode() -> AiMode {
 }
 
 /// Run code generation with Claude (requires API key)
-async fn run_with_claude(description: &str, lang: Language, output: Option<&str>) -> Result<()> {
+async fn run_with_claude(description: &str, lang: Language, output: Option<&str>, compliance: &ComplianceConfig, pipeline: &Pipeline) -> Result<()> {
     let client = ClaudeClient::from_env()?;
 
     let prompt = format!(
-        "Generate {} code for the following:\n\n{}",
-        lang.name(), description
+        "Generate {} code for the following:\n\n{}\n\n{}",
+        lang.name(), description, Environment::session().prompt_context(description)
     );
 
     print_thinking_with_provider(lang, "Claude");
@@ -222,8 +286,9 @@ async fn run_with_claude(description: &str, lang: Language, output: Option<&str>
     match conversation.send(&prompt).await {
         Ok(response) => {
             clear_line();
-            let code = clean_code_response(&response);
-            handle_output(output, &code, lang, description);
+            let code = lang.postprocess(pipeline, &clean_code_response(&response));
+            let provenance = Provenance::new("Claude", description);
+            handle_output(output, &code, lang, description, provenance, compliance).await;
         }
         Err(e) => {
             clear_line();
@@ -235,16 +300,19 @@ async fn run_with_claude(description: &str, lang: Language, output: Option<&str>
 }
 
 /// Run code generation with NEXUS Proxy (free tier, Gemini-powered)
-async fn run_with_proxy(description: &str, lang: Language, output: Option<&str>) -> Result<()> {
+async fn run_with_proxy(description: &str, lang: Language, output: Option<&str>, compliance: &ComplianceConfig, pipeline: &Pipeline) -> Result<()> {
     let proxy = ProxyClient::from_env();
 
     print_thinking_with_provider(lang, "NEXUS AI (Free)");
 
-    match proxy.generate(description, lang.code_fence()).await {
+    let request = format!("{}\n\n{}", description, Environment::session().prompt_context(description));
+
+    match proxy.generate(&request, lang.code_fence()).await {
         Ok(code) => {
             clear_line();
-            let code = clean_code_response(&code);
-            handle_output(output, &code, lang, description);
+            let code = lang.postprocess(pipeline, &clean_code_response(&code));
+            let provenance = Provenance::new("NEXUS AI (Free)", description);
+            handle_output(output, &code, lang, description, provenance, compliance).await;
         }
         Err(e) => {
             clear_line();
@@ -256,19 +324,130 @@ async fn run_with_proxy(description: &str, lang: Language, output: Option<&str>)
     Ok(())
 }
 
+/// Run code generation with the local model
+async fn run_with_local(description: &str, lang: Language, output: Option<&str>, compliance: &ComplianceConfig, pipeline: &Pipeline) -> Result<()> {
+    let client = AiRouter::local_client().with_system(&get_system_prompt(lang));
+
+    let prompt = format!(
+        "Generate {} code for the following:\n\n{}\n\n{}",
+        lang.name(), description, Environment::session().prompt_context(description)
+    );
+
+    print_thinking_with_provider(lang, "Local model");
+
+    match client.chat(&prompt).await {
+        Ok(response) => {
+            clear_line();
+            let code = lang.postprocess(pipeline, &clean_code_response(&response));
+            let provenance = Provenance::new("Local model", description);
+            handle_output(output, &code, lang, description, provenance, compliance).await;
+        }
+        Err(e) => {
+            clear_line();
+            print_error(&format!("Local model error: {}", e));
+        }
+    }
+
+    Ok(())
+}
+
 /// Handle the generated code output
-fn handle_output(output: Option<&str>, code: &str, lang: Language, description: &str) {
+async fn handle_output(
+    output: Option<&str>,
+    code: &str,
+    lang: Language,
+    description: &str,
+    mut provenance: Provenance,
+    compliance: &ComplianceConfig,
+) {
+    let missing = new_dependencies(code, lang);
+    print_dependency_warnings(&missing);
+
+    // Scripts and CI configs get a static safety pass before anything is written
+    let script_findings = output
+        .and_then(|o| ScriptKind::from_path(Path::new(o)))
+        .or_else(|| lang.script_kind())
+        .map(|kind| script_safety::scan(code, kind));
+
     if let Some(output_path) = output {
-        if let Err(e) = write_to_file(output_path, code) {
+        if let Some(findings) = &script_findings {
+            print_code_preview(code, lang, findings);
+            print_safety_summary(findings);
+
+            let question = format!("Write this script to {}?", output_path);
+            if !NexusForm::ask_confirm(&question, findings.is_empty()).unwrap_or(false) {
+                println!("{}  Script not written.{}", colors::MUTED, colors::RESET);
+                return;
+            }
+        }
+
+        if compliance.similarity_check {
+            if let Err(e) = provenance.check(code, Path::new("."), Some(Path::new(output_path)), compliance).await {
+                print_error(&format!("Similarity check failed: {}", e));
+            }
+            print_similarity_findings(&provenance);
+
+            if provenance.is_flagged()
+                && !NexusForm::ask_confirm("Write the file anyway?", false).unwrap_or(false)
+            {
+                return;
+            }
+        }
+
+        let code = match hooks::pre_apply(output_path, code.to_string()) {
+            Ok(code) => code,
+            Err(e) => {
+                print_error(&format!("{:#}", e));
+                return;
+            }
+        };
+        if let Err(e) = write_to_file(output_path, &code) {
             print_error(&format!("Failed to write file: {}", e));
             return;
         }
-        print_file_created(output_path, code);
+        Journal::current()
+            .record("generate", Change::FileWritten {
+                path: output_path.to_string(),
+                provenance: Some(provenance),
+            })
+            .ok();
+        print_file_created(output_path, &code);
+        tidy_written(output_path);
+        hooks::post_apply(output_path, &fs::read_to_string(output_path).unwrap_or_else(|_| code.to_string()));
     } else {
         let suggested_name = suggest_filename(description, lang);
-        print_code_preview(code, lang);
+        let findings = script_findings.unwrap_or_default();
+        print_code_preview(code, lang, &findings);
+        print_safety_summary(&findings);
         print_save_suggestion(&suggested_name);
     }
+
+    offer_dependency_install(&missing);
+}
+
+/// Offer to add each missing dependency through the package manager
+fn offer_dependency_install(missing: &[MissingDependency]) {
+    for dep in missing {
+        let action = format!("run `{}`", dep.add_command().join(" "));
+        if let Err(e) = permissions::request(Permission::Run, &action) {
+            print_error(&format!("{:#}", e));
+            continue;
+        }
+
+        match dep.install("generate") {
+            Ok(()) => println!(
+                "{}  {} Added {} to {}{}",
+                colors::SUCCESS, symbols::SUCCESS, dep.name, dep.kind.file_name(), colors::RESET
+            ),
+            Err(e) => print_error(&format!("Failed to add {}: {}", dep.name, e)),
+        }
+    }
+}
+
+/// Imports in generated code that the project manifest does not declare
+fn new_dependencies(code: &str, lang: Language) -> Vec<MissingDependency> {
+    let language = parser::Language::from_extension(lang.extension());
+    imports::missing_dependencies(code, language, &Environment::session().manifests)
 }
 
 /// Print help for proxy connection issues
@@ -299,7 +478,7 @@ fn determine_language(output: Option<&str>, language: Option<&str>) -> Result<La
     if let Some(lang_name) = language {
         let lang = Language::from_name(lang_name);
         if matches!(lang, Language::Unknown) {
-            anyhow::bail!("Unknown language: {}. Supported: rust, python, javascript, typescript, go, java, csharp, ruby, swift, kotlin", lang_name);
+            anyhow::bail!("Unknown language: {}. Supported: rust, python, javascript, typescript, go, java, csharp, ruby, swift, kotlin, shell, powershell, yaml", lang_name);
         }
         return Ok(lang);
     }
@@ -379,8 +558,25 @@ fn suggest_filename(description: &str, lang: Language) -> String {
 
 /// Write code to file
 fn write_to_file(path: &str, code: &str) -> Result<()> {
-    fs::write(path, code)
-        .with_context(|| format!("Failed to write to {}", path))
+    permissions::write_file(path, code)
+}
+
+/// Organize the imports of a file just written, then run the project's
+/// formatter on it
+fn tidy_written(path: &str) {
+    let root = Path::new(".");
+    let project = match ProjectConfig::load(root) {
+        Ok(project) => project,
+        Err(e) => return print_tidy_warning(&format!("{:#}", e)),
+    };
+    match import_organizer::organize_file(root, Path::new(path), &project.imports) {
+        Ok(Some(changes)) => print_import_changes(&changes),
+        Ok(None) => {}
+        Err(e) => print_tidy_warning(&format!("Imports not organized: {:#}", e)),
+    }
+    if let Some(outcome) = formatter::format_file(root, Path::new(path), &project.format) {
+        print_format_outcome(&outcome);
+    }
 }
 
 /// Print the header
@@ -433,7 +629,7 @@ fn clear_line() {
 }
 
 /// Print code preview (when no output file)
-fn print_code_preview(code: &str, _lang: Language) {
+fn print_code_preview(code: &str, _lang: Language, findings: &[SafetyFinding]) {
     println!();
     println!(
         "{}{}  {} Generated Code {}",
@@ -457,6 +653,9 @@ fn print_code_preview(code: &str, _lang: Language) {
             colors::FG,
             line
         );
+        for finding in findings.iter().filter(|f| f.line == i + 1) {
+            print_safety_finding(finding);
+        }
     }
 
     if lines.len() > max_lines {
@@ -464,6 +663,15 @@ fn print_code_preview(code: &str, _lang: Language) {
             "{}  │ {}... ({} more lines){}",
             colors::MUTED, colors::DIM, lines.len() - max_lines, colors::RESET
         );
+        // Findings past the preview are still shown
+        for finding in findings.iter().filter(|f| f.line > max_lines) {
+            println!(
+                "{}  │ {}{:>4}{} {}{}",
+                colors::MUTED, colors::DIM, finding.line, colors::RESET,
+                colors::FG, lines[finding.line - 1]
+            );
+            print_safety_finding(finding);
+        }
     }
 
     println!(
@@ -506,6 +714,60 @@ fn print_file_created(path: &str, code: &str) {
     println!();
 }
 
+fn print_import_changes(changes: &ImportChanges) {
+    if changes.is_empty() {
+        return;
+    }
+    let mut parts = Vec::new();
+    if !changes.added.is_empty() {
+        parts.push(format!("{} added", changes.added.len()));
+    }
+    if !changes.removed.is_empty() {
+        parts.push(format!("{} unused removed", changes.removed.len()));
+    }
+    if changes.duplicates > 0 {
+        parts.push(format!("{} duplicate removed", changes.duplicates));
+    }
+    if changes.sorted {
+        parts.push("sorted".to_string());
+    }
+    if let Some(tool) = &changes.tool {
+        parts.push(format!("ran {}", tool));
+    }
+    println!(
+        "{}  {} Imports: {}{}",
+        colors::SUCCESS, symbols::SUCCESS, parts.join(", "), colors::RESET
+    );
+    for import in changes.added.iter().map(|i| format!("+ {}", i)).chain(changes.removed.iter().map(|i| format!("- {}", i))) {
+        println!("{}      {}{}", colors::MUTED, import, colors::RESET);
+    }
+}
+
+fn print_format_outcome(outcome: &FormatOutcome) {
+    let (color, symbol, message) = match outcome {
+        FormatOutcome::Formatted(formatter) => {
+            (colors::SUCCESS, symbols::SUCCESS, format!("Formatted with {}", formatter))
+        }
+        FormatOutcome::Unchanged(formatter) => {
+            (colors::MUTED, symbols::SUCCESS, format!("Already formatted ({})", formatter))
+        }
+        FormatOutcome::NotInstalled(formatter) => {
+            (colors::WARNING, symbols::WARNING, format!("{} is not installed; file left unformatted", formatter))
+        }
+        FormatOutcome::Failed { formatter, message } => {
+            (colors::WARNING, symbols::WARNING, format!("{} failed: {}", formatter, message))
+        }
+    };
+    println!("{}  {} {}{}", color, symbol, message, colors::RESET);
+}
+
+fn print_tidy_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
 /// Print save suggestion
 fn print_save_suggestion(filename: &str) {
     println!(
@@ -515,6 +777,85 @@ fn print_save_suggestion(filename: &str) {
     println!();
 }
 
+/// Warn about imports of packages missing from the manifest
+fn print_dependency_warnings(missing: &[MissingDependency]) {
+    if missing.is_empty() {
+        return;
+    }
+
+    println!();
+    for dep in missing {
+        println!(
+            "{}  {} New dependency not in {}: {}{}{}",
+            colors::WARNING, symbols::WARNING, dep.kind.file_name(),
+            colors::FG, dep.name, colors::RESET
+        );
+    }
+}
+
+/// Print a safety finding under the line it refers to
+fn print_safety_finding(finding: &SafetyFinding) {
+    let color = match finding.severity {
+        Severity::High => colors::ERROR,
+        Severity::Medium => colors::WARNING,
+        Severity::Low => colors::MUTED,
+    };
+    println!(
+        "{}  │      {}{} {} [{}]: {}{}",
+        colors::MUTED, color, symbols::WARNING,
+        finding.severity.label(), finding.rule, finding.message, colors::RESET
+    );
+}
+
+/// Summarize the safety pass for a script
+fn print_safety_summary(findings: &[SafetyFinding]) {
+    if findings.is_empty() {
+        return;
+    }
+
+    let high = findings.iter().filter(|f| f.severity == Severity::High).count();
+    println!(
+        "{}  {} {} potential safety issue(s), {} high severity. Review before running.{}",
+        colors::WARNING, symbols::WARNING, findings.len(), high, colors::RESET
+    );
+    println!();
+}
+
+/// Report near-verbatim copies found by the similarity check
+fn print_similarity_findings(provenance: &Provenance) {
+    if !provenance.checked {
+        return;
+    }
+
+    println!();
+    if !provenance.is_flagged() {
+        println!(
+            "{}  {} No near-verbatim copies found{}",
+            colors::MUTED, symbols::SUCCESS, colors::RESET
+        );
+        return;
+    }
+
+    for m in &provenance.local_matches {
+        println!(
+            "{}  {} {:.0}% similar to {}{}:{}-{}{}",
+            colors::WARNING, symbols::WARNING, m.score * 100.0,
+            colors::FG, m.path, m.line_start, m.line_end, colors::RESET
+        );
+    }
+    for m in &provenance.public_matches {
+        println!(
+            "{}  {} Matches public code in {}{} ({}){}",
+            colors::WARNING, symbols::WARNING,
+            colors::FG, m.repository, m.url, colors::RESET
+        );
+    }
+    println!(
+        "{}  Check the license of the original before using this code.{}",
+        colors::MUTED, colors::RESET
+    );
+}
+
 /// Print error message
 fn print_error(message: &str) {
     println!(
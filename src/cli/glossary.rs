@@ -0,0 +1,280 @@
+//! Glossary command - domain terms of the codebase
+//!
+//! Mines identifiers and comments for the vocabulary specific to this
+//! project, has the AI group synonyms and define each term, and writes a
+//! Markdown glossary with references back into the code.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::glossary::{self, Definition, Entry, Term};
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::core::parser::{CodeParser, ParsedFile};
+use crate::index;
+use crate::ui::NexusForm;
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const GLOSSARY: &str = "󰗚";
+    pub const AI_ICON: &str = "󰌤";
+    pub const TERM: &str = "󰓹";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for defining terms
+const GLOSSARY_PROMPT: &str = r#"You are NEXUS AI, an expert at explaining legacy codebases to newcomers.
+
+You are given words mined from a codebase's identifiers and comments, each
+with its spellings, example identifiers and comment lines that mention it.
+
+Build a glossary of the project's domain vocabulary:
+1. Skip words that are generic programming vocabulary rather than domain terms
+2. Group words that mean the same thing (e.g. "client", "customer",
+   "account_holder") into one entry; list every grouped word in "members"
+3. Define each entry in 1-2 plain sentences, as this codebase uses it
+4. Base definitions on the evidence given; do not invent features
+
+Respond with only a JSON array:
+[{"term": "Customer", "aliases": ["client"], "members": ["customer", "client"],
+  "definition": "..."}]"#;
+
+/// Example identifiers sent per term
+const MAX_EXAMPLES: usize = 4;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+/// Build the project glossary
+pub async fn run(config: Config, limit: usize, min_files: usize, output: &str) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    print_header(provider_name);
+
+    let root = Path::new(".");
+    let mut parser = CodeParser::new().context("Failed to initialize code parser")?;
+    let files: Vec<ParsedFile> = index::collect_files(root, false)?
+        .iter()
+        .filter_map(|f| parser.parse_file(f).ok())
+        .collect();
+
+    let terms = glossary::mine(&files, min_files, limit);
+    if terms.is_empty() {
+        print_error(&format!("No terms used in at least {} files", min_files));
+        return Ok(());
+    }
+    print_terms(&terms, files.len());
+
+    if Path::new(output).exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", output), false)? {
+        print_warning("Glossary not written");
+        return Ok(());
+    }
+
+    // Comment lines from excluded paths stay local; identifiers are sent
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let listing: Vec<String> = terms
+        .iter()
+        .map(|t| {
+            let mut examples: Vec<&str> = t.references.iter().map(|r| r.symbol.as_str()).collect();
+            examples.dedup();
+            examples.truncate(MAX_EXAMPLES);
+            let mut entry = format!(
+                "- {} (spellings: {}; {} files; identifiers: {})",
+                t.name,
+                t.variants.iter().cloned().collect::<Vec<_>>().join(", "),
+                t.files,
+                examples.join(", ")
+            );
+            for (path, snippet) in &t.snippets {
+                if !exclusions.is_excluded(path) {
+                    entry.push_str(&format!("\n  comment: {}", context::sanitize(snippet)));
+                }
+            }
+            entry
+        })
+        .collect();
+
+    let prompt = format!(
+        "{}\n\n{}\n\n## Task\n\nWrite the glossary.",
+        context::REPOSITORY_CONTENT_RULES,
+        context::delimit("mined terms", &listing.join("\n"))
+    );
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(GLOSSARY_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", GLOSSARY_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(GLOSSARY_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+
+    let definitions = parse_definitions(&response);
+    let entries = glossary::entries(&terms, definitions);
+    if entries.is_empty() {
+        print_error("Could not read any definitions from the response");
+        return Ok(());
+    }
+
+    if let Some(parent) = Path::new(output).parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    permissions::write_file(output, glossary::render_markdown(&entries, root))?;
+    Journal::current()
+        .record("glossary", Change::FileWritten { path: output.to_string(), provenance: None })
+        .ok();
+
+    print_entries(&entries);
+    print_saved(output, entries.len());
+    Ok(())
+}
+
+/// Definitions from the JSON array in the response
+fn parse_definitions(response: &str) -> Vec<Definition> {
+    let (Some(start), Some(end)) = (response.find('['), response.rfind(']')) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&response[start..=end]).unwrap_or_default()
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(provider: &str) {
+    println!();
+    println!(
+        "{}{}  {} Glossary{}",
+        colors::PRIMARY, colors::BOLD, symbols::GLOSSARY, colors::RESET
+    );
+    println!(
+        "{}  │ {}Domain terms via {}{}",
+        colors::MUTED, colors::FG, provider, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_terms(terms: &[Term], file_count: usize) {
+    let names: Vec<&str> = terms.iter().map(|t| t.name.as_str()).collect();
+    println!(
+        "{}  {} terms mined from {} files{}",
+        colors::MUTED, terms.len(), file_count, colors::RESET
+    );
+    println!("{}  {}{}", colors::FG, names.join(", "), colors::RESET);
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is defining terms {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_entries(entries: &[Entry]) {
+    for entry in entries {
+        let aliases = if entry.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", entry.aliases.join(", "))
+        };
+        println!(
+            "{}  {} {}{}{}{}{}{}",
+            colors::MUTED, symbols::TERM, colors::FG, colors::BOLD, entry.term, colors::RESET,
+            colors::MUTED, aliases
+        );
+    }
+    println!();
+}
+
+fn print_saved(path: &str, count: usize) {
+    println!(
+        "{}{}  {} {} terms saved to {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, count, path, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
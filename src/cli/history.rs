@@ -0,0 +1,135 @@
+//! History command - saved chat sessions
+//!
+//! Lists the chats saved under `.nexus/history/` and exports them as
+//! Markdown, HTML or JSON transcripts.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions;
+use crate::core::transcript::{ExportFormat, Transcript};
+use crate::ui::NexusForm;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const HISTORY: &str = "󰋚";
+    pub const CHAT: &str = "󰭹";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// List saved chats, newest first
+pub async fn list(_config: Config) -> Result<()> {
+    print_header("Saved chats");
+
+    let transcripts = Transcript::list(Path::new("."))?;
+    if transcripts.is_empty() {
+        print_warning("No saved chats yet; start one with `nexus chat`");
+        return Ok(());
+    }
+
+    for t in &transcripts {
+        println!(
+            "{}  {} {}{}{}  {} · {} messages · {}{}",
+            colors::MUTED, symbols::CHAT, colors::FG, t.id, colors::MUTED,
+            t.title(), t.messages.len(), t.provider, colors::RESET
+        );
+    }
+    println!();
+    println!(
+        "{}  Export with: nexus history export <id> --format markdown|html|json{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+    Ok(())
+}
+
+/// Export a saved chat
+pub async fn export(_config: Config, id: &str, format: &str, output: Option<&str>) -> Result<()> {
+    print_header(&format!("Export {}", id));
+
+    let Some(format) = ExportFormat::parse(format) else {
+        print_error(&format!("Unknown format `{}` (expected markdown, html or json)", format));
+        return Ok(());
+    };
+    let transcript = match Transcript::load(Path::new("."), id) {
+        Ok(t) => t,
+        Err(e) => {
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+
+    let path = output.map(String::from).unwrap_or_else(|| transcript.file_name(format));
+    if Path::new(&path).exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", path), false)? {
+        print_warning("Export cancelled");
+        return Ok(());
+    }
+
+    permissions::write_file(&path, transcript.render(format)?)?;
+    Journal::current()
+        .record("history export", Change::FileWritten { path: path.clone(), provenance: None })
+        .ok();
+
+    print_success(&format!("{} messages saved to {}", transcript.messages.len(), path));
+    Ok(())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(detail: &str) {
+    println!();
+    println!(
+        "{}{}  {} History{}",
+        colors::PRIMARY, colors::BOLD, symbols::HISTORY, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, detail, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -2,12 +2,15 @@
 
 use anyhow::Result;
 
+use crate::core::packaging::Flavor;
+
 pub fn run() -> Result<()> {
     println!("NEXUS AI Forge v{}", env!("CARGO_PKG_VERSION"));
     println!();
     println!("System Information:");
     println!("  OS: {} {}", std::env::consts::OS, std::env::consts::ARCH);
     println!("  Rust: {}", rustc_version());
+    println!("  Installed as: {}", Flavor::current().label());
 
     println!();
     println!("Configuration:");
@@ -0,0 +1,198 @@
+//! Memory command - what NEXUS remembers about this project
+//!
+//! Shows, edits and prunes `.nexus/memory.md`, and distills finished chats
+//! and reviews into it when `[memory] enabled = true` in `.nexus.toml`.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{Config, ProjectConfig};
+use crate::core::memory::{self, ProjectMemory};
+use crate::core::permissions::{self, Permission};
+use crate::ui::NexusForm;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const MEMORY: &str = "󰍛";
+    pub const FACT: &str = "•";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// List the remembered facts
+pub async fn show(_config: Config) -> Result<()> {
+    let memory = ProjectMemory::current();
+    print_header(&memory.path().display().to_string());
+
+    let facts = memory.facts()?;
+    if facts.is_empty() {
+        print_warning("Nothing remembered yet");
+    }
+    for fact in &facts {
+        println!("{}  {} {}{}{}", colors::MUTED, symbols::FACT, colors::FG, fact, colors::RESET);
+    }
+    println!();
+    print_enabled_hint();
+    Ok(())
+}
+
+/// Open the memory file in `$VISUAL` / `$EDITOR`
+pub async fn edit(_config: Config) -> Result<()> {
+    let memory = ProjectMemory::current();
+    memory.ensure_exists()?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
+    // `code --wait` and similar carry their own arguments
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    permissions::request(Permission::Run, &format!("open {} in `{}`", memory.path().display(), editor))?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(memory.path())
+        .status()
+        .with_context(|| format!("Failed to start editor `{}` (set $EDITOR)", editor))?;
+    if !status.success() {
+        anyhow::bail!("Editor exited with an error");
+    }
+
+    print_success(&format!("{} facts remembered", memory.facts()?.len()));
+    print_enabled_hint();
+    Ok(())
+}
+
+/// Remove the facts containing `pattern`, or all of them
+pub async fn forget(_config: Config, pattern: Option<&str>, all: bool) -> Result<()> {
+    let memory = ProjectMemory::current();
+    print_header("Forget");
+
+    if all {
+        if !NexusForm::ask_confirm("Forget everything remembered about this project?", false)? {
+            print_warning("Nothing forgotten");
+            return Ok(());
+        }
+        let count = memory.forget_all()?;
+        print_success(&format!("Forgot {} facts", count));
+        return Ok(());
+    }
+
+    let Some(pattern) = pattern.filter(|p| !p.trim().is_empty()) else {
+        print_error("Name what to forget, e.g. `nexus memory forget sqlite`, or use --all");
+        return Ok(());
+    };
+    let removed = memory.forget(pattern)?;
+    if removed.is_empty() {
+        print_warning(&format!("No remembered fact contains \"{}\"", pattern));
+        return Ok(());
+    }
+    for fact in &removed {
+        println!("{}  {} {}{}", colors::MUTED, symbols::FACT, fact, colors::RESET);
+    }
+    println!();
+    print_success(&format!("Forgot {} facts", removed.len()));
+    Ok(())
+}
+
+/// Distill a finished chat or review into project memory, when memory is
+/// on; `ask` sends (system prompt, message) to the session's provider
+pub async fn remember<F, Fut>(session: &str, ask: F)
+where
+    F: FnOnce(String, String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    if !memory::enabled() || session.trim().is_empty() {
+        return;
+    }
+    println!("{}  Updating project memory...{}", colors::MUTED, colors::RESET);
+    match ProjectMemory::current().distill(session, ask).await {
+        Ok(added) if added.is_empty() => {
+            println!("{}  Nothing new to remember{}", colors::MUTED, colors::RESET);
+        }
+        Ok(added) => {
+            for fact in &added {
+                println!("{}  {} {}{}", colors::MUTED, symbols::FACT, fact, colors::RESET);
+            }
+            println!(
+                "{}  {} Remembered {} new facts (nexus memory show){}",
+                colors::SUCCESS, symbols::SUCCESS, added.len(), colors::RESET
+            );
+        }
+        Err(e) => print_warning(&format!("Project memory not updated: {:#}", e)),
+    }
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(detail: &str) {
+    println!();
+    println!(
+        "{}{}  {} Memory{}",
+        colors::PRIMARY, colors::BOLD, symbols::MEMORY, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, detail, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_enabled_hint() {
+    if ProjectConfig::load(Path::new(".")).is_ok_and(|project| project.memory.enabled) {
+        return;
+    }
+    println!(
+        "{}  Memory is off for this project; set `enabled = true` under [memory] in .nexus.toml{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
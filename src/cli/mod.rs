@@ -1,20 +1,39 @@
 //! CLI command implementations
 
+pub mod affected;
+pub mod api;
 pub mod ask;
+pub mod bundle;
 pub mod chat;
+pub mod comments;
 pub mod commit;
 pub mod convert;
+pub mod dashboard;
+pub mod diagram;
 pub mod diff;
 pub mod doc;
+pub mod eval;
+pub mod examples;
 pub mod explain;
 pub mod fix;
+pub mod fuzz;
+pub mod glossary;
 pub mod generate;
+pub mod history;
 pub mod index;
 pub mod info;
 pub mod init;
+pub mod memory;
+pub mod naming;
 pub mod optimize;
+pub mod package;
+pub mod record;
 pub mod refactor;
 pub mod review;
 pub mod search;
+pub mod services;
+pub mod shrink;
+pub mod similar;
 pub mod test;
+pub mod tour;
 pub mod update;
@@ -0,0 +1,174 @@
+//! Naming command - naming consistency across the codebase
+//!
+//! Reports symbols whose casing breaks their language's majority style and
+//! concepts reached through synonym verbs, with the scheme the codebase
+//! already leans towards and a rename plan to apply it.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions;
+use crate::core::naming::{self, Report};
+use crate::core::parser::{CodeParser, ParsedFile};
+use crate::index;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const NAMING: &str = "󰗧";
+    pub const RULE: &str = "󰄲";
+    pub const RENAME: &str = "󰑕";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Renames printed per finding before eliding the rest
+const MAX_SHOWN: usize = 8;
+
+/// Check naming consistency under `path`
+pub async fn check(_config: Config, path: &str, output: Option<&str>) -> Result<()> {
+    print_header(path);
+
+    let root = Path::new(path);
+    if !root.exists() {
+        print_error(&format!("Path not found: {}", path));
+        return Ok(());
+    }
+
+    let mut parser = CodeParser::new().context("Failed to initialize code parser")?;
+    let files: Vec<ParsedFile> = index::collect_files(root, false)?
+        .iter()
+        .filter_map(|f| parser.parse_file(f).ok())
+        .collect();
+
+    let report = naming::analyze(&files);
+    print_scheme(&report);
+
+    if report.findings.is_empty() {
+        print_success(&format!("Naming is consistent across {} files", files.len()));
+        return Ok(());
+    }
+    print_findings(&report);
+
+    if let Some(output) = output {
+        let content = if output.ends_with(".json") {
+            serde_json::to_string_pretty(&report)?
+        } else {
+            naming::render_markdown(&report)
+        };
+        permissions::write_file(output, content)?;
+        Journal::current()
+            .record("naming check", Change::FileWritten { path: output.to_string(), provenance: None })
+            .ok();
+        print_success(&format!("Rename plan saved to {}", output));
+    } else {
+        println!(
+            "{}  Save the rename plan with -o naming.md or -o naming.json{}",
+            colors::MUTED, colors::RESET
+        );
+        println!();
+    }
+
+    Ok(())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(path: &str) {
+    println!();
+    println!(
+        "{}{}  {} Naming Check{}",
+        colors::PRIMARY, colors::BOLD, symbols::NAMING, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, path, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_scheme(report: &Report) {
+    println!("{}{}  Scheme{}", colors::FG, colors::BOLD, colors::RESET);
+    for rule in &report.cases {
+        println!(
+            "{}  {} {}{} {}: {}{} ({}/{}){}",
+            colors::MUTED, symbols::RULE, colors::FG, rule.language, rule.kind,
+            rule.case.label(), colors::MUTED, rule.conforming, rule.total, colors::RESET
+        );
+    }
+    for rule in &report.verbs {
+        let used: Vec<String> = rule.used.iter().map(|(v, n)| format!("{} ×{}", v, n)).collect();
+        println!(
+            "{}  {} {}use `{}`{} ({}){}",
+            colors::MUTED, symbols::RULE, colors::FG, rule.preferred, colors::MUTED,
+            used.join(", "), colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_findings(report: &Report) {
+    for finding in &report.findings {
+        println!(
+            "{}  {} {}{}",
+            colors::WARNING, symbols::WARNING, finding.issue.describe(), colors::RESET
+        );
+        for r in finding.renames.iter().take(MAX_SHOWN) {
+            println!(
+                "{}    {} {}{} → {}{} {}:{} · {} refs{}{}",
+                colors::MUTED, symbols::RENAME, colors::FG, r.from, r.to, colors::MUTED,
+                r.path.display(), r.line, r.references,
+                if r.conflict { " · name taken" } else { "" },
+                colors::RESET
+            );
+        }
+        if finding.renames.len() > MAX_SHOWN {
+            println!(
+                "{}    … {} more{}",
+                colors::MUTED, finding.renames.len() - MAX_SHOWN, colors::RESET
+            );
+        }
+    }
+    println!();
+    println!(
+        "{}  {} inconsistencies, {} renames planned{}",
+        colors::MUTED, report.findings.len(), report.plan().len(), colors::RESET
+    );
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -5,11 +5,14 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::deep::{self, DeepResult, DeepTask, Stage};
+use crate::ai::postprocess::Pipeline;
 use crate::config::Config;
 use crate::core::parser::{CodeParser, Language, SymbolKind};
 
@@ -18,6 +21,7 @@ use crate::core::parser::{CodeParser, Language, SymbolKind};
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -96,14 +100,16 @@ Be specific with line numbers and provide before/after comparisons."#;
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, file: &str, focus: Option<&str>) -> Result<()> {
+pub async fn run(config: Config, file: &str, focus: Option<&str>, deep: bool) -> Result<()> {
     let path = Path::new(file);
 
     // Verify file exists
@@ -113,7 +119,7 @@ pub async fn run(_config: Config, file: &str, focus: Option<&str>) -> Result<()>
     }
 
     // Read source file
-    let content = fs::read_to_string(path)?;
+    let content = CloudExclusions::from_config(&config.privacy)?.read_file(path)?;
     let lang = Language::from_path(path);
     let lines = content.lines().count();
 
@@ -149,6 +155,7 @@ pub async fn run(_config: Config, file: &str, focus: Option<&str>) -> Result<()>
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Build focus area context
@@ -160,18 +167,37 @@ pub async fn run(_config: Config, file: &str, focus: Option<&str>) -> Result<()>
         Some(other) => &format!("\n\nFocus on: {}", other),
     };
 
+    context::print_injection_warnings(file, &content);
+
     // Prepare prompt
     let prompt = format!(
-        "## Code to Optimize\n\n**File:** `{}`\n**Language:** {}\n**Lines:** {}\n\n### Symbols Found:\n{}\n\n```{}\n{}\n```{}",
+        "{}\n\n## Code to Optimize\n\n**File:** `{}`\n**Language:** {}\n**Lines:** {}\n\n### Symbols Found:\n{}\n\n{}{}",
+        context::REPOSITORY_CONTENT_RULES,
         file,
         lang,
         lines,
         symbols_summary.join("\n"),
-        lang.to_string().to_lowercase(),
-        content,
+        context::delimit(file, &content),
         focus_context
     );
 
+    if deep {
+        let task = DeepTask { system: OPTIMIZE_PROMPT, code: &prompt, goal: "performance analysis" };
+        let response = deep::run(
+            &task,
+            &config.deep,
+            |system, prompt| ask(ai_mode, system, prompt),
+            print_deep_stage,
+        )
+        .await;
+        clear_line();
+        let result = response?;
+        print_deep_summary(&result);
+        let response = Pipeline::for_command(&config.postprocess, "optimize").apply(&result.response);
+        print_response(&response);
+        return Ok(());
+    }
+
     // Send to AI
     print_thinking(provider_name);
 
@@ -188,14 +214,27 @@ pub async fn run(_config: Config, file: &str, focus: Option<&str>) -> Result<()>
             let prompt_with_system = format!("{}\n\n{}", OPTIMIZE_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(OPTIMIZE_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "optimize").apply(&response);
     print_response(&response);
 
     Ok(())
 }
 
+/// One stateless request, for the passes of a deep analysis
+async fn ask(mode: AiMode, system: String, prompt: String) -> Result<String> {
+    match mode {
+        AiMode::Claude => ClaudeClient::from_env()?.send_with_system(&prompt, &system).await,
+        AiMode::Proxy => ProxyClient::from_env().chat(&format!("{}\n\n{}", system, prompt), None).await,
+        AiMode::Local => AiRouter::local_client().with_system(&system).chat(&prompt).await,
+    }
+}
+
 // ============================================
 // UI Functions
 // ============================================
@@ -237,6 +276,39 @@ fn print_thinking(provider: &str) {
     io::stdout().flush().ok();
 }
 
+/// Show which pass of a deep analysis is running
+fn print_deep_stage(stage: &Stage) {
+    let label = match stage {
+        Stage::Outline => "Outlining the areas to examine".to_string(),
+        Stage::DeepDive { index, total, area } => format!("Deep dive {}/{}: {}", index, total, area),
+        Stage::Synthesis => "Writing the report".to_string(),
+    };
+    clear_line();
+    print!(
+        "\r{}  {} {} {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        crate::ui::text::truncate(&label, 60),
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+/// Print how a deep analysis spent its budget
+fn print_deep_summary(result: &DeepResult) {
+    println!(
+        "{}  {} Deep analysis: {} passes, ~{} tokens, {}s{}",
+        colors::MUTED, symbols::SUCCESS, result.passes, result.tokens, result.elapsed.as_secs(), colors::RESET
+    );
+    if !result.skipped.is_empty() {
+        println!(
+            "{}  {} Budget reached; not examined in depth: {} (raise [deep] limits in the config file){}",
+            colors::WARNING, symbols::WARNING, result.skipped.join(", "), colors::RESET
+        );
+    }
+}
+
 fn clear_line() {
     print!("\r{}\r", " ".repeat(70));
     io::stdout().flush().ok();
@@ -262,7 +334,7 @@ fn print_response(response: &str) {
         } else if line.contains("🟢") || line.contains("Minor") {
             format!("{}{}", colors::PERF_LOW, line)
         } else {
-            format!("{}", line)
+            line.to_string()
         };
 
         println!("{}  │ {}{}", colors::MUTED, colored_line, colors::RESET);
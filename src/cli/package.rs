@@ -0,0 +1,51 @@
+//! Package command - release packaging helpers
+//!
+//! Generates the Homebrew formula and Scoop manifest of a release from its
+//! `SHA256SUMS` file. The release workflow runs this after uploading the
+//! binaries; the manifests go to the tap and bucket repositories.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+
+use crate::core::packaging::{self, Release};
+use crate::core::permissions;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+}
+
+mod symbols {
+    pub const SUCCESS: &str = "󰄂";
+}
+
+/// Print or write the `homebrew` or `scoop` manifest of a release. Without
+/// `output` only the manifest is printed, so it can be redirected.
+pub fn manifest(format: &str, version: Option<&str>, checksums: &str, output: Option<&str>) -> Result<()> {
+    let sums = fs::read_to_string(checksums).with_context(|| format!("Failed to read {}", checksums))?;
+    let release = Release {
+        version: version.unwrap_or(env!("CARGO_PKG_VERSION")).trim_start_matches('v').to_string(),
+        checksums: packaging::parse_checksums(&sums),
+    };
+
+    let manifest = match format {
+        "homebrew" => packaging::homebrew_formula(&release)?,
+        "scoop" => packaging::scoop_manifest(&release)?,
+        other => bail!("Unknown manifest format `{}` (expected homebrew or scoop)", other),
+    };
+
+    match output {
+        Some(path) => {
+            permissions::write_file(path, manifest)?;
+            println!(
+                "{}  {} {} manifest for v{} written to {}{}",
+                colors::SUCCESS, symbols::SUCCESS, format, release.version, path, colors::RESET
+            );
+        }
+        None => print!("{}", manifest),
+    }
+    Ok(())
+}
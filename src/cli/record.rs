@@ -0,0 +1,452 @@
+//! Record command - traffic capture and contract test generation
+//!
+//! Proxies a running service while you exercise it by hand, then turns
+//! representative requests into replayable contract tests mapped to the
+//! handlers that serve them.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context;
+use crate::config::Config;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions;
+use crate::core::manifest::{Manifest, ManifestKind};
+use crate::core::parser::Language;
+use crate::core::recording::{self, Exchange, Sample};
+use crate::index::{self, endpoints};
+use crate::ui::{text, NexusForm};
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const RECORD: &str = "󰑊";
+    pub const AI_ICON: &str = "󰌤";
+    pub const FILE: &str = "󰈙";
+    pub const ARROW: &str = "→";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for contract test generation
+const RECORD_PROMPT: &str = r#"You are NEXUS AI, an expert in API contract testing.
+
+You are given representative HTTP exchanges recorded against a running
+service, each mapped (when known) to the handler that serves it.
+
+Generate integration tests that replay each request against the service and
+assert on its contract:
+- The status code
+- The response content type
+- The structure of JSON bodies (required keys and value types), not volatile
+  values such as ids, timestamps or tokens
+- Stable scalar values only when they are clearly part of the contract
+
+Guidelines:
+- One test per sample, named after the handler and the scenario
+- Read the base URL from the NEXUS_CONTRACT_BASE_URL environment variable,
+  falling back to the recorded target
+- Credentials were redacted; read them from environment variables
+- Keep tests independent of each other where possible
+
+For Rust: an integration test file using tokio and reqwest
+For Python: pytest with requests
+For JavaScript/TypeScript: Jest/Vitest with fetch
+
+Output the complete test file in a single markdown code block with the
+appropriate language tag."#;
+
+/// Response body characters shown per sample
+const MAX_SAMPLE_BODY: usize = 1500;
+
+/// Columns for a request path in the tables
+const MAX_PATH_COLUMNS: usize = 48;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+pub async fn run(
+    _config: Config,
+    proxy: &str,
+    target: &str,
+    from: Option<&str>,
+    output: Option<&str>,
+) -> Result<()> {
+    let root = Path::new(".");
+
+    let recording_path = match from {
+        Some(path) => {
+            print_header(&format!("replaying {}", path), target);
+            PathBuf::from(path)
+        }
+        None => {
+            let listen = recording::listen_address(proxy);
+            let path = recording::new_recording_path(root);
+            print_header(&format!("http://{} {} {}", listen, symbols::ARROW, target), target);
+            print_listening(&listen);
+
+            let count = recording::serve(&listen, target, &path, print_exchange).await?;
+            println!();
+            if count == 0 {
+                print_warning("No requests were recorded");
+                return Ok(());
+            }
+            print_recording_saved(&path, count);
+            path
+        }
+    };
+
+    let exchanges = recording::load(&recording_path)?;
+    if exchanges.is_empty() {
+        print_error(&format!("No exchanges in {}", recording_path.display()));
+        return Ok(());
+    }
+
+    let files = index::collect_files(root, false)?;
+    let inventory = endpoints::inventory(&files);
+    let samples = recording::representatives(&exchanges, &inventory);
+
+    print_samples(&samples, inventory.len());
+
+    if !NexusForm::ask_confirm(&format!("Generate contract tests for {} samples?", samples.len()), true)? {
+        return Ok(());
+    }
+
+    let language = project_language(root, &inventory);
+    let output_path = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_output(language));
+
+    if output_path.exists()
+        && !NexusForm::ask_confirm(&format!("{} already exists. Overwrite?", output_path.display()), false)?
+    {
+        return Ok(());
+    }
+
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    let prompt = format!(
+        "{}\n\n## Service\n\n**Recorded target:** {}\n**Test language:** {}\n\n## Samples\n\n{}\n## Task\n\nGenerate contract tests that replay these requests.",
+        context::REPOSITORY_CONTENT_RULES,
+        target,
+        language,
+        samples.iter().enumerate().map(|(i, s)| describe_sample(i + 1, s)).collect::<String>()
+    );
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(RECORD_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", RECORD_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(RECORD_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+
+    let Some(code) = extract_code_block(&response) else {
+        print_response(&response);
+        print_warning("Could not extract test code. Showing full response.");
+        return Ok(());
+    };
+
+    if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    permissions::write_file(&output_path, format!("{}\n", code))?;
+    Journal::current()
+        .record("record", Change::FileWritten { path: output_path.display().to_string(), provenance: None })
+        .ok();
+
+    print_saved(&output_path, &code, language);
+    Ok(())
+}
+
+/// Prompt section for one representative exchange
+fn describe_sample(number: usize, sample: &Sample) -> String {
+    let exchange = &sample.exchange.redacted();
+    let mut text = format!(
+        "### Sample {}: {} {} {} {} (seen {}×)\n\n",
+        number, exchange.method, exchange.path, symbols::ARROW, exchange.status, sample.count
+    );
+
+    match sample.endpoint {
+        Some(endpoint) => text.push_str(&format!(
+            "**Handler:** `{}` ({}:{}), route `{} {}`\n",
+            endpoint.handler, endpoint.file.display(), endpoint.line, endpoint.method, endpoint.path
+        )),
+        None => text.push_str("**Handler:** unknown (no matching route definition found)\n"),
+    }
+
+    let request_type = exchange
+        .request_headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.as_str());
+    if let Some(body) = &exchange.request_body {
+        text.push_str(&format!(
+            "\n**Request body** ({}):\n{}\n",
+            request_type.unwrap_or("unknown type"),
+            context::delimit(&format!("request {} {}", exchange.method, exchange.path), body)
+        ));
+    }
+
+    if let Some(content_type) = exchange.response_header("content-type") {
+        text.push_str(&format!("\n**Response content type:** {}\n", content_type));
+    }
+    if let Some(shape) = exchange.response_shape() {
+        text.push_str(&format!(
+            "\n**Response shape:**\n```json\n{}\n```\n",
+            serde_json::to_string_pretty(&shape).unwrap_or_default()
+        ));
+    }
+    if let Some(body) = &exchange.response_body {
+        let excerpt: String = body.chars().take(MAX_SAMPLE_BODY).collect();
+        text.push_str(&format!(
+            "\n**Response body (example):**\n{}\n",
+            context::delimit(&format!("response {} {}", exchange.method, exchange.path), &excerpt)
+        ));
+    }
+
+    text.push('\n');
+    text
+}
+
+/// Language the tests should be written in
+fn project_language(root: &Path, inventory: &[endpoints::Endpoint]) -> Language {
+    if let Some(endpoint) = inventory.first() {
+        return Language::from_path(&endpoint.file);
+    }
+
+    match Manifest::discover(root).first().map(|m| m.kind) {
+        Some(ManifestKind::Cargo) => Language::Rust,
+        Some(ManifestKind::PyProject) => Language::Python,
+        Some(ManifestKind::Npm) if root.join("tsconfig.json").exists() => Language::TypeScript,
+        Some(ManifestKind::Npm) => Language::JavaScript,
+        None => Language::Python,
+    }
+}
+
+fn default_output(language: Language) -> PathBuf {
+    PathBuf::from(match language {
+        Language::Rust => "tests/recorded_contract.rs",
+        Language::TypeScript => "tests/recorded-contract.test.ts",
+        Language::JavaScript => "tests/recorded-contract.test.js",
+        _ => "tests/test_recorded_contract.py",
+    })
+}
+
+/// Extract the first code block from a markdown response
+fn extract_code_block(response: &str) -> Option<String> {
+    let start = response.find("```")?;
+    let after_fence = &response[start + 3..];
+    let code_start = after_fence.find('\n')? + 1;
+    let end = after_fence[code_start..].find("```")?;
+    let code = after_fence[code_start..code_start + end].trim();
+
+    (!code.is_empty()).then(|| code.to_string())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(mode: &str, target: &str) {
+    println!();
+    println!(
+        "{}{}  {} Traffic Recorder{}",
+        colors::PRIMARY, colors::BOLD, symbols::RECORD, colors::RESET
+    );
+    println!(
+        "{}  │ Service: {}{}{}",
+        colors::MUTED, colors::FG, target, colors::RESET
+    );
+    println!(
+        "{}  │ Source: {}{}{}",
+        colors::MUTED, colors::FG, mode, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_listening(listen: &str) {
+    println!(
+        "{}  {} Recording. Point your client at {}http://{}{}",
+        colors::WARNING, symbols::RECORD, colors::FG, listen, colors::RESET
+    );
+    println!(
+        "{}    Press Ctrl+C to stop and generate tests{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_exchange(exchange: &Exchange) {
+    let status_color = match exchange.status {
+        200..=399 => colors::SUCCESS,
+        400..=499 => colors::WARNING,
+        _ => colors::ERROR,
+    };
+    println!(
+        "{}  {}{}{} {}{}{} {}{}ms{}",
+        colors::MUTED, text::cell(&exchange.method, 7), colors::FG, text::truncate(&exchange.path, MAX_PATH_COLUMNS),
+        status_color, exchange.status, colors::MUTED,
+        symbols::ARROW, exchange.duration_ms, colors::RESET
+    );
+}
+
+fn print_recording_saved(path: &Path, count: usize) {
+    println!(
+        "{}  {} Recorded {} requests to {}{}{}",
+        colors::SUCCESS, symbols::SUCCESS, count, colors::FG, path.display(), colors::RESET
+    );
+    println!();
+}
+
+fn print_samples(samples: &[Sample], routes: usize) {
+    println!(
+        "{}{}  Representative requests{} {}({} routes in inventory){}",
+        colors::PRIMARY, colors::BOLD, colors::RESET, colors::MUTED, routes, colors::RESET
+    );
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+
+    for sample in samples {
+        let exchange = sample.exchange;
+        let handler = match sample.endpoint {
+            Some(e) => format!("{}{} {}({}:{})", colors::SUCCESS, e.handler, colors::MUTED, e.file.display(), e.line),
+            None => format!("{}unmapped", colors::WARNING),
+        };
+        println!(
+            "{}  │ {}{}{} {} {} {} ×{}  {}{}",
+            colors::MUTED, colors::FG, text::cell(&exchange.method, 7), text::truncate(exchange.route(), MAX_PATH_COLUMNS), symbols::ARROW,
+            exchange.status, colors::MUTED, sample.count, handler, colors::RESET
+        );
+    }
+
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is writing contract tests {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_response(response: &str) {
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+
+    for line in response.lines() {
+        println!("{}  │ {}{}", colors::MUTED, colors::FG, line);
+    }
+
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!();
+}
+
+fn print_saved(path: &Path, code: &str, language: Language) {
+    println!(
+        "{}{}  {} Contract tests saved to {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, path.display(), colors::RESET
+    );
+    println!(
+        "{}  {} {} lines · {}{}",
+        colors::MUTED, symbols::FILE, code.lines().count(), language, colors::RESET
+    );
+    println!(
+        "{}    Run against a live service; set NEXUS_CONTRACT_BASE_URL to override the target{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -5,11 +5,13 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
 use crate::config::Config;
 use crate::core::parser::Language;
 
@@ -18,6 +20,7 @@ use crate::core::parser::Language;
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -37,6 +40,7 @@ mod symbols {
     pub const AI_ICON: &str = "󰌤";
     pub const FILE: &str = "󰈙";
     pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
     pub const ERROR: &str = "󰅚";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
@@ -63,20 +67,25 @@ Be thorough but focused - only make changes that improve the code according to t
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, paths: &[String], description: &str) -> Result<()> {
+pub async fn run(config: Config, paths: &[String], description: &str) -> Result<()> {
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+
     print_header(description);
 
     let ai_mode = determine_ai_mode();
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Collect all files to refactor
@@ -86,7 +95,7 @@ pub async fn run(_config: Config, paths: &[String], description: &str) -> Result
         let path = Path::new(path_str);
 
         if path.is_file() {
-            if let Some(content) = read_file_if_supported(path) {
+            if let Some(content) = read_file_if_supported(path, &exclusions) {
                 let lang = Language::from_path(path);
                 files_content.push((path_str.clone(), content, lang));
             }
@@ -103,18 +112,17 @@ pub async fn run(_config: Config, paths: &[String], description: &str) -> Result
                     name != "build" &&
                     name != "dist"
                 })
+                .flatten()
             {
-                if let Ok(entry) = entry {
-                    let file_path = entry.path();
-                    if file_path.is_file() {
-                        if let Some(content) = read_file_if_supported(file_path) {
-                            let lang = Language::from_path(file_path);
-                            files_content.push((
-                                file_path.display().to_string(),
-                                content,
-                                lang
-                            ));
-                        }
+                let file_path = entry.path();
+                if file_path.is_file() {
+                    if let Some(content) = read_file_if_supported(file_path, &exclusions) {
+                        let lang = Language::from_path(file_path);
+                        files_content.push((
+                            file_path.display().to_string(),
+                            content,
+                            lang
+                        ));
                     }
                 }
             }
@@ -131,16 +139,14 @@ pub async fn run(_config: Config, paths: &[String], description: &str) -> Result
 
     // Build the prompt with all file contents
     let mut code_context = String::new();
-    for (path, content, lang) in &files_content {
-        let lang_str = lang.to_string().to_lowercase();
-        code_context.push_str(&format!(
-            "\n### File: `{}`\n```{}\n{}\n```\n",
-            path, lang_str, content
-        ));
+    for (path, content, _lang) in &files_content {
+        context::print_injection_warnings(path, content);
+        code_context.push_str(&format!("\n{}\n", context::delimit(path, content)));
     }
 
     let full_prompt = format!(
-        "## Refactoring Request\n\n{}\n\n## Code to Refactor\n{}",
+        "{}\n\n## Refactoring Request\n\n{}\n\n## Code to Refactor\n{}",
+        context::REPOSITORY_CONTENT_RULES,
         description, code_context
     );
 
@@ -160,9 +166,13 @@ pub async fn run(_config: Config, paths: &[String], description: &str) -> Result
             let prompt_with_system = format!("{}\n\n{}", REFACTOR_PROMPT, full_prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(REFACTOR_PROMPT).chat(&full_prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "refactor").apply(&response);
     print_response(&response);
 
     // Ask if user wants to apply changes
@@ -172,13 +182,13 @@ pub async fn run(_config: Config, paths: &[String], description: &str) -> Result
 }
 
 /// Read file if it's a supported language
-fn read_file_if_supported(path: &Path) -> Option<String> {
+fn read_file_if_supported(path: &Path, exclusions: &CloudExclusions) -> Option<String> {
     let lang = Language::from_path(path);
     if lang == Language::Unknown {
         return None;
     }
 
-    fs::read_to_string(path).ok()
+    exclusions.read_file(path).ok()
 }
 
 // ============================================
@@ -211,8 +221,8 @@ fn print_files_summary(files: &[(String, String, Language)]) {
     for (path, content, lang) in files.iter().take(10) {
         let lines = content.lines().count();
         println!(
-            "{}     {} {} ({}, {} lines){}",
-            colors::MUTED, "•", path, lang, lines, colors::RESET
+            "{}     • {} ({}, {} lines){}",
+            colors::MUTED, path, lang, lines, colors::RESET
         );
     }
 
@@ -6,13 +6,29 @@
 #![allow(dead_code)]
 
 use anyhow::{Context, Result};
-use std::path::Path;
+use console::Term;
+use dialoguer::Select;
 use std::fs;
+use std::path::Path;
 use std::io::{self, Write};
 
 use crate::ai::{ClaudeClient, Conversation};
-use crate::config::Config;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::deep::{self, DeepResult, DeepTask, Stage};
+use crate::ai::postprocess::Pipeline;
+use crate::config::{Config, ProjectConfig};
+use crate::core::edits;
+use crate::core::findings::FindingsStore;
+use crate::core::formatter;
+use crate::core::graders::{self, Grader, ReviewFinding, Severity};
+use crate::core::hooks;
+use crate::core::import_organizer;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions::{self, Permission};
 use crate::core::parser::{CodeParser, Language};
+use crate::core::references::{self, ReferenceIndex, ReferenceKind, UnknownReference};
+use crate::ui::NexusTheme;
+use super::memory;
 
 // ANSI color codes from design system
 mod colors {
@@ -37,6 +53,8 @@ mod symbols {
     pub const ERROR: &str = "󰅚";
     pub const WARNING: &str = "󰀦";
     pub const SUCCESS: &str = "󰄂";
+    pub const GRADER: &str = "󰡪";
+    pub const EXPLAIN: &str = "󰋖";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
@@ -78,6 +96,20 @@ impl ReviewFocus {
     }
 }
 
+/// Appended to every review prompt so graders get structured findings
+const FINDINGS_FORMAT: &str = r#"After the review, list every issue you raised in a fenced JSON block:
+
+```json
+[{"path": "src/db.rs", "line": 42, "severity": "critical|high|medium|low",
+  "title": "One-line summary", "detail": "Why it matters and how to fix it"}]
+```
+
+Use an empty array when there are no issues. `line` may be omitted when an
+issue is not tied to one line."#;
+
+/// Keeps follow-up answers free of another findings list
+const FOLLOW_UP_RULES: &str = "Answer only this request; do not repeat the review or add a JSON findings block.";
+
 /// Get system prompt based on focus area
 fn get_system_prompt(focus: ReviewFocus) -> &'static str {
     match focus {
@@ -228,7 +260,17 @@ Be thorough but prioritized. Focus on actionable feedback."#,
     }
 }
 
-pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) -> Result<()> {
+pub async fn run(
+    config: Config,
+    paths: &[String],
+    focus: Option<&[String]>,
+    grader_paths: &[String],
+    no_graders: bool,
+    interactive: bool,
+    deep: bool,
+) -> Result<()> {
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+
     // Determine focus areas
     let focus_areas: Vec<ReviewFocus> = if let Some(areas) = focus {
         areas.iter().map(|s| ReviewFocus::from_str(s)).collect()
@@ -241,6 +283,18 @@ pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) ->
     // Print header
     print_header(paths, primary_focus);
 
+    // Project graders plus any passed explicitly
+    let mut graders: Vec<Grader> = if no_graders { Vec::new() } else { graders::discover(Path::new(".")) };
+    for path in grader_paths {
+        match Grader::from_path(Path::new(path)) {
+            Ok(grader) => graders.push(grader),
+            Err(e) => {
+                print_error(&e.to_string());
+                return Ok(());
+            }
+        }
+    }
+
     // Try to create Claude client
     let client = match ClaudeClient::from_env() {
         Ok(c) => c,
@@ -262,13 +316,14 @@ pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) ->
     let mut all_content = String::new();
     let mut file_count = 0;
     let mut total_lines = 0;
+    let mut reviewed: Vec<String> = Vec::new();
     let mut parser = CodeParser::new().context("Failed to initialize parser")?;
 
     for path_str in paths {
         let path = Path::new(path_str);
 
         if path.is_file() {
-            if let Ok(content) = fs::read_to_string(path) {
+            if let Ok(content) = exclusions.read_file(path) {
                 let line_count = content.lines().count();
                 total_lines += line_count;
                 file_count += 1;
@@ -289,12 +344,13 @@ pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) ->
                     String::new()
                 };
 
+                context::print_injection_warnings(path_str, &content);
+                reviewed.push(path_str.clone());
                 all_content.push_str(&format!(
-                    "\n## File: {} {}\n```{}\n{}\n```\n",
+                    "\n## File: {} {}\n{}\n",
                     path_str,
                     structure_info,
-                    language.name().to_lowercase(),
-                    content
+                    context::delimit(path_str, &content)
                 ));
             }
         } else if path.is_dir() {
@@ -312,33 +368,34 @@ pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) ->
                     name != "__pycache__" &&
                     name != "vendor"
                 })
+                .flatten()
             {
-                if let Ok(entry) = entry {
-                    let file_path = entry.path();
-                    if file_path.is_file() {
-                        let language = Language::from_path(file_path);
-                        if language != Language::Unknown {
-                            if let Ok(content) = fs::read_to_string(file_path) {
-                                let line_count = content.lines().count();
-                                total_lines += line_count;
-                                file_count += 1;
-
-                                // Limit to reasonable size
-                                if total_lines > 2000 {
-                                    print_warning(&format!(
-                                        "Limiting review to {} files ({} lines) for best results",
-                                        file_count, total_lines
-                                    ));
-                                    break;
-                                }
-
-                                all_content.push_str(&format!(
-                                    "\n## File: {}\n```{}\n{}\n```\n",
-                                    file_path.display(),
-                                    language.name().to_lowercase(),
-                                    content
+                let file_path = entry.path();
+                if file_path.is_file() {
+                    let language = Language::from_path(file_path);
+                    if language != Language::Unknown {
+                        if let Ok(content) = exclusions.read_file(file_path) {
+                            let line_count = content.lines().count();
+                            total_lines += line_count;
+                            file_count += 1;
+
+                            // Limit to reasonable size
+                            if total_lines > 2000 {
+                                print_warning(&format!(
+                                    "Limiting review to {} files ({} lines) for best results",
+                                    file_count, total_lines
                                 ));
+                                break;
                             }
+
+                            let file_str = file_path.display().to_string();
+                            context::print_injection_warnings(&file_str, &content);
+                            reviewed.push(file_str.clone());
+                            all_content.push_str(&format!(
+                                "\n## File: {}\n{}\n",
+                                file_str,
+                                context::delimit(&file_str, &content)
+                            ));
                         }
                     }
                 }
@@ -352,31 +409,273 @@ pub async fn run(_config: Config, paths: &[String], focus: Option<&[String]>) ->
     }
 
     print_stats(file_count, total_lines);
+    if !graders.is_empty() {
+        print_graders(&graders);
+    }
 
     // Build prompt
     let prompt = format!(
-        "Please review the following code:\n{}\n\nProvide a thorough {} review.",
+        "{}\n\nPlease review the following code:\n{}\n\nProvide a thorough {} review.",
+        context::REPOSITORY_CONTENT_RULES,
         all_content, primary_focus.name().to_lowercase()
     );
 
+    let system = format!("{}\n\n{}", get_system_prompt(primary_focus), FINDINGS_FORMAT);
+    let mut conversation = Conversation::new(client.clone())
+        .with_system(&system);
+
     // Send to Claude
-    print_thinking(primary_focus);
+    let result = if deep {
+        let code = format!("{}\n\nCode under review:\n{}", context::REPOSITORY_CONTENT_RULES, all_content);
+        let goal = format!("{} review", primary_focus.name().to_lowercase());
+        let task = DeepTask { system: &system, code: &code, goal: &goal };
+        let result = deep::run(
+            &task,
+            &config.deep,
+            |system, prompt| {
+                let client = &client;
+                async move { client.send_with_system(&prompt, &system).await }
+            },
+            print_deep_stage,
+        )
+        .await;
+        clear_line();
+        result.map(|result| {
+            print_deep_summary(&result);
+            // Follow-ups in interactive mode see the review as one exchange
+            conversation.record(&prompt, &result.response);
+            result.response
+        })
+    } else {
+        print_thinking(primary_focus);
+        conversation.send(&prompt).await
+    };
 
-    let mut conversation = Conversation::new(client)
-        .with_system(get_system_prompt(primary_focus));
+    match result {
+        Ok(response) => {
+            clear_line();
+            let (review, mut findings) = graders::parse_findings(&response);
+            let review = Pipeline::for_command(&config.postprocess, "review").apply(&review);
+            let known = ReferenceIndex::build(Path::new("."))?;
+            let unknown = known.check(&review);
+            print_response(&references::annotate(&review, &unknown), primary_focus);
+            print_unknown_references(&unknown);
+            if !graders.is_empty() {
+                run_graders(&graders, primary_focus, &reviewed, &mut findings);
+                print_findings(&findings, &known);
+            }
+            let store = FindingsStore::current();
+            if let Err(e) = store.record(&reviewed, &findings) {
+                print_warning(&format!("Findings not saved: {}", e));
+            }
+            if interactive {
+                let applied = browse_findings(&mut conversation, &config, &findings, &reviewed).await;
+                if !applied.is_empty() {
+                    let open: Vec<ReviewFinding> =
+                        findings.iter().filter(|f| !applied.contains(&f.id)).cloned().collect();
+                    store.record(&reviewed, &open).ok();
+                }
+            }
+            memory::remember(&review, |system, message| {
+                let client = &client;
+                async move { client.send_with_system(&message, &system).await }
+            })
+            .await;
+        }
+        Err(e) => {
+            clear_line();
+            print_error(&format!("AI error: {}", e));
+        }
+    }
 
+    Ok(())
+}
+
+/// Pass the findings through each grader in turn. Graders run locally, so
+/// they get the files as they are on disk.
+fn run_graders(graders: &[Grader], focus: ReviewFocus, reviewed: &[String], findings: &mut Vec<ReviewFinding>) {
+    let files: Vec<(String, String)> = reviewed
+        .iter()
+        .map(|path| (path.clone(), fs::read_to_string(path).unwrap_or_default()))
+        .collect();
+    let focus = focus.name().to_lowercase();
+
+    for grader in graders {
+        match grader.run(&focus, findings, &files) {
+            Ok(output) => {
+                let summary = graders::apply(findings, output, &grader.name);
+                print_grader_summary(&grader.name, &summary);
+            }
+            Err(e) => print_warning(&format!("Grader {}", e)),
+        }
+    }
+}
+
+/// Let the user pick findings one at a time: `e` asks for a deeper
+/// explanation with an example fix, `a` asks for a patch and applies it.
+/// Returns the ids of the findings fixed.
+async fn browse_findings(
+    conversation: &mut Conversation,
+    config: &Config,
+    findings: &[ReviewFinding],
+    reviewed: &[String],
+) -> Vec<String> {
+    if findings.is_empty() {
+        return Vec::new();
+    }
+    let term = Term::stderr();
+    if !term.is_term() {
+        print_warning("Interactive findings need a terminal");
+        return Vec::new();
+    }
+
+    let mut sorted: Vec<&ReviewFinding> = findings.iter().collect();
+    sorted.sort_by_key(|f| std::cmp::Reverse(f.severity));
+    let mut applied: Vec<String> = Vec::new();
+    let theme = NexusTheme::new();
+
+    loop {
+        let items: Vec<String> = sorted
+            .iter()
+            .map(|f| {
+                let mark = if applied.contains(&f.id) { "  ✓ applied" } else { "" };
+                format!("{:<8} {}  {}{}", f.severity.label(), f.title, location(f), mark)
+            })
+            .collect();
+        println!();
+        let selection = Select::with_theme(&theme)
+            .with_prompt("Findings (Esc to finish)")
+            .items(&items)
+            .default(0)
+            .interact_on_opt(&term);
+        let Ok(Some(index)) = selection else {
+            return applied;
+        };
+        let finding = sorted[index];
+
+        print_finding_detail(finding);
+        let key = match term.read_char() {
+            Ok(key) => key.to_ascii_lowercase(),
+            Err(_) => return applied,
+        };
+        match key {
+            'e' => explain_finding(conversation, config, finding).await,
+            'a' if apply_fix(conversation, finding, reviewed).await => applied.push(finding.id.clone()),
+            'q' => return applied,
+            _ => {}
+        }
+    }
+}
+
+/// Ask for an expanded explanation of a finding with an example fix
+async fn explain_finding(conversation: &mut Conversation, config: &Config, finding: &ReviewFinding) {
+    let prompt = format!(
+        "Explain this finding in more depth: {} ({}, {}).\n\
+         Say why it is a problem, when it would cause trouble, and show a short \
+         example of the fixed code.\n\n{}",
+        finding.title, finding.severity.label(), location(finding), FOLLOW_UP_RULES
+    );
+    print_thinking_on(&finding.title);
     match conversation.send(&prompt).await {
         Ok(response) => {
             clear_line();
-            print_response(&response, primary_focus);
+            let response = Pipeline::for_command(&config.postprocess, "review").apply(&response);
+            print_explanation(&finding.title, &response);
         }
         Err(e) => {
             clear_line();
             print_error(&format!("AI error: {}", e));
         }
     }
+}
 
-    Ok(())
+/// Ask for a patch fixing a finding, show it, and write it after
+/// confirmation. Only files that were reviewed are changed.
+async fn apply_fix(conversation: &mut Conversation, finding: &ReviewFinding, reviewed: &[String]) -> bool {
+    if !reviewed.iter().any(|r| Path::new(r) == Path::new(&finding.path)) {
+        print_warning(&format!("{} was not part of this review; not patching it", finding.path));
+        return false;
+    }
+    let prompt = format!(
+        "Write a patch for this finding: {} ({}). Change only {}.\n\n{}\n\n{}",
+        finding.title, location(finding), finding.path, edits::EDIT_FORMAT, FOLLOW_UP_RULES
+    );
+    print_thinking_on(&finding.title);
+    let response = match conversation.send(&prompt).await {
+        Ok(response) => response,
+        Err(e) => {
+            clear_line();
+            print_error(&format!("AI error: {}", e));
+            return false;
+        }
+    };
+    clear_line();
+
+    let patch = edits::parse(&response);
+    if patch.is_empty() {
+        print_warning("The AI did not return a patch");
+        return false;
+    }
+    let content = match fs::read_to_string(&finding.path) {
+        Ok(content) => content,
+        Err(e) => {
+            print_error(&format!("Failed to read {}: {}", finding.path, e));
+            return false;
+        }
+    };
+    let patched = match edits::apply(&content, &patch) {
+        Ok(patched) => patched,
+        Err(e) => {
+            print_warning(&format!("Patch not applied: {}", e));
+            return false;
+        }
+    };
+
+    print_patch(&finding.path, &patch);
+    if let Err(e) = permissions::request(Permission::Write, &format!("apply this patch to {}", finding.path)) {
+        print_warning(&format!("{:#}", e));
+        return false;
+    }
+    let patched = match hooks::pre_apply(&finding.path, patched) {
+        Ok(patched) => patched,
+        Err(e) => {
+            print_error(&format!("{:#}", e));
+            return false;
+        }
+    };
+    if let Err(e) = fs::write(&finding.path, &patched) {
+        print_error(&format!("Failed to write {}: {}", finding.path, e));
+        return false;
+    }
+    Journal::current()
+        .record("review", Change::FileWritten { path: finding.path.clone(), provenance: None })
+        .ok();
+    println!(
+        "{}  {} Patched {}{}",
+        colors::SUCCESS, symbols::SUCCESS, finding.path, colors::RESET
+    );
+    tidy_written(&finding.path);
+    hooks::post_apply(&finding.path, &fs::read_to_string(&finding.path).unwrap_or(patched));
+    true
+}
+
+/// Organize the imports of a patched file, then run the project's formatter
+fn tidy_written(path: &str) {
+    let root = Path::new(".");
+    let Ok(project) = ProjectConfig::load(root) else {
+        return;
+    };
+    if let Err(e) = import_organizer::organize_file(root, Path::new(path), &project.imports) {
+        print_warning(&format!("Imports not organized: {:#}", e));
+    }
+    formatter::format_file(root, Path::new(path), &project.format);
+}
+
+fn location(finding: &ReviewFinding) -> String {
+    match finding.line {
+        Some(line) => format!("{}:{}", finding.path, line),
+        None => finding.path.clone(),
+    }
 }
 
 /// Print the header
@@ -421,6 +720,15 @@ fn print_stats(file_count: usize, total_lines: usize) {
     );
 }
 
+/// Print the graders that will post-process findings
+fn print_graders(graders: &[Grader]) {
+    let names: Vec<&str> = graders.iter().map(|g| g.name.as_str()).collect();
+    println!(
+        "{}  {} Graders: {}{}",
+        colors::MUTED, symbols::GRADER, names.join(", "), colors::RESET
+    );
+}
+
 /// Print thinking indicator
 fn print_thinking(focus: ReviewFocus) {
     print!(
@@ -434,6 +742,39 @@ fn print_thinking(focus: ReviewFocus) {
     io::stdout().flush().ok();
 }
 
+/// Show which pass of a deep review is running
+fn print_deep_stage(stage: &Stage) {
+    let label = match stage {
+        Stage::Outline => "Outlining the areas to examine".to_string(),
+        Stage::DeepDive { index, total, area } => format!("Deep dive {}/{}: {}", index, total, area),
+        Stage::Synthesis => "Writing the report".to_string(),
+    };
+    clear_line();
+    print!(
+        "\r{}  {} {} {}{}",
+        colors::AI_ACCENT,
+        symbols::AI_ICON,
+        crate::ui::text::truncate(&label, 60),
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+/// Print how a deep review spent its budget
+fn print_deep_summary(result: &DeepResult) {
+    println!(
+        "{}  {} Deep review: {} passes, ~{} tokens, {}s{}",
+        colors::MUTED, symbols::SUCCESS, result.passes, result.tokens, result.elapsed.as_secs(), colors::RESET
+    );
+    if !result.skipped.is_empty() {
+        print_warning(&format!(
+            "Budget reached; not examined in depth: {} (raise [deep] limits in the config file)",
+            result.skipped.join(", ")
+        ));
+    }
+}
+
 /// Clear the current line
 fn clear_line() {
     print!("\r{}\r", " ".repeat(70));
@@ -477,6 +818,150 @@ fn print_response(response: &str, focus: ReviewFocus) {
     println!();
 }
 
+/// Print what a grader changed
+fn print_grader_summary(name: &str, summary: &graders::GraderSummary) {
+    println!(
+        "{}  {} {}: {} added, {} suppressed, {} rescored{}",
+        colors::MUTED, symbols::GRADER, name, summary.added, summary.suppressed, summary.rescored, colors::RESET
+    );
+}
+
+/// List the paths and names the review mentions that the project lacks
+fn print_unknown_references(unknown: &[UnknownReference]) {
+    if unknown.is_empty() {
+        return;
+    }
+    println!(
+        "{}  {} {} reference{} not found in the project{}",
+        colors::WARNING, references::MARKER, unknown.len(),
+        if unknown.len() == 1 { "" } else { "s" }, colors::RESET
+    );
+    for reference in unknown {
+        let kind = match reference.kind {
+            ReferenceKind::Path => "file",
+            ReferenceKind::Symbol => "symbol",
+        };
+        println!("{}  │ {} {}{}", colors::MUTED, kind, reference.name, colors::RESET);
+    }
+    println!();
+}
+
+/// Print the findings left after grading, most severe first. Locations
+/// outside the project are marked.
+fn print_findings(findings: &[ReviewFinding], known: &ReferenceIndex) {
+    let mut sorted: Vec<&ReviewFinding> = findings.iter().collect();
+    sorted.sort_by_key(|f| std::cmp::Reverse(f.severity));
+
+    println!();
+    println!(
+        "{}{}  Graded Findings ({}){}",
+        colors::PRIMARY, colors::BOLD, findings.len(), colors::RESET
+    );
+    for finding in sorted {
+        let color = match finding.severity {
+            Severity::Critical => colors::ERROR,
+            Severity::High | Severity::Medium => colors::WARNING,
+            Severity::Low => colors::MUTED,
+        };
+        let mut location = location(finding);
+        if !known.knows_path(&finding.path) {
+            location = format!("{} {}", location, references::MARKER);
+        }
+        let mut origin = Vec::new();
+        if finding.source != "nexus" {
+            origin.push(format!("from {}", finding.source));
+        }
+        if let Some(grader) = &finding.rescored_by {
+            origin.push(format!("rescored by {}", grader));
+        }
+        let origin = if origin.is_empty() { String::new() } else { format!(" ({})", origin.join(", ")) };
+        println!(
+            "{}  {:<8}{} {}  {}{}{}{}",
+            color, finding.severity.label(), colors::FG, finding.title,
+            colors::MUTED, location, origin, colors::RESET
+        );
+    }
+    println!();
+}
+
+/// Print a selected finding and the keys that act on it
+fn print_finding_detail(finding: &ReviewFinding) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::PRIMARY, colors::BOLD, finding.id, finding.title, colors::RESET
+    );
+    println!(
+        "{}  │ {} · {}{}",
+        colors::MUTED, finding.severity.label(), location(finding), colors::RESET
+    );
+    if let Some(detail) = &finding.detail {
+        for line in detail.lines() {
+            println!("{}  │ {}{}{}", colors::MUTED, colors::FG, line, colors::RESET);
+        }
+    }
+    println!(
+        "{}  ╰ {}e{} explain  {}a{} apply fix  {}q{} quit  {}any other key{} back{}",
+        colors::MUTED,
+        colors::AI_ACCENT, colors::MUTED,
+        colors::AI_ACCENT, colors::MUTED,
+        colors::AI_ACCENT, colors::MUTED,
+        colors::AI_ACCENT, colors::MUTED,
+        colors::RESET
+    );
+}
+
+/// Print thinking indicator for a follow-up on a finding
+fn print_thinking_on(title: &str) {
+    print!(
+        "\r{}  {} Working on {} {}{}",
+        colors::AI_ACCENT,
+        symbols::AI_ICON,
+        crate::ui::text::truncate(title, 40),
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+/// Print the expanded explanation of a finding
+fn print_explanation(title: &str, response: &str) {
+    println!();
+    println!(
+        "{}{}  {} {}{}",
+        colors::AI_ACCENT, colors::BOLD, symbols::EXPLAIN, title, colors::RESET
+    );
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    for line in response.lines() {
+        println!("{}  │ {}{}{}", colors::MUTED, colors::FG, line, colors::RESET);
+    }
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+}
+
+/// Print the edits of a patch as removed and added lines
+fn print_patch(path: &str, patch: &[edits::Edit]) {
+    println!();
+    println!(
+        "{}{}  {} {}{}",
+        colors::PRIMARY, colors::BOLD, symbols::FILE, path, colors::RESET
+    );
+    for edit in patch {
+        println!("{}  ╭{}─{}", colors::MUTED, "─".repeat(60), colors::RESET);
+        for line in edit.search.lines() {
+            println!("{}  │ {}- {}{}", colors::MUTED, colors::ERROR, line, colors::RESET);
+        }
+        for line in edit.replace.lines() {
+            println!("{}  │ {}+ {}{}", colors::MUTED, colors::SUCCESS, line, colors::RESET);
+        }
+        println!("{}  ╰{}─{}", colors::MUTED, "─".repeat(60), colors::RESET);
+    }
+}
+
 /// Print error message
 fn print_error(message: &str) {
     println!(
@@ -5,12 +5,17 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
+use std::collections::HashSet;
 use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::config::Config;
+use crate::core::affected;
 use crate::core::parser::{CodeParser, Language, ParsedFile, SymbolKind};
+use crate::core::query::{Query, QueryHit};
+use crate::core::saved_search::SavedSearches;
+use crate::ui::text;
 
 // ANSI color codes
 mod colors {
@@ -19,6 +24,7 @@ mod colors {
     pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
     pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
     pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
     pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
     pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
     pub const HIGHLIGHT: &str = "\x1b[38;2;255;183;77m";     // Orange highlight
@@ -30,6 +36,9 @@ mod symbols {
     pub const FUNCTION: &str = "󰊕";
     pub const STRUCT: &str = "󰆧";
     pub const MATCH: &str = "󰄬";
+    pub const GROUP: &str = "󰉋";
+    pub const SAVED: &str = "󰃀";
+    pub const ERROR: &str = "󰅚";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
@@ -53,9 +62,43 @@ enum MatchType {
     PartialName,
     ContentMatch,
     ContextMatch,
+    /// Matched every term of a structured query
+    Structured,
 }
 
-pub async fn run(_config: Config, query: &str, limit: usize) -> Result<()> {
+/// How `--group-by` groups results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    File,
+    Kind,
+    Package,
+}
+
+impl GroupBy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "file" => Some(GroupBy::File),
+            "kind" => Some(GroupBy::Kind),
+            "package" | "pkg" => Some(GroupBy::Package),
+            _ => None,
+        }
+    }
+}
+
+/// Hits listed per group before the rest collapse into a count
+const GROUP_PREVIEW: usize = 5;
+
+/// A set of results sharing a file, kind or package
+struct ResultGroup<'a> {
+    label: String,
+    results: Vec<&'a SearchResult>,
+}
+
+/// Search the codebase. Queries with `key:value` terms (`kind:fn
+/// calls:send`) are evaluated as structured queries. With `group_by`
+/// (`file`, `kind` or `package`), `limit` caps the number of groups rather
+/// than results.
+pub async fn run(_config: Config, query: &str, limit: usize, group_by: Option<&str>) -> Result<()> {
     print_header(query);
 
     // Parse codebase
@@ -71,22 +114,118 @@ pub async fn run(_config: Config, query: &str, limit: usize) -> Result<()> {
     print_status(&format!("Searching {} files...", parsed_files.len()));
 
     // Perform search
-    let results = search_codebase(&parsed_files, query, limit);
+    let root = Path::new(".").canonicalize().unwrap_or_else(|_| Path::new(".").to_path_buf());
+    let structured = Query::is_structured(query);
+    let mut results = if structured {
+        match Query::parse(query) {
+            Ok(parsed) => parsed.run(&parsed_files, &root).into_iter().map(structured_result).collect(),
+            Err(e) => {
+                clear_line();
+                print_error(&e.to_string());
+                return Ok(());
+            }
+        }
+    } else {
+        dedupe(search_codebase(&parsed_files, query))
+    };
     clear_line();
 
     if results.is_empty() {
-        print_no_results(query);
+        print_no_results(query, structured);
         return Ok(());
     }
 
     // Display results
-    print_results(&results, query);
+    match group_by.and_then(GroupBy::parse) {
+        Some(group_by) => {
+            let mut groups = group_results(&results, group_by, &root);
+            groups.truncate(limit);
+            print_groups(&groups, group_by, &root, results.len(), query);
+        }
+        None => {
+            results.truncate(limit);
+            print_results(&results, query);
+        }
+    }
+
+    Ok(())
+}
+
+/// Save a query under `name` before running it
+pub fn save(name: &str, query: &str, group_by: Option<&str>) -> Result<()> {
+    let mut searches = SavedSearches::open()?;
+    let replaced = searches.insert(name, query, group_by)?;
+    searches.save()?;
+    let verb = if replaced { "Updated" } else { "Saved" };
+    print_success(&format!("{} search `{}`; run it with nexus search --saved {}", verb, name, name));
+    Ok(())
+}
+
+/// Run a saved search. A `group_by` given now overrides the saved one.
+pub async fn run_saved(config: Config, name: &str, limit: usize, group_by: Option<&str>) -> Result<()> {
+    let searches = SavedSearches::open()?;
+    let Some(search) = searches.get(name) else {
+        let names: Vec<&str> = searches.iter().map(|(n, _)| n.as_str()).collect();
+        if names.is_empty() {
+            print_error(&format!("No saved search `{}`; save one with nexus search --save {} \"<query>\"", name, name));
+        } else {
+            print_error(&format!("No saved search `{}` (saved: {})", name, names.join(", ")));
+        }
+        return Ok(());
+    };
+    let group_by = group_by.or(search.group_by.as_deref());
+    run(config, &search.query, limit, group_by).await
+}
+
+pub async fn list_saved(_config: Config) -> Result<()> {
+    let searches = SavedSearches::open()?;
+    println!();
+    println!(
+        "{}{}  {} Saved Searches{}",
+        colors::PRIMARY, colors::BOLD, symbols::SAVED, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, searches.path().display(), colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
 
+    if searches.is_empty() {
+        print_warning("No saved searches. Save one with: nexus search --save <name> \"<query>\"");
+        println!();
+        return Ok(());
+    }
+
+    let width = searches.iter().map(|(n, _)| text::width(n)).max().unwrap_or(0);
+    for (name, search) in searches.iter() {
+        let group = search.group_by.as_deref().map(|g| format!("  --group-by {}", g)).unwrap_or_default();
+        println!(
+            "{}  {} {}{}{}  \"{}\"{}{}",
+            colors::MUTED, symbols::SAVED, colors::FG, text::pad(name, width), colors::MUTED,
+            text::truncate(&search.query, 60), group, colors::RESET
+        );
+    }
+    println!();
     Ok(())
 }
 
-/// Search the codebase for the query
-fn search_codebase(files: &[ParsedFile], query: &str, limit: usize) -> Vec<SearchResult> {
+pub async fn delete_saved(_config: Config, name: &str) -> Result<()> {
+    let mut searches = SavedSearches::open()?;
+    if searches.remove(name).is_none() {
+        print_error(&format!("No saved search `{}`", name));
+        return Ok(());
+    }
+    searches.save()?;
+    print_success(&format!("Deleted saved search `{}`", name));
+    Ok(())
+}
+
+/// Search the codebase for the query, best matches first
+fn search_codebase(files: &[ParsedFile], query: &str) -> Vec<SearchResult> {
     let query_lower = query.to_lowercase();
     let query_words: Vec<&str> = query_lower.split_whitespace().collect();
 
@@ -167,7 +306,7 @@ fn search_codebase(files: &[ParsedFile], query: &str, limit: usize) -> Vec<Searc
                 results.push(SearchResult {
                     file_path: file.path.display().to_string(),
                     symbol_name: symbol.name.clone(),
-                    symbol_kind: symbol.kind.clone(),
+                    symbol_kind: symbol.kind,
                     line_start: symbol.line_start,
                     line_end: symbol.line_end,
                     signature: symbol.signature.clone(),
@@ -182,12 +321,109 @@ fn search_codebase(files: &[ParsedFile], query: &str, limit: usize) -> Vec<Searc
     // Sort by score (descending)
     results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
 
-    // Limit results
-    results.truncate(limit);
+    results
+}
+
+fn structured_result(hit: QueryHit) -> SearchResult {
+    SearchResult {
+        file_path: hit.path.display().to_string(),
+        symbol_name: hit.symbol.name,
+        symbol_kind: hit.symbol.kind,
+        line_start: hit.symbol.line_start,
+        line_end: hit.symbol.line_end,
+        context: hit.symbol.signature.clone().unwrap_or_default(),
+        signature: hit.symbol.signature,
+        score: 1.0,
+        match_type: MatchType::Structured,
+    }
+}
 
+/// Drop hits that repeat another: symbols sharing a range keep only the
+/// best, and impl blocks go when one of their own items matched
+fn dedupe(mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    results.retain(|r| seen.insert((r.file_path.clone(), r.line_start, r.line_end)));
+
+    let members: Vec<(String, usize, usize)> = results
+        .iter()
+        .filter(|r| r.symbol_kind != SymbolKind::Impl)
+        .map(|r| (r.file_path.clone(), r.line_start, r.line_end))
+        .collect();
+    results.retain(|r| {
+        r.symbol_kind != SymbolKind::Impl
+            || !members
+                .iter()
+                .any(|(file, start, end)| *file == r.file_path && *start >= r.line_start && *end <= r.line_end)
+    });
     results
 }
 
+/// Results grouped in order of each group's best hit
+fn group_results<'a>(results: &'a [SearchResult], group_by: GroupBy, root: &Path) -> Vec<ResultGroup<'a>> {
+    let packages = match group_by {
+        GroupBy::Package => affected::discover_packages(root).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    let mut groups: Vec<ResultGroup> = Vec::new();
+    for result in results {
+        let label = match group_by {
+            GroupBy::File => display_path(&result.file_path, root),
+            GroupBy::Kind => kind_label(result.symbol_kind).to_string(),
+            GroupBy::Package => {
+                let path = Path::new(&result.file_path);
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                affected::owner(&packages, relative)
+                    .map(|p| p.name.clone())
+                    .unwrap_or_else(|| "(no package)".to_string())
+            }
+        };
+        match groups.iter_mut().find(|g| g.label == label) {
+            Some(group) => group.results.push(result),
+            None => groups.push(ResultGroup { label, results: vec![result] }),
+        }
+    }
+    groups
+}
+
+fn display_path(file_path: &str, root: &Path) -> String {
+    let path = Path::new(file_path);
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+fn kind_label(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function => "fn",
+        SymbolKind::Struct => "struct",
+        SymbolKind::Class => "class",
+        SymbolKind::Enum => "enum",
+        SymbolKind::Trait => "trait",
+        SymbolKind::Interface => "interface",
+        SymbolKind::Module => "mod",
+        SymbolKind::Constant => "const",
+        SymbolKind::Impl => "impl",
+        SymbolKind::TypeAlias => "type",
+    }
+}
+
+fn kind_icon(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Function => symbols::FUNCTION,
+        SymbolKind::Struct | SymbolKind::Class => symbols::STRUCT,
+        _ => symbols::FILE,
+    }
+}
+
+fn match_indicator(match_type: &MatchType) -> String {
+    match match_type {
+        MatchType::ExactName => format!("{}exact{}", colors::SUCCESS, colors::RESET),
+        MatchType::PartialName => format!("{}name{}", colors::WARNING, colors::RESET),
+        MatchType::ContentMatch => format!("{}content{}", colors::PRIMARY, colors::RESET),
+        MatchType::ContextMatch => format!("{}context{}", colors::MUTED, colors::RESET),
+        MatchType::Structured => format!("{}query{}", colors::SUCCESS, colors::RESET),
+    }
+}
+
 /// Index all supported files in the codebase
 fn index_codebase(path: &Path) -> Result<Vec<ParsedFile>> {
     let abs_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
@@ -213,16 +449,15 @@ fn index_codebase(path: &Path) -> Result<Vec<ParsedFile>> {
             name != "__pycache__" &&
             name != "vendor"
         })
+        .flatten()
     {
-        if let Ok(entry) = entry {
-            let file_path = entry.path();
-
-            if file_path.is_file() {
-                let language = Language::from_path(file_path);
-                if language != Language::Unknown {
-                    if let Ok(parsed) = parser.parse_file(file_path) {
-                        parsed_files.push(parsed);
-                    }
+        let file_path = entry.path();
+
+        if file_path.is_file() {
+            let language = Language::from_path(file_path);
+            if language != Language::Unknown {
+                if let Ok(parsed) = parser.parse_file(file_path) {
+                    parsed_files.push(parsed);
                 }
             }
         }
@@ -274,43 +509,17 @@ fn print_results(results: &[SearchResult], query: &str) {
     println!();
 
     for (i, result) in results.iter().enumerate() {
-        let kind_icon = match result.symbol_kind {
-            SymbolKind::Function => symbols::FUNCTION,
-            SymbolKind::Struct | SymbolKind::Class => symbols::STRUCT,
-            _ => symbols::FILE,
-        };
-
-        let kind_str = match result.symbol_kind {
-            SymbolKind::Function => "fn",
-            SymbolKind::Struct => "struct",
-            SymbolKind::Class => "class",
-            SymbolKind::Enum => "enum",
-            SymbolKind::Trait => "trait",
-            SymbolKind::Interface => "interface",
-            SymbolKind::Module => "mod",
-            SymbolKind::Constant => "const",
-            SymbolKind::Impl => "impl",
-            SymbolKind::TypeAlias => "type",
-        };
-
-        let match_indicator = match result.match_type {
-            MatchType::ExactName => format!("{}exact{}", colors::SUCCESS, colors::RESET),
-            MatchType::PartialName => format!("{}name{}", colors::WARNING, colors::RESET),
-            MatchType::ContentMatch => format!("{}content{}", colors::PRIMARY, colors::RESET),
-            MatchType::ContextMatch => format!("{}context{}", colors::MUTED, colors::RESET),
-        };
-
         // Result header
         println!(
             "{}  {}. {} {}{}{} ({}) [{}]",
             colors::MUTED,
             i + 1,
-            kind_icon,
+            kind_icon(result.symbol_kind),
             colors::FG,
             result.symbol_name,
             colors::RESET,
-            kind_str,
-            match_indicator
+            kind_label(result.symbol_kind),
+            match_indicator(&result.match_type)
         );
 
         // File location
@@ -325,12 +534,11 @@ fn print_results(results: &[SearchResult], query: &str) {
 
         // Signature or context preview
         if let Some(sig) = &result.signature {
-            let sig_preview: String = sig.chars().take(80).collect();
             println!(
                 "{}      {}{}",
                 colors::MUTED,
-                sig_preview,
-                if sig.len() > 80 { "..." } else { "" }
+                text::truncate_code(sig, 80),
+                colors::RESET
             );
         }
 
@@ -345,12 +553,67 @@ fn print_results(results: &[SearchResult], query: &str) {
     println!();
 }
 
-fn print_no_results(query: &str) {
+fn print_groups(groups: &[ResultGroup], group_by: GroupBy, root: &Path, total: usize, query: &str) {
+    println!(
+        "{}{}  {} Found {} results for \"{}\" in {} groups{}",
+        colors::SUCCESS, colors::BOLD, symbols::MATCH,
+        total, query, groups.len(), colors::RESET
+    );
+    println!();
+
+    for group in groups {
+        println!(
+            "{}  {} {}{}{} {}({}){}",
+            colors::PRIMARY, symbols::GROUP, colors::BOLD, text::truncate_start(&group.label, 60),
+            colors::RESET, colors::MUTED, group.results.len(), colors::RESET
+        );
+        for result in group.results.iter().take(GROUP_PREVIEW) {
+            // The group header already names the file
+            let file = match group_by {
+                GroupBy::File => String::new(),
+                _ => text::truncate_start(&display_path(&result.file_path, root), 40),
+            };
+            println!(
+                "{}    {} {}{}{} ({}) {}:{} [{}]",
+                colors::MUTED,
+                kind_icon(result.symbol_kind),
+                colors::FG,
+                result.symbol_name,
+                colors::MUTED,
+                kind_label(result.symbol_kind),
+                file,
+                result.line_start,
+                match_indicator(&result.match_type)
+            );
+        }
+        if group.results.len() > GROUP_PREVIEW {
+            println!(
+                "{}    … {} more{}",
+                colors::MUTED, group.results.len() - GROUP_PREVIEW, colors::RESET
+            );
+        }
+        println!();
+    }
+}
+
+fn print_no_results(query: &str, structured: bool) {
     println!(
         "{}  {} No results found for \"{}\"{}",
         colors::WARNING, symbols::SEARCH, query, colors::RESET
     );
     println!();
+    if structured {
+        println!(
+            "{}  Every term must match; drop one to widen the query.{}",
+            colors::MUTED, colors::RESET
+        );
+        println!(
+            "{}  Keys: kind:fn,struct name:parse* lang:rust path:src/ai calls:send calledby:main, -term to negate{}",
+            colors::MUTED, colors::RESET
+        );
+        println!();
+        return;
+    }
     println!(
         "{}  Try:{}",
         colors::MUTED, colors::RESET
@@ -370,6 +633,20 @@ fn print_no_results(query: &str) {
     println!();
 }
 
+fn print_success(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::SUCCESS, symbols::MATCH, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
+
 fn print_warning(message: &str) {
     println!(
         "{}  {} {}{}",
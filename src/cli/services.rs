@@ -0,0 +1,181 @@
+//! Services command - how the services of a system depend on each other
+//!
+//! Builds a system-level graph from docker-compose files, Kubernetes
+//! manifests and the services' own code, and points out dependencies that
+//! are used but undeclared or that nothing answers to.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::diagram;
+use crate::core::permissions;
+use crate::core::journal::{Change, Journal};
+use crate::core::services::{EdgeKind, Issue, ServiceMap};
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const SERVICES: &str = "󰒍";
+    pub const SERVICE: &str = "󰆧";
+    pub const ARROW: &str = "→";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Service dependency graph of the system under the current directory
+pub async fn map(_config: Config, output: Option<&str>) -> Result<()> {
+    print_header();
+
+    let map = ServiceMap::discover(Path::new("."))?;
+    if map.services.is_empty() {
+        print_warning("No services found (looked for docker-compose files and Kubernetes manifests)");
+        return Ok(());
+    }
+
+    print_dependencies(&map);
+    print_issues(&map.issues);
+
+    let mermaid = map.render_mermaid();
+    let Some(path) = output else {
+        print_diagram(&mermaid);
+        return Ok(());
+    };
+
+    let content = if path.ends_with(".md") || path.ends_with(".markdown") {
+        diagram::fenced(&mermaid)
+    } else {
+        mermaid
+    };
+    permissions::write_file(path, content)?;
+    Journal::current()
+        .record("services map", Change::FileWritten { path: path.to_string(), provenance: None })
+        .ok();
+
+    print_success(&format!("Diagram saved to {}", path));
+    Ok(())
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header() {
+    println!();
+    println!(
+        "{}{}  {} Service Map{}",
+        colors::PRIMARY, colors::BOLD, symbols::SERVICES, colors::RESET
+    );
+    println!(
+        "{}  │ {}compose, Kubernetes manifests and code{}",
+        colors::MUTED, colors::FG, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_dependencies(map: &ServiceMap) {
+    println!(
+        "{}  {} services, {} dependencies{}",
+        colors::MUTED, map.services.len(), map.edges.len(), colors::RESET
+    );
+    println!();
+
+    for service in &map.services {
+        println!(
+            "{}  {} {}{}{} {}:{}{}",
+            colors::MUTED, symbols::SERVICE, colors::FG, service.name, colors::MUTED,
+            service.manifest.display(), service.line, colors::RESET
+        );
+        for ((_, to), evidence) in map.edges.iter().filter(|((from, _), _)| *from == service.name) {
+            let color = if map.is_undeclared(&service.name, to) { colors::WARNING } else { colors::FG };
+            println!(
+                "{}    {} {}{}{}",
+                colors::MUTED, symbols::ARROW, color, to, colors::RESET
+            );
+            for e in evidence.iter().filter(|e| e.kind != EdgeKind::Declared) {
+                println!(
+                    "{}      {} {} ({}:{}){}",
+                    colors::MUTED, e.kind.label(), e.detail, e.file.display(), e.line, colors::RESET
+                );
+            }
+        }
+    }
+    println!();
+}
+
+fn print_issues(issues: &[Issue]) {
+    if issues.is_empty() {
+        print_success("Every dependency is declared and reachable");
+        return;
+    }
+
+    for issue in issues {
+        let location = match issue {
+            Issue::Unreachable { evidence, .. } => format!(" ({}:{})", evidence.file.display(), evidence.line),
+            _ => String::new(),
+        };
+        println!(
+            "{}  {} {}{}{}{}",
+            colors::WARNING, symbols::WARNING, issue.describe(), colors::MUTED, location, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_diagram(mermaid: &str) {
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    for line in mermaid.lines() {
+        println!("{}  │ {}{}{}", colors::MUTED, colors::FG, line, colors::RESET);
+    }
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!(
+        "{}  Paste into a ```mermaid block, or use -o services.md / -o services.mmd{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_success(message: &str) {
+    println!(
+        "{}{}  {} {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, message, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
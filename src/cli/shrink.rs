@@ -0,0 +1,358 @@
+//! Shrink command - crash reproducer minimization
+//!
+//! Minimizes a crashing input with delta debugging, then asks the AI to
+//! explain the minimized trigger and propose a fix.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions;
+use crate::core::shrink::{self, Outcome, Reproducer};
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const SHRINK: &str = "󰘕";
+    pub const AI_ICON: &str = "󰌤";
+    pub const FILE: &str = "󰈙";
+    pub const CRASH: &str = "󰚌";
+    pub const SUCCESS: &str = "󰄂";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for explaining a minimized crash
+const SHRINK_PROMPT: &str = r#"You are NEXUS AI, an expert debugging assistant.
+
+You are given a crashing input that has been minimized with delta debugging,
+the command that reproduces the crash, its error output and, when available,
+the source code around the crash site.
+
+Output Format:
+1. **Trigger**: What property of the minimized input causes the crash
+2. **Root Cause**: Why the code fails on it
+3. **Fix**: A minimal, targeted fix as a code block
+4. **Regression Test**: A test that feeds the minimized input
+
+Use markdown code blocks with the appropriate language tag for code."#;
+
+/// Source lines shown on each side of the crash site
+const SOURCE_CONTEXT_LINES: usize = 12;
+
+/// Bytes of a binary input shown to the AI
+const MAX_HEX_BYTES: usize = 256;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+pub async fn run(
+    config: Config,
+    input: &str,
+    command: &[String],
+    output: Option<&str>,
+    timeout_secs: u64,
+    explain: bool,
+) -> Result<()> {
+    print_header(input, command);
+
+    let input_path = Path::new(input);
+    let original = fs::read(input_path)
+        .with_context(|| format!("Failed to read input: {}", input))?;
+
+    let mut reproducer = Reproducer::new(command.to_vec(), Duration::from_secs(timeout_secs), input_path)?;
+
+    // The original input must crash, or there is nothing to minimize
+    let baseline = reproducer.run(&original)?;
+    if !baseline.crashed() {
+        let reason = if baseline.timed_out { "timed out" } else { "exited successfully" };
+        print_error(&format!("The command {} on this input; nothing to minimize", reason));
+        return Ok(());
+    }
+
+    let signature = baseline.signature();
+    print_crash(&signature);
+
+    let pb = create_spinner();
+    let minimized = shrink::shrink_bytes(&original, &mut |candidate: &[u8]| {
+        pb.set_message(format!("{} bytes · {} runs", candidate.len(), reproducer.runs));
+        pb.tick();
+        reproducer.reproduces(candidate, &signature)
+    });
+    pb.finish_and_clear();
+
+    let output_path = output.map(String::from).unwrap_or_else(|| format!("{}.min", input));
+    permissions::write_file(&output_path, &minimized)?;
+    Journal::current()
+        .record("shrink", Change::FileWritten { path: output_path.clone(), provenance: None })
+        .ok();
+
+    print_result(&output_path, original.len(), &minimized, reproducer.runs);
+
+    if !explain {
+        return Ok(());
+    }
+
+    let outcome = reproducer.run(&minimized)?;
+    explain_crash(&config, command, &minimized, &outcome).await
+}
+
+/// Ask the AI to explain the minimized trigger and propose a fix
+async fn explain_crash(config: &Config, command: &[String], minimized: &[u8], outcome: &Outcome) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    let mut prompt = format!(
+        "{}\n\n## Reproduce Command\n\n`{}`\n\n## Crash Signature\n\n{}\n\n## Minimized Input ({} bytes)\n\n{}\n\n## Error Output\n\n```\n{}\n```\n",
+        context::REPOSITORY_CONTENT_RULES,
+        command.join(" "),
+        outcome.signature(),
+        minimized.len(),
+        render_input(minimized),
+        outcome.stderr_tail()
+    );
+
+    if let Some((file, line)) = crash_site(&outcome.stderr) {
+        let exclusions = CloudExclusions::from_config(&config.privacy)?;
+        if let Ok(source) = exclusions.read_file(Path::new(&file)) {
+            let excerpt = source_excerpt(&source, line);
+            prompt.push_str(&format!(
+                "\n## Crash Site ({}:{})\n\n{}\n",
+                file, line, context::delimit(&file, &excerpt)
+            ));
+        }
+    }
+
+    prompt.push_str("\n## Task\n\nExplain what in the minimized input triggers the crash and propose a fix.");
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(SHRINK_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", SHRINK_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(SHRINK_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+    print_response(&response);
+
+    Ok(())
+}
+
+/// Show text inputs verbatim and binary inputs as hex
+fn render_input(input: &[u8]) -> String {
+    match std::str::from_utf8(input) {
+        Ok(text) => format!("```\n{}\n```", text),
+        Err(_) => {
+            let hex: Vec<String> = input.iter().take(MAX_HEX_BYTES).map(|b| format!("{:02x}", b)).collect();
+            let more = if input.len() > MAX_HEX_BYTES { " ..." } else { "" };
+            format!("Binary (hex):\n```\n{}{}\n```", hex.join(" "), more)
+        }
+    }
+}
+
+/// Source file and line of the crash, from a Rust panic or Python traceback
+fn crash_site(stderr: &str) -> Option<(String, usize)> {
+    // thread 'main' panicked at src/parse.rs:42:9:
+    if let Some(idx) = stderr.find("panicked at ") {
+        let location = stderr[idx + 12..].split_whitespace().next()?;
+        let mut parts = location.trim_end_matches(':').split(':');
+        let file = parts.next()?.to_string();
+        let line = parts.next()?.parse().ok()?;
+        return Some((file, line));
+    }
+
+    // File "app/parser.py", line 12, in parse (innermost frame is last)
+    stderr.lines().rev().find_map(|l| {
+        let rest = l.trim().strip_prefix("File \"")?;
+        let (file, rest) = rest.split_once('"')?;
+        let line = rest.trim_start_matches(", line ").split(',').next()?.trim().parse().ok()?;
+        Path::new(file).exists().then(|| (file.to_string(), line))
+    })
+}
+
+/// Numbered lines around a line of interest
+fn source_excerpt(source: &str, line: usize) -> String {
+    let start = line.saturating_sub(SOURCE_CONTEXT_LINES + 1);
+    source
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take(SOURCE_CONTEXT_LINES * 2 + 1)
+        .map(|(i, l)| format!("{:>5}{} {}", i + 1, if i + 1 == line { ">" } else { " " }, l))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(input: &str, command: &[String]) {
+    println!();
+    println!(
+        "{}{}  {} Crash Minimizer{}",
+        colors::PRIMARY, colors::BOLD, symbols::SHRINK, colors::RESET
+    );
+    println!(
+        "{}  │ Input: {}{}{}",
+        colors::MUTED, colors::FG, input, colors::RESET
+    );
+    println!(
+        "{}  │ Command: {}{}{}",
+        colors::MUTED, colors::FG, command.join(" "), colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_crash(signature: &str) {
+    println!(
+        "{}  {} Crash reproduced: {}{}{}",
+        colors::WARNING, symbols::CRASH, colors::FG, signature, colors::RESET
+    );
+}
+
+fn create_spinner() -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(ProgressStyle::default_spinner()
+        .template("{spinner:.cyan} {prefix:.bold} {msg:.dim}")
+        .unwrap()
+        .tick_strings(&symbols::SPINNER));
+    pb.set_prefix("Shrinking");
+    pb
+}
+
+fn print_result(path: &str, original: usize, minimized: &[u8], runs: usize) {
+    let reduction = if original > 0 {
+        100.0 - (minimized.len() as f64 / original as f64 * 100.0)
+    } else {
+        0.0
+    };
+
+    println!();
+    println!(
+        "{}{}  {} Minimized {} → {} bytes ({:.1}% smaller, {} runs){}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS,
+        original, minimized.len(), reduction, runs, colors::RESET
+    );
+    println!(
+        "{}  {} {}{}{}",
+        colors::MUTED, symbols::FILE, colors::FG, path, colors::RESET
+    );
+
+    if let Ok(text) = std::str::from_utf8(minimized) {
+        println!(
+            "{}  ╭{}─{}",
+            colors::MUTED, "─".repeat(60), colors::RESET
+        );
+        for line in text.lines().take(20) {
+            println!("{}  │ {}{}{}", colors::MUTED, colors::FG, line, colors::RESET);
+        }
+        println!(
+            "{}  ╰{}─{}",
+            colors::MUTED, "─".repeat(60), colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is analyzing the crash {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_response(response: &str) {
+    println!(
+        "{}{}  {} Crash Analysis{}",
+        colors::SUCCESS, colors::BOLD, symbols::AI_ICON, colors::RESET
+    );
+    println!(
+        "{}  ╭{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+
+    for line in response.lines() {
+        println!("{}  │ {}{}", colors::MUTED, colors::FG, line);
+    }
+
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(60), colors::RESET
+    );
+    println!();
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
@@ -0,0 +1,192 @@
+//! Similar command - find code like a snippet
+//!
+//! Ranks the project's functions and types by how closely they resemble a
+//! range of a file or a pasted snippet, to find duplicate logic, prior art,
+//! or the right home for a new function.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::index::{self, similarity};
+use crate::ui::text;
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const SIMILAR: &str = "󰘙";
+    pub const FILE: &str = "󰈙";
+    pub const COPY: &str = "󰆏";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+}
+
+/// Shingle overlap from which a match is flagged as a near copy
+const NEAR_COPY_OVERLAP: f32 = 0.8;
+
+/// Lines of context around a single queried line
+const LINE_CONTEXT: usize = 10;
+
+/// The code to compare against
+struct Target {
+    label: String,
+    code: String,
+    /// The queried range, left out of the results
+    range: Option<(PathBuf, usize, usize)>,
+}
+
+/// Find code like `target`: `file:10-40`, `file:25`, `file`, `-` for stdin,
+/// or a snippet
+pub async fn run(_config: Config, target: &str, limit: usize) -> Result<()> {
+    let target = match resolve_target(target) {
+        Ok(target) => target,
+        Err(e) => {
+            print_header(target);
+            print_error(&e.to_string());
+            return Ok(());
+        }
+    };
+    print_header(&target.label);
+
+    let root = Path::new(".");
+    let files: Vec<PathBuf> = index::collect_files(root, false)?
+        .into_iter()
+        .map(|f| clean(&f))
+        .collect();
+    let exclude = target.range.as_ref().map(|(path, start, end)| (path.as_path(), *start, *end));
+    let ranked = similarity::rank_similar(&target.code, &files, exclude, limit);
+
+    if ranked.is_empty() {
+        print_warning("Nothing similar found");
+        println!();
+        return Ok(());
+    }
+    print_matches(&ranked);
+    Ok(())
+}
+
+fn resolve_target(target: &str) -> Result<Target> {
+    if target == "-" {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code).context("Failed to read stdin")?;
+        return Ok(Target { label: "stdin".to_string(), code, range: None });
+    }
+
+    let (path, lines) = match target.rsplit_once(':') {
+        Some((path, lines)) if !lines.is_empty() && lines.chars().all(|c| c.is_ascii_digit() || c == '-') => {
+            (path, Some(lines))
+        }
+        _ => (target, None),
+    };
+    if !Path::new(path).is_file() {
+        // Not a file: the argument is the code itself
+        return Ok(Target { label: text::truncate_code(target, 50), code: target.to_string(), range: None });
+    }
+
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let total = content.lines().count();
+    let (start, end) = match lines {
+        None => (1, total),
+        Some(lines) => match lines.split_once('-') {
+            Some((start, end)) => (start.parse().context("Invalid start line")?, end.parse().context("Invalid end line")?),
+            None => {
+                let line: usize = lines.parse().context("Invalid line")?;
+                (line.saturating_sub(LINE_CONTEXT).max(1), line + LINE_CONTEXT)
+            }
+        },
+    };
+    let end = end.min(total);
+    if start == 0 || start > end {
+        anyhow::bail!("No lines {}-{} in {} ({} lines)", start, end, path, total);
+    }
+
+    let code = content.lines().skip(start - 1).take(end - start + 1).collect::<Vec<_>>().join("\n");
+    Ok(Target {
+        label: format!("{}:{}-{}", path, start, end),
+        code,
+        range: Some((clean(Path::new(path)), start, end)),
+    })
+}
+
+/// `./src/lib.rs` → `src/lib.rs`
+fn clean(path: &Path) -> PathBuf {
+    path.strip_prefix(".").unwrap_or(path).to_path_buf()
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(target: &str) {
+    println!();
+    println!(
+        "{}{}  {} Similar Code{}",
+        colors::PRIMARY, colors::BOLD, symbols::SIMILAR, colors::RESET
+    );
+    println!(
+        "{}  │ Like: {}{}{}",
+        colors::MUTED, colors::FG, target, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_matches(ranked: &[similarity::RankedChunk]) {
+    for chunk in ranked {
+        let percent = (chunk.score * 100.0).round() as u32;
+        let color = match percent {
+            70.. => colors::SUCCESS,
+            40..=69 => colors::WARNING,
+            _ => colors::MUTED,
+        };
+        let copy = if chunk.overlap >= NEAR_COPY_OVERLAP {
+            format!("  {}{} near copy", colors::WARNING, symbols::COPY)
+        } else {
+            String::new()
+        };
+        println!(
+            "{}{:>4}%{}  {}{}{}  {}{} {}:{}-{}{}{}",
+            color, percent, colors::RESET,
+            colors::FG, chunk.name.as_deref().unwrap_or("(code)"), colors::RESET,
+            colors::MUTED, symbols::FILE, text::truncate_start(&chunk.path.display().to_string(), 50),
+            chunk.line_start, chunk.line_end, copy, colors::RESET
+        );
+    }
+    println!();
+    println!(
+        "{}  💡 Use 'nexus explain <file>' for detailed explanation{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
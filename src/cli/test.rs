@@ -5,12 +5,16 @@
 #![allow(dead_code)]
 
 use anyhow::Result;
-use std::fs;
 use std::io::{self, Write};
 use std::path::Path;
 
 use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::ai::postprocess::Pipeline;
 use crate::config::Config;
+use crate::core::hooks;
+use crate::core::permissions;
 use crate::core::parser::{CodeParser, Language};
 
 /// AI Provider mode
@@ -18,6 +22,7 @@ use crate::core::parser::{CodeParser, Language};
 enum AiMode {
     Claude,
     Proxy,
+    Local,
 }
 
 // ANSI color codes
@@ -37,6 +42,7 @@ mod symbols {
     pub const AI_ICON: &str = "󰌤";
     pub const FILE: &str = "󰈙";
     pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
     pub const ERROR: &str = "󰅚";
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
@@ -68,20 +74,23 @@ Use markdown code blocks with the appropriate language tag."#;
 
 /// Determine which AI mode to use
 fn determine_ai_mode() -> AiMode {
-    if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
         AiMode::Claude
     } else {
         AiMode::Proxy
     }
 }
 
-pub async fn run(_config: Config, file: &str, output: Option<&str>) -> Result<()> {
+pub async fn run(config: Config, file: &str, output: Option<&str>) -> Result<()> {
     print_header(file);
 
     let ai_mode = determine_ai_mode();
     let provider_name = match ai_mode {
         AiMode::Claude => "Claude",
         AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
     };
 
     // Read the file
@@ -91,7 +100,7 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>) -> Result<()
         return Ok(());
     }
 
-    let content = fs::read_to_string(path)?;
+    let content = CloudExclusions::from_config(&config.privacy)?.read_file(path)?;
     let lang = Language::from_path(path);
     let lines = content.lines().count();
 
@@ -101,6 +110,7 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>) -> Result<()
     let symbol_count = parsed.symbols.len();
 
     print_file_info(file, lang, lines, symbol_count);
+    context::print_injection_warnings(file, &content);
 
     // Build symbol list for context
     let symbol_list: Vec<String> = parsed.symbols
@@ -109,12 +119,12 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>) -> Result<()
         .collect();
 
     let prompt = format!(
-        "## Code to Test\n\n**File:** `{}`\n**Language:** {}\n\n### Symbols found:\n{}\n\n```{}\n{}\n```\n\n## Task\n\nGenerate comprehensive unit tests for this code.",
+        "{}\n\n## Code to Test\n\n**File:** `{}`\n**Language:** {}\n\n### Symbols found:\n{}\n\n{}\n\n## Task\n\nGenerate comprehensive unit tests for this code.",
+        context::REPOSITORY_CONTENT_RULES,
         file,
         lang,
         symbol_list.join("\n"),
-        lang.to_string().to_lowercase(),
-        content
+        context::delimit(file, &content)
     );
 
     // Send to AI
@@ -133,14 +143,20 @@ pub async fn run(_config: Config, file: &str, output: Option<&str>) -> Result<()
             let prompt_with_system = format!("{}\n\n{}", TEST_PROMPT, prompt);
             proxy.chat(&prompt_with_system, None).await?
         }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(TEST_PROMPT).chat(&prompt).await?
+        }
     };
 
     clear_line();
+    let response = Pipeline::for_command(&config.postprocess, "test").apply(&response);
 
     // Extract code from response if output file specified
     if let Some(out_path) = output {
         if let Some(code) = extract_code_block(&response, lang) {
-            fs::write(out_path, &code)?;
+            let code = hooks::pre_apply(out_path, code)?;
+            permissions::write_file(out_path, &code)?;
+            hooks::post_apply(out_path, &code);
             print_saved(out_path, &code);
         } else {
             print_response(&response);
@@ -0,0 +1,310 @@
+//! Tour command - guided code tours for new teammates
+//!
+//! Retrieves the code most relevant to a topic, has the AI order it into
+//! annotated steps, and writes a CodeTour file that VS Code can replay.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ai::{ClaudeClient, Conversation, ProxyClient};
+use crate::ai::router::AiRouter;
+use crate::ai::context::{self, CloudExclusions};
+use crate::config::Config;
+use crate::core::journal::{Change, Journal};
+use crate::core::permissions;
+use crate::core::parser::{CodeParser, ParsedFile};
+use crate::core::tour::{self, Candidate, Tour, TourStep, TOURS_DIR};
+use crate::index;
+use crate::ui::NexusForm;
+
+/// AI Provider mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AiMode {
+    Claude,
+    Proxy,
+    Local,
+}
+
+// ANSI color codes
+mod colors {
+    pub const RESET: &str = "\x1b[0m";
+    pub const BOLD: &str = "\x1b[1m";
+    pub const PRIMARY: &str = "\x1b[38;2;100;181;246m";      // #64B5F6
+    pub const SUCCESS: &str = "\x1b[38;2;165;214;167m";      // #A5D6A7
+    pub const ERROR: &str = "\x1b[38;2;239;154;154m";        // #EF9A9A
+    pub const WARNING: &str = "\x1b[38;2;255;202;40m";       // #FFCA28
+    pub const MUTED: &str = "\x1b[38;2;84;110;122m";         // #546E7A
+    pub const FG: &str = "\x1b[38;2;212;212;215m";           // #D4D4D7
+}
+
+mod symbols {
+    pub const TOUR: &str = "󰆋";
+    pub const AI_ICON: &str = "󰌤";
+    pub const FILE: &str = "󰈙";
+    pub const SUCCESS: &str = "󰄂";
+    pub const WARNING: &str = "󰀦";
+    pub const ERROR: &str = "󰅚";
+    pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+}
+
+/// System prompt for writing a tour
+const TOUR_PROMPT: &str = r#"You are NEXUS AI, a senior engineer onboarding a new teammate.
+
+You are given a topic and excerpts of the code most relevant to it, each
+tagged with its file path and starting line number. Write a guided tour
+that walks through the topic in the order a newcomer should read it:
+entry point first, then the core logic, then supporting pieces.
+
+Rules:
+1. Only use files and line numbers that appear in the excerpts
+2. Point each step at the line where the interesting code starts
+3. Each description is 1-3 sentences of Markdown explaining what happens
+   here and why it matters for the topic
+4. Keep titles short (at most 6 words)
+
+Respond with only a JSON object:
+{"title": "...", "description": "one-sentence overview",
+ "steps": [{"file": "src/x.rs", "line": 12, "title": "...", "description": "..."}]}"#;
+
+/// Symbols retrieved and shown to the AI
+const MAX_CANDIDATES: usize = 12;
+
+/// Lines of each symbol included in the prompt
+const EXCERPT_LINES: usize = 40;
+
+/// Determine which AI mode to use
+fn determine_ai_mode() -> AiMode {
+    if AiRouter::local_default() {
+        AiMode::Local
+    } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        AiMode::Claude
+    } else {
+        AiMode::Proxy
+    }
+}
+
+/// Tour as the AI returns it
+#[derive(Debug, Deserialize)]
+struct DraftTour {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    steps: Vec<TourStep>,
+}
+
+/// Generate a CodeTour for a topic
+pub async fn generate(config: Config, topic: &str, max_steps: usize, output: Option<&str>) -> Result<()> {
+    let ai_mode = determine_ai_mode();
+    let provider_name = match ai_mode {
+        AiMode::Claude => "Claude",
+        AiMode::Proxy => "NEXUS AI (Free)",
+        AiMode::Local => "Local model",
+    };
+
+    print_header(topic, provider_name);
+
+    let root = Path::new(".");
+    let mut parser = CodeParser::new().context("Failed to initialize code parser")?;
+    let files: Vec<ParsedFile> = index::collect_files(root, false)?
+        .iter()
+        .filter_map(|f| parser.parse_file(f).ok())
+        .collect();
+
+    let candidates = tour::rank_candidates(&files, topic, MAX_CANDIDATES);
+    if candidates.is_empty() {
+        print_error(&format!("No code found matching \"{}\"", topic));
+        return Ok(());
+    }
+    print_candidates(&candidates);
+
+    let path = match output {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(TOURS_DIR).join(tour::tour_file_name(topic)),
+    };
+    if path.exists() && !NexusForm::ask_confirm(&format!("{} exists. Overwrite?", path.display()), false)? {
+        print_warning("Tour not written");
+        return Ok(());
+    }
+
+    let exclusions = CloudExclusions::from_config(&config.privacy)?;
+    let excerpts: Vec<String> = candidates
+        .iter()
+        .filter_map(|c| {
+            let content = exclusions.read_file(&c.path).ok()?;
+            let excerpt: Vec<&str> = content
+                .lines()
+                .skip(c.line_start.saturating_sub(1))
+                .take((c.line_end + 1 - c.line_start.min(c.line_end)).min(EXCERPT_LINES))
+                .collect();
+            let source = format!("{} (from line {})", tour::tour_path(&c.path, root), c.line_start);
+            Some(context::delimit(&source, &excerpt.join("\n")))
+        })
+        .collect();
+
+    let prompt = format!(
+        "{}\n\n## Topic\n\n{}\n\n## Code\n\n{}\n\n## Task\n\nWrite a tour of at most {} steps.",
+        context::REPOSITORY_CONTENT_RULES,
+        topic,
+        excerpts.join("\n\n"),
+        max_steps
+    );
+
+    print_thinking(provider_name);
+
+    let response = match ai_mode {
+        AiMode::Claude => {
+            let client = ClaudeClient::from_env()?;
+            let mut conversation = Conversation::new(client)
+                .with_system(TOUR_PROMPT);
+
+            conversation.send(&prompt).await?
+        }
+        AiMode::Proxy => {
+            let proxy = ProxyClient::from_env();
+            let prompt_with_system = format!("{}\n\n{}", TOUR_PROMPT, prompt);
+            proxy.chat(&prompt_with_system, None).await?
+        }
+        AiMode::Local => {
+            AiRouter::local_client().with_system(TOUR_PROMPT).chat(&prompt).await?
+        }
+    };
+
+    clear_line();
+
+    let Some(draft) = parse_draft(&response) else {
+        print_error("Could not read a tour from the response");
+        return Ok(());
+    };
+
+    let proposed = draft.steps.len();
+    let mut steps = tour::validate_steps(draft.steps, &candidates, &files, root);
+    steps.truncate(max_steps);
+    if steps.len() < proposed {
+        print_warning(&format!("Dropped {} steps pointing outside the retrieved code", proposed - steps.len()));
+    }
+    if steps.is_empty() {
+        print_error("The tour has no valid steps");
+        return Ok(());
+    }
+
+    let title = if draft.title.trim().is_empty() { topic } else { draft.title.trim() };
+    let tour = Tour::new(title, draft.description.trim(), steps);
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    permissions::write_file(&path, serde_json::to_string_pretty(&tour)? + "\n")?;
+    Journal::current()
+        .record("tour generate", Change::FileWritten { path: path.display().to_string(), provenance: None })
+        .ok();
+
+    print_tour(&tour);
+    print_saved(&path);
+    Ok(())
+}
+
+/// The JSON object in the response
+fn parse_draft(response: &str) -> Option<DraftTour> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    serde_json::from_str(&response[start..=end]).ok()
+}
+
+// ============================================
+// UI Functions
+// ============================================
+
+fn print_header(topic: &str, provider: &str) {
+    println!();
+    println!(
+        "{}{}  {} Code Tour{}",
+        colors::PRIMARY, colors::BOLD, symbols::TOUR, colors::RESET
+    );
+    println!(
+        "{}  │ {}{}{}",
+        colors::MUTED, colors::FG, topic, colors::RESET
+    );
+    println!(
+        "{}  │ {}via {}{}",
+        colors::MUTED, colors::MUTED, provider, colors::RESET
+    );
+    println!(
+        "{}  ╰{}─{}",
+        colors::MUTED, "─".repeat(50), colors::RESET
+    );
+    println!();
+}
+
+fn print_candidates(candidates: &[Candidate]) {
+    for c in candidates {
+        println!(
+            "{}  {} {}{}{} ({}:{}){}",
+            colors::MUTED, symbols::FILE, colors::FG, c.name, colors::MUTED,
+            c.path.display(), c.line_start, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_thinking(provider: &str) {
+    print!(
+        "\r{}  {} {} is writing the tour {}{}",
+        colors::WARNING,
+        symbols::AI_ICON,
+        provider,
+        symbols::SPINNER[0],
+        colors::RESET
+    );
+    io::stdout().flush().ok();
+}
+
+fn clear_line() {
+    print!("\r{}\r", " ".repeat(70));
+    io::stdout().flush().ok();
+}
+
+fn print_tour(tour: &Tour) {
+    println!("{}{}  {}{}", colors::FG, colors::BOLD, tour.title, colors::RESET);
+    for (n, step) in tour.steps.iter().enumerate() {
+        let title = if step.title.is_empty() { &step.description } else { &step.title };
+        println!(
+            "{}  {:>2}. {}{}{} {}:{}{}",
+            colors::MUTED, n + 1, colors::FG, title, colors::MUTED,
+            step.file, step.line, colors::RESET
+        );
+    }
+    println!();
+}
+
+fn print_saved(path: &Path) {
+    println!(
+        "{}{}  {} Tour saved to {}{}",
+        colors::SUCCESS, colors::BOLD, symbols::SUCCESS, path.display(), colors::RESET
+    );
+    println!(
+        "{}  Open it with the CodeTour extension in VS Code{}",
+        colors::MUTED, colors::RESET
+    );
+    println!();
+}
+
+fn print_warning(message: &str) {
+    println!(
+        "{}  {} {}{}",
+        colors::WARNING, symbols::WARNING, message, colors::RESET
+    );
+}
+
+fn print_error(message: &str) {
+    println!(
+        "\n{}  {} Error: {}{}",
+        colors::ERROR, symbols::ERROR, message, colors::RESET
+    );
+}
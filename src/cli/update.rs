@@ -1,6 +1,8 @@
 //! Self-update command for NEXUS AI Forge
 //!
 //! Checks GitHub releases for newer versions and updates the binary.
+//! Installs from a `.deb`, `.rpm` or MSI are updated with the newer
+//! package; Homebrew, Scoop and cargo installs are left to their manager.
 
 #![allow(dead_code)]
 
@@ -8,6 +10,11 @@ use anyhow::{Context, Result, anyhow};
 use std::io::{self, Write};
 use std::fs;
 use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use crate::core::packaging::{self, Flavor};
+use crate::core::permissions::{self, Permission};
 
 // ANSI color codes
 mod colors {
@@ -30,7 +37,7 @@ mod symbols {
     pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 }
 
-const GITHUB_REPO: &str = "mrsarac/NEXUS-AI-Forge";
+const GITHUB_REPO: &str = packaging::REPOSITORY;
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// GitHub Release API response
@@ -53,6 +60,7 @@ struct GitHubAsset {
 /// Run the update command
 pub async fn run(check_only: bool, force: bool) -> Result<()> {
     print_header();
+    let flavor = Flavor::current();
 
     // Check for latest version
     print_status("Checking for updates...");
@@ -79,12 +87,27 @@ pub async fn run(check_only: bool, force: bool) -> Result<()> {
         );
     }
 
+    print_flavor(flavor);
     if check_only {
         return Ok(());
     }
 
-    // Find the right asset for this platform
-    let asset = find_platform_asset(&latest.assets)?;
+    // Package managers own their copy of the binary
+    if let Some(command) = flavor.manager_command() {
+        print_managed(flavor, &command);
+        return Ok(());
+    }
+
+    // Find the right asset for this platform and install flavor
+    let asset = match flavor.package_asset(latest_version, env::consts::ARCH) {
+        Some(name) => find_package_asset(&latest.assets, &name)?,
+        None => find_platform_asset(&latest.assets)?,
+    };
+    let checksums_asset = latest
+        .assets
+        .iter()
+        .find(|a| a.name == packaging::CHECKSUMS_ASSET)
+        .ok_or_else(|| anyhow!("The release has no {}, so the download cannot be verified", packaging::CHECKSUMS_ASSET))?;
 
     // Confirm update
     if !force {
@@ -105,11 +128,18 @@ pub async fn run(check_only: bool, force: bool) -> Result<()> {
     print_downloading(&asset.name, asset.size);
 
     let binary_data = download_binary(&asset.browser_download_url).await?;
+    let checksums = download_binary(&checksums_asset.browser_download_url).await?;
+    packaging::verify_checksum(&asset.name, &binary_data, &String::from_utf8_lossy(&checksums))?;
     clear_line();
 
     print_installing();
-    install_binary(&binary_data)?;
-    clear_line();
+    if flavor.is_package() {
+        clear_line();
+        install_package(flavor, &asset.name, &binary_data)?;
+    } else {
+        install_binary(&binary_data)?;
+        clear_line();
+    }
 
     print_success(latest_version);
 
@@ -149,6 +179,7 @@ fn get_github_token() -> Option<String> {
 
 /// Fetch the latest release from GitHub
 async fn fetch_latest_release() -> Result<GitHubRelease> {
+    permissions::request(Permission::Network, "check GitHub for a new release")?;
     let client = reqwest::Client::builder()
         .user_agent("nexus-forge-updater")
         .build()?;
@@ -221,14 +252,8 @@ fn find_platform_asset(assets: &[GitHubAsset]) -> Result<&GitHubAsset> {
     let arch = env::consts::ARCH;
 
     // Map to expected asset names
-    let expected_name = match (os, arch) {
-        ("macos", "aarch64") => "nexus-darwin-arm64",
-        ("macos", "x86_64") => "nexus-darwin-x64",
-        ("linux", "x86_64") => "nexus-linux-x64",
-        ("linux", "aarch64") => "nexus-linux-arm64",
-        ("windows", "x86_64") => "nexus-windows-x64.exe",
-        _ => return Err(anyhow!("Unsupported platform: {}-{}", os, arch)),
-    };
+    let expected_name = packaging::binary_asset(os, arch)
+        .ok_or_else(|| anyhow!("Unsupported platform: {}-{}", os, arch))?;
 
     assets
         .iter()
@@ -240,8 +265,17 @@ fn find_platform_asset(assets: &[GitHubAsset]) -> Result<&GitHubAsset> {
         ))
 }
 
+/// Find the package of the newer release for a packaged install
+fn find_package_asset<'a>(assets: &'a [GitHubAsset], name: &str) -> Result<&'a GitHubAsset> {
+    assets
+        .iter()
+        .find(|a| a.name == name)
+        .ok_or_else(|| anyhow!("The release has no {} for this install. Download it manually from the release page.", name))
+}
+
 /// Download the binary from GitHub (supports private repos)
 async fn download_binary(url: &str) -> Result<Vec<u8>> {
+    permissions::request(Permission::Network, &format!("download {}", url))?;
     let client = reqwest::Client::builder()
         .user_agent("nexus-forge-updater")
         .redirect(reqwest::redirect::Policy::limited(10))
@@ -281,6 +315,7 @@ fn install_binary(data: &[u8]) -> Result<()> {
     // Get current binary path
     let current_exe = env::current_exe()
         .context("Failed to get current executable path")?;
+    permissions::request(Permission::Write, &format!("replace {}", current_exe.display()))?;
 
     // Create backup
     let backup_path = current_exe.with_extension("old");
@@ -316,6 +351,35 @@ fn install_binary(data: &[u8]) -> Result<()> {
     }
 }
 
+/// Install a downloaded `.deb`, `.rpm` or MSI with the system's installer,
+/// from a directory only this user can write to
+fn install_package(flavor: Flavor, name: &str, data: &[u8]) -> Result<()> {
+    let dir = tempfile::Builder::new()
+        .prefix("nexus-update-")
+        .tempdir()
+        .context("Failed to create a directory for the download")?;
+    let file_name = Path::new(name).file_name().ok_or_else(|| anyhow!("Invalid asset name: {}", name))?;
+    let path = dir.path().join(file_name);
+    fs::write(&path, data).with_context(|| format!("Failed to save {}", path.display()))?;
+    let (program, args) = flavor
+        .install_command(&path)
+        .ok_or_else(|| anyhow!("{} installs are not updated with a package", flavor.label()))?;
+
+    permissions::request(Permission::Run, &format!("run `{} {}`", program, args.join(" ")))?;
+    println!(
+        "{}  {} Running {} {}{}",
+        colors::MUTED, symbols::INFO, program, args.join(" "), colors::RESET
+    );
+    let status = Command::new(program)
+        .args(&args)
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
+
 // ============================================
 // UI Functions
 // ============================================
@@ -398,6 +462,22 @@ fn print_update_available(current: &str, latest: &str, release: &GitHubRelease)
     );
 }
 
+fn print_flavor(flavor: Flavor) {
+    println!(
+        "{}  Installed as: {}{}",
+        colors::MUTED, flavor.label(), colors::RESET
+    );
+}
+
+fn print_managed(flavor: Flavor, command: &str) {
+    println!(
+        "\n{}  {} This copy is managed by {}. Update it with:{}",
+        colors::WARNING, symbols::INFO, flavor.label(), colors::RESET
+    );
+    println!("{}    {}{}", colors::FG, command, colors::RESET);
+    println!();
+}
+
 fn print_downloading(name: &str, size: u64) {
     let size_mb = size as f64 / 1024.0 / 1024.0;
     print!(
@@ -1,8 +1,11 @@
 //! Configuration management for NEXUS AI Forge
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::permissions::{Access, Permission};
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +14,22 @@ pub struct Config {
     pub ai: AiConfig,
     pub privacy: PrivacyConfig,
     pub index: IndexConfig,
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    #[serde(default)]
+    pub postprocess: PostprocessConfig,
+    /// Interactive chat settings
+    #[serde(default)]
+    pub chat: ChatConfig,
+    /// Budget of `--deep` analyses
+    #[serde(default)]
+    pub deep: DeepConfig,
+    /// Second-opinion check of answers (`--verify`)
+    #[serde(default)]
+    pub verify: VerifyConfig,
+    /// Named repositories that can be queried together, e.g. `api = "~/src/api"`
+    #[serde(default)]
+    pub roots: BTreeMap<String, PathBuf>,
     #[serde(skip)]
     pub verbose: bool,
 }
@@ -27,6 +46,10 @@ pub struct AiConfig {
     pub default_provider: String,
     pub local_fallback: bool,
     pub providers: AiProviders,
+    /// Limits per provider (`claude`, `proxy`), e.g.
+    /// `[ai.quotas.proxy] max_context_tokens = 16000`
+    #[serde(default)]
+    pub quotas: BTreeMap<String, QuotaConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +68,16 @@ pub struct ProviderConfig {
     pub temperature: Option<f32>,
 }
 
+/// Usage limits for one provider
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Largest prompt, history included, sent in one request
+    pub max_context_tokens: Option<usize>,
+    /// Requests allowed per day (UTC)
+    pub max_requests_per_day: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalProviderConfig {
     pub enabled: bool,
@@ -58,6 +91,11 @@ pub struct PrivacyConfig {
     pub send_code_to_cloud: bool,
     pub local_embeddings: bool,
     pub anonymize_telemetry: bool,
+    /// Glob patterns (e.g. `internal/crypto/**`) whose contents never go to
+    /// cloud providers; only their signatures are sent, and their changes
+    /// are left out of diffs
+    #[serde(default)]
+    pub cloud_exclude: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +105,92 @@ pub struct IndexConfig {
     pub max_file_size_mb: u32,
 }
 
+/// License compliance checks for generated code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComplianceConfig {
+    /// Check generated code for near-verbatim copies before writing it
+    pub similarity_check: bool,
+    /// Shingle overlap (0.0-1.0) at which a match is reported
+    pub similarity_threshold: f32,
+    /// GitHub-compatible code search endpoint, e.g. `https://api.github.com/search/code`
+    pub code_search_url: Option<String>,
+    /// Environment variable holding the code search token
+    pub code_search_token_env: String,
+}
+
+impl Default for ComplianceConfig {
+    fn default() -> Self {
+        Self {
+            similarity_check: false,
+            similarity_threshold: 0.8,
+            code_search_url: None,
+            code_search_token_env: "GITHUB_TOKEN".to_string(),
+        }
+    }
+}
+
+/// Processing of AI responses before they are shown or written
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostprocessConfig {
+    /// Phrases removed from prose, matched case-insensitively
+    pub banned_phrases: Vec<String>,
+    /// Formatter per language reading stdin, e.g. `python = "ruff format -"`
+    pub formatters: BTreeMap<String, String>,
+    /// Steps per command, replacing its defaults, e.g. `chat = ["strip_phrases"]`
+    pub commands: BTreeMap<String, Vec<String>>,
+}
+
+/// Settings of `nexus chat`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatConfig {
+    /// Send messages to the search, ask, edit or command pipeline their
+    /// intent calls for instead of always chatting; `/route` toggles it
+    pub route: bool,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self { route: true }
+    }
+}
+
+/// Verification of `ask` and `explain` answers by a second request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VerifyConfig {
+    /// Verify every answer, as if `--verify` were given
+    pub always: bool,
+    /// Verifier provider (`claude`, `proxy`, `local`); empty uses the one that answered
+    pub provider: String,
+    /// Verifier model, to get a second opinion from a different model
+    pub model: Option<String>,
+}
+
+/// Limits of a `--deep` analysis, which makes several AI passes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeepConfig {
+    /// Wall-clock time for all passes
+    pub max_seconds: u64,
+    /// Estimated tokens sent and received over all passes
+    pub max_tokens: usize,
+    /// Areas the outline may pick for a deep dive
+    pub max_areas: usize,
+}
+
+impl Default for DeepConfig {
+    fn default() -> Self {
+        Self {
+            max_seconds: 300,
+            max_tokens: 200_000,
+            max_areas: 5,
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -104,11 +228,13 @@ impl Default for Config {
                         endpoint: Some("http://localhost:11434".to_string()),
                     }),
                 },
+                quotas: BTreeMap::new(),
             },
             privacy: PrivacyConfig {
                 send_code_to_cloud: false,
                 local_embeddings: true,
                 anonymize_telemetry: true,
+                cloud_exclude: Vec::new(),
             },
             index: IndexConfig {
                 auto_index: true,
@@ -121,11 +247,55 @@ impl Default for Config {
                 ],
                 max_file_size_mb: 10,
             },
+            compliance: ComplianceConfig::default(),
+            postprocess: PostprocessConfig::default(),
+            chat: ChatConfig::default(),
+            verify: VerifyConfig::default(),
+            deep: DeepConfig::default(),
+            roots: BTreeMap::new(),
             verbose: false,
         }
     }
 }
 
+impl Config {
+    /// Directory of a registered root, with a leading `~` expanded
+    pub fn root_path(&self, name: &str) -> Option<PathBuf> {
+        let path = self.roots.get(name)?;
+        match path.strip_prefix("~") {
+            Ok(rest) => directories::BaseDirs::new().map(|d| d.home_dir().join(rest)),
+            Err(_) => Some(path.clone()),
+        }
+    }
+
+    /// Label and directory for a root name; names not in `[roots]` may be
+    /// paths, labelled with their directory name
+    pub fn resolve_root(&self, name: &str) -> Result<(String, PathBuf)> {
+        if let Some(root) = self.root_path(name) {
+            if !root.is_dir() {
+                bail!("Root `{}` points to {}, which is not a directory", name, root.display());
+            }
+            return Ok((name.to_string(), root));
+        }
+
+        let root = PathBuf::from(name);
+        if root.is_dir() {
+            let label = root
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| name.to_string());
+            return Ok((label, root));
+        }
+
+        if self.roots.is_empty() {
+            bail!("Unknown root `{}`; register repositories under [roots] in the config file", name);
+        }
+        let registered: Vec<&str> = self.roots.keys().map(String::as_str).collect();
+        bail!("Unknown root `{}` (registered: {})", name, registered.join(", "))
+    }
+}
+
 /// Get the configuration file path
 fn config_path() -> Result<PathBuf> {
     let config_dir = directories::ProjectDirs::from("com", "nexus", "forge")
@@ -155,6 +325,176 @@ pub fn load_config(custom_path: Option<&str>) -> Result<Config> {
     }
 }
 
+/// Per-project settings file in the project root, checked in with the code
+pub const PROJECT_CONFIG_FILE: &str = ".nexus.toml";
+
+/// Settings from `.nexus.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub format: FormatConfig,
+    pub imports: ImportsConfig,
+    pub hooks: HooksConfig,
+    pub permissions: PermissionsConfig,
+    pub memory: MemoryConfig,
+    pub privacy: ProjectPrivacyConfig,
+}
+
+/// Formatting of files NEXUS writes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatConfig {
+    /// Run the project's formatter on each file written
+    pub on_write: bool,
+    /// Formatter per language, `{file}` being the written file, e.g.
+    /// `python = "ruff format {file}"`; an empty command disables one
+    pub formatters: BTreeMap<String, String>,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            on_write: true,
+            formatters: BTreeMap::new(),
+        }
+    }
+}
+
+/// Import cleanup of files NEXUS writes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportsConfig {
+    /// Remove unused and duplicate imports, add missing ones, and sort them
+    pub on_write: bool,
+    /// Also run goimports or isort when installed and set up
+    pub tools: bool,
+}
+
+impl Default for ImportsConfig {
+    fn default() -> Self {
+        Self {
+            on_write: true,
+            tools: true,
+        }
+    }
+}
+
+/// Scripts run around AI operations, each a shell command run in the
+/// project root with a JSON payload on stdin, e.g.
+/// `pre_prompt = ["python scripts/redact.py"]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Before a request is sent; may rewrite the prompt or refuse it
+    pub pre_prompt: Vec<String>,
+    /// After a response arrives; may rewrite the response or reject it
+    pub post_response: Vec<String>,
+    /// Before generated content is written; may rewrite it or block the write
+    pub pre_apply: Vec<String>,
+    /// After a file is written; failures are only reported
+    pub post_apply: Vec<String>,
+    /// How long one hook may run
+    pub timeout_secs: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            pre_prompt: Vec::new(),
+            post_response: Vec::new(),
+            pre_apply: Vec::new(),
+            post_apply: Vec::new(),
+            timeout_secs: 30,
+        }
+    }
+}
+
+/// What NEXUS may do without asking: `allow`, `ask` or `deny` for each of
+/// reading files it picked itself, writing AI edits, running commands and
+/// sending code over the network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionsConfig {
+    pub read: Access,
+    pub write: Access,
+    pub run: Access,
+    pub network: Access,
+}
+
+impl Default for PermissionsConfig {
+    fn default() -> Self {
+        Self {
+            read: Access::Allow,
+            write: Access::Ask,
+            run: Access::Ask,
+            network: Access::Allow,
+        }
+    }
+}
+
+impl PermissionsConfig {
+    pub fn access(&self, permission: Permission) -> Access {
+        match permission {
+            Permission::Read => self.read,
+            Permission::Write => self.write,
+            Permission::Run => self.run,
+            Permission::Network => self.network,
+        }
+    }
+
+    pub fn access_mut(&mut self, permission: Permission) -> &mut Access {
+        match permission {
+            Permission::Read => &mut self.read,
+            Permission::Write => &mut self.write,
+            Permission::Run => &mut self.run,
+            Permission::Network => &mut self.network,
+        }
+    }
+}
+
+/// Privacy rules of one project, added to the global `[privacy]` ones
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectPrivacyConfig {
+    /// Glob patterns, relative to the project root, whose contents never go
+    /// to cloud providers
+    pub cloud_exclude: Vec<String>,
+}
+
+/// Long-term memory in `.nexus/memory.md`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Add remembered facts to every AI request, and distill new ones from
+    /// chats and reviews
+    pub enabled: bool,
+    /// Most characters of memory added to a request; the newest facts are kept
+    pub max_chars: usize,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_chars: 4000,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Settings of the project at `root`, or the defaults without a `.nexus.toml`
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(PROJECT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
 /// Initialize configuration file with defaults
 pub fn init_config() -> Result<()> {
     let path = config_path()?;
@@ -182,6 +522,21 @@ pub fn init_config() -> Result<()> {
     Ok(())
 }
 
+/// Write `config` to the configuration file, returning its path
+pub fn save_config(config: &Config) -> Result<PathBuf> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config directory {:?}", parent))?;
+    }
+
+    let content = toml::to_string_pretty(config)
+        .context("Failed to serialize config")?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write config to {:?}", path))?;
+    Ok(path)
+}
+
 /// Show current configuration
 pub fn show_config(config: &Config) -> Result<()> {
     let content = toml::to_string_pretty(config)
@@ -0,0 +1,174 @@
+//! AI activity log
+//!
+//! One line per AI request made from a project, with its provider and token
+//! counts, stored as JSON lines in `.nexus/activity.jsonl`. Claude reports
+//! exact counts; the proxy and Ollama counts are estimates.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::journal::{create_project_dir, PROJECT_DIR};
+
+const ACTIVITY_FILE: &str = "activity.jsonl";
+
+/// A single AI request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub timestamp: DateTime<Utc>,
+    /// NEXUS command that made the request
+    pub command: String,
+    pub provider: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl Interaction {
+    /// A request made now by the running command
+    pub fn new(provider: &str, input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            command: running_command(),
+            provider: provider.to_string(),
+            input_tokens,
+            output_tokens,
+        }
+    }
+
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Requests and tokens of one provider over a period
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Spend {
+    pub requests: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl Spend {
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Activity log for a project root
+pub struct ActivityLog {
+    path: PathBuf,
+}
+
+impl ActivityLog {
+    /// Log for the project in the current directory
+    pub fn current() -> Self {
+        Self::for_root(Path::new("."))
+    }
+
+    /// Log for a specific project root
+    pub fn for_root(root: &Path) -> Self {
+        Self {
+            path: root.join(PROJECT_DIR).join(ACTIVITY_FILE),
+        }
+    }
+
+    /// Append a request to the log
+    pub fn record(&self, interaction: &Interaction) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_project_dir(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open activity log {}", self.path.display()))?;
+
+        writeln!(file, "{}", serde_json::to_string(interaction)?)
+            .context("Failed to write activity entry")
+    }
+
+    /// Read all requests, oldest first
+    pub fn entries(&self) -> Result<Vec<Interaction>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read activity log {}", self.path.display()))?;
+
+        Ok(content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect())
+    }
+}
+
+/// Start of the week (Monday 00:00 UTC) containing `now`
+pub fn week_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let monday = now.date_naive() - Duration::days(now.weekday().num_days_from_monday() as i64);
+    Utc.from_utc_datetime(&monday.and_hms_opt(0, 0, 0).unwrap_or_default())
+}
+
+/// Spend per provider of the requests made since `since`
+pub fn spend_since(entries: &[Interaction], since: DateTime<Utc>) -> BTreeMap<String, Spend> {
+    let mut spend: BTreeMap<String, Spend> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.timestamp >= since) {
+        let total = spend.entry(entry.provider.clone()).or_default();
+        total.requests += 1;
+        total.input_tokens += entry.input_tokens;
+        total.output_tokens += entry.output_tokens;
+    }
+    spend
+}
+
+/// The subcommand this process runs, `chat` when there is none
+pub fn running_command() -> String {
+    std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .unwrap_or_else(|| "chat".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_log_and_weekly_spend() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = ActivityLog::for_root(temp_dir.path());
+        assert!(log.entries().unwrap().is_empty());
+
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap();
+        let interaction = |day, provider: &str, input, output| Interaction {
+            timestamp: at(day, 12),
+            command: "ask".to_string(),
+            provider: provider.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+        };
+        // 2024-05-12 is a Sunday, 05-13 the Monday after
+        log.record(&interaction(12, "claude", 900, 100)).unwrap();
+        log.record(&interaction(13, "claude", 400, 200)).unwrap();
+        log.record(&interaction(15, "claude", 100, 50)).unwrap();
+        log.record(&interaction(15, "proxy", 30, 20)).unwrap();
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].tokens(), 1000);
+
+        let monday = week_start(at(15, 18));
+        assert_eq!(monday, at(13, 0));
+        let spend = spend_since(&entries, monday);
+        assert_eq!(spend["claude"], Spend { requests: 2, input_tokens: 500, output_tokens: 250 });
+        assert_eq!(spend["proxy"].tokens(), 50);
+    }
+}
@@ -0,0 +1,343 @@
+//! Affected packages and tests
+//!
+//! Maps changed files onto the packages of a monorepo (directories with a
+//! Cargo.toml, package.json or pyproject.toml), follows the internal
+//! dependency graph to every package downstream of a change, and picks out
+//! the test files that exercise the changed code, so CI can run only the
+//! jobs that matter.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::git;
+use super::manifest::{Manifest, ManifestKind};
+use crate::index;
+
+/// A package in the repository
+#[derive(Debug, Clone, Serialize)]
+pub struct Package {
+    pub name: String,
+    /// Directory relative to the repository root (`.` for the root)
+    pub path: PathBuf,
+    pub ecosystem: &'static str,
+    /// Names of other packages in the repository this one depends on
+    pub depends_on: BTreeSet<String>,
+}
+
+/// Why a package or test is affected
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "reason", content = "via", rename_all = "snake_case")]
+pub enum Reason {
+    /// A file inside it changed
+    Changed,
+    /// It depends, directly or transitively, on a changed package
+    Dependency(String),
+    /// A file every package shares changed (lockfiles, CI config, ...)
+    Global(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedPackage {
+    pub name: String,
+    pub path: PathBuf,
+    pub ecosystem: &'static str,
+    #[serde(flatten)]
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AffectedTest {
+    pub path: PathBuf,
+    pub package: Option<String>,
+    #[serde(flatten)]
+    pub reason: TestReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", content = "via", rename_all = "snake_case")]
+pub enum TestReason {
+    /// The test file itself changed
+    Changed,
+    /// It mentions a changed module or an affected package
+    References(String),
+    /// Its package is affected for a reason no narrower rule explains
+    Package,
+}
+
+/// What a change set affects
+#[derive(Debug, Clone, Serialize)]
+pub struct Affected {
+    pub base: String,
+    pub changed_files: Vec<PathBuf>,
+    pub packages: Vec<AffectedPackage>,
+    pub tests: Vec<AffectedTest>,
+}
+
+/// Packages under `root`, with their dependencies on each other
+pub fn discover_packages(root: &Path) -> Result<Vec<Package>> {
+    let manifests = index::collect_matching(root, |p| {
+        let name = p.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        matches!(name, "Cargo.toml" | "package.json" | "pyproject.toml")
+    })?;
+
+    let mut packages: Vec<(Package, Vec<String>)> = manifests
+        .iter()
+        .filter_map(|path| {
+            let manifest = Manifest::load(path).ok()?;
+            let dir = path.parent()?.strip_prefix(root).unwrap_or(path.parent()?).to_path_buf();
+            let dir = if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir };
+
+            // A Cargo workspace root without [package] is not a package itself
+            if manifest.kind == ManifestKind::Cargo && manifest.name.is_none() {
+                return None;
+            }
+            let name = manifest.name.clone().or_else(|| {
+                let dir_name = path.parent()?.file_name()?.to_string_lossy().to_string();
+                Some(dir_name)
+            })?;
+
+            let dependencies = manifest.dependencies.iter().map(|d| d.name.clone()).collect();
+            Some((
+                Package {
+                    name,
+                    path: dir,
+                    ecosystem: manifest.kind.ecosystem(),
+                    depends_on: BTreeSet::new(),
+                },
+                dependencies,
+            ))
+        })
+        .collect();
+
+    let names: BTreeSet<String> = packages.iter().map(|(p, _)| p.name.clone()).collect();
+    for (package, dependencies) in &mut packages {
+        package.depends_on = dependencies
+            .iter()
+            .filter(|d| names.contains(*d) && **d != package.name)
+            .cloned()
+            .collect();
+    }
+
+    Ok(packages.into_iter().map(|(p, _)| p).collect())
+}
+
+/// Files changed since the merge base with `base`, plus uncommitted changes
+pub fn changed_files(root: &Path, base: &str) -> Result<Vec<PathBuf>> {
+    let git = |args: &[&str]| git::git(root, args);
+
+    let committed = git(&["diff", "--name-only", "--relative", &format!("{}...HEAD", base)])?;
+    let uncommitted = git(&["diff", "--name-only", "--relative", "HEAD"])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+
+    let files: BTreeSet<PathBuf> = committed
+        .lines()
+        .chain(uncommitted.lines())
+        .chain(untracked.lines())
+        .filter(|l| !l.trim().is_empty())
+        .map(PathBuf::from)
+        .collect();
+    Ok(files.into_iter().collect())
+}
+
+/// Documentation changes never affect builds or tests
+fn is_documentation(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    path.starts_with("docs")
+        || name.ends_with(".md")
+        || name.ends_with(".txt")
+        || name.starts_with("license")
+        || name.starts_with("changelog")
+}
+
+/// Top-level files (lockfiles, root manifests) and dot-directories (CI
+/// config) are shared by every package
+fn is_global(path: &Path) -> bool {
+    path.components().count() == 1
+        || path.components().next().is_some_and(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// The innermost package containing `file`
+pub fn owner<'a>(packages: &'a [Package], file: &Path) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|p| p.path == Path::new(".") || file.starts_with(&p.path))
+        .max_by_key(|p| if p.path == Path::new(".") { 0 } else { p.path.components().count() })
+}
+
+/// Name a test would use to refer to a source file's module
+fn module_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    if matches!(stem.as_str(), "mod" | "lib" | "main" | "index" | "__init__") {
+        return path.parent()?.file_name().map(|d| d.to_string_lossy().to_string());
+    }
+    Some(stem)
+}
+
+fn mentions(content: &str, word: &str) -> bool {
+    let variants = [word.to_string(), word.replace('-', "_")];
+    content
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .any(|token| variants.iter().any(|v| v == token))
+}
+
+/// Packages and tests affected by `changed`
+pub fn analyze(root: &Path, base: &str, packages: &[Package], changed: Vec<PathBuf>) -> Affected {
+    let relevant: Vec<&PathBuf> = changed.iter().filter(|f| !is_documentation(f)).collect();
+
+    let mut reasons: BTreeMap<String, Reason> = BTreeMap::new();
+    for file in &relevant {
+        match owner(packages, file).filter(|_| !is_global(file)) {
+            Some(package) => {
+                reasons.insert(package.name.clone(), Reason::Changed);
+            }
+            None => {
+                let global = Reason::Global(file.display().to_string());
+                for package in packages {
+                    reasons.entry(package.name.clone()).or_insert_with(|| global.clone());
+                }
+            }
+        }
+    }
+
+    // Everything downstream of a changed package
+    let mut queue: VecDeque<String> = reasons.keys().cloned().collect();
+    while let Some(name) = queue.pop_front() {
+        for dependent in packages.iter().filter(|p| p.depends_on.contains(&name)) {
+            if !reasons.contains_key(&dependent.name) {
+                reasons.insert(dependent.name.clone(), Reason::Dependency(name.clone()));
+                queue.push_back(dependent.name.clone());
+            }
+        }
+    }
+
+    let affected_packages: Vec<AffectedPackage> = packages
+        .iter()
+        .filter_map(|p| {
+            Some(AffectedPackage {
+                name: p.name.clone(),
+                path: p.path.clone(),
+                ecosystem: p.ecosystem,
+                reason: reasons.get(&p.name)?.clone(),
+            })
+        })
+        .collect();
+
+    let changed_modules: Vec<String> = relevant
+        .iter()
+        .filter(|f| !index::is_test_path(f))
+        .filter_map(|f| module_name(f))
+        .collect();
+
+    let test_files = index::collect_files(root, false).unwrap_or_default();
+    let mut tests = Vec::new();
+    for path in test_files {
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        if !index::is_test_path(&relative) {
+            continue;
+        }
+        let package = owner(packages, &relative);
+        let reason = package.and_then(|p| reasons.get(&p.name));
+
+        let test_reason = if changed.contains(&relative) {
+            Some(TestReason::Changed)
+        } else {
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let referenced = changed_modules
+                .iter()
+                .chain(reasons.keys().filter(|name| package.is_none_or(|p| &p.name != *name)))
+                .find(|word| mentions(&content, word));
+            match (referenced, reason) {
+                (Some(word), _) => Some(TestReason::References(word.clone())),
+                // Downstream and global changes can break any test in the package
+                (None, Some(Reason::Dependency(_) | Reason::Global(_))) => Some(TestReason::Package),
+                _ => None,
+            }
+        };
+
+        if let Some(reason) = test_reason {
+            tests.push(AffectedTest {
+                path: relative,
+                package: package.map(|p| p.name.clone()),
+                reason,
+            });
+        }
+    }
+    tests.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Affected {
+        base: base.to_string(),
+        changed_files: changed,
+        packages: affected_packages,
+        tests,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn monorepo() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "package.json", r#"{"name": "monorepo", "private": true}"#);
+        write(root, "packages/core/package.json", r#"{"name": "core"}"#);
+        write(root, "packages/core/src/money.js", "export const add = (a, b) => a + b;\n");
+        write(root, "packages/core/src/dates.js", "export const today = () => new Date();\n");
+        write(root, "packages/core/tests/money.test.js", "import { add } from '../src/money';\n");
+        write(root, "packages/core/tests/dates.test.js", "import { today } from '../src/dates';\n");
+        write(root, "packages/api/package.json", r#"{"name": "api", "dependencies": {"core": "*"}}"#);
+        write(root, "packages/api/tests/routes.test.js", "import { app } from '../src/app';\n");
+        write(root, "packages/web/package.json", r#"{"name": "web"}"#);
+        write(root, "packages/web/tests/ui.test.js", "test('renders', () => {});\n");
+        dir
+    }
+
+    #[test]
+    fn test_dependency_graph_and_tests() {
+        let dir = monorepo();
+        let packages = discover_packages(dir.path()).unwrap();
+        let api = packages.iter().find(|p| p.name == "api").unwrap();
+        assert_eq!(api.depends_on.iter().collect::<Vec<_>>(), vec!["core"]);
+
+        let affected = analyze(dir.path(), "main", &packages, vec![PathBuf::from("packages/core/src/money.js"), PathBuf::from("README.md")]);
+
+        let names: Vec<(&str, &Reason)> = affected.packages.iter().map(|p| (p.name.as_str(), &p.reason)).collect();
+        assert_eq!(names, vec![("api", &Reason::Dependency("core".to_string())), ("core", &Reason::Changed)]);
+
+        let tests: Vec<(String, &TestReason)> = affected.tests.iter().map(|t| (t.path.display().to_string(), &t.reason)).collect();
+        assert_eq!(
+            tests,
+            vec![
+                ("packages/api/tests/routes.test.js".to_string(), &TestReason::Package),
+                ("packages/core/tests/money.test.js".to_string(), &TestReason::References("money".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_global_change_affects_every_package() {
+        let dir = monorepo();
+        let packages = discover_packages(dir.path()).unwrap();
+        let affected = analyze(dir.path(), "main", &packages, vec![PathBuf::from(".github/workflows/ci.yml")]);
+
+        assert_eq!(affected.packages.len(), 4);
+        assert!(affected.packages.iter().all(|p| p.reason == Reason::Global(".github/workflows/ci.yml".to_string())));
+        assert_eq!(affected.tests.len(), 4);
+
+        let json = serde_json::to_value(&affected).unwrap();
+        assert_eq!(json["packages"][0]["reason"], "global");
+        assert_eq!(json["packages"][0]["via"], ".github/workflows/ci.yml");
+    }
+}
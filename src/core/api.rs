@@ -0,0 +1,532 @@
+//! Public API surface of Rust crates
+//!
+//! Extracts the items a crate exposes (reachable through `pub mod` chains
+//! from the crate root) at a git revision or in the working tree, diffs two
+//! surfaces and derives the semver bump the changes require.
+//!
+//! Re-exports (`pub use`) and macro-generated items are not followed.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::git::git;
+use super::parser::{CodeParser, ParsedFile, Symbol, SymbolKind};
+
+/// Kinds of public items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Trait,
+    TraitMethod,
+    Constant,
+}
+
+impl ItemKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ItemKind::Function => "fn",
+            ItemKind::Method => "method",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::TraitMethod => "trait method",
+            ItemKind::Constant => "const",
+        }
+    }
+}
+
+/// A publicly reachable item
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiItem {
+    /// Full path from the crate root, e.g. `parser::CodeParser::new`
+    pub path: String,
+    pub kind: ItemKind,
+    /// Normalized declaration; for structs and enums it includes public
+    /// fields and variants
+    pub signature: String,
+    pub file: PathBuf,
+    /// Trait method without a default body
+    #[serde(skip)]
+    pub required: bool,
+    /// `#[non_exhaustive]` type, so new variants and fields are additive
+    #[serde(skip)]
+    pub non_exhaustive: bool,
+}
+
+/// The public items of a crate at one point in time
+#[derive(Debug, Clone, Default)]
+pub struct ApiSurface {
+    pub items: BTreeMap<String, ApiItem>,
+    /// `package.version` from the manifest
+    pub version: Option<String>,
+}
+
+impl ApiSurface {
+    /// Surface of the crate at `dir` in the working tree
+    pub fn working_tree(dir: &Path) -> Result<Self> {
+        let mut sources = BTreeMap::new();
+        for entry in walkdir::WalkDir::new(dir.join("src")).into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().is_some_and(|e| e == "rs") {
+                let relative = path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+                sources.insert(relative, fs::read_to_string(path)?);
+            }
+        }
+
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).ok();
+        Ok(Self::from_sources(&sources, manifest.as_deref()))
+    }
+
+    /// Surface of the crate at `dir` as of a git revision
+    pub fn at_revision(dir: &Path, revision: &str) -> Result<Self> {
+        let listing = git(dir, &["ls-tree", "-r", "--name-only", revision, "--", "src"])?;
+
+        let mut sources = BTreeMap::new();
+        for file in listing.lines().filter(|f| f.ends_with(".rs")) {
+            let content = git(dir, &["show", &format!("{}:./{}", revision, file)])?;
+            sources.insert(PathBuf::from(file), content);
+        }
+
+        let manifest = git(dir, &["show", &format!("{}:./Cargo.toml", revision)]).ok();
+        Ok(Self::from_sources(&sources, manifest.as_deref()))
+    }
+
+    /// Surface from crate-relative source paths (`src/lib.rs`, ...) and the manifest
+    pub fn from_sources(sources: &BTreeMap<PathBuf, String>, manifest: Option<&str>) -> Self {
+        let Ok(mut parser) = CodeParser::new() else {
+            return Self::default();
+        };
+        // A library's API starts at lib.rs; main.rs is only the root without one
+        let root = if sources.contains_key(Path::new("src/lib.rs")) { "src/lib.rs" } else { "src/main.rs" };
+
+        let files: BTreeMap<Vec<String>, ParsedFile> = sources
+            .iter()
+            .filter(|(path, _)| !is_root_file(path) || path.as_path() == Path::new(root))
+            .filter_map(|(path, content)| {
+                let module = module_path(path)?;
+                Some((module, parser.parse_source(path, content.clone()).ok()?))
+            })
+            .collect();
+
+        let mut items = BTreeMap::new();
+        for (module, file) in &files {
+            if !module_reachable(&files, module) {
+                continue;
+            }
+            for item in file_items(file, module) {
+                items.insert(item.path.clone(), item);
+            }
+        }
+
+        Self { items, version: manifest.and_then(package_version) }
+    }
+}
+
+/// `package.version`, following `version.workspace = true`
+pub fn package_version(manifest: &str) -> Option<String> {
+    let value: toml::Value = manifest.parse().ok()?;
+    match value.get("package")?.get("version")? {
+        toml::Value::String(version) => Some(version.clone()),
+        _ => value.get("workspace")?.get("package")?.get("version")?.as_str().map(String::from),
+    }
+}
+
+fn is_root_file(path: &Path) -> bool {
+    path == Path::new("src/lib.rs") || path == Path::new("src/main.rs")
+}
+
+/// Module path of a source file: `src/a/b.rs` and `src/a/b/mod.rs` → `[a, b]`
+fn module_path(path: &Path) -> Option<Vec<String>> {
+    let relative = path.strip_prefix("src").ok()?;
+    if relative.starts_with("bin") || is_root_file(path) {
+        return is_root_file(path).then(Vec::new);
+    }
+
+    let mut parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let last = parts.pop()?;
+    let stem = last.strip_suffix(".rs")?;
+    if stem != "mod" {
+        parts.push(stem.to_string());
+    }
+    Some(parts)
+}
+
+fn line(file: &ParsedFile, number: usize) -> &str {
+    file.content.lines().nth(number.saturating_sub(1)).unwrap_or("").trim()
+}
+
+/// `pub` without a restriction such as `pub(crate)`
+fn is_public(declaration: &str) -> bool {
+    declaration.strip_prefix("pub").is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Whether every module from the root to `module` is declared `pub mod`
+fn module_reachable(files: &BTreeMap<Vec<String>, ParsedFile>, module: &[String]) -> bool {
+    (1..=module.len()).all(|depth| {
+        let parent = &module[..depth - 1];
+        let name = &module[depth - 1];
+        files.get(parent).is_some_and(|file| {
+            file.symbols
+                .iter()
+                .any(|s| s.kind == SymbolKind::Module && &s.name == name && is_public(line(file, s.line_start)))
+        })
+    })
+}
+
+fn contains(outer: &Symbol, inner: &Symbol) -> bool {
+    !std::ptr::eq(outer, inner) && outer.line_start <= inner.line_start && inner.line_end <= outer.line_end
+}
+
+/// Public items declared in one file
+fn file_items(file: &ParsedFile, module: &[String]) -> Vec<ApiItem> {
+    let lines: Vec<&str> = file.content.lines().collect();
+    let mut items = Vec::new();
+
+    for symbol in &file.symbols {
+        let declaration = line(file, symbol.line_start);
+
+        // Inline modules must be public too
+        let mut path: Vec<String> = module.to_vec();
+        let mut hidden = false;
+        for m in file.symbols.iter().filter(|m| m.kind == SymbolKind::Module && contains(m, symbol)) {
+            hidden |= !is_public(line(file, m.line_start)) || line(file, m.line_start).ends_with(';');
+            path.push(m.name.clone());
+        }
+        if hidden {
+            continue;
+        }
+
+        let container = file
+            .symbols
+            .iter()
+            .filter(|c| matches!(c.kind, SymbolKind::Impl | SymbolKind::Trait) && contains(c, symbol))
+            .max_by_key(|c| c.line_start);
+
+        let kind = match (symbol.kind, container.map(|c| c.kind)) {
+            (SymbolKind::Function, Some(SymbolKind::Trait)) => ItemKind::TraitMethod,
+            (SymbolKind::Function, Some(SymbolKind::Impl)) => ItemKind::Method,
+            (SymbolKind::Function, _) => ItemKind::Function,
+            (SymbolKind::Struct, _) => ItemKind::Struct,
+            (SymbolKind::Enum, _) => ItemKind::Enum,
+            (SymbolKind::Trait, _) => ItemKind::Trait,
+            (SymbolKind::Constant, None) => ItemKind::Constant,
+            _ => continue,
+        };
+
+        let visible = match (kind, container) {
+            // Methods of a public trait are public; trait impls are covered by the trait
+            (ItemKind::TraitMethod, Some(t)) => is_public(line(file, t.line_start)),
+            (ItemKind::Method, Some(i)) => !line(file, i.line_start).contains(" for ") && is_public(declaration),
+            _ => is_public(declaration),
+        };
+        if !visible {
+            continue;
+        }
+
+        let owner = container.map(|c| {
+            let name = c.name.trim_start_matches("impl ");
+            name.split('<').next().unwrap_or(name).trim().to_string()
+        });
+        path.extend(owner);
+        path.push(symbol.name.clone());
+
+        let text = lines
+            .get(symbol.line_start.saturating_sub(1)..symbol.line_end.min(lines.len()))
+            .map(|l| l.join("\n"))
+            .unwrap_or_default();
+        let head = declaration_head(&text);
+
+        let signature = match kind {
+            ItemKind::Struct => {
+                let fields = member_lines(&text, is_public);
+                [head.clone()].into_iter().chain(fields).collect::<Vec<_>>().join("; ")
+            }
+            ItemKind::Enum => {
+                let variants = member_lines(&text, |l| !l.starts_with("//") && !l.starts_with('#'))
+                    .into_iter()
+                    .map(|v| v.trim_end_matches(',').to_string());
+                [head.clone()].into_iter().chain(variants).collect::<Vec<_>>().join("; ")
+            }
+            ItemKind::Constant => head.split('=').next().unwrap_or(&head).trim().to_string(),
+            _ => head.clone(),
+        };
+
+        let attributes = lines[..symbol.line_start.saturating_sub(1).min(lines.len())]
+            .iter()
+            .rev()
+            .take_while(|l| l.trim().starts_with("#[") || l.trim().starts_with("///"))
+            .any(|l| l.contains("non_exhaustive"));
+
+        items.push(ApiItem {
+            path: path.join("::"),
+            kind,
+            signature,
+            file: file.path.clone(),
+            required: kind == ItemKind::TraitMethod && text.trim_end().ends_with(';'),
+            non_exhaustive: attributes,
+        });
+    }
+
+    items
+}
+
+/// Declaration up to its body, with whitespace collapsed
+fn declaration_head(text: &str) -> String {
+    let end = text.find(['{', ';']).unwrap_or(text.len());
+    text[..end].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Top-level lines inside an item's braces that pass `keep`, trimmed
+fn member_lines(text: &str, keep: impl Fn(&str) -> bool) -> Vec<String> {
+    let Some(open) = text.find('{') else {
+        return Vec::new();
+    };
+
+    let mut depth = 0;
+    let mut members = Vec::new();
+    for l in text[open + 1..].lines() {
+        let trimmed = l.trim();
+        if depth == 0 && !trimmed.is_empty() && trimmed != "}" && keep(trimmed) {
+            members.push(trimmed.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        depth += l.matches(['{', '(']).count() as i32 - l.matches(['}', ')']).count() as i32;
+        depth = depth.max(0);
+    }
+    members
+}
+
+/// How an item changed between two surfaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiChange {
+    pub kind: ChangeKind,
+    pub path: String,
+    pub item: ItemKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    pub breaking: bool,
+}
+
+/// Semver bump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+    pub bump: Bump,
+}
+
+impl ApiDiff {
+    pub fn breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|c| c.breaking)
+    }
+}
+
+/// Changes from `old` to `new` and the bump they require
+pub fn diff(old: &ApiSurface, new: &ApiSurface) -> ApiDiff {
+    let mut changes = Vec::new();
+
+    for (path, before) in &old.items {
+        match new.items.get(path) {
+            None => changes.push(ApiChange {
+                kind: ChangeKind::Removed,
+                path: path.clone(),
+                item: before.kind,
+                before: Some(before.signature.clone()),
+                after: None,
+                breaking: true,
+            }),
+            Some(after) if after.signature != before.signature || after.kind != before.kind => {
+                changes.push(ApiChange {
+                    kind: ChangeKind::Changed,
+                    path: path.clone(),
+                    item: after.kind,
+                    before: Some(before.signature.clone()),
+                    after: Some(after.signature.clone()),
+                    breaking: is_breaking_change(before, after),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (path, after) in &new.items {
+        if old.items.contains_key(path) {
+            continue;
+        }
+        // A required method on an existing trait breaks every implementor
+        let breaking = after.required && {
+            let owner = path.rsplit_once("::").map(|(o, _)| o).unwrap_or("");
+            old.items.get(owner).is_some_and(|t| t.kind == ItemKind::Trait)
+        };
+        changes.push(ApiChange {
+            kind: ChangeKind::Added,
+            path: path.clone(),
+            item: after.kind,
+            before: None,
+            after: Some(after.signature.clone()),
+            breaking,
+        });
+    }
+
+    changes.sort_by(|a, b| b.breaking.cmp(&a.breaking).then(a.path.cmp(&b.path)));
+
+    let bump = if changes.iter().any(|c| c.breaking) {
+        Bump::Major
+    } else if !changes.is_empty() {
+        Bump::Minor
+    } else {
+        Bump::Patch
+    };
+
+    ApiDiff { changes, bump }
+}
+
+/// Struct and enum changes that only add public members are additive when
+/// the type is `#[non_exhaustive]`; everything else that changes shape breaks
+fn is_breaking_change(before: &ApiItem, after: &ApiItem) -> bool {
+    if before.kind != after.kind {
+        return true;
+    }
+    match before.kind {
+        ItemKind::Struct | ItemKind::Enum => {
+            let old: Vec<&str> = before.signature.split("; ").collect();
+            let new: Vec<&str> = after.signature.split("; ").collect();
+            let removed = old.iter().any(|m| !new.contains(m));
+            removed || !after.non_exhaustive
+        }
+        _ => true,
+    }
+}
+
+/// Version after applying `bump` to `current`. Below 1.0 a breaking change
+/// bumps the minor version and anything else the patch version, as Cargo
+/// treats `0.x` minors as incompatible.
+pub fn next_version(current: &str, bump: Bump) -> Option<String> {
+    let core = current.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let (major, minor, patch) = (parts.next()??, parts.next()??, parts.next()??);
+
+    let next = match (major, bump) {
+        (0, Bump::Major) => (0, minor + 1, 0),
+        (0, _) => (0, minor, patch + 1),
+        (_, Bump::Major) => (major + 1, 0, 0),
+        (_, Bump::Minor) => (major, minor + 1, 0),
+        (_, Bump::Patch) => (major, minor, patch + 1),
+    };
+    Some(format!("{}.{}.{}", next.0, next.1, next.2))
+}
+
+/// Whether moving from `old` to `new` is at least the given bump, using
+/// the same `0.x` rules as `next_version`. Pre-release tags count, so
+/// `2.0.0-rc.1` satisfies a major bump from `1.4.0`.
+pub fn bump_satisfied(old: &str, new: &str, bump: Bump) -> bool {
+    let parse = |v: &str| -> Option<(u64, u64, u64)> {
+        let core = v.trim().split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        Some((parts.next()??, parts.next()??, parts.next()??))
+    };
+    let (Some(required), Some(actual)) = (next_version(old, bump).as_deref().and_then(parse), parse(new)) else {
+        return false;
+    };
+    actual >= required
+}
+
+/// Split `a..b` into its revisions; an omitted end means the working tree
+pub fn parse_range(range: &str) -> (String, Option<String>) {
+    match range.split_once("..") {
+        Some((from, to)) => (
+            from.to_string(),
+            Some(to.trim_start_matches('.').to_string()).filter(|t| !t.is_empty()),
+        ),
+        None => (range.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface(files: &[(&str, &str)]) -> ApiSurface {
+        let sources = files.iter().map(|(p, c)| (PathBuf::from(p), c.to_string())).collect();
+        ApiSurface::from_sources(&sources, Some("[package]\nname = \"demo\"\nversion = \"1.4.0\"\n"))
+    }
+
+    #[test]
+    fn test_surface_follows_visibility() {
+        let api = surface(&[
+            ("src/main.rs", "fn main() {}\npub fn cli_only() {}\n"),
+            ("src/lib.rs", "pub mod shapes;\nmod internal;\n\npub fn area(\n    w: u32,\n    h: u32,\n) -> u32 {\n    w * h\n}\n\npub(crate) fn hidden() {}\n"),
+            ("src/shapes.rs", "pub struct Square {\n    pub side: u32,\n    cache: u32,\n}\n\nimpl Square {\n    pub fn new(side: u32) -> Self {\n        Self { side, cache: 0 }\n    }\n    fn private(&self) {}\n}\n\nimpl Default for Square {\n    fn default() -> Self { Self::new(1) }\n}\n\npub trait Shape {\n    fn area(&self) -> u32;\n}\n"),
+            ("src/internal.rs", "pub fn secret() {}\n"),
+        ]);
+
+        let paths: Vec<&str> = api.items.keys().map(|k| k.as_str()).collect();
+        assert_eq!(paths, vec!["area", "shapes::Shape", "shapes::Shape::area", "shapes::Square", "shapes::Square::new"]);
+        assert_eq!(api.items["area"].signature, "pub fn area( w: u32, h: u32, ) -> u32");
+        assert_eq!(api.items["shapes::Square"].signature, "pub struct Square; pub side: u32,");
+        assert!(api.items["shapes::Shape::area"].required);
+        assert_eq!(api.version.as_deref(), Some("1.4.0"));
+    }
+
+    #[test]
+    fn test_diff_and_bump() {
+        let old = surface(&[("src/lib.rs", "pub fn keep() {}\npub fn gone() {}\npub fn change(a: u32) {}\npub trait Plugin {\n    fn name(&self) -> String;\n}\n")]);
+        let additive = surface(&[("src/lib.rs", "pub fn keep() {}\npub fn gone() {}\npub fn change(a: u32) {}\npub fn extra() {}\npub trait Plugin {\n    fn name(&self) -> String;\n    fn version(&self) -> u32 { 1 }\n}\n")]);
+        let breaking = surface(&[("src/lib.rs", "pub fn keep() {}\npub fn change(a: u64) {}\npub trait Plugin {\n    fn name(&self) -> String;\n    fn id(&self) -> u32;\n}\n")]);
+
+        let minor = diff(&old, &additive);
+        assert_eq!(minor.bump, Bump::Minor);
+        assert_eq!(minor.changes.len(), 2);
+        assert_eq!(diff(&old, &old).bump, Bump::Patch);
+
+        let major = diff(&old, &breaking);
+        assert_eq!(major.bump, Bump::Major);
+        let broken: Vec<&str> = major.breaking().map(|c| c.path.as_str()).collect();
+        assert_eq!(broken, vec!["Plugin::id", "change", "gone"]);
+
+        assert_eq!(next_version("1.4.0", Bump::Major).as_deref(), Some("2.0.0"));
+        assert_eq!(next_version("0.5.3", Bump::Major).as_deref(), Some("0.6.0"));
+        assert_eq!(next_version("1.4.0-rc.1", Bump::Minor).as_deref(), Some("1.5.0"));
+        assert!(bump_satisfied("1.4.0", "2.0.0-rc.1", Bump::Major));
+        assert!(!bump_satisfied("1.4.0", "1.5.0", Bump::Major));
+        assert!(bump_satisfied("0.5.0", "0.6.0", Bump::Major));
+        assert!(!bump_satisfied("0.5.0", "0.5.1", Bump::Major));
+        assert_eq!(parse_range("v1.4.0..HEAD"), ("v1.4.0".to_string(), Some("HEAD".to_string())));
+        assert_eq!(parse_range("v1.4.0"), ("v1.4.0".to_string(), None));
+    }
+}
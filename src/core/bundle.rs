@@ -0,0 +1,318 @@
+//! Offline bundles for air-gapped machines
+//!
+//! A bundle is one `.tar.gz` holding everything nexus needs without network
+//! access: the binary, Ollama models copied from the local model store, and
+//! the documentation. Tree-sitter grammars are compiled into the binary, so
+//! the manifest only lists them.
+//!
+//! ```text
+//! bundle.json                       manifest
+//! bin/nexus                         binary
+//! models/manifests/<registry>/...   Ollama model manifests
+//! models/blobs/sha256-<digest>      model layers
+//! docs/*.md                         README and command reference
+//! ```
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "bundle.json";
+
+/// Grammars compiled into the binary
+pub const GRAMMARS: &[&str] = &["rust", "python", "javascript", "typescript"];
+
+/// Registry Ollama stores pulled models under
+const OLLAMA_REGISTRY: &str = "registry.ollama.ai";
+
+/// What a bundle contains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Version of the bundled binary
+    pub version: String,
+    pub created: DateTime<Utc>,
+    pub os: String,
+    pub arch: String,
+    /// Path of the binary in the archive
+    pub binary: String,
+    pub models: Vec<BundledModel>,
+    pub grammars: Vec<String>,
+    /// Documentation files, relative to `docs/`
+    pub docs: Vec<String>,
+}
+
+/// An Ollama model and its files, relative to the model store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledModel {
+    pub name: String,
+    pub files: Vec<String>,
+    pub size: u64,
+}
+
+/// What goes into a new bundle
+pub struct BundleSpec {
+    pub binary: PathBuf,
+    /// Ollama model store to copy models from
+    pub models_dir: PathBuf,
+    /// Models such as `codellama` or `qwen2.5-coder:7b`
+    pub models: Vec<String>,
+    /// Documentation: file name and content
+    pub docs: Vec<(String, String)>,
+}
+
+/// Where `install` puts a bundle's contents
+pub struct InstallTargets {
+    pub bin_dir: PathBuf,
+    pub models_dir: PathBuf,
+    pub docs_dir: PathBuf,
+}
+
+/// Ollama's model store: `$OLLAMA_MODELS`, else `~/.ollama/models`
+pub fn ollama_models_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("OLLAMA_MODELS") {
+        return Some(PathBuf::from(dir));
+    }
+    directories::BaseDirs::new().map(|dirs| dirs.home_dir().join(".ollama").join("models"))
+}
+
+/// Manifest path of a model in the store: `llama3.2:3b` is
+/// `manifests/registry.ollama.ai/library/llama3.2/3b`
+fn model_manifest_path(name: &str) -> Result<String> {
+    let (model, tag) = name.rsplit_once(':').unwrap_or((name, "latest"));
+    let parts: Vec<&str> = model.split('/').collect();
+    let path = match parts.as_slice() {
+        [model] => format!("{}/library/{}", OLLAMA_REGISTRY, model),
+        [namespace, model] => format!("{}/{}/{}", OLLAMA_REGISTRY, namespace, model),
+        [registry, namespace, model] => format!("{}/{}/{}", registry, namespace, model),
+        _ => bail!("Not a model name: {}", name),
+    };
+    if tag.is_empty() || path.split('/').any(|p| p.is_empty() || p == "..") {
+        bail!("Not a model name: {}", name);
+    }
+    Ok(format!("manifests/{}/{}", path, tag))
+}
+
+/// A model's manifest and blob files in the store at `models_dir`
+pub fn model_files(models_dir: &Path, name: &str) -> Result<BundledModel> {
+    #[derive(Deserialize)]
+    struct Layer {
+        digest: String,
+    }
+    #[derive(Deserialize)]
+    struct OllamaManifest {
+        config: Option<Layer>,
+        #[serde(default)]
+        layers: Vec<Layer>,
+    }
+
+    let manifest_path = model_manifest_path(name)?;
+    let content = fs::read_to_string(models_dir.join(&manifest_path))
+        .with_context(|| format!("Model {} is not in {} (run `ollama pull {}` first)", name, models_dir.display(), name))?;
+    let manifest: OllamaManifest =
+        serde_json::from_str(&content).with_context(|| format!("Unreadable manifest for model {}", name))?;
+
+    let mut files = vec![manifest_path];
+    let mut size = content.len() as u64;
+    for layer in manifest.config.iter().chain(&manifest.layers) {
+        let blob = format!("blobs/{}", layer.digest.replace(':', "-"));
+        let metadata = fs::metadata(models_dir.join(&blob))
+            .with_context(|| format!("Model {} is missing {}", name, blob))?;
+        size += metadata.len();
+        if !files.contains(&blob) {
+            files.push(blob);
+        }
+    }
+    Ok(BundledModel { name: name.to_string(), files, size })
+}
+
+/// Write a bundle to `output`
+pub fn create(spec: &BundleSpec, output: &Path) -> Result<BundleManifest> {
+    let models = spec
+        .models
+        .iter()
+        .map(|name| model_files(&spec.models_dir, name))
+        .collect::<Result<Vec<_>>>()?;
+    let binary_name = spec.binary.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "nexus".to_string());
+    let manifest = BundleManifest {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created: Utc::now(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        binary: format!("bin/{}", binary_name),
+        models,
+        grammars: GRAMMARS.iter().map(|g| g.to_string()).collect(),
+        docs: spec.docs.iter().map(|(name, _)| name.clone()).collect(),
+    };
+
+    let file = File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append_bytes(&mut archive, MANIFEST_FILE, serde_json::to_string_pretty(&manifest)?.as_bytes(), 0o644)?;
+    archive
+        .append_path_with_name(&spec.binary, &manifest.binary)
+        .with_context(|| format!("Failed to add {}", spec.binary.display()))?;
+    for model in &manifest.models {
+        for file in &model.files {
+            archive
+                .append_path_with_name(spec.models_dir.join(file), format!("models/{}", file))
+                .with_context(|| format!("Failed to add {} of model {}", file, model.name))?;
+        }
+    }
+    for (name, content) in &spec.docs {
+        append_bytes(&mut archive, &format!("docs/{}", name), content.as_bytes(), 0o644)?;
+    }
+    archive.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+fn append_bytes<W: std::io::Write>(archive: &mut tar::Builder<W>, path: &str, data: &[u8], mode: u32) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(mode);
+    header.set_mtime(Utc::now().timestamp() as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+/// The manifest of the bundle at `archive`
+pub fn read_manifest(archive: &Path) -> Result<BundleManifest> {
+    let mut entries = open(archive)?;
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() == Path::new(MANIFEST_FILE) {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            return serde_json::from_str(&content).context("Unreadable bundle manifest");
+        }
+    }
+    bail!("{} is not a nexus bundle (no {})", archive.display(), MANIFEST_FILE)
+}
+
+/// Unpack the bundle at `archive` into `targets`
+pub fn install(archive: &Path, targets: &InstallTargets) -> Result<BundleManifest> {
+    let manifest = read_manifest(archive)?;
+    if manifest.os != std::env::consts::OS || manifest.arch != std::env::consts::ARCH {
+        bail!(
+            "This bundle is for {}-{}, not {}-{}",
+            manifest.os, manifest.arch, std::env::consts::OS, std::env::consts::ARCH
+        );
+    }
+
+    let mut entries = open(archive)?;
+    for entry in entries.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path.components().any(|c| !matches!(c, Component::Normal(_))) {
+            bail!("Unsafe path in bundle: {}", path.display());
+        }
+        // Only plain files are written; a link could let a later entry
+        // write outside the install directories
+        match entry.header().entry_type() {
+            tar::EntryType::Regular => {}
+            tar::EntryType::Directory => continue,
+            other => bail!("Unsupported entry in bundle ({:?}): {}", other, path.display()),
+        }
+        let destination = if let Ok(rest) = path.strip_prefix("bin") {
+            targets.bin_dir.join(rest)
+        } else if let Ok(rest) = path.strip_prefix("models") {
+            targets.models_dir.join(rest)
+        } else if let Ok(rest) = path.strip_prefix("docs") {
+            targets.docs_dir.join(rest)
+        } else {
+            continue;
+        };
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Unlinking first lets a running binary be replaced
+        if destination.is_file() {
+            fs::remove_file(&destination).ok();
+        }
+        entry
+            .unpack(&destination)
+            .with_context(|| format!("Failed to write {}", destination.display()))?;
+    }
+    Ok(manifest)
+}
+
+fn open(archive: &Path) -> Result<tar::Archive<GzDecoder<File>>> {
+    let file = File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+
+        // A model as `ollama pull` leaves it
+        let store = root.join("ollama");
+        let manifest_dir = store.join("manifests/registry.ollama.ai/library/coder");
+        fs::create_dir_all(&manifest_dir).unwrap();
+        fs::create_dir_all(store.join("blobs")).unwrap();
+        fs::write(
+            manifest_dir.join("7b"),
+            r#"{"config": {"digest": "sha256:aa"}, "layers": [{"digest": "sha256:bb"}, {"digest": "sha256:aa"}]}"#,
+        )
+        .unwrap();
+        fs::write(store.join("blobs/sha256-aa"), "config").unwrap();
+        fs::write(store.join("blobs/sha256-bb"), "weights").unwrap();
+        fs::write(root.join("nexus"), "binary").unwrap();
+
+        let spec = BundleSpec {
+            binary: root.join("nexus"),
+            models_dir: store.clone(),
+            models: vec!["coder:7b".to_string()],
+            docs: vec![("README.md".to_string(), "# NEXUS".to_string())],
+        };
+        let archive = root.join("bundle.tar.gz");
+        let created = create(&spec, &archive).unwrap();
+        assert_eq!(created.models[0].files, ["manifests/registry.ollama.ai/library/coder/7b", "blobs/sha256-aa", "blobs/sha256-bb"]);
+        assert_eq!(read_manifest(&archive).unwrap(), created);
+
+        let target = root.join("offline");
+        let targets = InstallTargets {
+            bin_dir: target.join("bin"),
+            models_dir: target.join("models"),
+            docs_dir: target.join("docs"),
+        };
+        install(&archive, &targets).unwrap();
+        assert_eq!(fs::read_to_string(target.join("bin/nexus")).unwrap(), "binary");
+        assert_eq!(fs::read_to_string(target.join("models/blobs/sha256-bb")).unwrap(), "weights");
+        assert!(target.join("models/manifests/registry.ollama.ai/library/coder/7b").is_file());
+        assert_eq!(fs::read_to_string(target.join("docs/README.md")).unwrap(), "# NEXUS");
+
+        // Links are refused, so no later entry can write through them
+        let linked = root.join("linked.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(File::create(&linked).unwrap(), Compression::default()));
+        let manifest = serde_json::to_vec(&created).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, MANIFEST_FILE, manifest.as_slice()).unwrap();
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(tar::EntryType::Symlink);
+        link.set_size(0);
+        builder.append_link(&mut link, "bin/escape", "/tmp").unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        let refused = install(&linked, &targets).unwrap_err().to_string();
+        assert!(refused.contains("bin/escape"), "{}", refused);
+
+        // Models must have been pulled, and names cannot leave the store
+        assert!(model_files(&store, "missing").unwrap_err().to_string().contains("ollama pull missing"));
+        assert!(model_manifest_path("../../etc:passwd").is_err());
+        assert_eq!(model_manifest_path("me/tool").unwrap(), "manifests/registry.ollama.ai/me/tool/latest");
+    }
+}
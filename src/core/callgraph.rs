@@ -0,0 +1,453 @@
+//! Static call graph
+//!
+//! Resolves call sites in function bodies to project functions by name,
+//! using `Type::` qualifiers and `self.` receivers to pick between methods,
+//! and renders call paths as Mermaid sequence diagrams or flowcharts.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::diagram::mermaid_id;
+use super::parser::{CodeParser, ParsedFile, SymbolKind};
+
+/// Steps rendered at most, so recursive code stays readable
+pub const MAX_STEPS: usize = 200;
+
+/// A function or method defined in the project
+#[derive(Debug, Clone)]
+pub struct FunctionDef {
+    pub name: String,
+    /// Enclosing impl or class, if any
+    pub owner: Option<String>,
+    pub path: PathBuf,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub signature: Option<String>,
+}
+
+impl FunctionDef {
+    /// `Owner::name` or just `name`
+    pub fn qualified_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{}::{}", owner, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// A resolved call inside a function body
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    pub callee: usize,
+    pub line: usize,
+    /// The source line containing the call
+    pub text: String,
+}
+
+/// One call along a walked path
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub caller: usize,
+    pub callee: usize,
+    /// Distance from the entry point, starting at 1
+    pub depth: usize,
+    pub line: usize,
+    pub text: String,
+    /// Human-readable description, when refined
+    pub label: Option<String>,
+}
+
+/// A function body as numbered lines, plus the line ranges of functions
+/// nested inside it
+type Body<'a> = (Vec<(usize, &'a str)>, Vec<(usize, usize)>);
+
+/// Calls between project functions
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    pub functions: Vec<FunctionDef>,
+    calls: Vec<Vec<CallSite>>,
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl CallGraph {
+    /// Parse and link a set of source files
+    pub fn build(files: &[PathBuf]) -> Result<Self> {
+        let mut parser = CodeParser::new()?;
+        let parsed: Vec<ParsedFile> = files.iter().filter_map(|f| parser.parse_file(f).ok()).collect();
+        Ok(Self::from_parsed(&parsed))
+    }
+
+    /// Link already parsed files
+    pub fn from_parsed(files: &[ParsedFile]) -> Self {
+        let mut graph = Self::default();
+        let mut bodies: Vec<Body> = Vec::new();
+
+        for file in files {
+            let lines: Vec<&str> = file.content.lines().collect();
+            let owners: Vec<_> = file.symbols
+                .iter()
+                .filter(|s| matches!(s.kind, SymbolKind::Impl | SymbolKind::Class))
+                .collect();
+            let functions: Vec<_> = file.symbols.iter().filter(|s| s.kind == SymbolKind::Function).collect();
+
+            for symbol in &functions {
+                let owner = owners
+                    .iter()
+                    .filter(|o| o.line_start <= symbol.line_start && symbol.line_end <= o.line_end)
+                    .max_by_key(|o| o.line_start)
+                    .map(|o| owner_name(&o.name));
+
+                graph.by_name.entry(symbol.name.clone()).or_default().push(graph.functions.len());
+                graph.functions.push(FunctionDef {
+                    name: symbol.name.clone(),
+                    owner,
+                    path: file.path.clone(),
+                    line_start: symbol.line_start,
+                    line_end: symbol.line_end,
+                    signature: symbol.signature.clone(),
+                });
+
+                let nested = functions
+                    .iter()
+                    .filter(|f| f.line_start > symbol.line_start && f.line_end <= symbol.line_end)
+                    .map(|f| (f.line_start, f.line_end))
+                    .collect();
+
+                // The body, without the signature line
+                let body = (symbol.line_start..symbol.line_end.min(lines.len()))
+                    .map(|i| (i + 1, lines[i]))
+                    .collect();
+                bodies.push((body, nested));
+            }
+        }
+
+        graph.calls = bodies
+            .iter()
+            .enumerate()
+            .map(|(caller, (body, nested))| graph.resolve_calls(caller, body, nested))
+            .collect();
+        graph
+    }
+
+    /// Functions matching `name` or `Owner::name`
+    pub fn find(&self, name: &str) -> Vec<usize> {
+        let (owner, name) = match name.rsplit_once("::").or_else(|| name.rsplit_once('.')) {
+            Some((owner, name)) => (Some(owner), name),
+            None => (None, name),
+        };
+
+        self.by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&i| owner.is_none_or(|o| self.functions[i].owner.as_deref() == Some(o)))
+            .collect()
+    }
+
+    /// Calls made by a function, in source order
+    pub fn calls_from(&self, function: usize) -> &[CallSite] {
+        &self.calls[function]
+    }
+
+    /// Depth-first walk from `entry`, expanding each function once
+    pub fn walk(&self, entry: usize, max_depth: usize) -> Vec<Step> {
+        let mut steps = Vec::new();
+        let mut expanded = HashSet::new();
+        self.walk_from(entry, 1, max_depth, &mut expanded, &mut steps);
+        steps
+    }
+
+    fn walk_from(&self, caller: usize, depth: usize, max_depth: usize, expanded: &mut HashSet<usize>, steps: &mut Vec<Step>) {
+        if depth > max_depth || !expanded.insert(caller) {
+            return;
+        }
+
+        for call in &self.calls[caller] {
+            if steps.len() >= MAX_STEPS {
+                return;
+            }
+            steps.push(Step {
+                caller,
+                callee: call.callee,
+                depth,
+                line: call.line,
+                text: call.text.trim().to_string(),
+                label: None,
+            });
+            self.walk_from(call.callee, depth + 1, max_depth, expanded, steps);
+        }
+    }
+
+    fn resolve_calls(&self, caller: usize, body: &[(usize, &str)], nested: &[(usize, usize)]) -> Vec<CallSite> {
+        let mut sites = Vec::new();
+
+        for &(line, text) in body {
+            // Lines of nested functions belong to those functions
+            if nested.iter().any(|&(start, end)| start <= line && line <= end) || is_comment(text) {
+                continue;
+            }
+
+            for (qualifier, name) in call_names(text) {
+                if let Some(callee) = self.resolve(caller, qualifier.as_deref(), &name) {
+                    sites.push(CallSite { callee, line, text: text.to_string() });
+                }
+            }
+        }
+
+        sites
+    }
+
+    /// Pick the most likely definition for a call
+    fn resolve(&self, caller: usize, qualifier: Option<&str>, name: &str) -> Option<usize> {
+        let from = &self.functions[caller];
+        let candidates = self.by_name.get(name).map(Vec::as_slice).unwrap_or_default();
+        let owned_by = |owner: &str| {
+            candidates.iter().copied().find(|&i| self.functions[i].owner.as_deref() == Some(owner))
+        };
+        let free = || candidates.iter().copied().filter(|&i| self.functions[i].owner.is_none());
+
+        match qualifier {
+            // Plain call: a free function, preferably from the same file
+            None => free()
+                .find(|&i| self.functions[i].path == from.path)
+                .or_else(|| free().next()),
+            Some("self" | "Self" | "this") => owned_by(from.owner.as_deref()?),
+            Some("crate" | "super") => free().next(),
+            // Type::method or Class.method
+            Some(q) if q.starts_with(char::is_uppercase) => owned_by(q),
+            // module::function, or a method on a value of unknown type
+            Some(q) => free()
+                .find(|&i| module_name(&self.functions[i].path) == q)
+                .or_else(|| {
+                    let unambiguous = candidates.len() == 1 && !COMMON_METHODS.contains(&name);
+                    unambiguous.then(|| candidates[0])
+                }),
+        }
+    }
+
+    /// Render steps as a Mermaid sequence diagram with one participant per file
+    pub fn render_sequence(&self, entry: usize, steps: &[Step], root: &Path) -> String {
+        let mut out = String::from("sequenceDiagram\n");
+        let mut participants: Vec<&Path> = Vec::new();
+
+        for index in std::iter::once(entry).chain(steps.iter().flat_map(|s| [s.caller, s.callee])) {
+            let path = self.functions[index].path.as_path();
+            if !participants.contains(&path) {
+                participants.push(path);
+                out.push_str(&format!(
+                    "    participant {} as {}\n",
+                    participant_id(path), display_path(path, root)
+                ));
+            }
+        }
+
+        for step in steps {
+            let caller = &self.functions[step.caller];
+            let callee = &self.functions[step.callee];
+            let message = match &step.label {
+                Some(label) => format!("{}(): {}", callee.name, label),
+                None => format!("{}()", callee.qualified_name()),
+            };
+            out.push_str(&format!(
+                "    {}->>{}: {}\n",
+                participant_id(&caller.path), participant_id(&callee.path), mermaid_text(&message)
+            ));
+        }
+
+        out
+    }
+
+    /// Render steps as a Mermaid flowchart with one node per function
+    pub fn render_flowchart(&self, entry: usize, steps: &[Step], root: &Path) -> String {
+        let mut out = String::from("flowchart TD\n");
+        let mut nodes = vec![entry];
+        nodes.extend(steps.iter().map(|s| s.callee));
+
+        let mut seen = HashSet::new();
+        for index in nodes {
+            if seen.insert(index) {
+                let f = &self.functions[index];
+                out.push_str(&format!(
+                    "    f{}[\"{}<br/><small>{}:{}</small>\"]\n",
+                    index, mermaid_text(&f.qualified_name()), display_path(&f.path, root), f.line_start
+                ));
+            }
+        }
+
+        for (n, step) in steps.iter().enumerate() {
+            let label = step.label.clone().unwrap_or_else(|| (n + 1).to_string());
+            out.push_str(&format!("    f{} -->|\"{}\"| f{}\n", step.caller, mermaid_text(&label), step.callee));
+        }
+
+        out
+    }
+}
+
+/// Method names too common to resolve on a receiver of unknown type
+const COMMON_METHODS: &[&str] = &[
+    "new", "default", "get", "set", "insert", "remove", "push", "pop", "len", "is_empty", "iter",
+    "map", "filter", "find", "collect", "clone", "to_string", "as_str", "display", "send", "recv",
+    "read", "write", "run", "join", "split", "parse", "contains", "extend", "next", "into", "from",
+    "update", "append", "close", "open", "load", "save", "add", "start", "stop", "call", "apply",
+    "format", "render", "build", "init", "print", "emit", "handle", "process", "from_str", "fmt",
+    "drop", "eq", "cmp", "hash", "deref",
+];
+
+/// Name a module is imported by: the file stem, or the directory for `mod.rs`/`__init__.py`/`index.js`
+fn module_name(path: &Path) -> String {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    if matches!(stem.as_str(), "mod" | "__init__" | "index") {
+        if let Some(dir) = path.parent().and_then(|p| p.file_name()) {
+            return dir.to_string_lossy().to_string();
+        }
+    }
+    stem
+}
+
+/// `impl Display for Foo<T>` → `Foo`; classes keep their name
+fn owner_name(symbol: &str) -> String {
+    let name = symbol.trim_start_matches("impl ");
+    let name = name.rsplit(" for ").next().unwrap_or(name);
+    name.split('<').next().unwrap_or(name).trim().to_string()
+}
+
+/// Identifiers followed by `(`, with their `Type::` or `receiver.` qualifier
+fn call_names(line: &str) -> Vec<(Option<String>, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut calls = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !(chars[i].is_alphabetic() || chars[i] == '_') || (i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_')) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+        let name: String = chars[start..i].iter().collect();
+
+        // Allow turbofish: name::<T>(
+        let mut j = i;
+        if chars.get(j) == Some(&':') && chars.get(j + 2) == Some(&'<') {
+            while j < chars.len() && chars[j] != '>' {
+                j += 1;
+            }
+            j += 1;
+        }
+        if chars.get(j) != Some(&'(') || is_keyword(&name) {
+            continue;
+        }
+
+        let qualifier = if start >= 2 && chars[start - 1] == ':' && chars[start - 2] == ':' {
+            Some(previous_word(&chars, start - 2))
+        } else if start >= 1 && chars[start - 1] == '.' {
+            Some(previous_word(&chars, start - 1))
+        } else {
+            None
+        };
+
+        // Definitions are not calls
+        let before: String = chars[..start].iter().collect();
+        let before = before.trim_end();
+        if before.ends_with("fn") || before.ends_with("def") || before.ends_with("function") {
+            continue;
+        }
+
+        calls.push((qualifier.filter(|q| !q.is_empty()), name));
+    }
+
+    calls
+}
+
+fn previous_word(chars: &[char], end: usize) -> String {
+    let mut start = end;
+    while start > 0 && (chars[start - 1].is_alphanumeric() || chars[start - 1] == '_') {
+        start -= 1;
+    }
+    chars[start..end].iter().collect()
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "if" | "while" | "for" | "match" | "return" | "loop" | "fn" | "def" | "function" | "switch"
+            | "catch" | "await" | "async" | "yield" | "new" | "typeof" | "Some" | "Ok" | "Err"
+            | "Box" | "Vec" | "String" | "elif" | "and" | "or" | "not" | "in" | "super"
+    )
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//") || line.starts_with('#') && !line.starts_with("#[") || line.starts_with('*')
+}
+
+fn participant_id(path: &Path) -> String {
+    mermaid_id(&path.with_extension("").display().to_string())
+}
+
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+/// Text safe inside a Mermaid message or label
+fn mermaid_text(text: &str) -> String {
+    text.replace(['"', ';', '#'], "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(files: &[(&str, &str)]) -> CallGraph {
+        let mut parser = CodeParser::new().unwrap();
+        let parsed: Vec<ParsedFile> = files
+            .iter()
+            .map(|(path, content)| parser.parse_source(Path::new(path), content.to_string()).unwrap())
+            .collect();
+        CallGraph::from_parsed(&parsed)
+    }
+
+    #[test]
+    fn test_calls_resolve_across_files_and_methods() {
+        let graph = parse(&[
+            ("src/main.rs", "fn main() {\n    let cfg = Config::load();\n    run(&cfg);\n}\n\nfn run(cfg: &Config) {\n    cfg.validate();\n    helper::log(\"start\");\n}\n"),
+            ("src/config.rs", "struct Config;\n\nimpl Config {\n    fn load() -> Self {\n        let c = Config;\n        c.validate();\n        c\n    }\n\n    fn validate(&self) {\n        self.check();\n    }\n\n    fn check(&self) {}\n}\n"),
+        ]);
+
+        let main = graph.find("main")[0];
+        let names: Vec<String> = graph.calls_from(main).iter().map(|c| graph.functions[c.callee].qualified_name()).collect();
+        assert_eq!(names, vec!["Config::load", "run"]);
+
+        let steps = graph.walk(main, 3);
+        let path: Vec<(usize, String)> = steps.iter().map(|s| (s.depth, graph.functions[s.callee].qualified_name())).collect();
+        assert_eq!(path, vec![
+            (1, "Config::load".to_string()),
+            (2, "Config::validate".to_string()),
+            (3, "Config::check".to_string()),
+            (1, "run".to_string()),
+            (2, "Config::validate".to_string()),
+        ]);
+
+        assert_eq!(graph.walk(main, 1).len(), 2);
+        assert_eq!(graph.find("Config::validate").len(), 1);
+    }
+
+    #[test]
+    fn test_render_sequence_diagram() {
+        let graph = parse(&[("app.py", "def handle(req):\n    user = load_user(req)\n    return render(user)\n\ndef load_user(req):\n    return None\n\ndef render(user):\n    return ''\n")]);
+        let entry = graph.find("handle")[0];
+        let mut steps = graph.walk(entry, 2);
+        steps[0].label = Some("Look up the requesting user".to_string());
+
+        let mermaid = graph.render_sequence(entry, &steps, Path::new("."));
+        assert_eq!(mermaid, "sequenceDiagram\n    participant app as app.py\n    app->>app: load_user(): Look up the requesting user\n    app->>app: render()\n");
+        assert!(graph.render_flowchart(entry, &steps, Path::new(".")).contains("f0 -->|\"2\"| f2"));
+    }
+}
@@ -0,0 +1,256 @@
+//! Comment pairing
+//!
+//! Pairs doc comments and docstrings with the symbols they describe, so the
+//! AI can judge whether the text still matches the implementation.
+
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::parser::{Language, ParsedFile, SymbolKind};
+
+/// Body lines sent with each comment
+pub const MAX_CODE_LINES: usize = 80;
+
+/// Comments shorter than this say too little to contradict anything
+const MIN_COMMENT_WORDS: usize = 4;
+
+/// A comment and the code it describes
+#[derive(Debug, Clone)]
+pub struct DocComment {
+    pub path: PathBuf,
+    pub symbol: String,
+    pub kind: SymbolKind,
+    /// First and last line of the comment, 1-based
+    pub comment_start: usize,
+    pub comment_end: usize,
+    /// Comment text without markers
+    pub comment: String,
+    /// The symbol's source, capped at `MAX_CODE_LINES`
+    pub code: String,
+}
+
+/// How sure the AI is that a comment is wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "low" => Some(Confidence::Low),
+            "medium" | "med" => Some(Confidence::Medium),
+            "high" => Some(Confidence::High),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+}
+
+/// A comment that no longer matches its code
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub path: PathBuf,
+    pub symbol: String,
+    pub line: usize,
+    pub comment: String,
+    pub issue: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    pub confidence: Confidence,
+}
+
+fn is_comment_line(trimmed: &str) -> bool {
+    (trimmed.starts_with("//") && !trimmed.starts_with("//!"))
+        || trimmed.starts_with("/*")
+        || trimmed.starts_with('*')
+        || trimmed.starts_with('#') && !trimmed.starts_with("#[") && !trimmed.starts_with("#!")
+}
+
+fn is_annotation(trimmed: &str) -> bool {
+    trimmed.starts_with("#[") || trimmed.starts_with('@')
+}
+
+/// Comment text without `///`, `*`, `#` or quote markers
+fn strip_markers(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|l| {
+            l.trim()
+                .trim_start_matches(['/', '*', '#', '!'])
+                .trim_end_matches("*/")
+                .trim_matches(|c| c == '"' || c == '\'')
+                .trim()
+        })
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Comment lines directly above a symbol, skipping attributes and decorators
+fn leading_comment(lines: &[&str], line_start: usize) -> Option<(usize, usize)> {
+    let mut index = line_start.checked_sub(2)?;
+    while is_annotation(lines.get(index)?.trim()) {
+        index = index.checked_sub(1)?;
+    }
+
+    let end = index;
+    if !is_comment_line(lines[end].trim()) {
+        return None;
+    }
+    let mut start = end;
+    while start > 0 && is_comment_line(lines[start - 1].trim()) {
+        start -= 1;
+    }
+    Some((start, end))
+}
+
+/// A Python docstring: the first statement after the `def`/`class` header
+fn docstring(lines: &[&str], line_start: usize, line_end: usize) -> Option<(usize, usize)> {
+    let header_end = (line_start.saturating_sub(1)..line_end.min(lines.len()))
+        .find(|&i| lines[i].trim_end().ends_with(':'))?;
+    let start = (header_end + 1..line_end.min(lines.len())).find(|&i| !lines[i].trim().is_empty())?;
+
+    let first = lines[start].trim().trim_start_matches(['r', 'b', 'u', 'f']);
+    let quote = ["\"\"\"", "'''"].into_iter().find(|q| first.starts_with(q))?;
+    if first.len() >= 6 && first[3..].contains(quote) {
+        return Some((start, start));
+    }
+    let end = (start + 1..line_end.min(lines.len())).find(|&i| lines[i].contains(quote))?;
+    Some((start, end))
+}
+
+/// Every documented symbol in a file with its comment
+pub fn pair(file: &ParsedFile) -> Vec<DocComment> {
+    let lines: Vec<&str> = file.content.lines().collect();
+
+    file.symbols
+        .iter()
+        .filter(|s| !matches!(s.kind, SymbolKind::Impl | SymbolKind::Module))
+        .filter_map(|s| {
+            let (start, end) = leading_comment(&lines, s.line_start).or_else(|| {
+                (file.language == Language::Python).then(|| docstring(&lines, s.line_start, s.line_end)).flatten()
+            })?;
+
+            let comment = strip_markers(&lines[start..=end]);
+            if comment.split_whitespace().count() < MIN_COMMENT_WORDS {
+                return None;
+            }
+
+            let body_start = s.line_start.saturating_sub(1);
+            let body_end = s.line_end.min(lines.len());
+            let mut code = lines[body_start..body_end.min(body_start + MAX_CODE_LINES)].join("\n");
+            if body_end - body_start > MAX_CODE_LINES {
+                code.push_str("\n// ... (truncated)");
+            }
+
+            Some(DocComment {
+                path: file.path.clone(),
+                symbol: s.name.clone(),
+                kind: s.kind,
+                comment_start: start + 1,
+                comment_end: end + 1,
+                comment,
+                code,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct RawFinding {
+    id: usize,
+    issue: String,
+    #[serde(default)]
+    suggestion: Option<String>,
+    confidence: String,
+}
+
+/// Findings from the AI's JSON array, where `id` numbers `batch` from 1.
+/// Entries for unknown ids or with an unknown confidence are dropped.
+pub fn parse_findings(response: &str, batch: &[DocComment]) -> Vec<Finding> {
+    let (Some(start), Some(end)) = (response.find('['), response.rfind(']')) else {
+        return Vec::new();
+    };
+    let Ok(raw) = serde_json::from_str::<Vec<RawFinding>>(&response[start..=end]) else {
+        return Vec::new();
+    };
+
+    raw.into_iter()
+        .filter_map(|f| {
+            let pair = batch.get(f.id.checked_sub(1)?)?;
+            Some(Finding {
+                path: pair.path.clone(),
+                symbol: pair.symbol.clone(),
+                line: pair.comment_start,
+                comment: pair.comment.clone(),
+                issue: f.issue.trim().to_string(),
+                suggestion: f.suggestion.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+                confidence: Confidence::parse(&f.confidence)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+    use std::path::Path;
+
+    fn parsed(path: &str, content: &str) -> ParsedFile {
+        CodeParser::new().unwrap().parse_source(Path::new(path), content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_pair_rust_and_python() {
+        let rust = parsed(
+            "src/lib.rs",
+            "//! Crate docs\n\n/// Returns the number of retries before giving up\n#[inline]\npub fn retries() -> u32 {\n    3\n}\n\n/// Short\nfn short() {}\n\nfn undocumented() {}\n",
+        );
+        let pairs = pair(&rust);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].symbol, "retries");
+        assert_eq!((pairs[0].comment_start, pairs[0].comment_end), (3, 3));
+        assert_eq!(pairs[0].comment, "Returns the number of retries before giving up");
+        assert!(pairs[0].code.starts_with("pub fn retries()"));
+
+        let python = parsed(
+            "app.py",
+            "def total(items):\n    \"\"\"\n    Sum the prices of all items in cents.\n    \"\"\"\n    return sum(i.price for i in items)\n",
+        );
+        let pairs = pair(&python);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].comment, "Sum the prices of all items in cents.");
+        assert_eq!((pairs[0].comment_start, pairs[0].comment_end), (2, 4));
+    }
+
+    #[test]
+    fn test_parse_findings() {
+        let rust = parsed("src/lib.rs", "/// Returns the number of retries before giving up\npub fn retries() -> u32 {\n    3\n}\n");
+        let batch = pair(&rust);
+
+        let response = r#"Here you go:
+[{"id": 1, "issue": "Says retries, returns a constant", "confidence": "HIGH", "suggestion": ""},
+ {"id": 7, "issue": "Unknown", "confidence": "high"},
+ {"id": 1, "issue": "Bad confidence", "confidence": "certain"}]"#;
+        let findings = parse_findings(response, &batch);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].confidence, Confidence::High);
+        assert_eq!(findings[0].line, 1);
+        assert!(findings[0].suggestion.is_none());
+        assert!(Confidence::Medium > Confidence::Low);
+    }
+}
@@ -0,0 +1,359 @@
+//! Project activity dashboard
+//!
+//! Gathers what NEXUS knows about its use on a project into one summary:
+//! index health, recent AI requests, written files not yet committed, open
+//! review findings and this week's token spend. Rendered in the terminal by
+//! `nexus dashboard`, or as a standalone HTML page.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use super::activity::{self, ActivityLog, Interaction, Spend};
+use super::findings::{self, FindingsStore, OpenFinding};
+use super::git;
+use super::graders::Severity;
+use super::journal::{Change, Journal, JournalEntry};
+use crate::index::snapshot::{IndexHealth, IndexSnapshot};
+
+/// Recent AI requests shown
+pub const RECENT_INTERACTIONS: usize = 10;
+
+/// A file NEXUS wrote that has uncommitted changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingChange {
+    pub path: String,
+    /// Command that last wrote it
+    pub command: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Everything the dashboard shows
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub root: PathBuf,
+    pub generated: DateTime<Utc>,
+    /// `None` when the project was never indexed
+    pub index: Option<IndexHealth>,
+    /// Newest first
+    pub recent: Vec<Interaction>,
+    pub total_interactions: usize,
+    /// `None` outside a git repository
+    pub pending: Option<Vec<PendingChange>>,
+    pub findings: Vec<OpenFinding>,
+    pub week_start: DateTime<Utc>,
+    pub spend: BTreeMap<String, Spend>,
+}
+
+impl Dashboard {
+    /// Collect the dashboard of the project at `root`
+    pub fn gather(root: &Path) -> Result<Self> {
+        let now = Utc::now();
+        let interactions = ActivityLog::for_root(root).entries()?;
+        let week_start = activity::week_start(now);
+        let spend = activity::spend_since(&interactions, week_start);
+        let recent = interactions.iter().rev().take(RECENT_INTERACTIONS).cloned().collect();
+        let pending = match uncommitted_paths(root) {
+            Some(changed) => Some(pending_changes(&Journal::for_root(root).entries()?, &changed)),
+            None => None,
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            generated: now,
+            index: IndexSnapshot::health(root)?,
+            recent,
+            total_interactions: interactions.len(),
+            pending,
+            findings: FindingsStore::for_root(root).open()?,
+            week_start,
+            spend,
+        })
+    }
+
+    pub fn findings_by_severity(&self) -> BTreeMap<Severity, usize> {
+        findings::count_by_severity(&self.findings)
+    }
+
+    /// Requests and tokens of all providers this week
+    pub fn week_total(&self) -> Spend {
+        self.spend.values().fold(Spend::default(), |total, spend| Spend {
+            requests: total.requests + spend.requests,
+            input_tokens: total.input_tokens + spend.input_tokens,
+            output_tokens: total.output_tokens + spend.output_tokens,
+        })
+    }
+
+    /// The dashboard as a standalone HTML page
+    pub fn render_html(&self) -> String {
+        let project = self
+            .root
+            .canonicalize()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .unwrap_or_else(|| self.root.display().to_string());
+
+        let index = match &self.index {
+            None => "<p class=\"muted\">Not indexed yet. Run <code>nexus index</code>.</p>".to_string(),
+            Some(health) => {
+                let status = if health.is_fresh() {
+                    "<span class=\"ok\">up to date</span>".to_string()
+                } else {
+                    format!(
+                        "<span class=\"warn\">{} changed, {} added, {} removed since</span>",
+                        health.stale.len(), health.added.len(), health.removed.len()
+                    )
+                };
+                format!(
+                    "<p>{} files, {} lines, {} symbols · indexed {} · {}</p>",
+                    health.snapshot.files.len(),
+                    health.snapshot.total_lines,
+                    health.snapshot.symbols,
+                    local_time(&health.snapshot.indexed_at, "%Y-%m-%d %H:%M"),
+                    status
+                )
+            }
+        };
+
+        let mut recent = String::new();
+        for interaction in &self.recent {
+            recent.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td></tr>\n",
+                local_time(&interaction.timestamp, "%m-%d %H:%M"),
+                escape_html(&interaction.command),
+                escape_html(&interaction.provider),
+                interaction.tokens()
+            ));
+        }
+        let recent = if recent.is_empty() {
+            "<p class=\"muted\">No AI requests yet.</p>".to_string()
+        } else {
+            format!("<table>\n<tr><th>When</th><th>Command</th><th>Provider</th><th>Tokens</th></tr>\n{}</table>", recent)
+        };
+
+        let pending = match &self.pending {
+            None => "<p class=\"muted\">Not a git repository.</p>".to_string(),
+            Some(pending) if pending.is_empty() => "<p class=\"muted\">Nothing awaiting commit.</p>".to_string(),
+            Some(pending) => {
+                let items: String = pending
+                    .iter()
+                    .map(|c| {
+                        format!(
+                            "<li><code>{}</code> <span class=\"muted\">{} · {}</span></li>\n",
+                            escape_html(&c.path),
+                            escape_html(&c.command),
+                            local_time(&c.timestamp, "%m-%d %H:%M")
+                        )
+                    })
+                    .collect();
+                format!("<ul>\n{}</ul>", items)
+            }
+        };
+
+        let findings = if self.findings.is_empty() {
+            "<p class=\"muted\">No open findings.</p>".to_string()
+        } else {
+            let counts: Vec<String> = self
+                .findings_by_severity()
+                .iter()
+                .rev()
+                .map(|(severity, count)| format!("<span class=\"{0}\">{1} {0}</span>", severity.label(), count))
+                .collect();
+            let items: String = self
+                .findings
+                .iter()
+                .map(|f| {
+                    let location = match f.finding.line {
+                        Some(line) => format!("{}:{}", f.finding.path, line),
+                        None => f.finding.path.clone(),
+                    };
+                    format!(
+                        "<li><span class=\"{}\">{}</span> {} <code>{}</code></li>\n",
+                        f.finding.severity.label(),
+                        f.finding.severity.label(),
+                        escape_html(&f.finding.title),
+                        escape_html(&location)
+                    )
+                })
+                .collect();
+            format!("<p>{}</p>\n<ul>\n{}</ul>", counts.join(" · "), items)
+        };
+
+        let total = self.week_total();
+        let mut spend = String::new();
+        for (provider, s) in &self.spend {
+            spend.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape_html(provider), s.requests, s.input_tokens, s.output_tokens
+            ));
+        }
+        let spend = format!(
+            "<table>\n<tr><th>Provider</th><th>Requests</th><th>Input</th><th>Output</th></tr>\n{}\
+             <tr class=\"total\"><td>Total</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n</table>",
+            spend, total.requests, total.input_tokens, total.output_tokens
+        );
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>NEXUS dashboard · {project}</title>
+<style>
+body {{ font-family: -apple-system, "Segoe UI", sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #212121; }}
+section {{ border-left: 3px solid #64B5F6; padding: 0.25rem 1rem; margin: 1.5rem 0; }}
+h2 {{ font-size: 1.1rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ text-align: left; padding: 0.2rem 0.8rem 0.2rem 0; }}
+td.num {{ text-align: right; }}
+tr.total td {{ border-top: 1px solid #B0BEC5; font-weight: bold; }}
+code {{ font-family: "JetBrains Mono", Menlo, monospace; font-size: 0.9em; }}
+.muted {{ color: #546E7A; }}
+.ok {{ color: #2E7D32; }}
+.warn, .medium, .high {{ color: #F57F17; }}
+.critical {{ color: #C62828; font-weight: bold; }}
+.low {{ color: #546E7A; }}
+</style>
+</head>
+<body>
+<h1>{project}</h1>
+<p class="muted">Generated {generated}</p>
+<section><h2>Index</h2>
+{index}
+</section>
+<section><h2>Recent AI requests ({total_interactions} total)</h2>
+{recent}
+</section>
+<section><h2>Awaiting commit</h2>
+{pending}
+</section>
+<section><h2>Open findings</h2>
+{findings}
+</section>
+<section><h2>Tokens since {week_start}</h2>
+{spend}
+</section>
+</body>
+</html>
+"#,
+            project = escape_html(&project),
+            generated = local_time(&self.generated, "%Y-%m-%d %H:%M"),
+            index = index,
+            total_interactions = self.total_interactions,
+            recent = recent,
+            pending = pending,
+            findings = findings,
+            week_start = local_time(&self.week_start, "%Y-%m-%d"),
+            spend = spend
+        )
+    }
+}
+
+/// Files in the journal with uncommitted changes, newest write first
+pub fn pending_changes(entries: &[JournalEntry], changed: &BTreeSet<String>) -> Vec<PendingChange> {
+    let mut latest: BTreeMap<String, PendingChange> = BTreeMap::new();
+    for entry in entries {
+        let Change::FileWritten { path, .. } = &entry.change else {
+            continue;
+        };
+        let path = path.trim_start_matches("./").to_string();
+        if changed.contains(&path) {
+            latest.insert(
+                path.clone(),
+                PendingChange { path, command: entry.command.clone(), timestamp: entry.timestamp },
+            );
+        }
+    }
+    let mut pending: Vec<PendingChange> = latest.into_values().collect();
+    pending.sort_by_key(|c| std::cmp::Reverse(c.timestamp));
+    pending
+}
+
+/// Paths with uncommitted changes under `root`, relative to it, or `None`
+/// outside a git repository
+fn uncommitted_paths(root: &Path) -> Option<BTreeSet<String>> {
+    let git = |args: &[&str]| git::git(root, args).ok();
+    // Status paths are relative to the repository root
+    let prefix = git(&["rev-parse", "--show-prefix"])?;
+    let status = git(&["status", "--porcelain", "--untracked-files=all", "--", "."])?;
+    Some(
+        status
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .map(|path| path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"'))
+            .map(|path| path.strip_prefix(prefix.trim()).unwrap_or(path).to_string())
+            .collect(),
+    )
+}
+
+fn local_time(time: &DateTime<Utc>, format: &str) -> String {
+    time.with_timezone(&Local).format(format).to_string()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::graders::ReviewFinding;
+
+    #[test]
+    fn test_pending_changes_and_html() {
+        let written = |path: &str, command: &str, minute: i64| JournalEntry {
+            timestamp: DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minute),
+            command: command.to_string(),
+            change: Change::FileWritten { path: path.to_string(), provenance: None },
+        };
+        let entries = vec![
+            written("src/lib.rs", "generate", 1),
+            written("./src/lib.rs", "fix", 5),
+            written("src/committed.rs", "refactor", 3),
+            written("tests/api.rs", "test", 2),
+        ];
+        let changed: BTreeSet<String> = ["src/lib.rs", "tests/api.rs", "README.md"].iter().map(|s| s.to_string()).collect();
+        let pending = pending_changes(&entries, &changed);
+        let summary: Vec<(&str, &str)> = pending.iter().map(|c| (c.path.as_str(), c.command.as_str())).collect();
+        assert_eq!(summary, [("src/lib.rs", "fix"), ("tests/api.rs", "test")]);
+
+        let finding = ReviewFinding {
+            id: "F1".to_string(),
+            path: "src/db.rs".to_string(),
+            line: Some(42),
+            severity: Severity::Critical,
+            title: "SQL built from <input>".to_string(),
+            detail: None,
+            source: "nexus".to_string(),
+            rescored_by: None,
+        };
+        let now = Utc::now();
+        let dashboard = Dashboard {
+            root: PathBuf::from("."),
+            generated: now,
+            index: None,
+            recent: vec![Interaction {
+                timestamp: now,
+                command: "ask".to_string(),
+                provider: "claude".to_string(),
+                input_tokens: 1200,
+                output_tokens: 300,
+            }],
+            total_interactions: 1,
+            pending: Some(pending),
+            findings: vec![OpenFinding { reviewed_at: now, finding }],
+            week_start: activity::week_start(now),
+            spend: BTreeMap::from([("claude".to_string(), Spend { requests: 1, input_tokens: 1200, output_tokens: 300 })]),
+        };
+        let html = dashboard.render_html();
+        assert!(html.contains("Not indexed yet"));
+        assert!(html.contains("<td>claude</td><td class=\"num\">1500</td>"));
+        assert!(html.contains("SQL built from &lt;input&gt; <code>src/db.rs:42</code>"));
+        assert!(html.contains("<span class=\"critical\">1 critical</span>"));
+        assert!(html.contains("<code>tests/api.rs</code>"));
+        assert!(html.contains("<td class=\"num\">1</td><td class=\"num\">1200</td><td class=\"num\">300</td>"));
+    }
+}
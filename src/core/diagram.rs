@@ -0,0 +1,882 @@
+//! Mermaid diagram generation
+//!
+//! Entity-relationship diagrams from SQL schemas and ORM models, and module
+//! dependency graphs from imports.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::parser::Language;
+use crate::index;
+
+/// Placeholder in generated docs replaced by the module graph
+pub const MODULES_MARKER: &str = "<!-- nexus:diagram modules -->";
+
+/// Placeholder in generated docs replaced by the ER diagram
+pub const ER_MARKER: &str = "<!-- nexus:diagram er -->";
+
+/// Column marker in an ER diagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Primary,
+    Foreign,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: String,
+    pub key: Option<Key>,
+}
+
+/// A table or model
+#[derive(Debug, Clone)]
+pub struct Entity {
+    pub name: String,
+    /// Table name when it differs from the model name
+    pub table: Option<String>,
+    pub fields: Vec<Field>,
+    pub source: PathBuf,
+}
+
+impl Entity {
+    fn new(name: &str, source: &Path) -> Self {
+        Self { name: name.to_string(), table: None, fields: Vec::new(), source: source.to_path_buf() }
+    }
+
+    fn field(&mut self, name: &str, ty: &str, key: Option<Key>) {
+        if !name.is_empty() && !self.fields.iter().any(|f| f.name == name) {
+            self.fields.push(Field { name: name.to_string(), ty: ty.to_string(), key });
+        }
+    }
+
+    fn answers_to(&self, name: &str) -> bool {
+        let wanted = normalize_entity(name);
+        normalize_entity(&self.name) == wanted
+            || self.table.as_deref().is_some_and(|t| normalize_entity(t) == wanted)
+    }
+}
+
+/// A child entity referencing a parent through a foreign key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub child: String,
+    /// Referenced entity, as written in the source
+    pub parent: String,
+    pub via: String,
+}
+
+/// Entities and relations found in a project
+#[derive(Debug, Default)]
+pub struct ErModel {
+    pub entities: Vec<Entity>,
+    pub relations: Vec<Relation>,
+}
+
+impl ErModel {
+    /// Extract the data model from schema and source files
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        let mut model = Self::default();
+
+        for path in files {
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+            match (extension, Language::from_path(path)) {
+                ("sql", _) => model.scan_sql(path, &content),
+                ("prisma", _) => model.scan_prisma(path, &content),
+                (_, Language::Rust) => model.scan_rust(path, &content),
+                (_, Language::Python) => model.scan_python(path, &content),
+                _ => {}
+            }
+        }
+
+        model.infer_relations();
+        model
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Whether a file may hold schema definitions
+    pub fn is_schema_file(path: &Path) -> bool {
+        matches!(path.extension().and_then(|e| e.to_str()), Some("sql" | "prisma"))
+            || matches!(Language::from_path(path), Language::Rust | Language::Python)
+    }
+
+    /// Render as a Mermaid `erDiagram`
+    pub fn render_mermaid(&self) -> String {
+        let mut out = String::from("erDiagram\n");
+
+        for entity in &self.entities {
+            out.push_str(&format!("    {} {{\n", mermaid_id(&entity.name)));
+            for field in &entity.fields {
+                let key = match field.key {
+                    Some(Key::Primary) => " PK",
+                    Some(Key::Foreign) => " FK",
+                    None => "",
+                };
+                out.push_str(&format!("        {} {}{}\n", mermaid_id(&field.ty), mermaid_id(&field.name), key));
+            }
+            out.push_str("    }\n");
+        }
+
+        for relation in &self.relations {
+            let Some(parent) = self.entities.iter().find(|e| e.answers_to(&relation.parent)) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "    {} ||--o{{ {} : \"{}\"\n",
+                mermaid_id(&parent.name), mermaid_id(&relation.child), relation.via
+            ));
+        }
+
+        out
+    }
+
+    fn relate(&mut self, child: &str, parent: &str, via: &str) {
+        let relation = Relation { child: child.to_string(), parent: parent.to_string(), via: via.to_string() };
+        if !self.relations.contains(&relation) {
+            self.relations.push(relation);
+        }
+    }
+
+    /// `CREATE TABLE` statements with inline and table-level foreign keys
+    fn scan_sql(&mut self, path: &Path, content: &str) {
+        // ASCII lowercasing keeps byte offsets aligned with `content`
+        let lower = content.to_ascii_lowercase();
+        let mut search = 0;
+
+        while let Some(pos) = lower[search..].find("create table") {
+            let start = search + pos + "create table".len();
+
+            let Some(open) = content[start..].find('(').map(|i| start + i) else {
+                break;
+            };
+            let header = content[start..open].trim();
+            let header = header.strip_prefix("IF NOT EXISTS").or_else(|| header.strip_prefix("if not exists")).unwrap_or(header);
+            let name = sql_identifier(header);
+            let Some(close) = matching_paren(content, open) else {
+                break;
+            };
+            search = close;
+
+            let mut entity = Entity::new(&name, path);
+            let mut primary: Vec<String> = Vec::new();
+
+            for item in split_top_level(&content[open + 1..close]) {
+                let item_lower = item.to_lowercase();
+                let item_lower = item_lower.trim();
+
+                if item_lower.starts_with("primary key") {
+                    primary.extend(paren_list(item));
+                } else if item_lower.starts_with("foreign key") || (item_lower.starts_with("constraint") && item_lower.contains("foreign key")) {
+                    let columns = paren_list(item);
+                    if let Some(parent) = references(item) {
+                        for column in &columns {
+                            self.relate(&name, &parent, column);
+                        }
+                        entity.fields.iter_mut().filter(|f| columns.contains(&f.name)).for_each(|f| {
+                            f.key.get_or_insert(Key::Foreign);
+                        });
+                    }
+                } else if ["constraint", "unique", "check", "index", "key"].iter().any(|k| item_lower.starts_with(k)) {
+                    continue;
+                } else {
+                    let mut words = item.split_whitespace();
+                    let column = sql_identifier(words.next().unwrap_or(""));
+                    let ty = words.next().map(|t| t.split('(').next().unwrap_or(t)).unwrap_or("").to_lowercase();
+                    let key = if item_lower.contains("primary key") {
+                        Some(Key::Primary)
+                    } else if let Some(parent) = references(item) {
+                        self.relate(&name, &parent, &column);
+                        Some(Key::Foreign)
+                    } else {
+                        None
+                    };
+                    entity.field(&column, &ty, key);
+                }
+            }
+
+            for field in entity.fields.iter_mut().filter(|f| primary.contains(&f.name)) {
+                field.key = Some(Key::Primary);
+            }
+            self.entities.push(entity);
+        }
+    }
+
+    /// Prisma `model` blocks
+    fn scan_prisma(&mut self, path: &Path, content: &str) {
+        let models: BTreeSet<&str> = content
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("model "))
+            .filter_map(|l| l.split_whitespace().next())
+            .collect();
+
+        let mut current: Option<Entity> = None;
+        for line in content.lines().map(str::trim) {
+            if let Some(rest) = line.strip_prefix("model ") {
+                current = Some(Entity::new(rest.split_whitespace().next().unwrap_or(""), path));
+                continue;
+            }
+            let Some(entity) = current.as_mut() else {
+                continue;
+            };
+            if line.starts_with('}') {
+                self.entities.extend(current.take());
+                continue;
+            }
+            if line.starts_with("@@map(") {
+                entity.table = first_quoted(line);
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let (Some(name), Some(ty)) = (words.next(), words.next()) else {
+                continue;
+            };
+            if name.starts_with("//") || name.starts_with('@') {
+                continue;
+            }
+
+            let base = ty.trim_end_matches(['?', ']', '[']);
+            if models.contains(base) {
+                // Relation field: the side holding `fields:` owns the foreign key
+                if let Some(idx) = line.find("fields:") {
+                    for column in line[idx..].split(['[', ']']).nth(1).unwrap_or("").split(',') {
+                        self.relate(&entity.name, base, column.trim());
+                    }
+                }
+                continue;
+            }
+
+            let key = line.contains("@id").then_some(Key::Primary);
+            entity.field(name, base, key);
+        }
+
+        // Mark foreign key columns now that all relations are known
+        for relation in &self.relations {
+            if let Some(entity) = self.entities.iter_mut().find(|e| e.name == relation.child) {
+                for field in entity.fields.iter_mut().filter(|f| f.name == relation.via) {
+                    field.key.get_or_insert(Key::Foreign);
+                }
+            }
+        }
+    }
+
+    /// Diesel `table!` blocks and ORM-derived structs
+    fn scan_rust(&mut self, path: &Path, content: &str) {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut idx = 0;
+
+        while idx < lines.len() {
+            let line = lines[idx].trim();
+
+            // Test fixtures are not part of the data model
+            if line == "#[cfg(test)]" {
+                break;
+            }
+
+            // diesel::table! { users (id) { id -> Int4, ... } }
+            if line.ends_with("table! {") {
+                let Some(header) = lines.get(idx + 1).map(|l| l.trim()) else {
+                    break;
+                };
+                let name: String = header.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                let primary = header.split(['(', ')']).nth(1).unwrap_or("id").to_string();
+                let mut entity = Entity::new(&name, path);
+
+                idx += 2;
+                while idx < lines.len() && !lines[idx].trim().starts_with('}') {
+                    if let Some((column, ty)) = lines[idx].trim().trim_end_matches(',').split_once("->") {
+                        let column = column.trim();
+                        let key = (column == primary).then_some(Key::Primary);
+                        entity.field(column, ty.trim(), key);
+                    }
+                    idx += 1;
+                }
+                self.entities.push(entity);
+            }
+
+            // diesel::joinable!(posts -> users (user_id));
+            if let Some(rest) = line.find("joinable!(").map(|i| &line[i + 10..]) {
+                if let Some((child, rest)) = rest.split_once("->") {
+                    let parent = rest.split_whitespace().next().unwrap_or("");
+                    let via = rest.split(['(', ')']).nth(1).unwrap_or("");
+                    self.relate(child.trim(), parent, via);
+                }
+            }
+
+            // #[derive(Queryable)] / #[derive(FromRow)] / #[derive(DeriveEntityModel)]
+            if line.starts_with("#[derive(")
+                && ["Queryable", "Insertable", "FromRow", "DeriveEntityModel", "Selectable"].iter().any(|d| line.contains(d))
+            {
+                let mut table = None;
+                let mut j = idx + 1;
+                while j < lines.len() && lines[j].trim().starts_with("#[") {
+                    if lines[j].contains("table_name") {
+                        table = first_quoted(lines[j]).or_else(|| {
+                            lines[j].split("table_name").nth(1).map(|r| r.trim_matches(|c: char| !c.is_alphanumeric() && c != '_').to_string())
+                        });
+                    }
+                    j += 1;
+                }
+
+                let Some(rest) = lines.get(j).and_then(|l| l.trim().trim_start_matches("pub ").strip_prefix("struct ")) else {
+                    idx += 1;
+                    continue;
+                };
+                let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                let mut entity = Entity::new(&name, path);
+                entity.table = table;
+
+                j += 1;
+                while j < lines.len() && !lines[j].trim().starts_with('}') {
+                    let field = lines[j].trim();
+                    if !field.starts_with("#[") && !field.starts_with("//") {
+                        if let Some((column, ty)) = field.trim_start_matches("pub ").split_once(':') {
+                            let key = (column.trim() == "id" || lines[j - 1].contains("primary_key")).then_some(Key::Primary);
+                            entity.field(column.trim(), ty.trim().trim_end_matches(','), key);
+                        }
+                    }
+                    j += 1;
+                }
+
+                // Diesel's table! and a derived struct describe the same table
+                if !self.entities.iter().any(|e| entity.table.as_deref().is_some_and(|t| e.name == t)) {
+                    self.entities.push(entity);
+                }
+                idx = j;
+            }
+
+            idx += 1;
+        }
+    }
+
+    /// SQLAlchemy and Django model classes
+    fn scan_python(&mut self, path: &Path, content: &str) {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut idx = 0;
+
+        while idx < lines.len() {
+            let line = lines[idx];
+            let trimmed = line.trim_start();
+            let is_model = trimmed.starts_with("class ")
+                && trimmed.split_once('(').is_some_and(|(_, bases)| {
+                    bases.contains("Base") || bases.contains("Model") || bases.contains("SQLModel")
+                });
+            if !is_model {
+                idx += 1;
+                continue;
+            }
+
+            let indent = line.len() - trimmed.len();
+            let name: String = trimmed[6..].chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            let mut entity = Entity::new(&name, path);
+
+            idx += 1;
+            while idx < lines.len() {
+                let body = lines[idx];
+                let stripped = body.trim_start();
+                if !stripped.is_empty() && body.len() - stripped.len() <= indent {
+                    break;
+                }
+                idx += 1;
+
+                if let Some(table) = stripped.strip_prefix("__tablename__") {
+                    entity.table = first_quoted(table);
+                    continue;
+                }
+
+                let Some((target, value)) = stripped.split_once('=') else {
+                    continue;
+                };
+                let (column, annotation) = match target.split_once(':') {
+                    Some((c, a)) => (c.trim(), Some(a.trim())),
+                    None => (target.trim(), None),
+                };
+                if column.starts_with('_') || !column.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    continue;
+                }
+
+                let value = value.trim();
+                let Some(ty) = python_column_type(value, annotation) else {
+                    continue;
+                };
+
+                let mut key = value.contains("primary_key=True").then_some(Key::Primary);
+                if let Some(parent) = python_reference(value) {
+                    self.relate(&entity.name, &parent, column);
+                    key = key.or(Some(Key::Foreign));
+                }
+                entity.field(column, &ty, key);
+            }
+
+            self.entities.push(entity);
+        }
+    }
+
+    /// `user_id` columns pointing at a `User`/`users` entity
+    fn infer_relations(&mut self) {
+        let mut inferred = Vec::new();
+
+        for entity in &self.entities {
+            for field in &entity.fields {
+                let Some(target) = field.name.strip_suffix("_id") else {
+                    continue;
+                };
+                let known = self.relations.iter().any(|r| r.child == entity.name && r.via == field.name);
+                if !known && field.key.is_none() && self.entities.iter().any(|e| e.answers_to(target)) {
+                    inferred.push((entity.name.clone(), target.to_string(), field.name.clone()));
+                }
+            }
+        }
+
+        for (child, parent, via) in inferred {
+            if let Some(entity) = self.entities.iter_mut().find(|e| e.name == child) {
+                for field in entity.fields.iter_mut().filter(|f| f.name == via) {
+                    field.key = Some(Key::Foreign);
+                }
+            }
+            self.relate(&child, &parent, &via);
+        }
+    }
+}
+
+/// Import dependencies between project modules
+#[derive(Debug, Default)]
+pub struct ModuleGraph {
+    pub modules: BTreeSet<String>,
+    /// (from, to) pairs with the number of importing files
+    pub edges: BTreeMap<(String, String), usize>,
+}
+
+impl ModuleGraph {
+    /// Group files into modules `depth` directories deep and link their imports
+    pub fn build(root: &Path, files: &[PathBuf], depth: usize) -> Self {
+        let depth = depth.max(1);
+        let mut graph = Self::default();
+
+        let keyed: Vec<(&PathBuf, String)> = files
+            .iter()
+            .map(|f| (f, module_key(&relative_components(root, f), depth)))
+            .collect();
+        graph.modules = keyed.iter().map(|(_, k)| k.clone()).collect();
+
+        for (file, from) in &keyed {
+            let Ok(content) = fs::read_to_string(file) else {
+                continue;
+            };
+            let relative = relative_components(root, file);
+            let mut targets = BTreeSet::new();
+
+            for line in content.lines().map(str::trim) {
+                let components = match Language::from_path(file) {
+                    Language::Rust => rust_import_path(line),
+                    Language::Python => python_import_path(line, &relative),
+                    Language::JavaScript | Language::TypeScript => js_import_path(line, &relative),
+                    Language::Unknown => None,
+                };
+                let Some(components) = components else {
+                    continue;
+                };
+                if let Some(to) = graph.resolve(&components, depth) {
+                    if &to != from {
+                        targets.insert(to);
+                    }
+                }
+            }
+
+            for to in targets {
+                *graph.edges.entry((from.clone(), to)).or_insert(0) += 1;
+            }
+        }
+
+        graph
+    }
+
+    /// Longest known module matching an import path
+    fn resolve(&self, components: &[String], depth: usize) -> Option<String> {
+        (1..=components.len().min(depth))
+            .rev()
+            .map(|n| components[..n].join("/"))
+            .find(|key| self.modules.contains(key))
+    }
+
+    /// Render as a Mermaid `graph`
+    pub fn render_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+
+        for module in &self.modules {
+            out.push_str(&format!("    {}[\"{}\"]\n", mermaid_id(module), module));
+        }
+        for ((from, to), count) in &self.edges {
+            let label = if *count > 1 { format!("|{}|", count) } else { String::new() };
+            out.push_str(&format!("    {} -->{} {}\n", mermaid_id(from), label, mermaid_id(to)));
+        }
+
+        out
+    }
+}
+
+/// Build the ER model for a project root
+pub fn project_er_model(root: &Path) -> Result<ErModel> {
+    let files = index::collect_matching(root, ErModel::is_schema_file)?;
+    Ok(ErModel::from_files(&files))
+}
+
+/// Build the module graph for a project root
+pub fn project_module_graph(root: &Path, depth: usize) -> Result<ModuleGraph> {
+    let files = index::collect_files(root, false)?;
+    Ok(ModuleGraph::build(root, &files, depth))
+}
+
+/// Wrap a diagram in a Markdown `mermaid` code block
+pub fn fenced(mermaid: &str) -> String {
+    format!("```mermaid\n{}```\n", mermaid)
+}
+
+/// Substitute diagram markers in generated docs, appending any diagram
+/// the document did not place
+pub fn embed(document: &str, modules: &ModuleGraph, er: &ErModel) -> String {
+    let mut out = document.to_string();
+    let mut missing = Vec::new();
+
+    let diagrams = [
+        (MODULES_MARKER, "Module Dependencies", (!modules.modules.is_empty()).then(|| modules.render_mermaid())),
+        (ER_MARKER, "Data Model", (!er.is_empty()).then(|| er.render_mermaid())),
+    ];
+
+    for (marker, title, diagram) in diagrams {
+        match diagram {
+            Some(mermaid) if out.contains(marker) => out = out.replace(marker, &fenced(&mermaid)),
+            Some(mermaid) => missing.push(format!("### {}\n\n{}", title, fenced(&mermaid))),
+            None => out = out.replace(marker, ""),
+        }
+    }
+
+    if !missing.is_empty() {
+        out = format!("{}\n\n## Diagrams\n\n{}", out.trim_end(), missing.join("\n"));
+    }
+    out
+}
+
+/// Path components below the root, without a leading `src`/`lib` directory
+fn relative_components(root: &Path, file: &Path) -> Vec<String> {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    let mut components: Vec<String> = relative
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    if components.len() > 1 && matches!(components[0].as_str(), "src" | "lib") {
+        components.remove(0);
+    }
+    components
+}
+
+/// Module a file belongs to: its directory, or its stem at the top level
+fn module_key(components: &[String], depth: usize) -> String {
+    let Some((file, dirs)) = components.split_last() else {
+        return String::new();
+    };
+
+    if dirs.is_empty() {
+        let stem = Path::new(file).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        return stem;
+    }
+    dirs[..dirs.len().min(depth)].join("/")
+}
+
+/// `use crate::core::parser::X` → [core, parser, X]
+fn rust_import_path(line: &str) -> Option<Vec<String>> {
+    let rest = line.trim_start_matches("pub ").strip_prefix("use crate::")?;
+    Some(
+        rest.split(['{', ';', ' '])
+            .next()?
+            .split("::")
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// `from app.models import User` / `from .views import x`
+fn python_import_path(line: &str, file: &[String]) -> Option<Vec<String>> {
+    let module = if let Some(rest) = line.strip_prefix("from ") {
+        rest.split_whitespace().next()?
+    } else {
+        line.strip_prefix("import ")?.split([' ', ',']).next()?
+    };
+
+    let dots = module.chars().take_while(|c| *c == '.').count();
+    let mut components: Vec<String> = if dots > 0 {
+        let dir = &file[..file.len().saturating_sub(1)];
+        dir[..dir.len().saturating_sub(dots - 1)].to_vec()
+    } else {
+        Vec::new()
+    };
+    components.extend(module[dots..].split('.').filter(|s| !s.is_empty()).map(String::from));
+    Some(components)
+}
+
+/// Relative `import ... from './utils/format'` and `require('../db')`
+fn js_import_path(line: &str, file: &[String]) -> Option<Vec<String>> {
+    if !(line.starts_with("import ") || line.starts_with("export ") || line.contains("require(")) {
+        return None;
+    }
+    let spec = line
+        .split(['\'', '"'])
+        .nth(1)
+        .filter(|s| s.starts_with('.'))?;
+
+    let mut components: Vec<String> = file[..file.len().saturating_sub(1)].to_vec();
+    for part in spec.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                components.pop();
+            }
+            other => components.push(other.to_string()),
+        }
+    }
+    Some(components)
+}
+
+/// Column type from `Column(Integer)`, `mapped_column(...)` with `Mapped[int]`
+/// or Django's `models.CharField(...)`
+fn python_column_type(value: &str, annotation: Option<&str>) -> Option<String> {
+    let call = value.split('(').next()?.trim();
+    let func = call.rsplit('.').next()?;
+
+    if func == "Column" || func == "mapped_column" || func == "Field" {
+        if let Some(inner) = annotation.and_then(|a| a.strip_prefix("Mapped[")) {
+            return Some(inner.trim_end_matches(']').to_string());
+        }
+        let args = value.split_once('(')?.1;
+        let first = args.split([',', ')', '(']).next()?.trim();
+        let ty = first.rsplit('.').next()?;
+        return Some(if ty.is_empty() || ty.starts_with("ForeignKey") || ty.contains('"') || ty.contains('\'') {
+            annotation.unwrap_or("column").to_string()
+        } else {
+            ty.to_string()
+        });
+    }
+
+    (func.ends_with("Field") || func == "ForeignKey" || func == "OneToOneField").then(|| func.to_string())
+}
+
+/// Parent of a `ForeignKey("users.id")` or Django `ForeignKey(User, ...)`
+fn python_reference(value: &str) -> Option<String> {
+    let idx = value.find("ForeignKey(").or_else(|| value.find("OneToOneField("))?;
+    let args = value[idx..].split_once('(')?.1;
+    let first = args.split([',', ')']).next()?.trim();
+
+    let target = first.trim_matches(['"', '\'']);
+    let target = target.split('.').next().unwrap_or(target);
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+/// Referenced table after `REFERENCES`
+fn references(item: &str) -> Option<String> {
+    let idx = item.to_ascii_lowercase().find("references")?;
+    let rest = item[idx + "references".len()..].trim_start();
+    let name = rest.split(['(', ' ']).next()?;
+    Some(sql_identifier(name))
+}
+
+/// Column names inside the first parentheses
+fn paren_list(item: &str) -> Vec<String> {
+    item.split(['(', ')'])
+        .nth(1)
+        .unwrap_or("")
+        .split(',')
+        .map(sql_identifier)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Strip quoting and schema prefixes from a SQL identifier
+fn sql_identifier(raw: &str) -> String {
+    let raw = raw.trim().trim_end_matches(',');
+    let name = raw.rsplit('.').next().unwrap_or(raw);
+    name.trim_matches(['"', '`', '[', ']', '\'']).to_string()
+}
+
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+fn first_quoted(text: &str) -> Option<String> {
+    let start = text.find(['"', '\''])?;
+    let quote = text[start..].chars().next()?;
+    let body = &text[start + 1..];
+    Some(body[..body.find(quote)?].to_string())
+}
+
+/// Compare entity names across `User`, `users` and `user`
+fn normalize_entity(name: &str) -> String {
+    let lower = name.to_lowercase().replace('_', "");
+    lower.strip_suffix('s').map(String::from).unwrap_or(lower)
+}
+
+/// Identifier safe to use as a Mermaid node or attribute name
+pub fn mermaid_id(text: &str) -> String {
+    let id: String = text
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    let id = id.trim_matches('_').to_string();
+    if id.is_empty() { "_".to_string() } else { id }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_er_model_from_sql() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let schema = write(temp_dir.path(), "schema.sql", r#"
+CREATE TABLE users (
+    id SERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS "posts" (
+    id INTEGER,
+    author_id INTEGER NOT NULL,
+    title TEXT,
+    PRIMARY KEY (id),
+    FOREIGN KEY (author_id) REFERENCES users(id)
+);
+"#);
+
+        let model = ErModel::from_files(&[schema]);
+        assert_eq!(model.entities.len(), 2);
+        assert_eq!(model.entities[1].name, "posts");
+        assert_eq!(model.entities[1].fields[0].key, Some(Key::Primary));
+        assert_eq!(model.entities[1].fields[1].key, Some(Key::Foreign));
+
+        let mermaid = model.render_mermaid();
+        assert!(mermaid.contains("        varchar email\n"));
+        assert!(mermaid.contains("    users ||--o{ posts : \"author_id\"\n"));
+    }
+
+    #[test]
+    fn test_er_model_from_orm_models() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let python = write(temp_dir.path(), "models.py", r#"
+class User(Base):
+    __tablename__ = "users"
+    id = Column(Integer, primary_key=True)
+    name: Mapped[str] = mapped_column(String(50))
+
+class Order(Base):
+    __tablename__ = "orders"
+    id = Column(Integer, primary_key=True)
+    user_id = Column(Integer, ForeignKey("users.id"))
+
+def helper():
+    pass
+"#);
+        let rust = write(temp_dir.path(), "models.rs", r#"
+#[derive(Debug, sqlx::FromRow)]
+pub struct Invoice {
+    pub id: i64,
+    pub order_id: i64,
+    pub total: Option<f64>,
+}
+"#);
+
+        let model = ErModel::from_files(&[python, rust]);
+        let names: Vec<&str> = model.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Order", "Invoice"]);
+        assert_eq!(model.entities[0].fields[1], Field { name: "name".to_string(), ty: "str".to_string(), key: None });
+
+        let mermaid = model.render_mermaid();
+        assert!(mermaid.contains("    User ||--o{ Order : \"user_id\"\n"));
+        assert!(mermaid.contains("    Order ||--o{ Invoice : \"order_id\"\n"));
+        assert!(mermaid.contains("        Option_f64 total\n"));
+    }
+
+    #[test]
+    fn test_module_graph() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let files = vec![
+            write(root, "src/main.rs", "mod cli;\nuse crate::cli::run;\nuse crate::config::Config;\n"),
+            write(root, "src/config.rs", "use serde::Deserialize;\n"),
+            write(root, "src/cli/run.rs", "use crate::core::parser::{CodeParser, Language};\nuse super::colors;\n"),
+            write(root, "src/core/parser.rs", "use crate::config::Config;\n"),
+        ];
+
+        let graph = ModuleGraph::build(root, &files, 2);
+        let modules: Vec<&str> = graph.modules.iter().map(String::as_str).collect();
+        assert_eq!(modules, vec!["cli", "config", "core", "main"]);
+
+        let edges: Vec<(&str, &str)> = graph.edges.keys().map(|(a, b)| (a.as_str(), b.as_str())).collect();
+        assert_eq!(edges, vec![("cli", "core"), ("core", "config"), ("main", "cli"), ("main", "config")]);
+        assert!(graph.render_mermaid().contains("    main --> cli\n"));
+    }
+
+    #[test]
+    fn test_embed_diagrams_in_document() {
+        let mut graph = ModuleGraph::default();
+        graph.modules.insert("cli".to_string());
+
+        let placed = embed(&format!("# App\n\n{}\n", MODULES_MARKER), &graph, &ErModel::default());
+        assert_eq!(placed, "# App\n\n```mermaid\ngraph LR\n    cli[\"cli\"]\n```\n\n");
+
+        let appended = embed("# App\n", &graph, &ErModel::default());
+        assert!(appended.ends_with("## Diagrams\n\n### Module Dependencies\n\n```mermaid\ngraph LR\n    cli[\"cli\"]\n```\n"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Search/replace edits suggested by the AI
+//!
+//! Models are asked for patches as blocks that quote the code to change and
+//! give its replacement:
+//!
+//! ```text
+//! <<<<<<< SEARCH
+//! let query = format!("SELECT * FROM users WHERE id = {}", id);
+//! =======
+//! let query = "SELECT * FROM users WHERE id = ?1";
+//! >>>>>>> REPLACE
+//! ```
+//!
+//! Quoting the original is more robust than line numbers or unified diffs,
+//! which models often get slightly wrong. An edit only applies when its
+//! search text occurs exactly once in the file.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Result};
+
+const SEARCH_MARKER: &str = "<<<<<<< SEARCH";
+const DIVIDER: &str = "=======";
+const REPLACE_MARKER: &str = ">>>>>>> REPLACE";
+
+/// Tells the model how to write edits
+pub const EDIT_FORMAT: &str = r#"Give the change as one or more search/replace blocks:
+
+<<<<<<< SEARCH
+exact lines from the file, including indentation
+=======
+the lines that replace them
+>>>>>>> REPLACE
+
+Each SEARCH part must match the current file exactly and only once, so
+include enough surrounding lines to make it unique."#;
+
+/// One search/replace edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub search: String,
+    pub replace: String,
+}
+
+/// The edit blocks in a response, in order. Unterminated blocks are dropped.
+pub fn parse(response: &str) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let mut search: Option<Vec<&str>> = None;
+    let mut replace: Option<Vec<&str>> = None;
+
+    for line in response.lines() {
+        match line.trim_end() {
+            SEARCH_MARKER => {
+                search = Some(Vec::new());
+                replace = None;
+            }
+            DIVIDER if search.is_some() && replace.is_none() => replace = Some(Vec::new()),
+            REPLACE_MARKER => {
+                if let (Some(s), Some(r)) = (search.take(), replace.take()) {
+                    edits.push(Edit { search: s.join("\n"), replace: r.join("\n") });
+                }
+            }
+            _ => match (&mut search, &mut replace) {
+                (Some(_), Some(r)) => r.push(line),
+                (Some(s), None) => s.push(line),
+                _ => {}
+            },
+        }
+    }
+    edits
+}
+
+/// `content` with every edit applied in turn
+pub fn apply(content: &str, edits: &[Edit]) -> Result<String> {
+    let mut result = content.to_string();
+    for (i, edit) in edits.iter().enumerate() {
+        if edit.search.trim().is_empty() {
+            bail!("Edit {} has nothing to search for", i + 1);
+        }
+        match result.matches(edit.search.as_str()).count() {
+            1 => result = result.replacen(edit.search.as_str(), &edit.replace, 1),
+            0 => bail!("Edit {} does not match the file: {}", i + 1, first_line(&edit.search)),
+            n => bail!("Edit {} matches {} places in the file: {}", i + 1, n, first_line(&edit.search)),
+        }
+    }
+    Ok(result)
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().find(|l| !l.trim().is_empty()).unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_apply_edits() {
+        let response = "Use a bound parameter:\n\n```rust\n<<<<<<< SEARCH\n    let sql = format!(\"SELECT * FROM users WHERE id = {}\", id);\n=======\n    let sql = \"SELECT * FROM users WHERE id = ?1\";\n>>>>>>> REPLACE\n```\n";
+        let edits = parse(response);
+        assert_eq!(edits.len(), 1);
+
+        let file = "fn find(id: &str) {\n    let sql = format!(\"SELECT * FROM users WHERE id = {}\", id);\n    run(&sql);\n}\n";
+        assert_eq!(
+            apply(file, &edits).unwrap(),
+            "fn find(id: &str) {\n    let sql = \"SELECT * FROM users WHERE id = ?1\";\n    run(&sql);\n}\n"
+        );
+
+        // Stale or ambiguous edits are refused rather than guessed at
+        let stale = [Edit { search: "let sql = query(id);".to_string(), replace: String::new() }];
+        assert!(apply(file, &stale).unwrap_err().to_string().contains("does not match"));
+        let ambiguous = [Edit { search: "id".to_string(), replace: "key".to_string() }];
+        assert!(apply(file, &ambiguous).unwrap_err().to_string().contains("matches 3 places"));
+
+        // An unterminated block is not an edit
+        assert!(parse("<<<<<<< SEARCH\na\n=======\nb\n").is_empty());
+    }
+}
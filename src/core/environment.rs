@@ -0,0 +1,156 @@
+//! Toolchain environment capture
+//!
+//! Records the toolchain context of the current project (compiler and
+//! runtime versions, OS, manifest dependencies) once per session so that
+//! generation prompts target the right edition and library versions.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use super::manifest::{Manifest, ManifestKind};
+
+/// Maximum dependencies listed per manifest in a prompt
+const MAX_PROMPT_DEPENDENCIES: usize = 40;
+
+/// An installed toolchain component
+#[derive(Debug, Clone)]
+pub struct Toolchain {
+    pub name: String,
+    pub version: String,
+}
+
+/// Snapshot of the development environment
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub os: String,
+    pub arch: String,
+    pub toolchains: Vec<Toolchain>,
+    pub manifests: Vec<Manifest>,
+}
+
+static SESSION: OnceLock<Environment> = OnceLock::new();
+
+impl Environment {
+    /// Environment of the current directory, captured once per session
+    pub fn session() -> &'static Environment {
+        SESSION.get_or_init(|| Self::capture(Path::new(".")))
+    }
+
+    /// Capture the environment for a project root
+    pub fn capture(root: &Path) -> Self {
+        let manifests = Manifest::discover(root);
+        let mut toolchains = Vec::new();
+
+        let has = |kind: ManifestKind| manifests.iter().any(|m| m.kind == kind);
+
+        if has(ManifestKind::Cargo) {
+            toolchains.extend(probe("rustc", &["rustc", "--version"]));
+            toolchains.extend(probe("cargo", &["cargo", "--version"]));
+        }
+        if has(ManifestKind::Npm) {
+            toolchains.extend(probe("node", &["node", "--version"]));
+        }
+        if has(ManifestKind::PyProject) || root.join("requirements.txt").is_file() {
+            toolchains.extend(
+                probe("python", &["python3", "--version"])
+                    .or_else(|| probe("python", &["python", "--version"]))
+            );
+        }
+
+        Self {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            toolchains,
+            manifests,
+        }
+    }
+
+    /// Environment section plus pinned versions for a specific request
+    pub fn prompt_context(&self, request: &str) -> String {
+        match self.pinned_dependencies_section(request) {
+            Some(pinned) => format!("{}\n{}", self.to_prompt_section(), pinned),
+            None => self.to_prompt_section(),
+        }
+    }
+
+    /// Render exact versions of the dependencies mentioned in some text
+    ///
+    /// Returns `None` when the text references no known dependency.
+    pub fn pinned_dependencies_section(&self, text: &str) -> Option<String> {
+        let mut lines = Vec::new();
+        for manifest in &self.manifests {
+            for dep in manifest.referenced_in(text) {
+                lines.push(format!(
+                    "- {} = {} ({})",
+                    dep.name, dep.exact_version(), manifest.kind.file_name()
+                ));
+            }
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "## Pinned Dependencies\n\n\
+             The request references these project dependencies. \
+             Only use APIs that exist in these exact versions:\n\n{}\n",
+            lines.join("\n")
+        ))
+    }
+
+    /// Render the environment as a prompt section
+    pub fn to_prompt_section(&self) -> String {
+        let mut out = String::from("## Environment\n\n");
+        out.push_str(&format!("- OS: {} ({})\n", self.os, self.arch));
+
+        for tool in &self.toolchains {
+            out.push_str(&format!("- {}: {}\n", tool.name, tool.version));
+        }
+
+        for manifest in &self.manifests {
+            if let Some(edition) = &manifest.edition {
+                out.push_str(&format!("- {} target: {}\n", manifest.kind.ecosystem(), edition));
+            }
+        }
+
+        for manifest in &self.manifests {
+            let deps: Vec<_> = manifest.dependencies.iter().filter(|d| !d.dev).collect();
+            if deps.is_empty() {
+                continue;
+            }
+
+            out.push_str(&format!("\n### Dependencies ({})\n", manifest.kind.file_name()));
+            for dep in deps.iter().take(MAX_PROMPT_DEPENDENCIES) {
+                out.push_str(&format!("- {} {}\n", dep.name, dep.exact_version()));
+            }
+            if deps.len() > MAX_PROMPT_DEPENDENCIES {
+                out.push_str(&format!("- ... and {} more\n", deps.len() - MAX_PROMPT_DEPENDENCIES));
+            }
+        }
+
+        out.push_str(
+            "\nTarget these exact toolchain and library versions. \
+             Do not use language features or APIs newer than the versions listed.\n"
+        );
+
+        out
+    }
+}
+
+/// Run a version command and capture its first output line
+fn probe(name: &str, cmd: &[&str]) -> Option<Toolchain> {
+    let output = Command::new(cmd[0]).args(&cmd[1..]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Some tools (older Python) print the version to stderr
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+    let version = String::from_utf8_lossy(&text).lines().next()?.trim().to_string();
+
+    Some(Toolchain { name: name.to_string(), version })
+}
@@ -0,0 +1,433 @@
+//! Model evaluation suites
+//!
+//! A suite is a YAML file of task prompts, each with the properties a good
+//! answer must have, checked by automatic graders:
+//!
+//! ```yaml
+//! name: rust-basics
+//! providers: [claude, proxy]
+//! cases:
+//!   - name: parse-duration
+//!     language: rust
+//!     prompt: Write a Rust function `parse_duration(s: &str) -> Option<u64>` ...
+//!     expect:
+//!       - compiles
+//!       - no_unsafe
+//!       - contains: "fn parse_duration"
+//!       - not_contains: "unwrap()"
+//!       - max_lines: 40
+//!       - command: ./graders/check_style.sh
+//! ```
+//!
+//! Reports hold suite, case, provider and grader names with verdicts and
+//! timings, never prompts, answers or compiler output, so scoreboards can
+//! be shared across an organization. Names are kept as written in the suite.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use super::parser::Language;
+use super::permissions::{self, Permission};
+use super::{process, query};
+
+/// How long a `compiles` or `command` check may run
+const CHECK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Grader names accepted in `expect`
+pub const GRADERS: &[&str] = &["compiles", "no_unsafe", "contains", "not_contains", "max_lines", "command"];
+
+/// An evaluation suite file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Suite {
+    /// Defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    /// Providers to run against; all available ones when empty
+    #[serde(default)]
+    pub providers: Vec<String>,
+    /// Extra system instructions for every case
+    #[serde(default)]
+    pub system: Option<String>,
+    pub cases: Vec<Case>,
+}
+
+/// One task and what its answer must satisfy
+#[derive(Debug, Clone, Deserialize)]
+pub struct Case {
+    pub name: String,
+    pub prompt: String,
+    /// Language of the expected code, for `compiles`
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub expect: Vec<Grader>,
+}
+
+/// An automatic check on an answer
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawGrader")]
+pub enum Grader {
+    /// The code compiles (Rust, Python, JavaScript, TypeScript)
+    Compiles,
+    /// No `unsafe` in the code
+    NoUnsafe,
+    Contains(String),
+    NotContains(String),
+    /// The code is at most this many lines
+    MaxLines(usize),
+    /// A shell command that receives the code on stdin and passes with exit 0
+    Command(String),
+}
+
+/// `compiles` or `contains: "x"` as written in YAML
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawGrader {
+    Name(String),
+    Keyed(BTreeMap<String, serde_yaml::Value>),
+}
+
+impl TryFrom<RawGrader> for Grader {
+    type Error = String;
+
+    fn try_from(raw: RawGrader) -> std::result::Result<Self, Self::Error> {
+        let unknown = |name: &str| format!("unknown grader `{}` (expected one of: {})", name, GRADERS.join(", "));
+        match raw {
+            RawGrader::Name(name) => match name.as_str() {
+                "compiles" => Ok(Grader::Compiles),
+                "no_unsafe" => Ok(Grader::NoUnsafe),
+                _ => Err(unknown(&name)),
+            },
+            RawGrader::Keyed(map) => {
+                let mut entries = map.into_iter();
+                let (Some((name, value)), None) = (entries.next(), entries.next()) else {
+                    return Err("a grader takes exactly one key, like `contains: \"fn main\"`".to_string());
+                };
+                let text = || value.as_str().map(str::to_string).ok_or_else(|| format!("`{}` takes a string", name));
+                match name.as_str() {
+                    "contains" => Ok(Grader::Contains(text()?)),
+                    "not_contains" => Ok(Grader::NotContains(text()?)),
+                    "command" => Ok(Grader::Command(text()?)),
+                    "max_lines" => value
+                        .as_u64()
+                        .map(|n| Grader::MaxLines(n as usize))
+                        .ok_or_else(|| "`max_lines` takes a number".to_string()),
+                    _ => Err(unknown(&name)),
+                }
+            }
+        }
+    }
+}
+
+impl Grader {
+    pub fn label(&self) -> String {
+        match self {
+            Grader::Compiles => "compiles".to_string(),
+            Grader::NoUnsafe => "no_unsafe".to_string(),
+            Grader::Contains(text) => format!("contains `{}`", text),
+            Grader::NotContains(text) => format!("not_contains `{}`", text),
+            Grader::MaxLines(max) => format!("max_lines {}", max),
+            Grader::Command(command) => format!("command `{}`", command),
+        }
+    }
+}
+
+/// Result of one grader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Pass,
+    Fail,
+    /// The grader could not run here, e.g. no compiler installed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Grade {
+    pub grader: String,
+    pub verdict: Verdict,
+    /// Why it failed or was skipped; kept out of reports
+    #[serde(skip)]
+    pub detail: Option<String>,
+}
+
+/// One case run against one provider
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseResult {
+    pub suite: String,
+    pub case: String,
+    pub provider: String,
+    pub latency_ms: u64,
+    pub grades: Vec<Grade>,
+    /// The request failed; kept out of reports beyond the fact
+    #[serde(skip)]
+    pub error: Option<String>,
+    pub failed_request: bool,
+}
+
+impl CaseResult {
+    /// Answered, and no grader failed
+    pub fn passed(&self) -> bool {
+        !self.failed_request && self.grades.iter().all(|g| g.verdict != Verdict::Fail)
+    }
+}
+
+/// Totals for one provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderScore {
+    pub provider: String,
+    pub cases_passed: usize,
+    pub cases: usize,
+    pub checks_passed: usize,
+    pub checks: usize,
+    pub avg_latency_ms: u64,
+}
+
+impl ProviderScore {
+    /// Share of passed cases, 0-100
+    pub fn percent(&self) -> u32 {
+        (self.cases_passed * 100).checked_div(self.cases).unwrap_or(0) as u32
+    }
+}
+
+/// A shareable evaluation report
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub generated: DateTime<Utc>,
+    pub version: String,
+    pub scoreboard: Vec<ProviderScore>,
+    pub results: Vec<CaseResult>,
+}
+
+/// Load a suite, naming it after the file when it has no name
+pub fn load_suite(path: &Path) -> Result<Suite> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut suite: Suite =
+        serde_yaml::from_str(&content).with_context(|| format!("Invalid suite {}", path.display()))?;
+    if suite.name.is_empty() {
+        suite.name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    }
+    if suite.cases.is_empty() {
+        bail!("{} has no cases", path.display());
+    }
+    for case in &suite.cases {
+        if let Some(language) = &case.language {
+            if query::parse_language(language).is_none() {
+                bail!("Case `{}` in {}: unknown language `{}`", case.name, path.display(), language);
+            }
+        }
+    }
+    Ok(suite)
+}
+
+/// The code in an answer: its fenced blocks, or all of it when there are none
+pub fn extract_code(response: &str) -> String {
+    let mut blocks = Vec::new();
+    let mut rest = response;
+    while let Some(start) = rest.find("```") {
+        let after_fence = &rest[start + 3..];
+        let Some(code_start) = after_fence.find('\n').map(|i| i + 1) else {
+            break;
+        };
+        let Some(end) = after_fence[code_start..].find("```") else {
+            break;
+        };
+        blocks.push(after_fence[code_start..code_start + end].trim_end().to_string());
+        rest = &after_fence[code_start + end + 3..];
+    }
+
+    if blocks.is_empty() {
+        response.trim().to_string()
+    } else {
+        blocks.join("\n\n")
+    }
+}
+
+/// Run every grader of `case` on the code of an answer
+pub fn grade(case: &Case, code: &str) -> Vec<Grade> {
+    case.expect
+        .iter()
+        .map(|grader| {
+            let (verdict, detail) = match grader {
+                Grader::Compiles => compiles(case.language.as_deref(), code),
+                Grader::NoUnsafe => check(!has_word(code, "unsafe"), "uses `unsafe`"),
+                Grader::Contains(text) => check(code.contains(text.as_str()), "missing"),
+                Grader::NotContains(text) => check(!code.contains(text.as_str()), "present"),
+                Grader::MaxLines(max) => {
+                    let lines = code.lines().count();
+                    check(lines <= *max, &format!("{} lines", lines))
+                }
+                Grader::Command(command) => run_command(command, code),
+            };
+            Grade { grader: grader.label(), verdict, detail }
+        })
+        .collect()
+}
+
+/// Per-provider totals, in the order providers first appear
+pub fn scoreboard(results: &[CaseResult]) -> Vec<ProviderScore> {
+    let mut scores: Vec<ProviderScore> = Vec::new();
+    let mut latencies: Vec<u64> = Vec::new();
+    for result in results {
+        let pos = match scores.iter().position(|s| s.provider == result.provider) {
+            Some(pos) => pos,
+            None => {
+                scores.push(ProviderScore {
+                    provider: result.provider.clone(),
+                    cases_passed: 0,
+                    cases: 0,
+                    checks_passed: 0,
+                    checks: 0,
+                    avg_latency_ms: 0,
+                });
+                latencies.push(0);
+                scores.len() - 1
+            }
+        };
+        let score = &mut scores[pos];
+        score.cases += 1;
+        score.cases_passed += result.passed() as usize;
+        let counted = result.grades.iter().filter(|g| g.verdict != Verdict::Skipped);
+        score.checks += counted.clone().count();
+        score.checks_passed += counted.filter(|g| g.verdict == Verdict::Pass).count();
+        latencies[pos] += result.latency_ms;
+    }
+    for (score, total) in scores.iter_mut().zip(latencies) {
+        score.avg_latency_ms = total / score.cases.max(1) as u64;
+    }
+    scores
+}
+
+fn check(passed: bool, failure: &str) -> (Verdict, Option<String>) {
+    if passed {
+        (Verdict::Pass, None)
+    } else {
+        (Verdict::Fail, Some(failure.to_string()))
+    }
+}
+
+fn has_word(code: &str, word: &str) -> bool {
+    code.split(|c: char| !(c.is_alphanumeric() || c == '_')).any(|w| w == word)
+}
+
+/// Compile `code` with the language's own toolchain
+fn compiles(language: Option<&str>, code: &str) -> (Verdict, Option<String>) {
+    let Some(language) = language.and_then(query::parse_language) else {
+        return (Verdict::Skipped, Some("no `language` set for the case".to_string()));
+    };
+    let (file, command): (&str, &[&str]) = match language {
+        Language::Rust => ("answer.rs", &["rustc", "--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "-A", "warnings"]),
+        Language::Python => ("answer.py", &["python3", "-m", "py_compile"]),
+        Language::JavaScript => ("answer.js", &["node", "--check"]),
+        Language::TypeScript => ("answer.ts", &["tsc", "--noEmit", "--skipLibCheck"]),
+        Language::Unknown => return (Verdict::Skipped, None),
+    };
+
+    if let Err(e) = permissions::request(Permission::Run, &format!("run `{}` on an answer", command[0])) {
+        return (Verdict::Skipped, Some(format!("{:#}", e)));
+    }
+    // Removed when dropped, whatever the check's outcome
+    let scratch = match tempfile::Builder::new().prefix("nexus-eval-").tempdir() {
+        Ok(scratch) => scratch,
+        Err(e) => return (Verdict::Skipped, Some(e.to_string())),
+    };
+    let path = scratch.path().join(file);
+    if let Err(e) = fs::write(&path, code) {
+        return (Verdict::Skipped, Some(e.to_string()));
+    }
+
+    let output = process::run_with_timeout(
+        Command::new(command[0]).args(&command[1..]).arg(&path).current_dir(scratch.path()),
+        Vec::new(),
+        CHECK_TIMEOUT,
+    );
+    match output {
+        Ok(Some(output)) if output.status.success() => (Verdict::Pass, None),
+        Ok(None) => (Verdict::Fail, Some(format!("{} timed out after {}s", command[0], CHECK_TIMEOUT.as_secs()))),
+        Ok(Some(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let message = stderr.lines().chain(stdout.lines()).find(|l| !l.trim().is_empty()).unwrap_or("failed");
+            (Verdict::Fail, Some(message.trim().to_string()))
+        }
+        Err(_) => (Verdict::Skipped, Some(format!("{} is not installed", command[0]))),
+    }
+}
+
+/// Pipe `code` into a shell command
+fn run_command(command: &str, code: &str) -> (Verdict, Option<String>) {
+    if let Err(e) = permissions::request(Permission::Run, &format!("run `{}` on an answer", command)) {
+        return (Verdict::Skipped, Some(format!("{:#}", e)));
+    }
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    match process::run_with_timeout(Command::new(shell).args([flag, command]), code.as_bytes().to_vec(), CHECK_TIMEOUT) {
+        Ok(Some(output)) if output.status.success() => (Verdict::Pass, None),
+        Ok(Some(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            (Verdict::Fail, Some(stderr.lines().next().unwrap_or("non-zero exit").to_string()))
+        }
+        Ok(None) => (Verdict::Fail, Some(format!("timed out after {}s", CHECK_TIMEOUT.as_secs()))),
+        Err(e) => (Verdict::Skipped, Some(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_suite_grade_and_score() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("rust-basics.yaml");
+        fs::write(
+            &path,
+            "cases:\n  - name: add\n    language: rust\n    prompt: Write add\n    expect:\n      - no_unsafe\n      - contains: \"fn add\"\n      - not_contains: \"unwrap()\"\n      - max_lines: 3\n      - command: grep -q 'a + b'\n",
+        )
+        .unwrap();
+        let suite = load_suite(&path).unwrap();
+        assert_eq!(suite.name, "rust-basics");
+        assert_eq!(suite.cases[0].expect[1], Grader::Contains("fn add".to_string()));
+
+        fs::write(&path, "cases:\n  - name: x\n    prompt: y\n    expect: [fast]\n").unwrap();
+        assert!(format!("{:#}", load_suite(&path).unwrap_err()).contains("unknown grader `fast`"));
+
+        let answer = "Here it is:\n\n```rust\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n```\n";
+        let code = extract_code(answer);
+        assert_eq!(code, "pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}");
+        let verdicts: Vec<Verdict> = grade(&suite.cases[0], &code).iter().map(|g| g.verdict).collect();
+        assert_eq!(verdicts, [Verdict::Pass; 5]);
+
+        let bad = "pub unsafe fn add(a: i32, b: i32) -> i32 {\n    let sum = a.checked_add(b).unwrap();\n    sum\n}";
+        let verdicts: Vec<Verdict> = grade(&suite.cases[0], bad).iter().map(|g| g.verdict).collect();
+        assert_eq!(verdicts, [Verdict::Fail, Verdict::Pass, Verdict::Fail, Verdict::Fail, Verdict::Fail]);
+
+        let result = |provider: &str, grades: Vec<Grade>| CaseResult {
+            suite: suite.name.clone(),
+            case: "add".to_string(),
+            provider: provider.to_string(),
+            latency_ms: 100,
+            grades,
+            error: None,
+            failed_request: false,
+        };
+        let results = vec![
+            result("claude", grade(&suite.cases[0], &code)),
+            result("proxy", grade(&suite.cases[0], bad)),
+        ];
+        let scores = scoreboard(&results);
+        assert_eq!((scores[0].percent(), scores[0].checks_passed, scores[0].checks), (100, 5, 5));
+        assert_eq!((scores[1].percent(), scores[1].checks_passed), (0, 1));
+
+        // Reports carry verdicts, not answers or compiler output
+        let json = serde_json::to_string(&results).unwrap();
+        assert!(!json.contains("checked_add") && !json.contains("detail"));
+    }
+}
@@ -0,0 +1,298 @@
+//! Usage examples of a symbol
+//!
+//! Collects the places a function or type is used: call sites from the call
+//! graph for functions, name references for types. Then picks a small,
+//! diverse sample: different files and call shapes first, with tests only
+//! when production code runs out.
+
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::callgraph::CallGraph;
+use super::parser::{ParsedFile, SymbolKind};
+use crate::index;
+
+/// Lines of context kept on each side of a usage
+pub const CONTEXT_LINES: usize = 3;
+
+/// Where the symbol is defined
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub path: PathBuf,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub signature: Option<String>,
+}
+
+/// One place the symbol is used
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub path: PathBuf,
+    pub line: usize,
+    /// Qualified name of the enclosing function, if any
+    pub caller: Option<String>,
+    /// The line using the symbol
+    pub text: String,
+    /// The usage with surrounding lines, within the enclosing function
+    pub context: String,
+    /// First line of `context`
+    pub context_start: usize,
+}
+
+/// Definitions and usages of a symbol
+#[derive(Debug, Default)]
+pub struct Examples {
+    pub definitions: Vec<Definition>,
+    pub usages: Vec<Usage>,
+}
+
+/// Definitions and usages of `symbol` (`parse_config`, `Config::load`,
+/// `Order`), or `None` when the project defines no such function or type
+pub fn collect(files: &[ParsedFile], symbol: &str) -> Option<Examples> {
+    let graph = CallGraph::from_parsed(files);
+    let functions = graph.find(symbol);
+    if !functions.is_empty() {
+        return Some(function_examples(files, &graph, &functions));
+    }
+    type_examples(files, &graph, symbol)
+}
+
+/// Call sites of any of `targets`, outside the targets themselves
+fn function_examples(files: &[ParsedFile], graph: &CallGraph, targets: &[usize]) -> Examples {
+    let definitions = targets
+        .iter()
+        .map(|&i| {
+            let f = &graph.functions[i];
+            Definition { path: f.path.clone(), line_start: f.line_start, line_end: f.line_end, signature: f.signature.clone() }
+        })
+        .collect();
+
+    let mut usages = Vec::new();
+    for (caller, function) in graph.functions.iter().enumerate() {
+        if targets.contains(&caller) {
+            continue;
+        }
+        let Some(file) = files.iter().find(|f| f.path == function.path) else {
+            continue;
+        };
+        let mut seen_lines = HashSet::new();
+        for call in graph.calls_from(caller) {
+            if targets.contains(&call.callee) && seen_lines.insert(call.line) {
+                usages.push(usage(file, call.line, Some((function.line_start, function.line_end)), Some(function.qualified_name())));
+            }
+        }
+    }
+    usages.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Examples { definitions, usages }
+}
+
+/// Lines naming the type, other than its definition, imports and impl headers
+fn type_examples(files: &[ParsedFile], graph: &CallGraph, symbol: &str) -> Option<Examples> {
+    let name = symbol.rsplit("::").next().unwrap_or(symbol);
+    let is_type = |kind: SymbolKind| {
+        matches!(
+            kind,
+            SymbolKind::Struct | SymbolKind::Class | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Interface | SymbolKind::TypeAlias
+        )
+    };
+
+    let definitions: Vec<Definition> = files
+        .iter()
+        .flat_map(|file| {
+            file.symbols
+                .iter()
+                .filter(|s| s.name == name && is_type(s.kind))
+                .map(|s| Definition { path: file.path.clone(), line_start: s.line_start, line_end: s.line_end, signature: s.signature.clone() })
+        })
+        .collect();
+    if definitions.is_empty() {
+        return None;
+    }
+
+    let mut usages = Vec::new();
+    for file in files {
+        for (i, text) in file.content.lines().enumerate() {
+            let line = i + 1;
+            let inside_definition = definitions
+                .iter()
+                .any(|d| d.path == file.path && d.line_start <= line && line <= d.line_end);
+            if inside_definition || is_declaration(text) || !mentions(text, name) {
+                continue;
+            }
+
+            let function = graph
+                .functions
+                .iter()
+                .filter(|f| f.path == file.path && f.line_start <= line && line <= f.line_end)
+                .max_by_key(|f| f.line_start);
+            usages.push(usage(
+                file,
+                line,
+                function.map(|f| (f.line_start, f.line_end)),
+                function.map(|f| f.qualified_name()),
+            ));
+        }
+    }
+    Some(Examples { definitions, usages })
+}
+
+fn usage(file: &ParsedFile, line: usize, bounds: Option<(usize, usize)>, caller: Option<String>) -> Usage {
+    let lines: Vec<&str> = file.content.lines().collect();
+    let (low, high) = bounds.unwrap_or((1, lines.len()));
+    let start = line.saturating_sub(CONTEXT_LINES).max(low).max(1);
+    let end = (line + CONTEXT_LINES).min(high).min(lines.len());
+    Usage {
+        path: file.path.clone(),
+        line,
+        caller,
+        text: lines.get(line - 1).map(|l| l.trim().to_string()).unwrap_or_default(),
+        context: lines[start - 1..end].join("\n"),
+        context_start: start,
+    }
+}
+
+/// Pick up to `max` usages that show the symbol used in different ways:
+/// one per file and call shape from production code first, then other
+/// shapes, tests included, then whatever is left. Returned in file order.
+pub fn pick(usages: &[Usage], max: usize) -> Vec<Usage> {
+    let mut picked: Vec<usize> = Vec::new();
+    let mut files = HashSet::new();
+    let mut shapes = HashSet::new();
+
+    for pass in 0..3 {
+        for (i, u) in usages.iter().enumerate() {
+            if picked.len() >= max {
+                break;
+            }
+            let new_file = !files.contains(&u.path);
+            let new_shape = !shapes.contains(&shape(&u.text));
+            let wanted = match pass {
+                0 => new_file && new_shape && !index::is_test_path(&u.path),
+                1 => new_shape,
+                _ => true,
+            };
+            if wanted && !picked.contains(&i) {
+                picked.push(i);
+                files.insert(u.path.clone());
+                shapes.insert(shape(&u.text));
+            }
+        }
+    }
+
+    picked.sort_unstable();
+    picked.into_iter().map(|i| usages[i].clone()).collect()
+}
+
+/// A line with literals and names of locals blurred, so calls that differ
+/// only in their arguments compare equal
+fn shape(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_string: Option<char> = None;
+    for c in text.chars() {
+        match in_string {
+            Some(quote) if c == quote => {
+                in_string = None;
+                out.push('"');
+            }
+            Some(_) => {}
+            None if c == '"' => {
+                in_string = Some(c);
+                out.push('"');
+            }
+            None if c.is_ascii_digit() => {
+                if !out.ends_with('0') {
+                    out.push('0');
+                }
+            }
+            None if c.is_whitespace() => {}
+            None => out.push(c),
+        }
+    }
+    // `let config = ` and `let cfg = ` are the same shape
+    match out.split_once('=') {
+        Some((lhs, rhs)) if !rhs.starts_with('=') && !lhs.ends_with(['!', '<', '>', '=']) => format!("_={}", rhs),
+        _ => out,
+    }
+}
+
+/// Whether `name` appears in `text` as a whole word, outside a comment
+fn mentions(text: &str, name: &str) -> bool {
+    let trimmed = text.trim_start();
+    if trimmed.starts_with("//") || trimmed.starts_with('#') || trimmed.starts_with('*') {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(pos, _)| {
+        let before = text[..pos].chars().next_back();
+        let after = text[pos + name.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Imports and impl headers name a type without showing how to use it
+fn is_declaration(text: &str) -> bool {
+    let text = text.trim_start();
+    let text = text.strip_prefix("pub ").unwrap_or(text);
+    ["use ", "import ", "from ", "impl ", "impl<", "mod "].iter().any(|p| text.starts_with(p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+    use std::fs;
+
+    #[test]
+    fn test_collect_and_pick_examples() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("tests")).unwrap();
+        fs::write(
+            root.join("src/config.rs"),
+            "pub struct Config {\n    pub name: String,\n}\n\npub fn load_config(path: &str) -> Config {\n    Config { name: path.to_string() }\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/main.rs"),
+            "use crate::config::Config;\n\nfn main() {\n    let config = load_config(\"a.toml\");\n    let other = load_config(\"b.toml\");\n    run(&config);\n}\n\nfn reload() {\n    let _ = load_config(\"a.toml\").name;\n}\n\nfn run(config: &Config) {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("tests/config_test.rs"), "fn test_load() {\n    let config = load_config(\"test.toml\");\n}\n").unwrap();
+
+        let mut parser = CodeParser::new().unwrap();
+        let files: Vec<ParsedFile> = ["src/config.rs", "src/main.rs", "tests/config_test.rs"]
+            .iter()
+            .map(|f| parser.parse_file(&root.join(f)).unwrap())
+            .collect();
+
+        let examples = collect(&files, "load_config").unwrap();
+        assert_eq!(examples.definitions.len(), 1);
+        let lines: Vec<usize> = examples.usages.iter().map(|u| u.line).collect();
+        assert_eq!(lines, [4, 5, 10, 2]);
+        assert_eq!(examples.usages[0].caller.as_deref(), Some("main"));
+        assert!(examples.usages[0].context.starts_with("fn main() {"));
+
+        // The second `let ... = load_config("...")` and the test repeat a shape
+        let picked: Vec<(String, usize)> = pick(&examples.usages, 2)
+            .iter()
+            .map(|u| (u.path.file_name().unwrap().to_string_lossy().to_string(), u.line))
+            .collect();
+        assert_eq!(picked, [("main.rs".to_string(), 4), ("main.rs".to_string(), 10)]);
+        assert_eq!(pick(&examples.usages, 10).len(), 4);
+
+        // Types: references, not the definition, imports or comments
+        let examples = collect(&files, "Config").unwrap();
+        let lines: Vec<(String, usize)> = examples
+            .usages
+            .iter()
+            .map(|u| (u.path.file_name().unwrap().to_string_lossy().to_string(), u.line))
+            .collect();
+        assert_eq!(lines, [("config.rs".to_string(), 5), ("config.rs".to_string(), 6), ("main.rs".to_string(), 13)]);
+        assert_eq!(examples.usages[2].caller.as_deref(), Some("run"));
+
+        assert!(collect(&files, "Missing").is_none());
+    }
+}
@@ -0,0 +1,143 @@
+//! Open review findings
+//!
+//! Findings of the latest review of each file, kept in
+//! `.nexus/findings.json` until the file is reviewed again or a finding is
+//! fixed from `review --interactive`.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::graders::{ReviewFinding, Severity};
+use super::journal::{create_project_dir, PROJECT_DIR};
+
+const FINDINGS_FILE: &str = "findings.json";
+
+/// A finding and when it was reported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenFinding {
+    pub reviewed_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub finding: ReviewFinding,
+}
+
+/// Open findings of a project root
+pub struct FindingsStore {
+    path: PathBuf,
+}
+
+impl FindingsStore {
+    /// Findings of the project in the current directory
+    pub fn current() -> Self {
+        Self::for_root(Path::new("."))
+    }
+
+    /// Findings of a specific project root
+    pub fn for_root(root: &Path) -> Self {
+        Self {
+            path: root.join(PROJECT_DIR).join(FINDINGS_FILE),
+        }
+    }
+
+    /// Open findings, most severe first
+    pub fn open(&self) -> Result<Vec<OpenFinding>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let mut findings: Vec<OpenFinding> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))?;
+        findings.sort_by_key(|f| std::cmp::Reverse(f.finding.severity));
+        Ok(findings)
+    }
+
+    /// Replace the findings of the `reviewed` files with those of a new review
+    pub fn record(&self, reviewed: &[String], findings: &[ReviewFinding]) -> Result<()> {
+        let reviewed: Vec<&str> = reviewed.iter().map(|p| normalize(p)).collect();
+        let mut open: Vec<OpenFinding> = self
+            .open()?
+            .into_iter()
+            .filter(|f| !reviewed.contains(&normalize(&f.finding.path)))
+            .collect();
+
+        let now = Utc::now();
+        for finding in findings {
+            let duplicate = open.iter().any(|f| {
+                normalize(&f.finding.path) == normalize(&finding.path) && f.finding.title == finding.title
+            });
+            if !duplicate {
+                open.push(OpenFinding { reviewed_at: now, finding: finding.clone() });
+            }
+        }
+        self.save(&open)
+    }
+
+    fn save(&self, findings: &[OpenFinding]) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_project_dir(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(findings)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+/// Number of findings per severity
+pub fn count_by_severity(findings: &[OpenFinding]) -> BTreeMap<Severity, usize> {
+    let mut counts = BTreeMap::new();
+    for finding in findings {
+        *counts.entry(finding.finding.severity).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_replaces_findings_of_reviewed_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = FindingsStore::for_root(temp_dir.path());
+        let finding = |path: &str, severity, title: &str| ReviewFinding {
+            id: String::new(),
+            path: path.to_string(),
+            line: None,
+            severity,
+            title: title.to_string(),
+            detail: None,
+            source: "nexus".to_string(),
+            rescored_by: None,
+        };
+
+        store
+            .record(
+                &["src/db.rs".to_string(), "src/api.rs".to_string()],
+                &[finding("src/db.rs", Severity::Low, "Magic number"), finding("src/api.rs", Severity::Critical, "No auth")],
+            )
+            .unwrap();
+        // Reviewing db.rs again drops its old findings but keeps api.rs's
+        store
+            .record(&["./src/db.rs".to_string()], &[finding("./src/db.rs", Severity::High, "SQL built from input")])
+            .unwrap();
+
+        let open = store.open().unwrap();
+        let titles: Vec<&str> = open.iter().map(|f| f.finding.title.as_str()).collect();
+        assert_eq!(titles, ["No auth", "SQL built from input"]);
+        let counts = count_by_severity(&open);
+        assert_eq!(counts.get(&Severity::Critical), Some(&1));
+        assert_eq!(counts.get(&Severity::Low), None);
+
+        store.record(&["src/api.rs".to_string()], &[]).unwrap();
+        assert_eq!(store.open().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! Project formatter for written files
+//!
+//! Detects the formatter a project already uses (rustfmt, gofmt, black or
+//! ruff from `pyproject.toml`, prettier from its config files) and runs it on
+//! a file NEXUS just wrote, so applied changes match the house style instead
+//! of churning formatting in review. Python and JavaScript files are only
+//! formatted when the project is set up for a formatter.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::permissions::{self, Permission};
+use crate::config::FormatConfig;
+
+/// Prettier config files, any of which means the project uses prettier
+const PRETTIER_CONFIGS: &[&str] = &[
+    ".prettierrc",
+    ".prettierrc.json",
+    ".prettierrc.yaml",
+    ".prettierrc.yml",
+    ".prettierrc.js",
+    ".prettierrc.cjs",
+    ".prettierrc.mjs",
+    ".prettierrc.toml",
+    "prettier.config.js",
+    "prettier.config.cjs",
+    "prettier.config.mjs",
+];
+
+/// A formatter command for one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formatter {
+    /// Short name shown to the user
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+/// What running the formatter did
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatOutcome {
+    Formatted(String),
+    Unchanged(String),
+    NotInstalled(String),
+    Failed { formatter: String, message: String },
+}
+
+/// Formatter key of a file, as used in `[format.formatters]`
+pub fn language_key(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    Some(match ext.as_str() {
+        "rs" => "rust",
+        "py" | "pyi" => "python",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "go" => "go",
+        _ => return None,
+    })
+}
+
+/// The formatter for `path` in the project at `root`, or `None` when the
+/// language has none, the project does not use one, or it is disabled
+pub fn detect(root: &Path, path: &Path, config: &FormatConfig) -> Option<Formatter> {
+    if !config.on_write {
+        return None;
+    }
+    let key = language_key(path)?;
+    let file = path.to_string_lossy().to_string();
+
+    if let Some(command) = config.formatters.get(key) {
+        let mut words: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+        if words.is_empty() {
+            return None;
+        }
+        if words.iter().any(|w| w.contains("{file}")) {
+            words = words.into_iter().map(|w| w.replace("{file}", &file)).collect();
+        } else {
+            words.push(file);
+        }
+        let program = words.remove(0);
+        return Some(Formatter { name: program_name(&program), program, args: words });
+    }
+
+    let (program, args): (String, Vec<String>) = match key {
+        "rust" => ("rustfmt".to_string(), vec!["--edition".to_string(), rust_edition(root), file]),
+        "go" => ("gofmt".to_string(), vec!["-w".to_string(), file]),
+        "python" => {
+            let pyproject = fs::read_to_string(root.join("pyproject.toml")).unwrap_or_default();
+            if pyproject.contains("[tool.ruff") || root.join("ruff.toml").exists() || root.join(".ruff.toml").exists() {
+                ("ruff".to_string(), vec!["format".to_string(), file])
+            } else if pyproject.contains("[tool.black]") {
+                ("black".to_string(), vec!["-q".to_string(), file])
+            } else {
+                return None;
+            }
+        }
+        _ => {
+            if !uses_prettier(root) {
+                return None;
+            }
+            let local = root.join("node_modules").join(".bin").join("prettier");
+            let program = if local.exists() { local.to_string_lossy().to_string() } else { "prettier".to_string() };
+            (program, vec!["--write".to_string(), file])
+        }
+    };
+    Some(Formatter { name: program_name(&program), program, args })
+}
+
+/// Run the project's formatter on a file just written, or `None` when
+/// there is no formatter for it
+pub fn format_file(root: &Path, path: &Path, config: &FormatConfig) -> Option<FormatOutcome> {
+    let formatter = detect(root, path, config)?;
+    let full: PathBuf = if path.is_absolute() { path.to_path_buf() } else { root.join(path) };
+    let before = fs::read(&full).ok();
+
+    let action = format!("run `{} {}`", formatter.program, formatter.args.join(" "));
+    if let Err(e) = permissions::request(Permission::Run, &action) {
+        return Some(FormatOutcome::Failed { formatter: formatter.name, message: format!("{:#}", e) });
+    }
+    let output = match Command::new(&formatter.program).args(&formatter.args).current_dir(root).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Some(FormatOutcome::NotInstalled(formatter.name)),
+        Err(e) => return Some(FormatOutcome::Failed { formatter: formatter.name, message: e.to_string() }),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr.lines().find(|l| !l.trim().is_empty()).unwrap_or("exited with an error").trim().to_string();
+        return Some(FormatOutcome::Failed { formatter: formatter.name, message });
+    }
+
+    Some(if fs::read(&full).ok() == before {
+        FormatOutcome::Unchanged(formatter.name)
+    } else {
+        FormatOutcome::Formatted(formatter.name)
+    })
+}
+
+/// Edition from the project's `Cargo.toml`, so rustfmt parses the code the
+/// same way the compiler does
+fn rust_edition(root: &Path) -> String {
+    fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+        .and_then(|manifest| {
+            let package = manifest.get("package")?;
+            package.get("edition")?.as_str().map(str::to_string)
+        })
+        .unwrap_or_else(|| "2021".to_string())
+}
+
+fn uses_prettier(root: &Path) -> bool {
+    if PRETTIER_CONFIGS.iter().any(|f| root.join(f).exists()) {
+        return true;
+    }
+    fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|p| serde_json::from_str::<serde_json::Value>(&p).ok())
+        .is_some_and(|p| {
+            p.get("prettier").is_some()
+                || ["dependencies", "devDependencies"].iter().any(|d| p.get(d).and_then(|d| d.get("prettier")).is_some())
+        })
+}
+
+fn program_name(program: &str) -> String {
+    Path::new(program).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| program.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let mut config = FormatConfig::default();
+
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"demo\"\nedition = \"2018\"\n").unwrap();
+        let rust = detect(root, Path::new("src/lib.rs"), &config).unwrap();
+        assert_eq!(rust.args, ["--edition", "2018", "src/lib.rs"]);
+
+        // Python and JavaScript only when the project is set up for a formatter
+        assert!(detect(root, Path::new("app.py"), &config).is_none());
+        assert!(detect(root, Path::new("app.ts"), &config).is_none());
+        fs::write(root.join("pyproject.toml"), "[tool.black]\nline-length = 100\n").unwrap();
+        assert_eq!(detect(root, Path::new("app.py"), &config).unwrap().name, "black");
+        fs::write(root.join("package.json"), r#"{"devDependencies": {"prettier": "^3.0.0"}}"#).unwrap();
+        assert_eq!(detect(root, Path::new("app.ts"), &config).unwrap().args, ["--write", "app.ts"]);
+        assert!(detect(root, Path::new("notes.md"), &config).is_none());
+
+        config.formatters.insert("python".to_string(), "ruff format --quiet {file}".to_string());
+        config.formatters.insert("go".to_string(), String::new());
+        let python = detect(root, Path::new("app.py"), &config).unwrap();
+        assert_eq!((python.program.as_str(), python.args.as_slice()), ("ruff", &["format".to_string(), "--quiet".to_string(), "app.py".to_string()][..]));
+        assert!(detect(root, Path::new("main.go"), &config).is_none());
+
+        fs::write(root.join("app.py"), "x = 1\n").unwrap();
+        config.formatters.insert("python".to_string(), "true".to_string());
+        assert_eq!(format_file(root, Path::new("app.py"), &config), Some(FormatOutcome::Unchanged("true".to_string())));
+        config.formatters.insert("python".to_string(), "nexus-missing-formatter".to_string());
+        assert!(matches!(format_file(root, Path::new("app.py"), &config), Some(FormatOutcome::NotInstalled(_))));
+
+        config.on_write = false;
+        assert!(format_file(root, Path::new("src/lib.rs"), &config).is_none());
+    }
+}
@@ -0,0 +1,48 @@
+//! Git queries
+//!
+//! Thin wrappers over the `git` binary for the commands that read the
+//! repository: the API surface at a revision, the files a branch changed,
+//! uncommitted work, and where a diff's paths are rooted.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Stdout of `git args` run in `dir`; an error when git fails
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        bail!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Top level of the repository containing `dir`, which `git diff` paths are
+/// relative to
+pub fn repo_root(dir: &Path) -> Result<PathBuf> {
+    let top = git(dir, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(top.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_root_from_subdirectory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        git(&root, &["init", "-q"]).unwrap();
+        std::fs::create_dir_all(root.join("src/cli")).unwrap();
+
+        assert_eq!(repo_root(&root.join("src/cli")).unwrap().canonicalize().unwrap(), root);
+        assert!(git(&root, &["no-such-command"]).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! Domain glossary
+//!
+//! Mines the words that make up identifiers and comments, keeps the ones
+//! specific to this codebase, and merges them with AI-written definitions
+//! into a Markdown glossary with file references.
+
+#![allow(dead_code)]
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::parser::{identifier_words, ParsedFile, SymbolKind};
+use crate::index::is_test_path;
+
+/// References kept per term
+pub const MAX_REFERENCES: usize = 5;
+
+/// Comment snippets kept per term as context for definitions
+const MAX_SNIPPETS: usize = 2;
+
+/// Words every codebase uses; they say nothing about the domain
+const GENERIC_WORDS: &[&str] = &[
+    "get", "set", "new", "from", "into", "with", "self", "this", "that", "data", "value", "values",
+    "result", "results", "error", "errors", "string", "list", "item", "items", "type", "types",
+    "name", "names", "path", "paths", "file", "files", "config", "init", "main", "test", "tests",
+    "some", "none", "option", "options", "index", "info", "print", "read", "write", "run", "args",
+    "parse", "build", "create", "update", "delete", "remove", "handle", "handler", "default",
+    "impl", "func", "function", "method", "class", "struct", "object", "json", "text", "line",
+    "lines", "char", "count", "check", "make", "load", "save", "input", "output", "util", "utils",
+    "helper", "helpers", "call", "next", "size", "true", "false", "null", "async", "await",
+    "return", "todo", "fixme", "note", "format", "message", "context", "state", "params", "param",
+    "request", "response", "start", "stop", "find", "add", "len", "key", "keys", "map", "vec",
+    "when", "then", "else", "only", "each", "used", "uses", "use", "returns", "given", "should",
+    "must", "will", "would", "which", "there", "their", "them", "they", "also", "more", "than",
+    "other", "first", "last", "same", "into", "does", "done", "have", "has", "been", "being",
+    "current", "already", "before", "after", "without", "empty", "whether", "every", "over",
+    "under", "here", "where", "while", "what", "kept", "keep", "need", "needs", "like", "just",
+    "code", "success", "warning", "clear", "header", "source", "summary", "added", "created",
+    "send", "match", "mode", "supported", "directory", "import", "extract", "content", "display",
+];
+
+/// Where a term appears
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub path: PathBuf,
+    pub line: usize,
+    pub symbol: String,
+}
+
+/// A candidate domain term with all its spellings
+#[derive(Debug, Clone)]
+pub struct Term {
+    /// Most common spelling
+    pub name: String,
+    pub variants: BTreeSet<String>,
+    /// Occurrences in identifiers and comments
+    pub count: usize,
+    /// Distinct files the term appears in
+    pub files: usize,
+    pub references: Vec<Reference>,
+    /// Comment lines that mention the term, with the file they are from
+    pub snippets: Vec<(PathBuf, String)>,
+}
+
+#[derive(Default)]
+struct Tally {
+    spellings: BTreeMap<String, usize>,
+    count: usize,
+    files: BTreeSet<PathBuf>,
+    references: Vec<Reference>,
+    snippets: Vec<(PathBuf, String)>,
+}
+
+/// Collapse inflections so "invoice", "invoices" and "invoicing" count as
+/// one term. The result is a key, not a word.
+pub fn stem(word: &str) -> String {
+    let mut word = word.to_lowercase();
+    for (suffix, replacement) in [("ies", "y"), ("sses", "ss"), ("ing", ""), ("ed", ""), ("es", ""), ("s", "")] {
+        if let Some(base) = word.strip_suffix(suffix) {
+            if base.len() >= 3 && !(suffix == "s" && base.ends_with('s')) {
+                word = format!("{}{}", base, replacement);
+                break;
+            }
+        }
+    }
+    if word.len() > 3 && word.ends_with('e') {
+        word.pop();
+    }
+    word
+}
+
+fn is_candidate_word(word: &str) -> bool {
+    word.len() >= 4 && word.chars().all(|c| c.is_ascii_alphabetic()) && !GENERIC_WORDS.contains(&word)
+}
+
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("//")
+        || line.starts_with('#') && !line.starts_with("#[") && !line.starts_with("#!")
+        || line.starts_with("/*")
+        || line.starts_with('*')
+        || line.starts_with("\"\"\"")
+        || line.starts_with("--")
+}
+
+/// Domain terms ranked by how widely they are used, at most `limit`
+pub fn mine(files: &[ParsedFile], min_files: usize, limit: usize) -> Vec<Term> {
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+
+    let files: Vec<&ParsedFile> = files.iter().filter(|f| !is_test_path(&f.path)).collect();
+
+    // Identifiers decide what the terms are
+    for file in &files {
+        for symbol in &file.symbols {
+            if matches!(symbol.kind, SymbolKind::Impl) || symbol.name.starts_with("test_") {
+                continue;
+            }
+            for word in identifier_words(&symbol.name) {
+                if !is_candidate_word(&word) {
+                    continue;
+                }
+                let tally = tallies.entry(stem(&word)).or_default();
+                *tally.spellings.entry(word).or_default() += 1;
+                tally.count += 1;
+                tally.files.insert(file.path.clone());
+                if tally.references.len() < MAX_REFERENCES * 4 {
+                    tally.references.push(Reference {
+                        path: file.path.clone(),
+                        line: symbol.line_start,
+                        symbol: symbol.name.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    // Comments add weight and the context a definition needs
+    for file in &files {
+        for line in file.content.lines().filter(|l| is_comment(l)) {
+            let mut counted = HashSet::new();
+            for word in line.split(|c: char| !c.is_alphanumeric() && c != '_').flat_map(identifier_words) {
+                let key = stem(&word);
+                let Some(tally) = tallies.get_mut(&key) else { continue };
+                if !counted.insert(key) {
+                    continue;
+                }
+                tally.count += 1;
+                tally.files.insert(file.path.clone());
+                if tally.snippets.len() < MAX_SNIPPETS {
+                    let snippet = line.trim().trim_start_matches(['/', '#', '*', '-', '"', '!']).trim();
+                    if snippet.len() > 20 {
+                        tally.snippets.push((file.path.clone(), snippet.chars().take(160).collect()));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut terms: Vec<Term> = tallies
+        .into_values()
+        .filter(|t| t.files.len() >= min_files && !t.spellings.is_empty())
+        .map(|t| {
+            let name = t
+                .spellings
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then(b.0.len().cmp(&a.0.len())))
+                .map(|(s, _)| s.clone())
+                .unwrap_or_default();
+            Term {
+                name,
+                variants: t.spellings.into_keys().collect(),
+                count: t.count,
+                files: t.files.len(),
+                references: spread_references(t.references),
+                snippets: t.snippets,
+            }
+        })
+        .collect();
+
+    terms.sort_by(|a, b| b.files.cmp(&a.files).then(b.count.cmp(&a.count)).then(a.name.cmp(&b.name)));
+    terms.truncate(limit);
+    terms
+}
+
+/// References from as many different files as possible
+fn spread_references(references: Vec<Reference>) -> Vec<Reference> {
+    let mut seen = HashSet::new();
+    let (first, rest): (Vec<Reference>, Vec<Reference>) =
+        references.into_iter().partition(|r| seen.insert(r.path.clone()));
+    first.into_iter().chain(rest).take(MAX_REFERENCES).collect()
+}
+
+/// A group of synonymous terms as the AI defines it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Definition {
+    pub term: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub definition: String,
+    /// Mined terms the group covers
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// One glossary entry with the references of every synonym
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub term: String,
+    pub aliases: Vec<String>,
+    pub definition: String,
+    pub references: Vec<Reference>,
+}
+
+/// Attach references to the AI's definitions. Definitions that match no
+/// mined term are dropped, so every entry is grounded in the code.
+pub fn entries(terms: &[Term], definitions: Vec<Definition>) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = definitions
+        .into_iter()
+        .filter_map(|d| {
+            let keys: HashSet<String> = d
+                .members
+                .iter()
+                .chain(std::iter::once(&d.term))
+                .chain(&d.aliases)
+                .flat_map(|m| identifier_words(m))
+                .map(|w| stem(&w))
+                .collect();
+
+            let matched: Vec<&Term> = terms.iter().filter(|t| keys.contains(&stem(&t.name))).collect();
+            if matched.is_empty() || d.definition.trim().is_empty() {
+                return None;
+            }
+
+            let references = spread_references(matched.iter().flat_map(|t| t.references.clone()).collect());
+            Some(Entry {
+                term: d.term.trim().to_string(),
+                aliases: d.aliases.into_iter().filter(|a| !a.eq_ignore_ascii_case(&d.term)).collect(),
+                definition: d.definition.trim().to_string(),
+                references,
+            })
+        })
+        .collect();
+
+    entries.sort_by_key(|e| e.term.to_lowercase());
+    entries.dedup_by(|a, b| a.term.eq_ignore_ascii_case(&b.term));
+    entries
+}
+
+/// Markdown glossary, with paths shown relative to `root`
+pub fn render_markdown(entries: &[Entry], root: &Path) -> String {
+    let mut out = String::from("# Glossary\n\nDomain terms used across the codebase, generated by `nexus glossary`.\n");
+
+    for entry in entries {
+        out.push_str(&format!("\n## {}\n\n", entry.term));
+        if !entry.aliases.is_empty() {
+            out.push_str(&format!("*Also:* {}\n\n", entry.aliases.join(", ")));
+        }
+        out.push_str(&entry.definition);
+        out.push_str("\n\n");
+        for r in &entry.references {
+            let path = r.path.strip_prefix(root).unwrap_or(&r.path);
+            out.push_str(&format!("- `{}:{}` — `{}`\n", path.display(), r.line, r.symbol));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+
+    fn parsed(path: &str, content: &str) -> ParsedFile {
+        CodeParser::new().unwrap().parse_source(Path::new(path), content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("invoices"), "invoic");
+        assert_eq!(stem("invoice"), "invoic");
+        assert_eq!(stem("invoicing"), "invoic");
+        assert_eq!(stem("policies"), "policy");
+        assert_eq!(stem("ledgers"), "ledger");
+        assert_eq!(stem("address"), "address");
+    }
+
+    #[test]
+    fn test_mine_and_render() {
+        let files = vec![
+            parsed("./src/billing.rs", "// Every invoice belongs to exactly one ledger account\npub struct Invoice;\n\npub fn post_invoice_to_ledger() {}\n"),
+            parsed("./src/ledger.rs", "pub struct Ledger;\n\npub fn get_invoices() {}\n"),
+            parsed("./tests/invoice_test.rs", "fn invoice_fixture() {}\n"),
+        ];
+
+        let terms = mine(&files, 2, 10);
+        let names: Vec<&str> = terms.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["invoice", "ledger"]);
+        assert!(terms[0].variants.contains("invoices"));
+        assert_eq!(terms[0].snippets.len(), 1);
+        assert!(terms[0].references.iter().all(|r| !r.path.starts_with("./tests")));
+
+        let definitions = vec![
+            Definition {
+                term: "Invoice".to_string(),
+                aliases: vec!["bill".to_string()],
+                definition: "A request for payment.".to_string(),
+                members: vec!["invoice".to_string()],
+            },
+            Definition {
+                term: "Customer".to_string(),
+                aliases: Vec::new(),
+                definition: "Not in the code.".to_string(),
+                members: Vec::new(),
+            },
+        ];
+        let entries = entries(&terms, definitions);
+        assert_eq!(entries.len(), 1);
+
+        let markdown = render_markdown(&entries, Path::new("."));
+        assert!(markdown.contains("## Invoice\n\n*Also:* bill\n\nA request for payment.\n\n- `src/billing.rs:2` — `Invoice`\n"));
+    }
+}
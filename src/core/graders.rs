@@ -0,0 +1,324 @@
+//! Review grader plugins
+//!
+//! External programs that post-process review findings, so in-house linters
+//! and policies can shape NEXUS reports. A grader is an executable, or a
+//! WASI module (`.wasm`) run with `wasmtime`, found in `.nexus/graders/` or
+//! passed with `--grader`.
+//!
+//! Each grader reads one JSON document on stdin:
+//!
+//! ```json
+//! {"version": 1, "focus": "security",
+//!  "findings": [{"id": "F1", "path": "src/db.rs", "line": 42, "severity": "high",
+//!                "title": "SQL built from user input", "source": "nexus"}],
+//!  "files": [{"path": "src/db.rs", "content": "..."}]}
+//! ```
+//!
+//! and answers on stdout with the changes it wants, all optional:
+//!
+//! ```json
+//! {"add": [{"path": "src/db.rs", "line": 7, "severity": "medium", "title": "..."}],
+//!  "suppress": ["F1"],
+//!  "rescore": [{"id": "F2", "severity": "low"}]}
+//! ```
+//!
+//! Graders run in order; each sees the findings left by the previous one.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use super::journal::PROJECT_DIR;
+use super::permissions::{self, Permission};
+use super::process;
+
+/// Directory under `.nexus/` holding project graders
+pub const GRADERS_DIR: &str = "graders";
+
+/// Protocol version sent to graders
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// How long a grader may run
+const GRADER_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// A structured review finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    #[serde(default)]
+    pub id: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// `nexus` for the AI, otherwise the grader that added it
+    #[serde(default)]
+    pub source: String,
+    /// Grader that last changed the severity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rescored_by: Option<String>,
+}
+
+/// How a grader is started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraderKind {
+    Executable,
+    Wasm,
+}
+
+#[derive(Debug, Clone)]
+pub struct Grader {
+    pub name: String,
+    pub path: PathBuf,
+    pub kind: GraderKind,
+}
+
+/// What a grader changed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GraderSummary {
+    pub added: usize,
+    pub suppressed: usize,
+    pub rescored: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct GraderInput<'a> {
+    version: u32,
+    focus: &'a str,
+    findings: &'a [ReviewFinding],
+    files: Vec<SourceFile<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SourceFile<'a> {
+    path: &'a str,
+    content: &'a str,
+}
+
+/// A grader's reply
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct GraderOutput {
+    pub add: Vec<ReviewFinding>,
+    pub suppress: Vec<String>,
+    pub rescore: Vec<Rescore>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Rescore {
+    pub id: String,
+    pub severity: Severity,
+}
+
+impl Grader {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            bail!("No grader at {}", path.display());
+        }
+        let kind = if path.extension().is_some_and(|e| e == "wasm") {
+            GraderKind::Wasm
+        } else {
+            GraderKind::Executable
+        };
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        Ok(Self { name, path: path.to_path_buf(), kind })
+    }
+
+    /// Send the findings and code to the grader and read its reply
+    pub fn run(&self, focus: &str, findings: &[ReviewFinding], files: &[(String, String)]) -> Result<GraderOutput> {
+        let input = GraderInput {
+            version: PROTOCOL_VERSION,
+            focus,
+            findings,
+            files: files.iter().map(|(path, content)| SourceFile { path, content }).collect(),
+        };
+        let input = serde_json::to_vec(&input)?;
+
+        permissions::request(Permission::Run, &format!("run the review grader {}", self.path.display()))?;
+        let mut command = match self.kind {
+            GraderKind::Executable => Command::new(&self.path),
+            GraderKind::Wasm => {
+                let mut command = Command::new("wasmtime");
+                command.arg("run").arg(&self.path);
+                command
+            }
+        };
+        let output = process::run_with_timeout(&mut command, input, GRADER_TIMEOUT).with_context(|| match self.kind {
+            GraderKind::Executable => format!("Failed to run {}", self.path.display()),
+            GraderKind::Wasm => format!("Failed to run {}: WASM graders need wasmtime on PATH", self.path.display()),
+        })?;
+        let Some(output) = output else {
+            bail!("{} timed out after {}s", self.name, GRADER_TIMEOUT.as_secs());
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            bail!("{} failed: {}", self.name, stderr.lines().next().unwrap_or("non-zero exit"));
+        }
+        if stdout.trim().is_empty() {
+            return Ok(GraderOutput::default());
+        }
+        serde_json::from_str(&stdout).with_context(|| format!("{} replied with invalid JSON", self.name))
+    }
+}
+
+/// Graders in `.nexus/graders/` under `root`, by name
+pub fn discover(root: &Path) -> Vec<Grader> {
+    let Ok(entries) = fs::read_dir(root.join(PROJECT_DIR).join(GRADERS_DIR)) else {
+        return Vec::new();
+    };
+    let mut graders: Vec<Grader> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .filter(|p| p.extension().is_some_and(|e| e == "wasm") || is_executable(p))
+        .filter_map(|p| Grader::from_path(&p).ok())
+        .collect();
+    graders.sort_by(|a, b| a.name.cmp(&b.name));
+    graders
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "exe" || e == "cmd" || e == "bat")
+}
+
+/// Apply a grader's reply to `findings`
+pub fn apply(findings: &mut Vec<ReviewFinding>, output: GraderOutput, grader: &str) -> GraderSummary {
+    // Numbered before suppressing, so an added finding never takes a removed one's id
+    let mut next = max_id(findings) + 1;
+    let before = findings.len();
+    findings.retain(|f| !output.suppress.contains(&f.id));
+    let mut summary = GraderSummary { suppressed: before - findings.len(), ..Default::default() };
+
+    for rescore in output.rescore {
+        if let Some(finding) = findings.iter_mut().find(|f| f.id == rescore.id) {
+            if finding.severity != rescore.severity {
+                finding.severity = rescore.severity;
+                finding.rescored_by = Some(grader.to_string());
+                summary.rescored += 1;
+            }
+        }
+    }
+
+    for mut finding in output.add {
+        finding.id = format!("F{}", next);
+        next += 1;
+        finding.source = grader.to_string();
+        findings.push(finding);
+        summary.added += 1;
+    }
+    summary
+}
+
+/// Findings from the fenced JSON block the review ends with, numbered
+/// `F1`, `F2`, ..., and the review text without that block
+pub fn parse_findings(response: &str) -> (String, Vec<ReviewFinding>) {
+    let Some(start) = response.rfind("```json") else {
+        return (response.to_string(), Vec::new());
+    };
+    let body_start = start + "```json".len();
+    let Some(len) = response[body_start..].find("```") else {
+        return (response.to_string(), Vec::new());
+    };
+    let Ok(mut findings) = serde_json::from_str::<Vec<ReviewFinding>>(response[body_start..body_start + len].trim()) else {
+        return (response.to_string(), Vec::new());
+    };
+
+    for (i, finding) in findings.iter_mut().enumerate() {
+        finding.id = format!("F{}", i + 1);
+        finding.source = "nexus".to_string();
+    }
+    let review = format!("{}{}", &response[..start], &response[body_start + len + 3..]);
+    (review.trim_end().to_string(), findings)
+}
+
+fn max_id(findings: &[ReviewFinding]) -> usize {
+    findings
+        .iter()
+        .filter_map(|f| f.id.strip_prefix('F')?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_apply_grader_output() {
+        let response = "## Security Review\n\nSQL injection in query.\n\n```json\n[{\"path\": \"src/db.rs\", \"line\": 42, \"severity\": \"high\", \"title\": \"SQL built from input\"},\n {\"path\": \"src/db.rs\", \"severity\": \"low\", \"title\": \"Magic number\"}]\n```\n";
+        let (review, mut findings) = parse_findings(response);
+        assert_eq!(review, "## Security Review\n\nSQL injection in query.");
+        assert_eq!(findings.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), ["F1", "F2"]);
+
+        let output: GraderOutput = serde_json::from_str(
+            r#"{"suppress": ["F2"], "rescore": [{"id": "F1", "severity": "critical"}],
+                "add": [{"path": "src/db.rs", "line": 7, "severity": "medium", "title": "Banned API"}]}"#,
+        )
+        .unwrap();
+        let summary = apply(&mut findings, output, "house-lint");
+        assert_eq!(summary, GraderSummary { added: 1, suppressed: 1, rescored: 1 });
+        assert_eq!(findings[0].severity, Severity::Critical);
+        assert_eq!(findings[0].rescored_by.as_deref(), Some("house-lint"));
+        assert_eq!((findings[1].id.as_str(), findings[1].source.as_str()), ("F3", "house-lint"));
+
+        assert!(parse_findings("no findings block").1.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_executable_grader() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join(PROJECT_DIR).join(GRADERS_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("no-todo.sh");
+        // Suppresses every finding when the code has no TODO
+        fs::write(&script, "#!/bin/sh\nif grep -q TODO; then echo '{}'; else echo '{\"suppress\": [\"F1\"]}'; fi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(dir.join("README.md"), "not a grader").unwrap();
+
+        let graders = discover(temp_dir.path());
+        assert_eq!(graders.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), ["no-todo"]);
+
+        let (_, findings) = parse_findings("```json\n[{\"path\": \"a.rs\", \"severity\": \"low\", \"title\": \"x\"}]\n```");
+        let files = vec![("a.rs".to_string(), "fn main() {}".to_string())];
+        let output = graders[0].run("all", &findings, &files).unwrap();
+        assert_eq!(output.suppress, ["F1"]);
+    }
+}
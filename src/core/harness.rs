@@ -0,0 +1,446 @@
+//! Fuzzing harness generation
+//!
+//! Builds cargo-fuzz (libFuzzer) and atheris harnesses for a single
+//! function from its parsed signature.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::path::Path;
+
+use super::parser::{Language, Symbol};
+
+/// How a fuzz input is turned into a function argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamInput {
+    /// `&[u8]`: the raw fuzz input
+    Bytes,
+    /// `&str`: the fuzz input as UTF-8
+    Str,
+    /// Built from `arbitrary`; `borrowed` when the function takes a reference
+    Arbitrary { ty: String, borrowed: bool },
+    /// Python value drawn from `atheris.FuzzedDataProvider`
+    Provided { consume: String },
+}
+
+/// A function parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub input: ParamInput,
+}
+
+/// Target function to fuzz
+#[derive(Debug, Clone)]
+pub struct FuzzTarget {
+    pub name: String,
+    pub language: Language,
+    /// Import path: `crate::module::fn` for Rust, `package.module` for Python
+    pub module_path: String,
+    pub params: Vec<Param>,
+}
+
+impl FuzzTarget {
+    /// Build a target from a parsed function symbol
+    pub fn from_symbol(symbol: &Symbol, content: &str, file: &Path, root: &Path, crate_name: Option<&str>) -> Result<Self> {
+        let signature = full_signature(content, symbol);
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        let language = Language::from_path(file);
+
+        let (module_path, params) = match language {
+            Language::Rust => {
+                let crate_name = crate_name.unwrap_or("crate").replace('-', "_");
+                (rust_module_path(relative, &crate_name), rust_params(&signature)?)
+            }
+            Language::Python => (python_module_path(relative), python_params(&signature)),
+            other => anyhow::bail!("Fuzzing {} code is not supported (Rust and Python only)", other.name()),
+        };
+
+        Ok(Self { name: symbol.name.clone(), language, module_path, params })
+    }
+
+    /// Harness binary/script name
+    pub fn harness_name(&self) -> String {
+        format!("fuzz_{}", self.name)
+    }
+
+    /// Whether the harness needs the `arbitrary` crate
+    pub fn needs_arbitrary(&self) -> bool {
+        self.params.iter().any(|p| matches!(p.input, ParamInput::Arbitrary { .. }))
+    }
+
+    /// Render the harness source
+    pub fn render(&self) -> String {
+        match self.language {
+            Language::Python => self.render_atheris(),
+            _ => self.render_cargo_fuzz(),
+        }
+    }
+
+    fn render_cargo_fuzz(&self) -> String {
+        let call = format!("{}::{}", self.module_path, self.name);
+
+        let body = match self.params.as_slice() {
+            [] => format!("fuzz_target!(|_data: &[u8]| {{\n    let _ = {}();\n}});\n", call),
+            [Param { input: ParamInput::Bytes, .. }] => {
+                format!("fuzz_target!(|data: &[u8]| {{\n    let _ = {}(data);\n}});\n", call)
+            }
+            [Param { input: ParamInput::Str, .. }] => format!(
+                "fuzz_target!(|data: &[u8]| {{\n    if let Ok(input) = std::str::from_utf8(data) {{\n        let _ = {}(input);\n    }}\n}});\n",
+                call
+            ),
+            params => {
+                let types: Vec<String> = params.iter().map(|p| owned_type(&p.input)).collect();
+                let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+                let args: Vec<String> = params
+                    .iter()
+                    .map(|p| match &p.input {
+                        ParamInput::Bytes | ParamInput::Str => format!("&{}", p.name),
+                        ParamInput::Arbitrary { borrowed: true, .. } => format!("&{}", p.name),
+                        _ => p.name.clone(),
+                    })
+                    .collect();
+
+                format!(
+                    "fuzz_target!(|input: ({},)| {{\n    let ({},) = input;\n    let _ = {}({});\n}});\n",
+                    types.join(", "), names.join(", "), call, args.join(", ")
+                )
+            }
+        };
+
+        format!("#![no_main]\n\nuse libfuzzer_sys::fuzz_target;\n\n{}", body)
+    }
+
+    fn render_atheris(&self) -> String {
+        let args: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| match &p.input {
+                ParamInput::Provided { consume } => format!("fdp.{}", consume),
+                _ => "fdp.ConsumeUnicodeNoSurrogates(256)".to_string(),
+            })
+            .collect();
+
+        format!(
+            r#"import sys
+
+import atheris
+
+with atheris.instrument_imports():
+    from {module} import {name}
+
+
+def TestOneInput(data: bytes) -> None:
+    fdp = atheris.FuzzedDataProvider(data)
+    # Catch exceptions {name} raises by design (e.g. ValueError) here
+    {name}({args})
+
+
+def main() -> None:
+    atheris.Setup(sys.argv, TestOneInput)
+    atheris.Fuzz()
+
+
+if __name__ == "__main__":
+    main()
+"#,
+            module = self.module_path,
+            name = self.name,
+            args = args.join(", ")
+        )
+    }
+}
+
+/// Signature text from the definition line up to the closing parenthesis
+/// and return type, which may span several lines
+pub fn full_signature(content: &str, symbol: &Symbol) -> String {
+    let text: String = content
+        .lines()
+        .skip(symbol.line_start.saturating_sub(1))
+        .take(symbol.line_end + 1 - symbol.line_start)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut depth = 0i32;
+    let mut seen_paren = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => {
+                depth += 1;
+                seen_paren = true;
+            }
+            ')' => depth -= 1,
+            '{' | ';' if seen_paren && depth == 0 => return text[..i].trim().to_string(),
+            // Python's trailing colon, not Rust's `::` paths
+            ':' if seen_paren && depth == 0 && text[i + 1..].starts_with(char::is_whitespace) => {
+                return text[..i].trim().to_string();
+            }
+            _ => {}
+        }
+    }
+
+    text.lines().next().unwrap_or("").trim().to_string()
+}
+
+/// Text between the first `(` and its matching `)`
+fn parameter_list(signature: &str) -> &str {
+    let Some(start) = signature.find('(') else {
+        return "";
+    };
+
+    let mut depth = 0;
+    for (i, c) in signature[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &signature[start + 1..start + i];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// Split on commas that are not nested in brackets
+fn split_top_level(list: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+
+    for c in list.chars() {
+        match c {
+            '(' | '[' | '<' | '{' => depth += 1,
+            ')' | ']' | '>' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    parts.push(current);
+
+    parts.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()
+}
+
+fn rust_params(signature: &str) -> Result<Vec<Param>> {
+    let mut params = Vec::new();
+
+    for part in split_top_level(parameter_list(signature)) {
+        if part.ends_with("self") {
+            anyhow::bail!("Methods need a receiver and cannot be fuzzed directly; pick a free function");
+        }
+        let Some((name, ty)) = part.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().trim_start_matches("mut ").to_string();
+        let ty = ty.trim();
+
+        let input = match ty {
+            "&[u8]" => ParamInput::Bytes,
+            "&str" => ParamInput::Str,
+            _ => match ty.strip_prefix('&') {
+                Some(inner) => ParamInput::Arbitrary {
+                    ty: inner.trim_start_matches("mut ").trim().to_string(),
+                    borrowed: true,
+                },
+                None => ParamInput::Arbitrary { ty: ty.to_string(), borrowed: false },
+            },
+        };
+        params.push(Param { name, input });
+    }
+
+    Ok(params)
+}
+
+fn python_params(signature: &str) -> Vec<Param> {
+    split_top_level(parameter_list(signature))
+        .into_iter()
+        .filter(|p| p != "self" && p != "cls" && !p.starts_with('*') && p != "/")
+        .map(|part| {
+            let without_default = part.split('=').next().unwrap_or("").trim();
+            let (name, ty) = without_default.split_once(':').unwrap_or((without_default, ""));
+
+            let consume = match ty.trim() {
+                "bytes" | "bytearray" => "ConsumeBytes(256)",
+                "int" => "ConsumeInt(8)",
+                "float" => "ConsumeFloat()",
+                "bool" => "ConsumeBool()",
+                _ => "ConsumeUnicodeNoSurrogates(256)",
+            };
+
+            Param {
+                name: name.trim().to_string(),
+                input: ParamInput::Provided { consume: consume.to_string() },
+            }
+        })
+        .collect()
+}
+
+/// Owned type the `arbitrary` tuple holds for a parameter
+fn owned_type(input: &ParamInput) -> String {
+    match input {
+        ParamInput::Bytes => "Vec<u8>".to_string(),
+        ParamInput::Str => "String".to_string(),
+        ParamInput::Arbitrary { ty, .. } if ty == "str" => "String".to_string(),
+        ParamInput::Arbitrary { ty, .. } if ty == "[u8]" => "Vec<u8>".to_string(),
+        ParamInput::Arbitrary { ty, .. } => ty.clone(),
+        ParamInput::Provided { .. } => "Vec<u8>".to_string(),
+    }
+}
+
+/// `src/parser/mod.rs` -> `my_crate::parser`
+fn rust_module_path(relative: &Path, crate_name: &str) -> String {
+    let mut parts: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    if parts.first().map(String::as_str) == Some("src") {
+        parts.remove(0);
+    }
+    if matches!(parts.last().map(String::as_str), Some("mod" | "lib" | "main")) {
+        parts.pop();
+    }
+
+    std::iter::once(crate_name.to_string()).chain(parts).collect::<Vec<_>>().join("::")
+}
+
+/// `src/pkg/parser.py` -> `pkg.parser`
+fn python_module_path(relative: &Path) -> String {
+    let mut parts: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    if parts.first().map(String::as_str) == Some("src") {
+        parts.remove(0);
+    }
+    if parts.last().map(String::as_str) == Some("__init__") {
+        parts.pop();
+    }
+
+    parts.join(".")
+}
+
+/// `fuzz/Cargo.toml` for a new cargo-fuzz workspace
+pub fn fuzz_manifest(crate_name: &str, with_arbitrary: bool) -> String {
+    let arbitrary = if with_arbitrary {
+        "arbitrary = { version = \"1\", features = [\"derive\"] }\n"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"[package]
+name = "{name}-fuzz"
+version = "0.0.0"
+publish = false
+edition = "2021"
+
+[package.metadata]
+cargo-fuzz = true
+
+[dependencies]
+libfuzzer-sys = "0.4"
+{arbitrary}
+[dependencies.{name}]
+path = ".."
+
+# Keep the fuzz crate out of the parent workspace
+[workspace]
+members = ["."]
+"#,
+        name = crate_name,
+        arbitrary = arbitrary
+    )
+}
+
+/// `[[bin]]` entry registering a fuzz target
+pub fn fuzz_bin_entry(harness: &str) -> String {
+    format!(
+        "\n[[bin]]\nname = \"{0}\"\npath = \"fuzz_targets/{0}.rs\"\ntest = false\ndoc = false\nbench = false\n",
+        harness
+    )
+}
+
+/// `requirements.txt` with atheris appended, or `None` when it is already
+/// listed
+pub fn requirements_with_atheris(existing: &str) -> Option<String> {
+    if existing.lines().any(|l| l.trim().starts_with("atheris")) {
+        return None;
+    }
+    let separator = if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+    Some(format!("{}{}atheris\n", existing, separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::SymbolKind;
+
+    fn symbol(name: &str, line_start: usize, line_end: usize) -> Symbol {
+        Symbol { name: name.to_string(), kind: SymbolKind::Function, line_start, line_end, signature: None }
+    }
+
+    #[test]
+    fn test_rust_str_target() {
+        let content = "pub fn parse_header(\n    input: &str,\n) -> Option<Header> {\n    None\n}\n";
+        let target = FuzzTarget::from_symbol(
+            &symbol("parse_header", 1, 5), content,
+            Path::new("/p/src/http/mod.rs"), Path::new("/p"), Some("my-crate"),
+        ).unwrap();
+
+        assert_eq!(target.module_path, "my_crate::http");
+        assert_eq!(target.params, vec![Param { name: "input".to_string(), input: ParamInput::Str }]);
+        assert!(target.render().contains("std::str::from_utf8(data)"));
+        assert!(target.render().contains("my_crate::http::parse_header(input)"));
+        assert!(!target.needs_arbitrary());
+    }
+
+    #[test]
+    fn test_rust_arbitrary_target() {
+        let content = "fn decode(buf: &[u8], opts: &Options, limit: usize) -> std::io::Result<Frame> {}";
+        let target = FuzzTarget::from_symbol(
+            &symbol("decode", 1, 1), content,
+            Path::new("src/lib.rs"), Path::new(""), Some("codec"),
+        ).unwrap();
+
+        assert_eq!(full_signature(content, &symbol("decode", 1, 1)), "fn decode(buf: &[u8], opts: &Options, limit: usize) -> std::io::Result<Frame>");
+        assert!(target.needs_arbitrary());
+        let harness = target.render();
+        assert!(harness.contains("|input: (Vec<u8>, Options, usize,)|"));
+        assert!(harness.contains("codec::decode(&buf, &opts, limit)"));
+
+        let method = "fn len(&self) -> usize { 0 }";
+        assert!(FuzzTarget::from_symbol(&symbol("len", 1, 1), method, Path::new("src/lib.rs"), Path::new(""), None).is_err());
+    }
+
+    #[test]
+    fn test_python_target() {
+        let content = "def load(data: bytes, strict: bool = False, *args) -> dict:\n    return {}\n";
+        let target = FuzzTarget::from_symbol(
+            &symbol("load", 1, 2), content,
+            Path::new("src/pkg/codec/__init__.py"), Path::new(""), None,
+        ).unwrap();
+
+        assert_eq!(target.module_path, "pkg.codec");
+        let harness = target.render();
+        assert!(harness.contains("from pkg.codec import load"));
+        assert!(harness.contains("load(fdp.ConsumeBytes(256), fdp.ConsumeBool())"));
+    }
+
+    #[test]
+    fn test_requirements_with_atheris() {
+        assert_eq!(requirements_with_atheris("").unwrap(), "atheris\n");
+        assert_eq!(requirements_with_atheris("requests==2.31\n").unwrap(), "requests==2.31\natheris\n");
+        assert_eq!(requirements_with_atheris("requests==2.31").unwrap(), "requests==2.31\natheris\n");
+        assert!(requirements_with_atheris("atheris>=2.3\n").is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! Lifecycle hooks around AI operations
+//!
+//! Scripts from `[hooks]` in `.nexus.toml`, run with one JSON document on
+//! stdin, for redaction, notification or policy checks:
+//!
+//! | Hook            | Payload                                   | Reply may set         |
+//! |-----------------|-------------------------------------------|-----------------------|
+//! | `pre_prompt`    | `provider`, `system`, `messages`          | `system`, `messages`  |
+//! | `post_response` | `provider`, `system`, `messages`, `response` | `response`         |
+//! | `pre_apply`     | `path`, `content`                         | `content`             |
+//! | `post_apply`    | `path`, `content`                         |                       |
+//!
+//! Every payload also has `version`, `event` and `command`. A hook may print
+//! nothing to leave the payload as it is. A non-zero exit refuses the
+//! request, response or write, with the hook's stderr as the reason, except
+//! for `post_apply`, whose failures are only reported. Hooks of one event run
+//! in order; each sees the payload as the previous one left it.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::permissions::{self, Permission};
+use super::{activity, process};
+use crate::config::{HooksConfig, ProjectConfig};
+
+/// Payload version sent to hooks
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Hooks that apply to every AI operation of this process
+static ACTIVE: OnceLock<Hooks> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PrePrompt,
+    PostResponse,
+    PreApply,
+    PostApply,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PrePrompt => "pre_prompt",
+            HookEvent::PostResponse => "post_response",
+            HookEvent::PreApply => "pre_apply",
+            HookEvent::PostApply => "post_apply",
+        }
+    }
+}
+
+/// A message of a prompt, as hooks see it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// `user`, `assistant` or `system`
+    pub role: String,
+    pub content: String,
+}
+
+/// What is about to be sent to a provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Prompt {
+    pub system: Option<String>,
+    pub messages: Vec<PromptMessage>,
+}
+
+/// The hooks of a project
+#[derive(Debug, Clone)]
+pub struct Hooks {
+    config: HooksConfig,
+    root: PathBuf,
+}
+
+impl Hooks {
+    pub fn new(config: HooksConfig, root: &Path) -> Self {
+        Self { config, root: root.to_path_buf() }
+    }
+
+    /// Run `root`'s hooks around every AI operation of this process
+    pub fn install(root: &Path) {
+        match ProjectConfig::load(root) {
+            Ok(project) => {
+                ACTIVE.set(Self::new(project.hooks, root)).ok();
+            }
+            Err(e) => tracing::warn!("Hooks not loaded: {:#}", e),
+        }
+    }
+
+    fn commands(&self, event: HookEvent) -> &[String] {
+        match event {
+            HookEvent::PrePrompt => &self.config.pre_prompt,
+            HookEvent::PostResponse => &self.config.post_response,
+            HookEvent::PreApply => &self.config.pre_apply,
+            HookEvent::PostApply => &self.config.post_apply,
+        }
+    }
+
+    /// Pass `prompt` through the `pre_prompt` hooks
+    pub fn pre_prompt(&self, provider: &str, prompt: Prompt) -> Result<Prompt> {
+        if self.config.pre_prompt.is_empty() {
+            return Ok(prompt);
+        }
+        let mut payload = to_object(&prompt)?;
+        payload.insert("provider".to_string(), provider.into());
+        let payload = self.run(HookEvent::PrePrompt, payload)?;
+        serde_json::from_value(Value::Object(payload)).context("A pre_prompt hook replied with an invalid prompt")
+    }
+
+    /// Pass the response to `prompt` through the `post_response` hooks
+    pub fn post_response(&self, provider: &str, prompt: &Prompt, response: String) -> Result<String> {
+        if self.config.post_response.is_empty() {
+            return Ok(response);
+        }
+        let mut payload = to_object(prompt)?;
+        payload.insert("provider".to_string(), provider.into());
+        payload.insert("response".to_string(), response.into());
+        let payload = self.run(HookEvent::PostResponse, payload)?;
+        string_field(&payload, "response", HookEvent::PostResponse)
+    }
+
+    /// Pass content about to be written to `path` through the `pre_apply` hooks
+    pub fn pre_apply(&self, path: &str, content: String) -> Result<String> {
+        if self.config.pre_apply.is_empty() {
+            return Ok(content);
+        }
+        let payload = self.run(HookEvent::PreApply, file_payload(path, &content))?;
+        string_field(&payload, "content", HookEvent::PreApply)
+    }
+
+    /// Tell the `post_apply` hooks that `path` was written
+    pub fn post_apply(&self, path: &str, content: &str) {
+        if self.config.post_apply.is_empty() {
+            return;
+        }
+        if let Err(e) = self.run(HookEvent::PostApply, file_payload(path, content)) {
+            tracing::warn!("{:#}", e);
+        }
+    }
+
+    /// Run the hooks of `event` in order, each getting the payload the
+    /// previous one replied with
+    fn run(&self, event: HookEvent, mut payload: Map<String, Value>) -> Result<Map<String, Value>> {
+        payload.insert("version".to_string(), PROTOCOL_VERSION.into());
+        payload.insert("event".to_string(), event.name().into());
+        payload.insert("command".to_string(), activity::running_command().into());
+
+        for command in self.commands(event) {
+            let reply = self.run_one(event, command, &payload)?;
+            if let Some(reply) = reply {
+                payload.extend(reply);
+            }
+        }
+        Ok(payload)
+    }
+
+    /// Run one hook; `None` when it printed nothing
+    fn run_one(&self, event: HookEvent, command: &str, payload: &Map<String, Value>) -> Result<Option<Map<String, Value>>> {
+        permissions::request(Permission::Run, &format!("run the {} hook `{}`", event.name(), command))?;
+        let input = serde_json::to_vec(payload)?;
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let output = process::run_with_timeout(
+            shell(command).current_dir(&self.root).env("NEXUS_HOOK", event.name()),
+            input,
+            timeout,
+        )
+        .with_context(|| format!("Failed to run {} hook `{}`", event.name(), command))?;
+        let Some(output) = output else {
+            bail!("{} hook `{}` timed out after {}s", event.name(), command, timeout.as_secs());
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() {
+            let reason = stderr.trim();
+            let reason = if reason.is_empty() { "non-zero exit" } else { reason };
+            bail!("Refused by {} hook `{}`: {}", event.name(), command, reason);
+        }
+        if stdout.trim().is_empty() {
+            return Ok(None);
+        }
+        match serde_json::from_str(&stdout) {
+            Ok(Value::Object(reply)) => Ok(Some(reply)),
+            _ => bail!("{} hook `{}` replied with something other than a JSON object", event.name(), command),
+        }
+    }
+}
+
+/// `pre_prompt` hooks of this process, when installed
+pub fn pre_prompt(provider: &str, prompt: Prompt) -> Result<Prompt> {
+    match ACTIVE.get() {
+        Some(hooks) => hooks.pre_prompt(provider, prompt),
+        None => Ok(prompt),
+    }
+}
+
+/// `post_response` hooks of this process, when installed
+pub fn post_response(provider: &str, prompt: &Prompt, response: String) -> Result<String> {
+    match ACTIVE.get() {
+        Some(hooks) => hooks.post_response(provider, prompt, response),
+        None => Ok(response),
+    }
+}
+
+/// `pre_apply` hooks of this process, when installed
+pub fn pre_apply(path: &str, content: String) -> Result<String> {
+    match ACTIVE.get() {
+        Some(hooks) => hooks.pre_apply(path, content),
+        None => Ok(content),
+    }
+}
+
+/// `post_apply` hooks of this process, when installed
+pub fn post_apply(path: &str, content: &str) {
+    if let Some(hooks) = ACTIVE.get() {
+        hooks.post_apply(path, content);
+    }
+}
+
+fn to_object(value: &impl Serialize) -> Result<Map<String, Value>> {
+    match serde_json::to_value(value)? {
+        Value::Object(map) => Ok(map),
+        _ => bail!("Hook payload is not an object"),
+    }
+}
+
+fn file_payload(path: &str, content: &str) -> Map<String, Value> {
+    let mut payload = Map::new();
+    payload.insert("path".to_string(), path.into());
+    payload.insert("content".to_string(), content.into());
+    payload
+}
+
+fn string_field(payload: &Map<String, Value>, field: &str, event: HookEvent) -> Result<String> {
+    match payload.get(field) {
+        Some(Value::String(value)) => Ok(value.clone()),
+        _ => bail!("A {} hook replied with a non-string `{}`", event.name(), field),
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hooks_rewrite_and_refuse() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let config = HooksConfig {
+            // Redaction by rewriting the payload, then a second hook that sees the result
+            pre_prompt: vec![
+                "sed 's/hunter2/[REDACTED]/g'".to_string(),
+                "grep -q REDACTED && cat > /dev/null || { echo 'redaction missing' >&2; exit 1; }".to_string(),
+            ],
+            post_response: vec!["grep -q '\"provider\":\"claude\"' && echo '{\"response\": \"checked\"}'".to_string()],
+            pre_apply: vec!["if grep -q '\"path\":\"secrets/'; then echo 'secrets/ is off limits' >&2; exit 3; fi".to_string()],
+            post_apply: vec!["cat > applied.json".to_string()],
+            timeout_secs: 5,
+        };
+        let hooks = Hooks::new(config, root);
+
+        let prompt = Prompt {
+            system: Some("You review code".to_string()),
+            messages: vec![PromptMessage { role: "user".to_string(), content: "password is hunter2".to_string() }],
+        };
+        let sent = hooks.pre_prompt("claude", prompt).unwrap();
+        assert_eq!(sent.messages[0].content, "password is [REDACTED]");
+        assert_eq!(sent.system.as_deref(), Some("You review code"));
+
+        assert_eq!(hooks.post_response("claude", &sent, "answer".to_string()).unwrap(), "checked");
+
+        assert_eq!(hooks.pre_apply("src/lib.rs", "fn a() {}".to_string()).unwrap(), "fn a() {}");
+        let refused = hooks.pre_apply("secrets/key.rs", String::new()).unwrap_err().to_string();
+        assert!(refused.contains("secrets/ is off limits"), "{}", refused);
+
+        hooks.post_apply("src/lib.rs", "fn a() {}");
+        let applied: Value = serde_json::from_str(&std::fs::read_to_string(root.join("applied.json")).unwrap()).unwrap();
+        assert_eq!(applied["event"], "post_apply");
+        assert_eq!(applied["path"], "src/lib.rs");
+        assert_eq!(applied["version"], PROTOCOL_VERSION);
+
+        // Nothing configured leaves everything as it is
+        let none = Hooks::new(HooksConfig::default(), root);
+        assert_eq!(none.pre_apply("a", "b".to_string()).unwrap(), "b");
+    }
+}
@@ -0,0 +1,633 @@
+//! Import organizer for written files
+//!
+//! Models often forget an import or repeat one. After a file is written,
+//! this removes duplicate and unused imports, adds well-known standard
+//! library imports the code uses without declaring, and sorts each group of
+//! imports. Projects set up for isort, and Go files when goimports is
+//! installed, are then handed to those tools. rust-analyzer only offers its
+//! import assists over LSP, so Rust files rely on the built-in pass.
+//!
+//! Removal is conservative: Rust imports of capitalized names may be traits
+//! used only through their methods, side-effect imports in JavaScript run
+//! code, and `__init__.py` imports are re-exports, so all of these stay.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::parser::{CodeParser, Language};
+use super::permissions::{self, Permission};
+use crate::config::ImportsConfig;
+
+/// Standard library names added when used without an import: name, import,
+/// and whether the name is a module (used as `name::` or `name.`)
+const RUST_KNOWN: &[(&str, &str, bool)] = &[
+    ("HashMap", "use std::collections::HashMap;", false),
+    ("HashSet", "use std::collections::HashSet;", false),
+    ("BTreeMap", "use std::collections::BTreeMap;", false),
+    ("BTreeSet", "use std::collections::BTreeSet;", false),
+    ("VecDeque", "use std::collections::VecDeque;", false),
+    ("Arc", "use std::sync::Arc;", false),
+    ("Mutex", "use std::sync::Mutex;", false),
+    ("RwLock", "use std::sync::RwLock;", false),
+    ("Rc", "use std::rc::Rc;", false),
+    ("RefCell", "use std::cell::RefCell;", false),
+    ("Path", "use std::path::Path;", false),
+    ("PathBuf", "use std::path::PathBuf;", false),
+    ("Duration", "use std::time::Duration;", false),
+    ("Instant", "use std::time::Instant;", false),
+    ("fs", "use std::fs;", true),
+    ("io", "use std::io;", true),
+    ("fmt", "use std::fmt;", true),
+    ("env", "use std::env;", true),
+];
+
+const PYTHON_KNOWN: &[(&str, &str, bool)] = &[
+    ("os", "import os", true),
+    ("sys", "import sys", true),
+    ("re", "import re", true),
+    ("json", "import json", true),
+    ("math", "import math", true),
+    ("time", "import time", true),
+    ("logging", "import logging", true),
+    ("subprocess", "import subprocess", true),
+    ("Path", "from pathlib import Path", false),
+    ("dataclass", "from dataclasses import dataclass", false),
+    ("Any", "from typing import Any", false),
+    ("Optional", "from typing import Optional", false),
+    ("List", "from typing import List", false),
+    ("Dict", "from typing import Dict", false),
+    ("Tuple", "from typing import Tuple", false),
+    ("Union", "from typing import Union", false),
+];
+
+/// What organizing a file's imports changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportChanges {
+    /// Imports added for names the code used without importing
+    pub added: Vec<String>,
+    /// Imports removed because nothing used them
+    pub removed: Vec<String>,
+    /// Repeated imports removed
+    pub duplicates: usize,
+    /// Whether a group of imports was reordered
+    pub sorted: bool,
+    /// External tool run afterwards, if any
+    pub tool: Option<String>,
+}
+
+impl ImportChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.duplicates == 0 && !self.sorted && self.tool.is_none()
+    }
+}
+
+/// A top-level import statement, possibly spanning several lines
+#[derive(Debug, Clone)]
+struct Statement {
+    first: usize,
+    last: usize,
+    text: String,
+    names: Vec<String>,
+    /// Never removed as unused
+    keep: bool,
+}
+
+/// Organize the imports of `code`. `defined` holds the names the file
+/// itself defines, which never need an import.
+pub fn organize(code: &str, language: Language, defined: &HashSet<String>, keep_unused: bool) -> (String, ImportChanges) {
+    let lines: Vec<&str> = code.lines().collect();
+    let statements = find_statements(&lines, language);
+    let mut changes = ImportChanges::default();
+
+    let in_statement: HashSet<usize> = statements.iter().flat_map(|s| s.first..=s.last).collect();
+    let body: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !in_statement.contains(i))
+        .map(|(_, l)| strip_strings(l, language))
+        .collect();
+    let body = strip_comments(&body, language).join("\n");
+
+    // Duplicates and unused imports
+    let mut seen = HashSet::new();
+    let mut kept: Vec<Statement> = Vec::new();
+    for statement in &statements {
+        if !seen.insert(normalize(&statement.text)) {
+            changes.duplicates += 1;
+            continue;
+        }
+        if keep_unused || statement.keep || statement.names.is_empty() {
+            kept.push(statement.clone());
+            continue;
+        }
+        let removable = |name: &String| language != Language::Rust || name.starts_with(|c: char| c.is_lowercase());
+        let used: Vec<&String> = statement.names.iter().filter(|n| !removable(n) || uses(&body, n)).collect();
+        if used.is_empty() {
+            changes.removed.push(normalize(&statement.text));
+        } else if used.len() < statement.names.len() && statement.first == statement.last {
+            match narrow(&statement.text, &used, language) {
+                Some(text) => {
+                    let unused: Vec<&str> = statement.names.iter().filter(|n| !used.contains(n)).map(String::as_str).collect();
+                    changes.removed.push(format!("{} ({})", normalize(&statement.text), unused.join(", ")));
+                    kept.push(Statement { text, ..statement.clone() });
+                }
+                None => kept.push(statement.clone()),
+            }
+        } else {
+            kept.push(statement.clone());
+        }
+    }
+
+    // Known names used without an import. Imports inside modules and
+    // functions count too, since the names they bring in are in scope there.
+    let trimmed: Vec<&str> = lines.iter().map(|l| l.trim_start()).collect();
+    let nested = find_statements(&trimmed, language);
+    let imported: HashSet<&str> = nested.iter().flat_map(|s| s.names.iter().map(String::as_str)).collect();
+    let known = match language {
+        Language::Rust => RUST_KNOWN,
+        Language::Python => PYTHON_KNOWN,
+        _ => &[],
+    };
+    for (name, import, module) in known {
+        if imported.contains(name) || defined.contains(*name) || changes.added.iter().any(|a| a == import) {
+            continue;
+        }
+        let used = if *module {
+            let separator = if language == Language::Rust { "::" } else { "." };
+            uses_qualified(&body, name, separator)
+        } else {
+            uses_unqualified(&body, name)
+        };
+        if used {
+            changes.added.push(import.to_string());
+        }
+    }
+
+    // Rebuild: each group of adjacent statements is replaced in place
+    let mut out: Vec<String> = Vec::new();
+    let mut additions = changes.added.clone();
+    let mut i = 0;
+    let header_end = header_end(&lines, language);
+    while i < lines.len() {
+        if statements.is_empty() && i == header_end && !additions.is_empty() {
+            out.append(&mut additions);
+            if !lines[i].trim().is_empty() {
+                out.push(String::new());
+            }
+        }
+        let Some(group_start) = statements.iter().position(|s| s.first == i) else {
+            out.push(lines[i].to_string());
+            i += 1;
+            continue;
+        };
+        let mut group_end = group_start;
+        while group_end + 1 < statements.len() && statements[group_end + 1].first == statements[group_end].last + 1 {
+            group_end += 1;
+        }
+        let members: Vec<usize> = (group_start..=group_end).collect();
+        let group: Vec<&Statement> = kept
+            .iter()
+            .filter(|k| members.iter().any(|&m| statements[m].first == k.first))
+            .collect();
+        // An attribute before the group belongs to its first import, and the
+        // order of side-effect imports in JavaScript matters
+        let attached = i > 0 && lines[i - 1].trim_start().starts_with("#[");
+        let side_effects = matches!(language, Language::JavaScript | Language::TypeScript)
+            && group.iter().any(|s| s.names.is_empty());
+        let sortable = !attached && !side_effects;
+
+        let mut texts: Vec<String> = group.iter().map(|s| s.text.clone()).collect();
+        if sortable {
+            changes.sorted |= texts.windows(2).any(|w| sort_key(&w[0], language) > sort_key(&w[1], language));
+        }
+        if group_start == 0 {
+            texts.append(&mut additions);
+        }
+        if sortable {
+            texts.sort_by_key(|t| sort_key(t, language));
+        }
+        out.extend(texts);
+        i = statements[group_end].last + 1;
+    }
+    if !additions.is_empty() {
+        out.append(&mut additions);
+    }
+
+    let mut organized = out.join("\n");
+    if code.ends_with('\n') {
+        organized.push('\n');
+    }
+    (organized, changes)
+}
+
+/// Organize the imports of a file just written, then run the project's
+/// import tool on it. `None` when there is nothing to do for the file.
+pub fn organize_file(root: &Path, path: &Path, config: &ImportsConfig) -> Result<Option<ImportChanges>> {
+    if !config.on_write {
+        return Ok(None);
+    }
+    let full = if path.is_absolute() { path.to_path_buf() } else { root.join(path) };
+    let file = path.to_string_lossy().to_string();
+
+    if path.extension().is_some_and(|e| e == "go") {
+        let tool = (config.tools && run_tool(root, "goimports", &["-w", &file])).then(|| "goimports".to_string());
+        return Ok(tool.map(|tool| ImportChanges { tool: Some(tool), ..Default::default() }));
+    }
+
+    let language = Language::from_path(path);
+    if language == Language::Unknown {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&full).with_context(|| format!("Failed to read {}", full.display()))?;
+    let defined: HashSet<String> = CodeParser::new()
+        .and_then(|mut parser| parser.parse_source(path, content.clone()))
+        .map(|parsed| parsed.symbols.into_iter().map(|s| s.name).collect())
+        .unwrap_or_default();
+    let keep_unused = path.file_name().is_some_and(|n| n == "__init__.py");
+
+    let (organized, mut changes) = organize(&content, language, &defined, keep_unused);
+    if organized != content {
+        fs::write(&full, &organized).with_context(|| format!("Failed to write {}", full.display()))?;
+    }
+
+    if config.tools && language == Language::Python && uses_isort(root) && run_tool(root, "isort", &["-q", &file]) {
+        changes.tool = Some("isort".to_string());
+    }
+    Ok(Some(changes))
+}
+
+/// Whether the tool ran and succeeded; a missing tool is not an error
+fn run_tool(root: &Path, program: &str, args: &[&str]) -> bool {
+    if permissions::request(Permission::Run, &format!("run `{} {}`", program, args.join(" "))).is_err() {
+        return false;
+    }
+    Command::new(program)
+        .args(args)
+        .current_dir(root)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+fn uses_isort(root: &Path) -> bool {
+    let configured = |file: &str, section: &str| {
+        fs::read_to_string(root.join(file)).is_ok_and(|content| content.contains(section))
+    };
+    root.join(".isort.cfg").exists() || configured("pyproject.toml", "[tool.isort]") || configured("setup.cfg", "[isort]")
+}
+
+fn find_statements(lines: &[&str], language: Language) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if !is_import_start(line, language) {
+            i += 1;
+            continue;
+        }
+        let mut last = i;
+        while last + 1 < lines.len() && !is_import_end(&lines[i..=last], language) {
+            last += 1;
+        }
+        let text = lines[i..=last].join("\n");
+        let (names, keep) = names(&text, language);
+        statements.push(Statement { first: i, last, text, names, keep });
+        i = last + 1;
+    }
+    statements
+}
+
+/// Top-level import lines only; imports inside functions are left alone
+fn is_import_start(line: &str, language: Language) -> bool {
+    match language {
+        Language::Rust => {
+            let rest = line.strip_prefix("pub(crate) ").or_else(|| line.strip_prefix("pub ")).unwrap_or(line);
+            rest.starts_with("use ")
+        }
+        Language::Python => line.starts_with("import ") || (line.starts_with("from ") && line.contains(" import")),
+        Language::JavaScript | Language::TypeScript => line.starts_with("import ") && !line.starts_with("import("),
+        Language::Unknown => false,
+    }
+}
+
+fn is_import_end(lines: &[&str], language: Language) -> bool {
+    let text = lines.join("\n");
+    let last = lines.last().map(|l| l.trim_end()).unwrap_or_default();
+    match language {
+        Language::Rust => last.ends_with(';'),
+        Language::Python => {
+            let open = text.matches('(').count() > text.matches(')').count();
+            !open && !last.ends_with('\\')
+        }
+        _ => {
+            let side_effect = lines.len() == 1 && (last.starts_with("import '") || last.starts_with("import \""));
+            side_effect || last.contains("from '") || last.contains("from \"") || last.contains("from `")
+        }
+    }
+}
+
+/// Names a statement brings into scope, and whether it must be kept anyway
+fn names(text: &str, language: Language) -> (Vec<String>, bool) {
+    let flat = normalize(text);
+    let split = |list: &str| -> Vec<String> {
+        list.split(',')
+            .map(|item| item.trim().trim_matches(|c| c == '(' || c == ')' || c == '{' || c == '}').trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| item.rsplit(" as ").next().unwrap_or(item).trim().to_string())
+            .collect()
+    };
+
+    match language {
+        Language::Rust => {
+            let keep = flat.starts_with("pub") || flat.contains('*');
+            let path = flat.trim_start_matches("pub(crate) ").trim_start_matches("pub ").trim_start_matches("use ").trim_end_matches(';');
+            let names = match path.split_once('{') {
+                Some((prefix, list)) => split(&list.replace(['{', '}'], ","))
+                    .into_iter()
+                    .map(|name| {
+                        let last = name.rsplit("::").next().unwrap_or(&name).to_string();
+                        if last == "self" {
+                            prefix.trim_end_matches("::").rsplit("::").next().unwrap_or_default().to_string()
+                        } else {
+                            last
+                        }
+                    })
+                    .collect(),
+                None => split(path).into_iter().map(|p| p.rsplit("::").next().unwrap_or(&p).to_string()).collect(),
+            };
+            (names, keep)
+        }
+        Language::Python => {
+            let keep = flat.contains('*') || flat.starts_with("from __future__");
+            let names = match flat.split_once(" import ") {
+                Some((_, list)) if flat.starts_with("from ") => split(list),
+                _ => split(flat.trim_start_matches("import "))
+                    .into_iter()
+                    .map(|module| module.split('.').next().unwrap_or_default().to_string())
+                    .collect(),
+            };
+            (names, keep)
+        }
+        _ => {
+            let Some(clause) = flat.strip_prefix("import ").and_then(|rest| rest.rsplit_once(" from ")).map(|(c, _)| c) else {
+                return (Vec::new(), true);
+            };
+            let clause = clause.trim_start_matches("type ");
+            let names: Vec<String> = split(&clause.replace(['{', '}'], ","))
+                .into_iter()
+                .map(|n| n.trim_start_matches("type ").trim_start_matches("* as ").to_string())
+                .collect();
+            // Older JSX transforms need React in scope without naming it
+            let keep = names.iter().any(|n| n == "React");
+            (names, keep)
+        }
+    }
+}
+
+/// The statement importing only `used`, for flat import lists
+fn narrow(text: &str, used: &[&String], language: Language) -> Option<String> {
+    let items = |list: &str| -> Vec<String> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|item| used.iter().any(|u| item.rsplit(" as ").next().is_some_and(|n| n.trim() == u.as_str())))
+            .map(str::to_string)
+            .collect()
+    };
+    match language {
+        Language::Python => {
+            let (head, list) = text.split_once(" import ")?;
+            if list.contains('(') || !head.starts_with("from ") {
+                return None;
+            }
+            Some(format!("{} import {}", head, items(list).join(", ")))
+        }
+        Language::JavaScript | Language::TypeScript => {
+            let open = text.find('{')?;
+            let close = text.find('}')?;
+            let default = text["import ".len()..open].trim().trim_end_matches(',').trim();
+            if !default.is_empty() {
+                return None;
+            }
+            Some(format!("{}{{ {} }}{}", &text[..open], items(&text[open + 1..close]).join(", "), &text[close + 1..]))
+        }
+        // Rust brace groups nest; leave them to the compiler's warnings
+        _ => None,
+    }
+}
+
+/// Lines before the first item: comments, attributes, shebangs, docstrings
+fn header_end(lines: &[&str], language: Language) -> usize {
+    let mut i = 0;
+    let mut in_docstring = false;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        let header = match language {
+            Language::Rust => line.is_empty() || line.starts_with("//") || line.starts_with("#!["),
+            Language::Python => {
+                if in_docstring {
+                    in_docstring = !line.contains("\"\"\"");
+                    true
+                } else if let Some(rest) = line.strip_prefix("\"\"\"") {
+                    in_docstring = !rest.contains("\"\"\"");
+                    true
+                } else {
+                    line.is_empty() || line.starts_with('#')
+                }
+            }
+            _ => line.is_empty() || line.starts_with("//") || line.starts_with("\"use ") || line.starts_with("'use "),
+        };
+        if !header {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn sort_key(text: &str, language: Language) -> (u8, String) {
+    let flat = normalize(text);
+    let rank = match language {
+        // isort puts `import x` before `from x import y` within a section
+        Language::Python => u8::from(flat.starts_with("from ")),
+        // Relative paths lead, as rustfmt orders them
+        Language::Rust => {
+            let path = flat.trim_start_matches("pub(crate) ").trim_start_matches("pub ").trim_start_matches("use ");
+            u8::from(!path.starts_with("self::") && !path.starts_with("super::"))
+        }
+        _ => 0,
+    };
+    // rustfmt puts `a::{b, c}` before `a::d::e`
+    let flat = if language == Language::Rust { flat.replace('{', " ") } else { flat };
+    (rank, flat.to_lowercase())
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The lines with comments removed, following `/* ... */` blocks across
+/// lines; string contents must already be blanked
+fn strip_comments(lines: &[String], language: Language) -> Vec<String> {
+    if language == Language::Python {
+        return lines.iter().filter(|l| !l.trim_start().starts_with('#')).cloned().collect();
+    }
+
+    let mut out = Vec::with_capacity(lines.len());
+    let mut in_block = false;
+    for line in lines {
+        let mut code = String::new();
+        let mut rest = line.as_str();
+        loop {
+            if in_block {
+                match rest.find("*/") {
+                    Some(end) => {
+                        rest = &rest[end + 2..];
+                        in_block = false;
+                    }
+                    None => break,
+                }
+                continue;
+            }
+            match (rest.find("//"), rest.find("/*")) {
+                (Some(line_comment), block) if block.is_none_or(|b| line_comment < b) => {
+                    code.push_str(&rest[..line_comment]);
+                    break;
+                }
+                (_, Some(block)) => {
+                    code.push_str(&rest[..block]);
+                    code.push(' ');
+                    rest = &rest[block + 2..];
+                    in_block = true;
+                }
+                _ => {
+                    code.push_str(rest);
+                    break;
+                }
+            }
+        }
+        out.push(code);
+    }
+    out
+}
+
+/// The line with the contents of string literals blanked out, so names
+/// mentioned in strings do not count as uses
+fn strip_strings(line: &str, language: Language) -> String {
+    let quotes: &[char] = match language {
+        // Single quotes in Rust are chars and lifetimes
+        Language::Rust => &['"'],
+        _ => &['"', '\'', '`'],
+    };
+    let mut out = String::with_capacity(line.len());
+    let mut open: Option<char> = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match open {
+            Some(quote) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == quote {
+                    open = None;
+                    out.push(c);
+                    continue;
+                }
+                out.push(' ');
+            }
+            None => {
+                if quotes.contains(&c) {
+                    open = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Positions where `name` occurs in `body` as a whole word
+fn occurrences<'a>(body: &'a str, name: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    body.match_indices(name).filter_map(move |(pos, _)| {
+        let before = &body[..pos];
+        let after = &body[pos + name.len()..];
+        let bounded = !before.chars().next_back().is_some_and(is_word) && !after.chars().next().is_some_and(is_word);
+        bounded.then_some((before, after))
+    })
+}
+
+fn uses(body: &str, name: &str) -> bool {
+    occurrences(body, name).next().is_some()
+}
+
+/// Used on its own, not as the tail of a path (`std::fmt::Display`) or field
+fn uses_unqualified(body: &str, name: &str) -> bool {
+    occurrences(body, name).any(|(before, _)| !before.ends_with("::") && !before.ends_with('.'))
+}
+
+/// Used as the head of a path, `fs::read` or `os.path`
+fn uses_qualified(body: &str, name: &str, separator: &str) -> bool {
+    occurrences(body, name)
+        .any(|(before, after)| after.starts_with(separator) && !before.ends_with("::") && !before.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_organize_imports() {
+        let defined = HashSet::new();
+
+        let rust = "//! Demo\n\nuse std::io::Write;\nuse anyhow::Result;\nuse std::fs;\nuse anyhow::Result;\nuse crate::util::helper;\n\nfn save(out: &mut impl Write) -> Result<()> {\n    let mut seen = HashMap::new();\n    fs::write(\"a\", \"b\")?;\n    Ok(())\n}\n";
+        let (organized, changes) = organize(rust, Language::Rust, &defined, false);
+        assert_eq!(
+            organized,
+            "//! Demo\n\nuse anyhow::Result;\nuse std::collections::HashMap;\nuse std::fs;\nuse std::io::Write;\n\nfn save(out: &mut impl Write) -> Result<()> {\n    let mut seen = HashMap::new();\n    fs::write(\"a\", \"b\")?;\n    Ok(())\n}\n"
+        );
+        assert_eq!(changes.duplicates, 1);
+        assert_eq!(changes.removed, ["use crate::util::helper;"]);
+        assert_eq!(changes.added, ["use std::collections::HashMap;"]);
+
+        let python = "\"\"\"Tools.\"\"\"\n\nfrom typing import List, Dict\nimport sys\n\n\ndef run(args: List[str]) -> None:\n    print(os.getcwd(), args)\n";
+        let (organized, changes) = organize(python, Language::Python, &defined, false);
+        assert_eq!(
+            organized,
+            "\"\"\"Tools.\"\"\"\n\nimport os\nfrom typing import List\n\n\ndef run(args: List[str]) -> None:\n    print(os.getcwd(), args)\n"
+        );
+        assert_eq!(changes.removed, ["from typing import List, Dict (Dict)", "import sys"]);
+
+        // Names the file defines need no import
+        let defined: HashSet<String> = ["Path".to_string()].into_iter().collect();
+        let (_, changes) = organize("class Path:\n    pass\n\nPath()\n", Language::Python, &defined, false);
+        assert!(changes.is_empty());
+
+        // Side-effect imports stay, and keep their order
+        // Mentions in strings are not uses, and imports in nested modules count
+        let rust = "fn main() {\n    println!(\"HashMap\");\n}\n\nmod tests {\n    use std::fs;\n\n    fn read() {\n        fs::read(\"a\");\n    }\n}\n";
+        let (organized, changes) = organize(rust, Language::Rust, &HashSet::new(), false);
+        assert_eq!(organized, rust);
+        assert!(changes.is_empty());
+
+        let js = "import { b, a } from './lib';\nimport './styles.css';\n\nexport const value = a();\n";
+        let (organized, changes) = organize(js, Language::JavaScript, &HashSet::new(), false);
+        assert_eq!(organized, "import { a } from './lib';\nimport './styles.css';\n\nexport const value = a();\n");
+        assert!(!changes.sorted);
+
+        // Only lines inside `/* ... */` are comments, not every line starting with `*`
+        let rust = "use crate::state::reset;\n\n/* Reset runs\n * before load */\nfn clear(state: &mut State) {\n    *state = reset();\n}\n";
+        let (organized, changes) = organize(rust, Language::Rust, &HashSet::new(), false);
+        assert_eq!(organized, rust);
+        assert!(changes.is_empty());
+        let (_, changes) = organize("use crate::a::b;\n\n/*\n * b();\n */\n", Language::Rust, &HashSet::new(), false);
+        assert_eq!(changes.removed, ["use crate::a::b;"]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Import extraction and dependency validation
+//!
+//! Finds the external packages a piece of code imports and checks them
+//! against the project manifests, so generated code that silently pulls
+//! in a new dependency can be flagged before it is written.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::process::Command;
+
+use super::journal::{Change, Journal};
+use super::manifest::{self, Manifest, ManifestKind};
+use super::parser::Language;
+
+/// Rust crates that never appear in a manifest
+const RUST_BUILTIN: &[&str] = &["std", "core", "alloc", "crate", "self", "super", "proc_macro", "test"];
+
+/// Python standard library modules (common subset)
+const PYTHON_STDLIB: &[&str] = &[
+    "__future__", "abc", "argparse", "array", "ast", "asyncio", "base64", "bisect", "builtins",
+    "calendar", "collections", "concurrent", "configparser", "contextlib", "copy", "csv",
+    "ctypes", "dataclasses", "datetime", "decimal", "difflib", "enum", "errno", "fnmatch",
+    "fractions", "functools", "gc", "getpass", "glob", "gzip", "hashlib", "heapq", "hmac",
+    "html", "http", "importlib", "inspect", "io", "ipaddress", "itertools", "json", "logging",
+    "math", "mimetypes", "multiprocessing", "operator", "os", "pathlib", "pickle", "platform",
+    "pprint", "queue", "random", "re", "secrets", "select", "shlex", "shutil", "signal",
+    "socket", "sqlite3", "ssl", "statistics", "string", "struct", "subprocess", "sys",
+    "tempfile", "textwrap", "threading", "time", "timeit", "tomllib", "traceback", "types",
+    "typing", "unicodedata", "unittest", "urllib", "uuid", "warnings", "weakref", "xml",
+    "zipfile", "zlib",
+];
+
+/// Node.js built-in modules
+const NODE_BUILTIN: &[&str] = &[
+    "assert", "buffer", "child_process", "cluster", "crypto", "dns", "events", "fs", "http",
+    "http2", "https", "module", "net", "os", "path", "perf_hooks", "process", "querystring",
+    "readline", "stream", "string_decoder", "timers", "tls", "tty", "url", "util", "v8", "vm",
+    "worker_threads", "zlib",
+];
+
+/// An import not declared in the project manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingDependency {
+    /// Package name as imported in code
+    pub name: String,
+    /// Manifest the dependency should be added to
+    pub kind: ManifestKind,
+}
+
+impl MissingDependency {
+    /// Package manager command that adds this dependency
+    pub fn add_command(&self) -> Vec<String> {
+        let package = match self.kind {
+            ManifestKind::PyProject => manifest::python_distribution_for(&self.name)
+                .unwrap_or(&self.name)
+                .to_string(),
+            _ => self.name.clone(),
+        };
+
+        let base: &[&str] = match self.kind {
+            ManifestKind::Cargo => &["cargo", "add"],
+            ManifestKind::Npm => &["npm", "install", "-D"],
+            ManifestKind::PyProject => &["poetry", "add"],
+        };
+
+        base.iter().map(|s| s.to_string()).chain(std::iter::once(package)).collect()
+    }
+
+    /// Run the add command and record it in the change journal
+    pub fn install(&self, command: &str) -> Result<()> {
+        let args = self.add_command();
+        let status = Command::new(&args[0])
+            .args(&args[1..])
+            .status()
+            .with_context(|| format!("Failed to run {}", args[0]))?;
+
+        if !status.success() {
+            anyhow::bail!("`{}` exited with {}", args.join(" "), status);
+        }
+
+        Journal::current().record(command, Change::DependencyAdded {
+            name: self.name.clone(),
+            manifest: self.kind.file_name().to_string(),
+            install_command: args.join(" "),
+        })
+    }
+}
+
+/// Manifest kind that declares dependencies for a language
+pub fn manifest_kind_for(language: Language) -> Option<ManifestKind> {
+    match language {
+        Language::Rust => Some(ManifestKind::Cargo),
+        Language::Python => Some(ManifestKind::PyProject),
+        Language::JavaScript | Language::TypeScript => Some(ManifestKind::Npm),
+        Language::Unknown => None,
+    }
+}
+
+/// Extract the external package names imported by a piece of code
+pub fn extract_imports(code: &str, language: Language) -> Vec<String> {
+    let mut imports = BTreeSet::new();
+
+    for line in code.lines() {
+        let line = line.trim();
+        let found = match language {
+            Language::Rust => rust_import(line),
+            Language::Python => python_import(line),
+            Language::JavaScript | Language::TypeScript => js_import(line),
+            Language::Unknown => None,
+        };
+        if let Some(name) = found {
+            imports.insert(name);
+        }
+    }
+
+    imports.into_iter().collect()
+}
+
+/// Imports in the code that are not declared in the matching manifest
+///
+/// Returns nothing when the project has no manifest for the language,
+/// since there is nothing to validate against.
+pub fn missing_dependencies(code: &str, language: Language, manifests: &[Manifest]) -> Vec<MissingDependency> {
+    let Some(kind) = manifest_kind_for(language) else {
+        return Vec::new();
+    };
+    let Some(manifest) = manifests.iter().find(|m| m.kind == kind) else {
+        return Vec::new();
+    };
+
+    // A crate never needs to depend on itself
+    let own_name = manifest.name.as_deref().map(|n| n.replace('-', "_"));
+
+    extract_imports(code, language)
+        .into_iter()
+        .filter(|name| own_name.as_deref() != Some(name.as_str()))
+        .filter(|name| manifest.dependency_for_import(name).is_none())
+        .map(|name| MissingDependency { name, kind })
+        .collect()
+}
+
+fn rust_import(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("pub ").unwrap_or(line);
+    let rest = rest.strip_prefix("use ")
+        .or_else(|| rest.strip_prefix("extern crate "))?;
+
+    let name: String = rest
+        .trim_start_matches("::")
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+
+    if name.is_empty() || RUST_BUILTIN.contains(&name.as_str()) {
+        return None;
+    }
+    Some(name)
+}
+
+fn python_import(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("import ")
+        .or_else(|| line.strip_prefix("from "))?;
+
+    // Relative imports are always local
+    if rest.starts_with('.') {
+        return None;
+    }
+
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+
+    if name.is_empty() || PYTHON_STDLIB.contains(&name.as_str()) {
+        return None;
+    }
+    Some(name)
+}
+
+fn js_import(line: &str) -> Option<String> {
+    let spec = if line.starts_with("import ") || line.starts_with("export ") {
+        if !line.contains(" from ") && !line.starts_with("import '") && !line.starts_with("import \"") {
+            return None;
+        }
+        quoted_after(line, line.rfind(" from ").map(|i| i + 6).unwrap_or(7))?
+    } else if let Some(idx) = line.find("require(") {
+        quoted_after(line, idx + 8)?
+    } else {
+        return None;
+    };
+
+    if spec.starts_with('.') || spec.starts_with('/') || spec.starts_with("node:") {
+        return None;
+    }
+
+    // Keep scoped packages (`@scope/pkg`), drop subpaths (`lodash/fp`)
+    let mut parts = spec.split('/');
+    let name = if spec.starts_with('@') {
+        format!("{}/{}", parts.next()?, parts.next()?)
+    } else {
+        parts.next()?.to_string()
+    };
+
+    if NODE_BUILTIN.contains(&name.as_str()) {
+        return None;
+    }
+    Some(name)
+}
+
+/// Read a quoted string literal starting at or after `from`
+fn quoted_after(line: &str, from: usize) -> Option<String> {
+    let rest = line.get(from..)?;
+    let start = rest.find(['\'', '"', '`'])?;
+    let quote = rest[start..].chars().next()?;
+    let body = &rest[start + 1..];
+    let end = body.find(quote)?;
+    Some(body[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::manifest::Dependency;
+    use std::path::PathBuf;
+
+    fn manifest(kind: ManifestKind, deps: &[&str]) -> Manifest {
+        Manifest {
+            kind,
+            path: PathBuf::new(),
+            name: Some("demo".to_string()),
+            edition: None,
+            dependencies: deps.iter()
+                .map(|d| Dependency { name: d.to_string(), version: "1".to_string(), dev: false, locked: None })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_extract_imports_per_language() {
+        let rust = "use std::io;\nuse serde::Serialize;\npub use crate::x;\nextern crate rand;";
+        assert_eq!(extract_imports(rust, Language::Rust), vec!["rand", "serde"]);
+
+        let python = "import os\nfrom requests import get\nfrom .local import x\nimport yaml";
+        assert_eq!(extract_imports(python, Language::Python), vec!["requests", "yaml"]);
+
+        let js = "import fs from 'fs';\nimport { z } from \"zod\";\nconst x = require('@aws-sdk/client-s3/dist');\nimport './style.css';";
+        assert_eq!(extract_imports(js, Language::JavaScript), vec!["@aws-sdk/client-s3", "zod"]);
+    }
+
+    #[test]
+    fn test_missing_dependencies() {
+        let manifests = vec![manifest(ManifestKind::Cargo, &["serde-json", "tokio"])];
+        let code = "use serde_json::Value;\nuse tokio::fs;\nuse reqwest::Client;\nuse demo::lib;";
+
+        let missing = missing_dependencies(code, Language::Rust, &manifests);
+        assert_eq!(missing, vec![MissingDependency { name: "reqwest".to_string(), kind: ManifestKind::Cargo }]);
+
+        // No manifest for the language: nothing to validate
+        assert!(missing_dependencies("import requests", Language::Python, &manifests).is_empty());
+    }
+
+    #[test]
+    fn test_add_command() {
+        let dep = MissingDependency { name: "bs4".to_string(), kind: ManifestKind::PyProject };
+        assert_eq!(dep.add_command(), vec!["poetry", "add", "beautifulsoup4"]);
+
+        let dep = MissingDependency { name: "zod".to_string(), kind: ManifestKind::Npm };
+        assert_eq!(dep.add_command(), vec!["npm", "install", "-D", "zod"]);
+    }
+}
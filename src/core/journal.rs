@@ -0,0 +1,150 @@
+//! Change journal
+//!
+//! Append-only record of the changes NEXUS makes to a project (files
+//! written, dependencies added), stored as JSON lines in `.nexus/journal.jsonl`.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::provenance::Provenance;
+
+/// Project-local directory for NEXUS state
+pub const PROJECT_DIR: &str = ".nexus";
+
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Keeps `.nexus/` out of `git add .`, except the graders meant to be shared
+const PROJECT_GITIGNORE: &str = "# Local NEXUS state: history, recordings, memory, indexes\n*\n!.gitignore\n!graders/\n!graders/**\n";
+
+/// Create `dir`, a directory inside a project's `.nexus/`, adding the
+/// `.gitignore` that keeps local state out of commits when it is missing
+pub fn create_project_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    if let Some(project_dir) = dir.ancestors().find(|d| d.file_name().is_some_and(|n| n == PROJECT_DIR)) {
+        let ignore = project_dir.join(".gitignore");
+        if !ignore.exists() {
+            fs::write(&ignore, PROJECT_GITIGNORE)
+                .with_context(|| format!("Failed to write {}", ignore.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// A change made to the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Change {
+    /// A file was created or overwritten
+    FileWritten {
+        path: String,
+        /// Origin of generated content, for license audits
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provenance: Option<Provenance>,
+    },
+    /// A dependency was added through the package manager
+    DependencyAdded { name: String, manifest: String, install_command: String },
+}
+
+/// A single journal entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+    /// NEXUS command that made the change
+    pub command: String,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+/// Change journal for a project root
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Journal for the project in the current directory
+    pub fn current() -> Self {
+        Self::for_root(Path::new("."))
+    }
+
+    /// Journal for a specific project root
+    pub fn for_root(root: &Path) -> Self {
+        Self {
+            path: root.join(PROJECT_DIR).join(JOURNAL_FILE),
+        }
+    }
+
+    /// Append a change to the journal
+    pub fn record(&self, command: &str, change: Change) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_project_dir(parent)?;
+        }
+
+        let entry = JournalEntry {
+            timestamp: Utc::now(),
+            command: command.to_string(),
+            change,
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open journal {}", self.path.display()))?;
+
+        writeln!(file, "{}", serde_json::to_string(&entry)?)
+            .context("Failed to write journal entry")?;
+
+        Ok(())
+    }
+
+    /// Read all journal entries, oldest first
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read journal {}", self.path.display()))?;
+
+        Ok(content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let journal = Journal::for_root(temp_dir.path());
+
+        journal.record("generate", Change::FileWritten {
+            path: "src/lib.rs".to_string(),
+            provenance: Some(Provenance::new("Claude", "parse headers")),
+        }).unwrap();
+        journal.record("fix", Change::DependencyAdded {
+            name: "serde".to_string(),
+            manifest: "Cargo.toml".to_string(),
+            install_command: "cargo add serde".to_string(),
+        }).unwrap();
+
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "generate");
+        assert!(matches!(&entries[0].change, Change::FileWritten { provenance: Some(p), .. } if p.provider == "Claude"));
+        assert!(matches!(&entries[1].change, Change::DependencyAdded { name, .. } if name == "serde"));
+        let ignore = fs::read_to_string(temp_dir.path().join(PROJECT_DIR).join(".gitignore")).unwrap();
+        assert!(ignore.lines().any(|l| l == "*"));
+    }
+}
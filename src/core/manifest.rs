@@ -0,0 +1,470 @@
+//! Project manifest parsing
+//!
+//! Reads dependency declarations from Cargo.toml, package.json and
+//! pyproject.toml (plus their lockfiles, when present) so AI prompts can
+//! target the exact versions a project uses.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Supported manifest formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestKind {
+    Cargo,
+    Npm,
+    PyProject,
+}
+
+impl ManifestKind {
+    /// File name of the manifest
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ManifestKind::Cargo => "Cargo.toml",
+            ManifestKind::Npm => "package.json",
+            ManifestKind::PyProject => "pyproject.toml",
+        }
+    }
+
+    /// Ecosystem name shown in prompts
+    pub fn ecosystem(&self) -> &'static str {
+        match self {
+            ManifestKind::Cargo => "Rust",
+            ManifestKind::Npm => "Node.js",
+            ManifestKind::PyProject => "Python",
+        }
+    }
+
+    /// Lockfile holding resolved versions for this manifest
+    pub fn lock_file_name(&self) -> &'static str {
+        match self {
+            ManifestKind::Cargo => "Cargo.lock",
+            ManifestKind::Npm => "package-lock.json",
+            ManifestKind::PyProject => "poetry.lock",
+        }
+    }
+
+    fn all() -> [ManifestKind; 3] {
+        [ManifestKind::Cargo, ManifestKind::Npm, ManifestKind::PyProject]
+    }
+}
+
+/// A single declared dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub version: String,
+    pub dev: bool,
+    /// Exact version resolved by the lockfile
+    pub locked: Option<String>,
+}
+
+impl Dependency {
+    /// Most precise version known: locked if available, else the requirement
+    pub fn exact_version(&self) -> &str {
+        self.locked.as_deref().unwrap_or(&self.version)
+    }
+}
+
+/// Parsed project manifest
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub kind: ManifestKind,
+    pub path: PathBuf,
+    pub name: Option<String>,
+    /// Language edition or runtime requirement (Rust edition, engines.node, requires-python)
+    pub edition: Option<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Manifest {
+    /// Find and parse all supported manifests in a project root
+    pub fn discover(root: &Path) -> Vec<Manifest> {
+        ManifestKind::all()
+            .iter()
+            .map(|kind| root.join(kind.file_name()))
+            .filter(|path| path.is_file())
+            .filter_map(|path| Self::load(&path).ok())
+            .map(|mut manifest| {
+                manifest.apply_lockfile(root);
+                manifest
+            })
+            .collect()
+    }
+
+    /// Parse a manifest file, detecting its format from the file name
+    pub fn load(path: &Path) -> Result<Manifest> {
+        let file_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+
+        let kind = ManifestKind::all()
+            .into_iter()
+            .find(|k| k.file_name() == file_name)
+            .with_context(|| format!("Unsupported manifest: {}", path.display()))?;
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+
+        let mut manifest = match kind {
+            ManifestKind::Cargo => parse_cargo(&content)?,
+            ManifestKind::Npm => parse_package_json(&content)?,
+            ManifestKind::PyProject => parse_pyproject(&content)?,
+        };
+        manifest.path = path.to_path_buf();
+
+        Ok(manifest)
+    }
+
+    /// Look up a dependency by name
+    pub fn dependency(&self, name: &str) -> Option<&Dependency> {
+        self.dependencies.iter().find(|d| d.name == name)
+    }
+
+    /// Look up a dependency by its import name (`serde_json` for `serde-json`, `bs4` for `beautifulsoup4`)
+    pub fn dependency_for_import(&self, import: &str) -> Option<&Dependency> {
+        let wanted = normalize_name(import);
+        let aliased = python_distribution_for(import).map(normalize_name);
+
+        self.dependencies.iter().find(|d| {
+            let name = normalize_name(&d.name);
+            name == wanted || aliased.as_deref() == Some(name.as_str())
+        })
+    }
+
+    /// Dependencies whose names appear as words in the given text
+    pub fn referenced_in(&self, text: &str) -> Vec<&Dependency> {
+        let words: std::collections::HashSet<String> = text
+            .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '@' || c == '/'))
+            .filter(|w| !w.is_empty())
+            .map(normalize_name)
+            .collect();
+
+        self.dependencies
+            .iter()
+            .filter(|d| words.contains(&normalize_name(&d.name)))
+            .collect()
+    }
+
+    /// Fill in locked versions from the lockfile next to the manifest
+    fn apply_lockfile(&mut self, root: &Path) {
+        let lock_path = root.join(self.kind.lock_file_name());
+        let Ok(content) = fs::read_to_string(&lock_path) else {
+            return;
+        };
+
+        let locked = match self.kind {
+            ManifestKind::Cargo | ManifestKind::PyProject => parse_toml_lock(&content),
+            ManifestKind::Npm => parse_npm_lock(&content),
+        };
+
+        for dep in &mut self.dependencies {
+            dep.locked = locked
+                .iter()
+                .find(|(name, _)| normalize_name(name) == normalize_name(&dep.name))
+                .map(|(_, version)| version.clone());
+        }
+    }
+}
+
+/// Normalize a package name for comparison (case, `-` vs `_`)
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+/// Python import names that differ from their distribution names
+pub fn python_distribution_for(import: &str) -> Option<&'static str> {
+    match import {
+        "yaml" => Some("pyyaml"),
+        "bs4" => Some("beautifulsoup4"),
+        "PIL" => Some("pillow"),
+        "cv2" => Some("opencv-python"),
+        "sklearn" => Some("scikit-learn"),
+        "dateutil" => Some("python-dateutil"),
+        "dotenv" => Some("python-dotenv"),
+        "jwt" => Some("pyjwt"),
+        "attr" => Some("attrs"),
+        _ => None,
+    }
+}
+
+/// Parse `[[package]]` name/version pairs from Cargo.lock or poetry.lock
+fn parse_toml_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = toml::from_str::<toml::Value>(content) else {
+        return Vec::new();
+    };
+
+    value.get("package")
+        .and_then(|p| p.as_array())
+        .map(|packages| {
+            packages.iter()
+                .filter_map(|pkg| {
+                    let name = pkg.get("name")?.as_str()?;
+                    let version = pkg.get("version")?.as_str()?;
+                    Some((name.to_string(), version.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse top-level package versions from package-lock.json (lockfile v2/v3)
+fn parse_npm_lock(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    value.get("packages")
+        .and_then(|p| p.as_object())
+        .map(|packages| {
+            packages.iter()
+                .filter_map(|(key, pkg)| {
+                    let name = key.strip_prefix("node_modules/")?;
+                    if name.contains("/node_modules/") {
+                        return None;
+                    }
+                    let version = pkg.get("version")?.as_str()?;
+                    Some((name.to_string(), version.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a Cargo.toml manifest
+fn parse_cargo(content: &str) -> Result<Manifest> {
+    let value: toml::Value = toml::from_str(content)
+        .context("Failed to parse Cargo.toml")?;
+
+    let package = value.get("package");
+    let name = package
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from);
+    let edition = package
+        .and_then(|p| p.get("edition"))
+        .and_then(|e| e.as_str())
+        .map(|e| format!("edition {}", e));
+
+    let mut dependencies = Vec::new();
+    for (section, dev) in [("dependencies", false), ("dev-dependencies", true)] {
+        if let Some(table) = value.get(section).and_then(|d| d.as_table()) {
+            for (dep_name, spec) in table {
+                let version = match spec {
+                    toml::Value::String(v) => v.clone(),
+                    toml::Value::Table(t) => t.get("version")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("*")
+                        .to_string(),
+                    _ => "*".to_string(),
+                };
+                dependencies.push(Dependency { name: dep_name.clone(), version, dev, locked: None });
+            }
+        }
+    }
+
+    Ok(Manifest {
+        kind: ManifestKind::Cargo,
+        path: PathBuf::new(),
+        name,
+        edition,
+        dependencies,
+    })
+}
+
+/// Parse a package.json manifest
+fn parse_package_json(content: &str) -> Result<Manifest> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .context("Failed to parse package.json")?;
+
+    let name = value.get("name").and_then(|n| n.as_str()).map(String::from);
+    let edition = value.get("engines")
+        .and_then(|e| e.get("node"))
+        .and_then(|n| n.as_str())
+        .map(|n| format!("node {}", n));
+
+    let mut dependencies = Vec::new();
+    for (section, dev) in [("dependencies", false), ("devDependencies", true)] {
+        if let Some(map) = value.get(section).and_then(|d| d.as_object()) {
+            for (dep_name, version) in map {
+                dependencies.push(Dependency {
+                    name: dep_name.clone(),
+                    version: version.as_str().unwrap_or("*").to_string(),
+                    dev,
+                    locked: None,
+                });
+            }
+        }
+    }
+
+    Ok(Manifest {
+        kind: ManifestKind::Npm,
+        path: PathBuf::new(),
+        name,
+        edition,
+        dependencies,
+    })
+}
+
+/// Parse a pyproject.toml manifest (PEP 621 and Poetry layouts)
+fn parse_pyproject(content: &str) -> Result<Manifest> {
+    let value: toml::Value = toml::from_str(content)
+        .context("Failed to parse pyproject.toml")?;
+
+    let project = value.get("project");
+    let poetry = value.get("tool").and_then(|t| t.get("poetry"));
+
+    let name = project
+        .or(poetry)
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from);
+
+    let mut edition = project
+        .and_then(|p| p.get("requires-python"))
+        .and_then(|r| r.as_str())
+        .map(|r| format!("python {}", r));
+
+    let mut dependencies = Vec::new();
+
+    // PEP 621: dependencies = ["requests>=2.31", ...]
+    if let Some(list) = project.and_then(|p| p.get("dependencies")).and_then(|d| d.as_array()) {
+        for spec in list.iter().filter_map(|s| s.as_str()) {
+            dependencies.push(parse_requirement(spec, false));
+        }
+    }
+    if let Some(groups) = project
+        .and_then(|p| p.get("optional-dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        for list in groups.values().filter_map(|l| l.as_array()) {
+            for spec in list.iter().filter_map(|s| s.as_str()) {
+                dependencies.push(parse_requirement(spec, true));
+            }
+        }
+    }
+
+    // Poetry: [tool.poetry.dependencies] name = "^1.0"
+    for (section, dev) in [("dependencies", false), ("dev-dependencies", true)] {
+        if let Some(table) = poetry.and_then(|p| p.get(section)).and_then(|d| d.as_table()) {
+            for (dep_name, spec) in table {
+                let version = match spec {
+                    toml::Value::String(v) => v.clone(),
+                    toml::Value::Table(t) => t.get("version")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("*")
+                        .to_string(),
+                    _ => "*".to_string(),
+                };
+                if dep_name == "python" {
+                    edition.get_or_insert_with(|| format!("python {}", version));
+                    continue;
+                }
+                dependencies.push(Dependency { name: dep_name.clone(), version, dev, locked: None });
+            }
+        }
+    }
+
+    Ok(Manifest {
+        kind: ManifestKind::PyProject,
+        path: PathBuf::new(),
+        name,
+        edition,
+        dependencies,
+    })
+}
+
+/// Split a PEP 508 requirement string into name and version specifier
+fn parse_requirement(spec: &str, dev: bool) -> Dependency {
+    let spec = spec.split(';').next().unwrap_or(spec).trim();
+    let split_at = spec
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(spec.len());
+
+    let name = spec[..split_at].to_string();
+    let rest = spec[split_at..].trim();
+    // Drop extras like `uvicorn[standard]`
+    let rest = match rest.strip_prefix('[') {
+        Some(r) => r.split_once(']').map(|(_, v)| v.trim()).unwrap_or(""),
+        None => rest,
+    };
+
+    Dependency {
+        name,
+        version: if rest.is_empty() { "*".to_string() } else { rest.to_string() },
+        dev,
+        locked: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_manifest() {
+        let manifest = parse_cargo(r#"
+[package]
+name = "demo"
+edition = "2021"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+anyhow = "1.0"
+
+[dev-dependencies]
+tempfile = "3.9"
+"#).unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("demo"));
+        assert_eq!(manifest.edition.as_deref(), Some("edition 2021"));
+        assert_eq!(manifest.dependency("serde").unwrap().version, "1.0");
+        assert!(manifest.dependency("tempfile").unwrap().dev);
+    }
+
+    #[test]
+    fn test_parse_pyproject_requirements() {
+        let manifest = parse_pyproject(r#"
+[project]
+name = "demo"
+requires-python = ">=3.10"
+dependencies = ["requests>=2.31", "uvicorn[standard]==0.23.2; python_version >= '3.8'", "click"]
+"#).unwrap();
+
+        assert_eq!(manifest.edition.as_deref(), Some("python >=3.10"));
+        assert_eq!(manifest.dependency("requests").unwrap().version, ">=2.31");
+        assert_eq!(manifest.dependency("uvicorn").unwrap().version, "==0.23.2");
+        assert_eq!(manifest.dependency("click").unwrap().version, "*");
+    }
+
+    #[test]
+    fn test_lockfile_and_references() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), r#"
+[package]
+name = "demo"
+
+[dependencies]
+serde-json = "1.0"
+tokio = "1"
+"#).unwrap();
+        fs::write(temp_dir.path().join("Cargo.lock"), r#"
+[[package]]
+name = "tokio"
+version = "1.35.1"
+"#).unwrap();
+
+        let manifests = Manifest::discover(temp_dir.path());
+        let manifest = &manifests[0];
+
+        assert_eq!(manifest.dependency("tokio").unwrap().exact_version(), "1.35.1");
+        assert_eq!(manifest.dependency("serde-json").unwrap().exact_version(), "1.0");
+        assert!(manifest.dependency_for_import("serde_json").is_some());
+
+        let referenced = manifest.referenced_in("spawn a Tokio task");
+        assert_eq!(referenced.len(), 1);
+        assert_eq!(referenced[0].name, "tokio");
+    }
+}
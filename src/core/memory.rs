@@ -0,0 +1,278 @@
+//! Long-term project memory
+//!
+//! Facts and decisions distilled from past chats and reviews, kept as a
+//! Markdown list in `.nexus/memory.md` that can be read and edited by hand.
+//! With `[memory] enabled = true` in `.nexus.toml`, every AI request on the
+//! project gets the list in its system prompt, and chats and reviews add to
+//! it when they end.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::hooks::Prompt;
+use super::journal::{create_project_dir, PROJECT_DIR};
+use crate::config::{MemoryConfig, ProjectConfig};
+
+const MEMORY_FILE: &str = "memory.md";
+
+const HEADER: &str = "# Project memory\n\n\
+<!-- Facts and decisions NEXUS remembers about this project, one `- ` item each.\n\
+     Edit freely; `nexus memory forget` removes items. -->\n";
+
+/// Asks for the lasting facts of a chat or review, one per line
+pub const DISTILL_PROMPT: &str = "Extract the facts and decisions from this session that will \
+still matter in future sessions on this project: conventions agreed on, design decisions and \
+their reasons, constraints, and known problems. Leave out anything only relevant to this \
+session, and anything already listed under \"Already remembered\". Reply with a Markdown list, \
+one short self-contained sentence per item, and nothing else. Reply with `- none` if there is \
+nothing worth keeping.";
+
+/// Memory context of this process, `None` when the project has memory off
+static ACTIVE: OnceLock<Option<String>> = OnceLock::new();
+
+/// The memory file of a project root
+pub struct ProjectMemory {
+    path: PathBuf,
+}
+
+impl ProjectMemory {
+    /// Memory of the project in the current directory
+    pub fn current() -> Self {
+        Self::for_root(Path::new("."))
+    }
+
+    /// Memory of a specific project root
+    pub fn for_root(root: &Path) -> Self {
+        Self {
+            path: root.join(PROJECT_DIR).join(MEMORY_FILE),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Inject `root`'s memory into every AI request of this process, when
+    /// the project enabled it
+    pub fn install(root: &Path) {
+        let context = match ProjectConfig::load(root) {
+            Ok(project) if project.memory.enabled => {
+                Some(Self::for_root(root).context(&project.memory).unwrap_or_default())
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Project memory not loaded: {:#}", e);
+                None
+            }
+        };
+        ACTIVE.set(context).ok();
+    }
+
+    fn read(&self) -> Result<String> {
+        if !self.path.exists() {
+            return Ok(String::new());
+        }
+        fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))
+    }
+
+    fn write(&self, content: &str) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            create_project_dir(parent)?;
+        }
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Remembered items, oldest first
+    pub fn facts(&self) -> Result<Vec<String>> {
+        Ok(self.read()?.lines().filter_map(item).map(str::to_string).collect())
+    }
+
+    /// Create the file with its header if there is none, so it can be edited
+    pub fn ensure_exists(&self) -> Result<()> {
+        if self.path.exists() {
+            return Ok(());
+        }
+        self.write(HEADER)
+    }
+
+    /// Add the facts not remembered yet; returns those added
+    pub fn remember(&self, facts: &[String]) -> Result<Vec<String>> {
+        let known: Vec<String> = self.facts()?.iter().map(|f| normalize(f)).collect();
+        let mut added: Vec<String> = Vec::new();
+        for fact in facts {
+            let fact = fact.trim();
+            let key = normalize(fact);
+            if fact.is_empty() || known.contains(&key) || added.iter().any(|a| normalize(a) == key) {
+                continue;
+            }
+            added.push(fact.to_string());
+        }
+        if added.is_empty() {
+            return Ok(added);
+        }
+
+        let mut content = self.read()?;
+        if content.trim().is_empty() {
+            content = HEADER.to_string();
+        }
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for fact in &added {
+            content.push_str(&format!("- {}\n", fact));
+        }
+        self.write(&content)?;
+        Ok(added)
+    }
+
+    /// Remove the items containing `pattern` (case-insensitive); returns them
+    pub fn forget(&self, pattern: &str) -> Result<Vec<String>> {
+        let pattern = pattern.to_lowercase();
+        let content = self.read()?;
+        let mut removed = Vec::new();
+        let mut kept = String::new();
+        for line in content.lines() {
+            match item(line) {
+                Some(fact) if fact.to_lowercase().contains(&pattern) => removed.push(fact.to_string()),
+                _ => {
+                    kept.push_str(line);
+                    kept.push('\n');
+                }
+            }
+        }
+        if !removed.is_empty() {
+            self.write(&kept)?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove the memory file; returns how many items it had
+    pub fn forget_all(&self) -> Result<usize> {
+        let count = self.facts()?.len();
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove {}", self.path.display()))?;
+        }
+        Ok(count)
+    }
+
+    /// The items as system prompt context, newest kept when they do not all
+    /// fit in `max_chars`
+    pub fn context(&self, config: &MemoryConfig) -> Option<String> {
+        let facts = self.facts().ok()?;
+        let mut kept: Vec<&str> = Vec::new();
+        let mut size = 0;
+        for fact in facts.iter().rev() {
+            size += fact.len() + 3;
+            if size > config.max_chars {
+                break;
+            }
+            kept.push(fact);
+        }
+        if kept.is_empty() {
+            return None;
+        }
+        kept.reverse();
+        Some(format!(
+            "Remembered from earlier sessions on this project:\n{}",
+            kept.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n")
+        ))
+    }
+
+    /// Distill `session` into facts with `ask` (system prompt, message) and
+    /// remember the new ones
+    pub async fn distill<F, Fut>(&self, session: &str, ask: F) -> Result<Vec<String>>
+    where
+        F: FnOnce(String, String) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let known = self.facts()?;
+        let message = if known.is_empty() {
+            session.to_string()
+        } else {
+            format!(
+                "Already remembered:\n{}\n\nSession:\n{}",
+                known.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n"),
+                session
+            )
+        };
+        let response = ask(DISTILL_PROMPT.to_string(), message).await?;
+        self.remember(&parse_facts(&response))
+    }
+}
+
+/// Whether this process distills sessions into memory
+pub fn enabled() -> bool {
+    ACTIVE.get().is_some_and(Option::is_some)
+}
+
+/// `prompt` with this process's memory added to its system prompt
+pub fn inject(mut prompt: Prompt) -> Prompt {
+    if let Some(context) = ACTIVE.get().and_then(Option::as_ref).filter(|c| !c.is_empty()) {
+        prompt.system = Some(match prompt.system {
+            Some(system) => format!("{}\n\n{}", system, context),
+            None => context.clone(),
+        });
+    }
+    prompt
+}
+
+/// The list items of a distillation reply
+pub fn parse_facts(response: &str) -> Vec<String> {
+    response
+        .lines()
+        .filter_map(item)
+        .map(|fact| fact.trim_end_matches('.').trim().to_string() + ".")
+        .filter(|fact| fact.len() > 1 && !fact.eq_ignore_ascii_case("none."))
+        .collect()
+}
+
+/// The text of a `- ` or `* ` list item
+fn item(line: &str) -> Option<&str> {
+    let line = line.trim();
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .map(str::trim)
+        .filter(|fact| !fact.is_empty())
+}
+
+fn normalize(fact: &str) -> String {
+    fact.trim().trim_end_matches('.').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remember_forget_and_context() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let memory = ProjectMemory::for_root(temp_dir.path());
+        assert!(memory.facts().unwrap().is_empty());
+        assert!(memory.context(&MemoryConfig::default()).is_none());
+
+        let facts = parse_facts("Here you go:\n- Errors use anyhow\n* SQLite is the only supported database.\n- none\n");
+        assert_eq!(facts, ["Errors use anyhow.", "SQLite is the only supported database."]);
+        assert_eq!(memory.remember(&facts).unwrap().len(), 2);
+        // Already known, in a different case
+        let added = memory.remember(&["errors use anyhow".to_string(), "Tests live next to the code.".to_string()]).unwrap();
+        assert_eq!(added, ["Tests live next to the code."]);
+        assert!(memory.read().unwrap().starts_with("# Project memory"));
+
+        let small = MemoryConfig { enabled: true, max_chars: 70 };
+        let context = memory.context(&small).unwrap();
+        assert!(context.contains("- Tests live next to the code.") && !context.contains("anyhow"), "{}", context);
+
+        assert_eq!(memory.forget("SQLITE").unwrap(), ["SQLite is the only supported database."]);
+        assert_eq!(memory.facts().unwrap().len(), 2);
+        assert_eq!(memory.forget_all().unwrap(), 2);
+        assert!(!memory.path().exists());
+    }
+}
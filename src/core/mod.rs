@@ -1,4 +1,43 @@
 //! Core engine components
 
 pub mod parser;
+pub mod activity;
+pub mod affected;
+pub mod api;
+pub mod bundle;
 pub mod cache;
+pub mod callgraph;
+pub mod comments;
+pub mod dashboard;
+pub mod diagram;
+pub mod edits;
+pub mod environment;
+pub mod eval;
+pub mod examples;
+pub mod findings;
+pub mod formatter;
+pub mod git;
+pub mod glossary;
+pub mod graders;
+pub mod harness;
+pub mod hooks;
+pub mod import_organizer;
+pub mod imports;
+pub mod journal;
+pub mod manifest;
+pub mod memory;
+pub mod naming;
+pub mod packaging;
+pub mod permissions;
+pub mod pins;
+pub mod process;
+pub mod provenance;
+pub mod query;
+pub mod recording;
+pub mod references;
+pub mod saved_search;
+pub mod script_safety;
+pub mod services;
+pub mod shrink;
+pub mod tour;
+pub mod transcript;
@@ -0,0 +1,442 @@
+//! Naming consistency
+//!
+//! Finds symbols that break the casing the rest of the codebase uses for
+//! their language and kind, and concepts reached through several synonym
+//! verbs (`getUser`, `fetch_user`, `retrieveUser`). Derives a scheme from
+//! the majority usage and plans the renames that would apply it.
+
+#![allow(dead_code)]
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use super::glossary::stem;
+use super::parser::{identifier_words, Language, ParsedFile, SymbolKind};
+use crate::index::is_test_path;
+
+/// Verbs that mean the same thing, most conventional first
+const VERB_GROUPS: &[&[&str]] = &[
+    &["get", "fetch", "retrieve", "load", "lookup", "query", "obtain"],
+    &["create", "make", "build", "construct", "generate"],
+    &["delete", "remove", "destroy", "erase", "drop"],
+    &["update", "modify", "edit", "change"],
+    &["compute", "calculate", "calc"],
+    &["validate", "verify"],
+    &["init", "initialize", "setup"],
+    &["send", "emit", "dispatch", "publish"],
+    &["start", "begin", "launch"],
+    &["stop", "halt", "terminate"],
+];
+
+/// Identifier casing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Case {
+    Snake,
+    Camel,
+    Pascal,
+    ScreamingSnake,
+}
+
+impl Case {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Case::Snake => "snake_case",
+            Case::Camel => "camelCase",
+            Case::Pascal => "PascalCase",
+            Case::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+        }
+    }
+
+    /// Whether a name written this way is compatible with the case
+    fn accepts(&self, detected: Option<Case>, name: &str) -> bool {
+        match detected {
+            Some(case) => case == *self,
+            // A single lower-case word is both snake_case and camelCase
+            None => matches!(self, Case::Snake | Case::Camel) && name.chars().all(|c| c.is_lowercase() || c.is_ascii_digit()),
+        }
+    }
+
+    /// Join lower-case words in this case
+    pub fn render(&self, words: &[String]) -> String {
+        let capitalize = |w: &String| {
+            let mut chars = w.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        };
+        match self {
+            Case::Snake => words.join("_"),
+            Case::ScreamingSnake => words.join("_").to_uppercase(),
+            Case::Pascal => words.iter().map(capitalize).collect(),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| if i == 0 { w.clone() } else { capitalize(w) })
+                .collect(),
+        }
+    }
+}
+
+/// The casing of a name, or `None` when a single lower-case word leaves it open
+pub fn detect_case(name: &str) -> Option<Case> {
+    let name = name.trim_start_matches('_');
+    let has_lower = name.chars().any(|c| c.is_lowercase());
+    let has_upper = name.chars().any(|c| c.is_uppercase());
+    let first_upper = name.chars().next().is_some_and(|c| c.is_uppercase());
+
+    match (name.contains('_'), has_lower, has_upper) {
+        (_, false, true) if name.len() > 1 => Some(Case::ScreamingSnake),
+        (true, true, false) => Some(Case::Snake),
+        (false, true, true) if first_upper => Some(Case::Pascal),
+        (false, true, true) => Some(Case::Camel),
+        (false, true, false) => None,
+        _ => None,
+    }
+}
+
+/// Symbols compared with each other for casing
+fn kind_group(kind: SymbolKind) -> Option<&'static str> {
+    match kind {
+        SymbolKind::Function => Some("functions"),
+        SymbolKind::Struct | SymbolKind::Class | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Interface
+        | SymbolKind::TypeAlias => Some("types"),
+        _ => None,
+    }
+}
+
+fn verb_group(verb: &str) -> Option<usize> {
+    VERB_GROUPS.iter().position(|g| g.contains(&verb))
+}
+
+/// The casing a language and kind use across the codebase
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseRule {
+    pub language: &'static str,
+    pub kind: &'static str,
+    pub case: Case,
+    pub conforming: usize,
+    pub total: usize,
+}
+
+/// The verb used for a group of synonyms across the codebase
+#[derive(Debug, Clone, Serialize)]
+pub struct VerbRule {
+    pub preferred: String,
+    /// Verbs in use, most common first, with how often
+    pub used: Vec<(String, usize)>,
+}
+
+/// Why a symbol should be renamed
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum Issue {
+    Casing { language: &'static str, kind: &'static str, expected: Case },
+    Verb { concept: String, verbs: Vec<String>, preferred: String },
+}
+
+impl Issue {
+    pub fn describe(&self) -> String {
+        match self {
+            Issue::Casing { language, kind, expected } => {
+                format!("{} {} not in {}", language, kind, expected.label())
+            }
+            Issue::Verb { concept, verbs, preferred } => {
+                format!("`{}` reached via {} (use {})", concept, verbs.join(", "), preferred)
+            }
+        }
+    }
+}
+
+/// One step of the rename plan
+#[derive(Debug, Clone, Serialize)]
+pub struct Rename {
+    pub path: PathBuf,
+    pub line: usize,
+    pub from: String,
+    pub to: String,
+    /// Whole-word occurrences of the old name across the project
+    pub references: usize,
+    /// The new name is already taken in the same file
+    pub conflict: bool,
+}
+
+/// A group of symbols named inconsistently
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub issue: Issue,
+    pub renames: Vec<Rename>,
+}
+
+/// Naming scheme, findings and rename plan for a codebase
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub cases: Vec<CaseRule>,
+    pub verbs: Vec<VerbRule>,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    /// Every rename once, in file order
+    pub fn plan(&self) -> Vec<&Rename> {
+        let mut seen = HashSet::new();
+        let mut plan: Vec<&Rename> = self
+            .findings
+            .iter()
+            .flat_map(|f| &f.renames)
+            .filter(|r| seen.insert((r.path.clone(), r.line, r.from.clone())))
+            .collect();
+        plan.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        plan
+    }
+}
+
+struct Named<'a> {
+    file: &'a ParsedFile,
+    name: &'a str,
+    line: usize,
+    group: &'static str,
+    words: Vec<String>,
+}
+
+/// Analyze the symbols of the given files
+pub fn analyze(files: &[ParsedFile]) -> Report {
+    let symbols: Vec<Named> = files
+        .iter()
+        .filter(|f| f.language != Language::Unknown && !is_test_path(&f.path))
+        .flat_map(|file| {
+            file.symbols.iter().filter_map(move |s| {
+                // Dunder methods and tests follow fixed conventions
+                if s.name.starts_with("__") || s.name.starts_with("test") {
+                    return None;
+                }
+                Some(Named {
+                    file,
+                    name: &s.name,
+                    line: s.line_start,
+                    group: kind_group(s.kind)?,
+                    words: identifier_words(&s.name),
+                })
+            })
+        })
+        .filter(|n| !n.words.is_empty())
+        .collect();
+
+    // Majority casing per language and kind
+    let mut tallies: BTreeMap<(&'static str, &'static str), BTreeMap<usize, usize>> = BTreeMap::new();
+    const CASES: [Case; 4] = [Case::Snake, Case::Camel, Case::Pascal, Case::ScreamingSnake];
+    for s in &symbols {
+        if let Some(case) = detect_case(s.name) {
+            let index = CASES.iter().position(|c| *c == case).unwrap_or(0);
+            *tallies.entry((s.file.language.name(), s.group)).or_default().entry(index).or_default() += 1;
+        }
+    }
+
+    let cases: Vec<CaseRule> = tallies
+        .into_iter()
+        .filter_map(|((language, kind), counts)| {
+            let (&index, _) = counts.iter().max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))?;
+            let case = CASES[index];
+            let in_group: Vec<&Named> = symbols
+                .iter()
+                .filter(|s| s.file.language.name() == language && s.group == kind)
+                .collect();
+            Some(CaseRule {
+                language,
+                kind,
+                case,
+                conforming: in_group.iter().filter(|s| case.accepts(detect_case(s.name), s.name)).count(),
+                total: in_group.len(),
+            })
+        })
+        .collect();
+
+    let expected_case = |s: &Named| {
+        cases
+            .iter()
+            .find(|r| r.language == s.file.language.name() && r.kind == s.group)
+            .map(|r| r.case)
+    };
+
+    // Verb usage per synonym group, and the verbs each concept is reached through
+    let mut verb_counts: BTreeMap<usize, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut concepts: BTreeMap<(usize, String), Vec<usize>> = BTreeMap::new();
+    for (i, s) in symbols.iter().enumerate().filter(|(_, s)| s.group == "functions" && s.words.len() > 1) {
+        let Some(group) = verb_group(&s.words[0]) else { continue };
+        *verb_counts.entry(group).or_default().entry(s.words[0].clone()).or_default() += 1;
+        let concept = s.words[1..].iter().map(|w| stem(w)).collect::<Vec<_>>().join("_");
+        concepts.entry((group, concept)).or_default().push(i);
+    }
+
+    let verbs: BTreeMap<usize, VerbRule> = verb_counts
+        .into_iter()
+        .map(|(group, counts)| {
+            let mut used: Vec<(String, usize)> = counts.into_iter().collect();
+            let order = |v: &str| VERB_GROUPS[group].iter().position(|g| *g == v).unwrap_or(usize::MAX);
+            used.sort_by(|a, b| b.1.cmp(&a.1).then(order(&a.0).cmp(&order(&b.0))));
+            (group, VerbRule { preferred: used[0].0.clone(), used })
+        })
+        .collect();
+
+    let mut findings = Vec::new();
+
+    for ((group, _), members) in &concepts {
+        let used: BTreeSet<&str> = members.iter().map(|&i| symbols[i].words[0].as_str()).collect();
+        if used.len() < 2 {
+            continue;
+        }
+        let preferred = verbs[group].preferred.clone();
+        let concept_words = &symbols[members[0]].words[1..];
+
+        let renames = members
+            .iter()
+            .filter_map(|&i| {
+                let s = &symbols[i];
+                let mut words = s.words.clone();
+                words[0] = preferred.clone();
+                let case = expected_case(s).or(detect_case(s.name)).unwrap_or(Case::Snake);
+                rename(files, s, &render_name(s.name, case, &words))
+            })
+            .collect::<Vec<_>>();
+
+        if !renames.is_empty() {
+            findings.push(Finding {
+                issue: Issue::Verb {
+                    concept: concept_words.join(" "),
+                    verbs: used.into_iter().map(String::from).collect(),
+                    preferred,
+                },
+                renames,
+            });
+        }
+    }
+
+    for rule in &cases {
+        let renames: Vec<Rename> = symbols
+            .iter()
+            .filter(|s| s.file.language.name() == rule.language && s.group == rule.kind)
+            .filter(|s| !rule.case.accepts(detect_case(s.name), s.name))
+            .filter_map(|s| rename(files, s, &render_name(s.name, rule.case, &s.words)))
+            .collect();
+
+        if !renames.is_empty() {
+            findings.push(Finding {
+                issue: Issue::Casing { language: rule.language, kind: rule.kind, expected: rule.case },
+                renames,
+            });
+        }
+    }
+
+    Report { cases, verbs: verbs.into_values().filter(|v| v.used.len() > 1).collect(), findings }
+}
+
+/// Render words in a case, keeping a private `_` prefix
+fn render_name(original: &str, case: Case, words: &[String]) -> String {
+    let prefix: String = original.chars().take_while(|c| *c == '_').collect();
+    format!("{}{}", prefix, case.render(words))
+}
+
+fn rename(files: &[ParsedFile], symbol: &Named, to: &str) -> Option<Rename> {
+    if to == symbol.name {
+        return None;
+    }
+    Some(Rename {
+        path: symbol.file.path.clone(),
+        line: symbol.line,
+        from: symbol.name.to_string(),
+        to: to.to_string(),
+        references: files.iter().map(|f| count_word(&f.content, symbol.name)).sum(),
+        conflict: symbol.file.symbols.iter().any(|s| s.name == to),
+    })
+}
+
+/// Whole-word occurrences of `word` in `text`
+fn count_word(text: &str, word: &str) -> usize {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word)
+        .filter(|(i, _)| {
+            let before = text[..*i].chars().next_back();
+            let after = text[i + word.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+        .count()
+}
+
+/// The report as a Markdown rename plan
+pub fn render_markdown(report: &Report) -> String {
+    let mut out = String::from("# Naming Plan\n\n## Scheme\n\n");
+
+    for rule in &report.cases {
+        out.push_str(&format!(
+            "- {} {}: {} ({}/{} already conform)\n",
+            rule.language, rule.kind, rule.case.label(), rule.conforming, rule.total
+        ));
+    }
+    for rule in &report.verbs {
+        let others: Vec<&str> = rule.used.iter().skip(1).map(|(v, _)| v.as_str()).collect();
+        out.push_str(&format!("- Use `{}` rather than {}\n", rule.preferred, others.join(", ")));
+    }
+
+    out.push_str("\n## Renames\n\n| File | Line | From | To | References |\n|------|------|------|----|------------|\n");
+    for r in report.plan() {
+        out.push_str(&format!(
+            "| {} | {} | `{}` | `{}`{} | {} |\n",
+            r.path.display(),
+            r.line,
+            r.from,
+            r.to,
+            if r.conflict { " (name taken)" } else { "" },
+            r.references
+        ));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+    use std::path::Path;
+
+    fn parsed(path: &str, content: &str) -> ParsedFile {
+        CodeParser::new().unwrap().parse_source(Path::new(path), content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_detect_and_render_case() {
+        assert_eq!(detect_case("fetch_user"), Some(Case::Snake));
+        assert_eq!(detect_case("getUser"), Some(Case::Camel));
+        assert_eq!(detect_case("UserRecord"), Some(Case::Pascal));
+        assert_eq!(detect_case("MAX_RETRIES"), Some(Case::ScreamingSnake));
+        assert_eq!(detect_case("_private_helper"), Some(Case::Snake));
+        assert_eq!(detect_case("run"), None);
+
+        let words = identifier_words("get_user_id");
+        assert_eq!(Case::Camel.render(&words), "getUserId");
+        assert_eq!(Case::Pascal.render(&words), "GetUserId");
+        assert_eq!(render_name("_getUser", Case::Snake, &identifier_words("getUser")), "_get_user");
+    }
+
+    #[test]
+    fn test_analyze_finds_synonyms_and_casing() {
+        let files = vec![
+            parsed("./src/users.py", "def get_user(id):\n    pass\n\ndef get_order(id):\n    pass\n\ndef fetch_users():\n    pass\n\ndef retrieveUser(id):\n    return get_user(id)\n\ndef save_user(u):\n    pass\n"),
+            parsed("./src/view.py", "def render_page():\n    fetch_users()\n"),
+        ];
+
+        let report = analyze(&files);
+        let rule = report.cases.iter().find(|r| r.kind == "functions").unwrap();
+        assert_eq!(rule.case, Case::Snake);
+        assert_eq!((rule.conforming, rule.total), (5, 6));
+
+        assert_eq!(report.verbs[0].preferred, "get");
+
+        let plan: Vec<(&str, &str, usize)> = report.plan().iter().map(|r| (r.from.as_str(), r.to.as_str(), r.references)).collect();
+        assert_eq!(plan, vec![("fetch_users", "get_users", 2), ("retrieveUser", "get_user", 1)]);
+        assert!(report.plan()[1].conflict);
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("- Python functions: snake_case (5/6 already conform)\n"));
+        assert!(markdown.contains("- Use `get` rather than fetch, retrieve\n"));
+    }
+}
@@ -0,0 +1,310 @@
+//! Release packaging
+//!
+//! Releases ship a standalone binary per platform plus `.deb` and `.rpm`
+//! packages, a Windows MSI, and Homebrew and Scoop manifests generated from
+//! the release checksums. `nexus update` needs to know which of these it was
+//! installed from: package managers own their binaries, so those installs
+//! are updated through the manager or by installing the newer package
+//! rather than by replacing the executable.
+
+#![allow(dead_code)]
+
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const REPOSITORY: &str = "mrsarac/NEXUS-AI-Forge";
+
+/// Name of the packages and manifests
+pub const PACKAGE_NAME: &str = "nexus-forge";
+
+/// Release asset listing the `sha256sum` of every other asset
+pub const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// Written by the `.deb`, `.rpm` and MSI installers; holds the flavor name
+const MARKER_FILE: &str = "install-flavor";
+
+/// Where the Linux packages put the marker
+const SHARED_DIR: &str = "/usr/share/nexus-forge";
+
+/// Standalone binaries attached to each release: OS, architecture, asset
+const BINARIES: &[(&str, &str, &str)] = &[
+    ("macos", "aarch64", "nexus-darwin-arm64"),
+    ("macos", "x86_64", "nexus-darwin-x64"),
+    ("linux", "x86_64", "nexus-linux-x64"),
+    ("linux", "aarch64", "nexus-linux-arm64"),
+    ("windows", "x86_64", "nexus-windows-x64.exe"),
+];
+
+/// How this copy of nexus was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// A downloaded binary, the install script, or a source build
+    Binary,
+    Deb,
+    Rpm,
+    Msi,
+    Homebrew,
+    Scoop,
+    Cargo,
+}
+
+impl Flavor {
+    /// The flavor of the running executable
+    pub fn current() -> Self {
+        match std::env::current_exe() {
+            Ok(exe) => Self::detect(&exe.canonicalize().unwrap_or(exe), Path::new(SHARED_DIR)),
+            Err(_) => Flavor::Binary,
+        }
+    }
+
+    /// The flavor of the executable at `exe`. Installers leave a marker
+    /// next to it (MSI) or in `shared_dir` (Linux packages, which install
+    /// to `/usr/bin`); package managers are recognized by their layout.
+    pub fn detect(exe: &Path, shared_dir: &Path) -> Self {
+        let mut markers: Vec<PathBuf> = exe.parent().map(|dir| dir.join(MARKER_FILE)).into_iter().collect();
+        if exe.starts_with("/usr/bin") {
+            markers.push(shared_dir.join(MARKER_FILE));
+        }
+        for marker in markers {
+            if let Some(flavor) = fs::read_to_string(&marker).ok().and_then(|s| Self::parse(s.trim())) {
+                return flavor;
+            }
+        }
+
+        let parts: Vec<String> = exe
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().to_lowercase()),
+                _ => None,
+            })
+            .collect();
+        let has = |name: &str| parts.iter().any(|p| p == name);
+        if has("cellar") || has("homebrew") || has("linuxbrew") {
+            Flavor::Homebrew
+        } else if has("scoop") {
+            Flavor::Scoop
+        } else if parts.windows(2).any(|w| w[0] == ".cargo" && w[1] == "bin") {
+            Flavor::Cargo
+        } else {
+            Flavor::Binary
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "binary" => Some(Flavor::Binary),
+            "deb" => Some(Flavor::Deb),
+            "rpm" => Some(Flavor::Rpm),
+            "msi" => Some(Flavor::Msi),
+            "homebrew" => Some(Flavor::Homebrew),
+            "scoop" => Some(Flavor::Scoop),
+            "cargo" => Some(Flavor::Cargo),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Flavor::Binary => "standalone binary",
+            Flavor::Deb => ".deb package",
+            Flavor::Rpm => ".rpm package",
+            Flavor::Msi => "Windows installer (MSI)",
+            Flavor::Homebrew => "Homebrew",
+            Flavor::Scoop => "Scoop",
+            Flavor::Cargo => "cargo install",
+        }
+    }
+
+    /// Whether this install came from a `.deb`, `.rpm` or MSI
+    pub fn is_package(&self) -> bool {
+        matches!(self, Flavor::Deb | Flavor::Rpm | Flavor::Msi)
+    }
+
+    /// The command that updates installs owned by a package manager
+    pub fn manager_command(&self) -> Option<String> {
+        match self {
+            Flavor::Homebrew => Some(format!("brew upgrade {}", PACKAGE_NAME)),
+            Flavor::Scoop => Some(format!("scoop update {}", PACKAGE_NAME)),
+            Flavor::Cargo => Some(format!("cargo install {} --force", PACKAGE_NAME)),
+            _ => None,
+        }
+    }
+
+    /// The release asset that updates this install, when it is a package
+    pub fn package_asset(&self, version: &str, arch: &str) -> Option<String> {
+        match (self, arch) {
+            (Flavor::Deb, "x86_64") => Some(format!("{}_{}-1_amd64.deb", PACKAGE_NAME, version)),
+            (Flavor::Deb, "aarch64") => Some(format!("{}_{}-1_arm64.deb", PACKAGE_NAME, version)),
+            (Flavor::Rpm, "x86_64" | "aarch64") => Some(format!("{}-{}-1.{}.rpm", PACKAGE_NAME, version, arch)),
+            (Flavor::Msi, "x86_64") => Some(format!("{}-{}-x86_64.msi", PACKAGE_NAME, version)),
+            _ => None,
+        }
+    }
+
+    /// Program and arguments that install a downloaded package
+    pub fn install_command(&self, package: &Path) -> Option<(&'static str, Vec<String>)> {
+        let package = package.to_string_lossy().to_string();
+        match self {
+            Flavor::Deb => Some(("sudo", vec!["dpkg".to_string(), "-i".to_string(), package])),
+            Flavor::Rpm => Some(("sudo", vec!["rpm".to_string(), "-U".to_string(), package])),
+            Flavor::Msi => Some(("msiexec", vec!["/i".to_string(), package, "/passive".to_string()])),
+            _ => None,
+        }
+    }
+}
+
+/// The standalone binary for a platform
+pub fn binary_asset(os: &str, arch: &str) -> Option<&'static str> {
+    BINARIES.iter().find(|(o, a, _)| *o == os && *a == arch).map(|(_, _, asset)| *asset)
+}
+
+/// Checksums by file name from `sha256sum` output
+pub fn parse_checksums(text: &str) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+            let name = name.trim_start().trim_start_matches('*');
+            Some((name.rsplit('/').next().unwrap_or(name).to_string(), hash.to_lowercase()))
+        })
+        .collect()
+}
+
+/// Check a downloaded asset against the release's `sha256sum` output
+pub fn verify_checksum(asset: &str, data: &[u8], checksums: &str) -> Result<()> {
+    let expected = parse_checksums(checksums)
+        .remove(asset)
+        .ok_or_else(|| anyhow!("No checksum for {} in {}", asset, CHECKSUMS_ASSET))?;
+    let actual: String = Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err(anyhow!("Checksum mismatch for {}: expected {}, got {}", asset, expected, actual));
+    }
+    Ok(())
+}
+
+/// A release the manifests point at
+pub struct Release {
+    pub version: String,
+    pub checksums: BTreeMap<String, String>,
+}
+
+impl Release {
+    fn url(&self, asset: &str) -> String {
+        format!("https://github.com/{}/releases/download/v{}/{}", REPOSITORY, self.version, asset)
+    }
+
+    fn checksum(&self, asset: &str) -> Result<&str> {
+        self.checksums
+            .get(asset)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("No checksum for {} in the checksums file", asset))
+    }
+}
+
+/// Homebrew formula installing the macOS and Linux binaries
+pub fn homebrew_formula(release: &Release) -> Result<String> {
+    let mut platforms = String::new();
+    for (block, os) in [("on_macos", "macos"), ("on_linux", "linux")] {
+        let arm = binary_asset(os, "aarch64").unwrap_or_default();
+        let intel = binary_asset(os, "x86_64").unwrap_or_default();
+        platforms.push_str(&format!(
+            "  {} do\n    if Hardware::CPU.arm?\n      url \"{}\"\n      sha256 \"{}\"\n    else\n      url \"{}\"\n      sha256 \"{}\"\n    end\n  end\n\n",
+            block,
+            release.url(arm),
+            release.checksum(arm)?,
+            release.url(intel),
+            release.checksum(intel)?
+        ));
+    }
+
+    Ok(format!(
+        r##"class NexusForge < Formula
+  desc "{description}"
+  homepage "https://github.com/{repository}"
+  version "{version}"
+  license "MIT"
+
+{platforms}  def install
+    bin.install Dir["nexus-*"].first => "nexus"
+  end
+
+  test do
+    assert_match version.to_s, shell_output("#{{bin}}/nexus --version")
+  end
+end
+"##,
+        description = env!("CARGO_PKG_DESCRIPTION"),
+        repository = REPOSITORY,
+        version = release.version,
+        platforms = platforms
+    ))
+}
+
+/// Scoop manifest installing the Windows binary
+pub fn scoop_manifest(release: &Release) -> Result<String> {
+    let asset = binary_asset("windows", "x86_64").unwrap_or_default();
+    let manifest = serde_json::json!({
+        "version": release.version,
+        "description": env!("CARGO_PKG_DESCRIPTION"),
+        "homepage": format!("https://github.com/{}", REPOSITORY),
+        "license": "MIT",
+        "architecture": {
+            "64bit": {
+                "url": format!("{}#/nexus.exe", release.url(asset)),
+                "hash": release.checksum(asset)?,
+            }
+        },
+        "bin": "nexus.exe",
+        "checkver": "github",
+        "autoupdate": {
+            "architecture": {
+                "64bit": {
+                    "url": format!("https://github.com/{}/releases/download/v$version/{}#/nexus.exe", REPOSITORY, asset),
+                }
+            }
+        }
+    });
+    Ok(format!("{}\n", serde_json::to_string_pretty(&manifest)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flavors_and_manifests() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let shared = temp_dir.path().join("share");
+        assert_eq!(Flavor::detect(Path::new("/opt/homebrew/Cellar/nexus-forge/0.5.0/bin/nexus"), &shared), Flavor::Homebrew);
+        assert_eq!(Flavor::detect(Path::new("/home/ada/.cargo/bin/nexus"), &shared), Flavor::Cargo);
+        assert_eq!(Flavor::detect(Path::new("/usr/local/bin/nexus"), &shared), Flavor::Binary);
+
+        // Installers leave a marker next to the binary
+        let bin = temp_dir.path().join("NEXUS AI Forge");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join(MARKER_FILE), "msi\n").unwrap();
+        assert_eq!(Flavor::detect(&bin.join("nexus.exe"), &shared), Flavor::Msi);
+        assert_eq!(Flavor::Deb.package_asset("0.6.0", "x86_64").unwrap(), "nexus-forge_0.6.0-1_amd64.deb");
+        assert!(Flavor::Homebrew.package_asset("0.6.0", "x86_64").is_none());
+
+        let sums = "AB12  nexus-darwin-arm64\ncd34 *dist/nexus-darwin-x64\nef56  nexus-linux-arm64\n0789  nexus-linux-x64\n";
+        let mut release = Release { version: "0.6.0".to_string(), checksums: parse_checksums(sums) };
+        assert_eq!(release.checksums["nexus-darwin-x64"], "cd34");
+        let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  nexus-linux-x64\n";
+        verify_checksum("nexus-linux-x64", b"hello", hello).unwrap();
+        assert!(verify_checksum("nexus-linux-x64", b"hello!", hello).unwrap_err().to_string().contains("mismatch"));
+        assert!(verify_checksum("nexus-linux-arm64", b"hello", hello).is_err());
+        let formula = homebrew_formula(&release).unwrap();
+        assert!(formula.contains("url \"https://github.com/mrsarac/NEXUS-AI-Forge/releases/download/v0.6.0/nexus-darwin-arm64\"\n      sha256 \"ab12\""));
+        assert!(formula.contains("shell_output(\"#{bin}/nexus --version\")"));
+
+        // Scoop needs the Windows binary's checksum
+        assert!(scoop_manifest(&release).unwrap_err().to_string().contains("nexus-windows-x64.exe"));
+        release.checksums.insert("nexus-windows-x64.exe".to_string(), "99aa".to_string());
+        let manifest: serde_json::Value = serde_json::from_str(&scoop_manifest(&release).unwrap()).unwrap();
+        assert_eq!(manifest["architecture"]["64bit"]["hash"], "99aa");
+        assert_eq!(manifest["version"], "0.6.0");
+    }
+}
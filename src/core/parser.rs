@@ -94,6 +94,11 @@ impl CodeParser {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
+        self.parse_source(path, content)
+    }
+
+    /// Parse already-loaded source, using the path to pick the language
+    pub fn parse_source(&mut self, path: &Path, content: String) -> Result<ParsedFile> {
         let language = Language::from_path(path);
 
         let tree = self.parse_content(&content, language)?;
@@ -464,6 +469,39 @@ impl SymbolCounts {
     }
 }
 
+/// Lower-case words of an identifier: `getUserID` and `get_user_id` both
+/// give `["get", "user", "id"]`
+pub fn identifier_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        // Split on lower→Upper and at the end of an acronym (IDParser → ID, Parser)
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1);
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit()
+                || (p.is_uppercase() && next.is_some_and(|n| n.is_lowercase())));
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,6 +515,14 @@ mod tests {
         assert_eq!(Language::from_extension("unknown"), Language::Unknown);
     }
 
+    #[test]
+    fn test_identifier_words() {
+        assert_eq!(identifier_words("getUserID"), vec!["get", "user", "id"]);
+        assert_eq!(identifier_words("get_user_id"), vec!["get", "user", "id"]);
+        assert_eq!(identifier_words("HTTPServerError"), vec!["http", "server", "error"]);
+        assert_eq!(identifier_words("XMLHttpRequest"), vec!["xml", "http", "request"]);
+    }
+
     #[test]
     fn test_parse_rust_code() {
         let mut parser = CodeParser::new().unwrap();
@@ -0,0 +1,212 @@
+//! Permissions for what NEXUS does on its own
+//!
+//! Reading files it picked itself, writing AI edits, running commands and
+//! sending code over the network each need a permission. `[permissions]` in
+//! `.nexus.toml` sets each to `allow`, `ask` or `deny`; `--grant` and
+//! `--deny-permission` override that for one run. `ask` prompts, where
+//! "always" grants the permission for the rest of the session, and refuses
+//! when there is no terminal to prompt on.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::{PermissionsConfig, ProjectConfig};
+use crate::ui::NexusForm;
+
+/// Permissions of this process
+static ACTIVE: OnceLock<Permissions> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    Read,
+    Write,
+    Run,
+    Network,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 4] = [Permission::Read, Permission::Write, Permission::Run, Permission::Network];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Permission::Read => "read",
+            Permission::Write => "write",
+            Permission::Run => "run",
+            Permission::Network => "network",
+        }
+    }
+
+    /// Parse a name as given to `--grant` and `--deny-permission`
+    pub fn parse(name: &str) -> Result<Self> {
+        match Self::ALL.iter().find(|p| p.name() == name.trim()) {
+            Some(permission) => Ok(*permission),
+            None => bail!("Unknown permission `{}`; expected read, write, run or network", name),
+        }
+    }
+}
+
+/// What happens when a permission is requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// Answer to a permission prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Once,
+    Always,
+    No,
+}
+
+/// Project defaults with the command line overrides applied
+#[derive(Debug)]
+pub struct Permissions {
+    config: PermissionsConfig,
+    /// Granted with "always" at a prompt
+    session: Mutex<BTreeSet<Permission>>,
+}
+
+impl Permissions {
+    /// `config` with `grant` set to allow and `deny` to deny; a permission in
+    /// both is denied
+    pub fn new(mut config: PermissionsConfig, grant: &[Permission], deny: &[Permission]) -> Self {
+        for permission in grant {
+            *config.access_mut(*permission) = Access::Allow;
+        }
+        for permission in deny {
+            *config.access_mut(*permission) = Access::Deny;
+        }
+        Self { config, session: Mutex::new(BTreeSet::new()) }
+    }
+
+    /// Apply `root`'s permissions, with the overrides, to this process
+    pub fn install(root: &Path, grant: &[Permission], deny: &[Permission]) {
+        let config = match ProjectConfig::load(root) {
+            Ok(project) => project.permissions,
+            Err(e) => {
+                tracing::warn!("Project permissions not loaded, using the defaults: {:#}", e);
+                PermissionsConfig::default()
+            }
+        };
+        ACTIVE.set(Self::new(config, grant, deny)).ok();
+    }
+
+    pub fn access(&self, permission: Permission) -> Access {
+        self.config.access(permission)
+    }
+
+    /// Check `permission` for `action`, calling `ask` when it has to be asked
+    pub fn request_with(
+        &self,
+        permission: Permission,
+        action: &str,
+        ask: impl FnOnce(&str) -> Answer,
+    ) -> Result<()> {
+        match self.access(permission) {
+            Access::Allow => Ok(()),
+            Access::Deny => bail!(
+                "Permission denied: {} ({}). Allow it with `--grant {}` or `{} = \"allow\"` under [permissions] in .nexus.toml",
+                permission.name(), action, permission.name(), permission.name()
+            ),
+            Access::Ask => {
+                if self.session.lock().map(|s| s.contains(&permission)).unwrap_or(false) {
+                    return Ok(());
+                }
+                match ask(&format!("Allow NEXUS to {}?", action)) {
+                    Answer::Once => Ok(()),
+                    Answer::Always => {
+                        if let Ok(mut session) = self.session.lock() {
+                            session.insert(permission);
+                        }
+                        Ok(())
+                    }
+                    Answer::No => bail!("Permission declined: {} ({})", permission.name(), action),
+                }
+            }
+        }
+    }
+
+    /// Check `permission` for `action`, prompting on the terminal when asked
+    pub fn request(&self, permission: Permission, action: &str) -> Result<()> {
+        self.request_with(permission, action, |question| prompt(permission, question))
+    }
+}
+
+/// Check `permission` for `action` against this process's permissions;
+/// everything is allowed when none are installed
+pub fn request(permission: Permission, action: &str) -> Result<()> {
+    match ACTIVE.get() {
+        Some(permissions) => permissions.request(permission, action),
+        None => Ok(()),
+    }
+}
+
+/// Write `contents` to `path` once the write permission allows it
+pub fn write_file(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    request(Permission::Write, &format!("write {}", path.display()))?;
+    std::fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Parse `--grant`/`--deny-permission` values
+pub fn parse_list(names: &[String]) -> Result<Vec<Permission>> {
+    names.iter().map(|name| Permission::parse(name)).collect()
+}
+
+fn prompt(permission: Permission, question: &str) -> Answer {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        tracing::warn!("No terminal to ask for the {} permission; refusing", permission.name());
+        return Answer::No;
+    }
+    let always = format!("Allow {} for the rest of this session", permission.name());
+    let choices = [
+        ("Yes", "Allow this once"),
+        ("Always", always.as_str()),
+        ("No", "Refuse"),
+    ];
+    // Commands default to no, as their confirmations did before
+    let recommended = if permission == Permission::Run { 2 } else { 0 };
+    match NexusForm::ask_choice(question, &choices, Some(recommended)) {
+        Ok(0) => Answer::Once,
+        Ok(1) => Answer::Always,
+        _ => Answer::No,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_overrides_and_session_grants() {
+        assert_eq!(parse_list(&["write".to_string(), " run".to_string()]).unwrap(), [Permission::Write, Permission::Run]);
+        assert!(Permission::parse("shell").is_err());
+
+        let defaults = Permissions::new(PermissionsConfig::default(), &[], &[]);
+        assert_eq!(defaults.access(Permission::Read), Access::Allow);
+        assert_eq!(defaults.access(Permission::Write), Access::Ask);
+
+        let permissions = Permissions::new(PermissionsConfig::default(), &[Permission::Run, Permission::Network], &[Permission::Network]);
+        assert_eq!(permissions.access(Permission::Run), Access::Allow);
+        let denied = permissions.request_with(Permission::Network, "send code to Claude", |_| Answer::Always);
+        assert!(denied.unwrap_err().to_string().contains("--grant network"));
+
+        // "Yes" asks again next time, "always" does not
+        assert!(permissions.request_with(Permission::Write, "write a.rs", |_| Answer::No).is_err());
+        permissions.request_with(Permission::Write, "write a.rs", |_| Answer::Once).unwrap();
+        permissions.request_with(Permission::Write, "write b.rs", |_| Answer::Always).unwrap();
+        permissions
+            .request_with(Permission::Write, "write c.rs", |_| panic!("asked after an always grant"))
+            .unwrap();
+    }
+}
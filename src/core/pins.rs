@@ -0,0 +1,288 @@
+//! Pinned chat context
+//!
+//! Files, symbols and notes the user pins in a chat stay in the prompt on
+//! every turn, outside the message history, so they survive `/clear` and
+//! `/compact`. Files and symbols are re-read each turn so edits show up.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::parser::{CodeParser, SymbolKind};
+use crate::ai::context::{self, CloudExclusions};
+use crate::index;
+
+/// Lines kept per pinned file or symbol
+pub const MAX_PIN_LINES: usize = 200;
+
+/// What a pin refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinTarget {
+    File(PathBuf),
+    Symbol { name: String, path: PathBuf },
+    Note(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Pin {
+    pub id: usize,
+    pub target: PinTarget,
+    /// Project root the pin's path is relative to
+    pub root: PathBuf,
+}
+
+impl Pin {
+    pub fn label(&self) -> String {
+        match &self.target {
+            PinTarget::File(path) => path.display().to_string(),
+            PinTarget::Symbol { name, path } => format!("{} ({})", name, path.display()),
+            PinTarget::Note(text) => {
+                let mut label: String = text.chars().take(50).collect();
+                if label.chars().count() < text.chars().count() {
+                    label.push('…');
+                }
+                format!("note: {}", label)
+            }
+        }
+    }
+}
+
+/// The pins of one chat session
+#[derive(Debug)]
+pub struct Pins {
+    /// Project root new pins are resolved in
+    root: PathBuf,
+    pins: Vec<Pin>,
+    next_id: usize,
+}
+
+impl Pins {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf(), pins: Vec::new(), next_id: 1 }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve later pins in another project; existing pins keep reading
+    /// from the project they were pinned in
+    pub fn set_root(&mut self, root: &Path) {
+        self.root = root.to_path_buf();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Pin> {
+        self.pins.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pins.is_empty()
+    }
+
+    /// Pin a file path, a symbol (`parse_config`, `Config::load`), or a note
+    /// (`note: ...`, or any text with spaces)
+    pub fn add(&mut self, target: &str) -> Result<&Pin> {
+        let target = resolve(target.trim(), &self.root)?;
+        if let Some(pos) = self.pins.iter().position(|p| p.target == target && p.root == self.root) {
+            return Ok(&self.pins[pos]);
+        }
+
+        self.pins.push(Pin { id: self.next_id, target, root: self.root.clone() });
+        self.next_id += 1;
+        Ok(&self.pins[self.pins.len() - 1])
+    }
+
+    /// Remove a pin by number (`2`, `#2`) or by its file or symbol name
+    pub fn remove(&mut self, which: &str) -> Option<Pin> {
+        let which = which.trim();
+        let pos = match which.trim_start_matches('#').parse::<usize>() {
+            Ok(id) => self.pins.iter().position(|p| p.id == id),
+            Err(_) => self.pins.iter().position(|p| match &p.target {
+                PinTarget::File(path) => path == Path::new(which),
+                PinTarget::Symbol { name, .. } => name == which,
+                PinTarget::Note(text) => text == which,
+            }),
+        }?;
+        Some(self.pins.remove(pos))
+    }
+
+    pub fn clear(&mut self) {
+        self.pins.clear();
+    }
+
+    /// Pinned items as a prompt section, read fresh from disk. Files
+    /// excluded from the cloud contribute only their signatures, by the
+    /// rules in `exclusions` of the project each was pinned in.
+    pub fn render(&self, exclusions: &[CloudExclusions]) -> Option<String> {
+        if self.pins.is_empty() {
+            return None;
+        }
+
+        let mut out = format!(
+            "## Pinned Context\n\nThe user pinned these items to keep them in view for the whole conversation.\n{}\n",
+            context::REPOSITORY_CONTENT_RULES
+        );
+        for pin in &self.pins {
+            let read = |path: &Path| match exclusions.iter().find(|e| e.root() == pin.root) {
+                Some(rules) => rules.read_file(&pin.root.join(path)),
+                None => anyhow::bail!("No exclusion rules for {}", pin.root.display()),
+            };
+            let body = match &pin.target {
+                PinTarget::File(path) => match read(path) {
+                    Ok(content) => context::delimit(&path.display().to_string(), &capped(&context::sanitize(&content))),
+                    Err(_) => "(file no longer exists)".to_string(),
+                },
+                PinTarget::Symbol { name, path } => match read(path)
+                    .ok()
+                    .and_then(|content| symbol_source(name, path, content))
+                {
+                    Some(code) => context::delimit(&format!("{} · {}", path.display(), name), &context::sanitize(&code)),
+                    None => "(symbol no longer found)".to_string(),
+                },
+                PinTarget::Note(text) => text.clone(),
+            };
+            out.push_str(&format!("\n### #{} {}\n\n{}\n", pin.id, pin.label(), body));
+        }
+        Some(out)
+    }
+}
+
+fn capped(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() <= MAX_PIN_LINES {
+        return content.to_string();
+    }
+    format!("{}\n... ({} more lines)", lines[..MAX_PIN_LINES].join("\n"), lines.len() - MAX_PIN_LINES)
+}
+
+/// Source of a symbol in a file's content
+fn symbol_source(name: &str, path: &Path, content: String) -> Option<String> {
+    let parsed = CodeParser::new().ok()?.parse_source(path, content).ok()?;
+    let (owner, short) = split_qualified(name);
+    let lines: Vec<&str> = parsed.content.lines().collect();
+
+    let symbol = parsed.symbols.iter().filter(|s| s.name == short && s.kind != SymbolKind::Impl).find(|s| {
+        owner.is_none_or(|o| {
+            // Rust impl blocks are named `impl Type`
+            parsed.symbols.iter().any(|p| {
+                (p.name == o || p.name.strip_prefix("impl ") == Some(o))
+                    && p.line_start < s.line_start
+                    && p.line_end >= s.line_end
+            })
+        })
+    })?;
+    let start = symbol.line_start.saturating_sub(1);
+    let end = symbol.line_end.min(lines.len());
+    Some(capped(&lines[start..end].join("\n")))
+}
+
+/// `Config::load` / `Config.load` → (`Some("Config")`, `"load"`)
+fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    match name.rsplit_once("::").or_else(|| name.rsplit_once('.')) {
+        Some((owner, short)) => (Some(owner.rsplit("::").next().unwrap_or(owner)), short),
+        None => (None, name),
+    }
+}
+
+/// What `/pin <target>` refers to
+fn resolve(target: &str, root: &Path) -> Result<PinTarget> {
+    if let Some(note) = target.strip_prefix("note:") {
+        let note = note.trim();
+        if note.is_empty() {
+            bail!("Empty note");
+        }
+        return Ok(PinTarget::Note(note.to_string()));
+    }
+    if target.is_empty() {
+        bail!("Nothing to pin");
+    }
+    if root.join(target).is_file() {
+        return Ok(PinTarget::File(PathBuf::from(target)));
+    }
+    if target.contains(char::is_whitespace) {
+        return Ok(PinTarget::Note(target.to_string()));
+    }
+
+    let (_, short) = split_qualified(target);
+    let mut found: Vec<PathBuf> = Vec::new();
+    for file in index::collect_files(root, false)? {
+        // Only parse files that mention the name
+        let Some(content) = fs::read_to_string(&file).ok().filter(|c| c.contains(short)) else {
+            continue;
+        };
+        if symbol_source(target, &file, content).is_some() {
+            found.push(file.strip_prefix(root).unwrap_or(&file).to_path_buf());
+        }
+    }
+
+    // Definitions in tests rarely are what the user means
+    if found.len() > 1 {
+        found.retain(|p| !index::is_test_path(p));
+    }
+    match found.as_slice() {
+        [] => bail!("No file or symbol named `{}`; use `/pin note: ...` for free text", target),
+        [path] => Ok(PinTarget::Symbol { name: target.to_string(), path: path.clone() }),
+        paths => {
+            let listed: Vec<String> = paths.iter().take(3).map(|p| p.display().to_string()).collect();
+            bail!("`{}` is defined in {} files ({}); pin the file or qualify the name", target, paths.len(), listed.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_pin_file_symbol_and_note() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/model.rs"),
+            "pub struct Order {\n    pub id: u64,\n}\n\nimpl Order {\n    pub fn total(&self) -> u64 {\n        0\n    }\n}\n\npub fn total() {}\n",
+        )
+        .unwrap();
+
+        let mut pins = Pins::new(root);
+        assert_eq!(pins.add("src/model.rs").unwrap().id, 1);
+        let symbol = pins.add("Order::total").unwrap();
+        assert_eq!(symbol.target, PinTarget::Symbol { name: "Order::total".to_string(), path: PathBuf::from("src/model.rs") });
+        assert!(matches!(pins.add("always use u64 cents").unwrap().target, PinTarget::Note(_)));
+        assert!(pins.add("Missing").is_err());
+        // Pinning the same thing twice keeps one pin
+        assert_eq!(pins.add("src/model.rs").unwrap().id, 1);
+        assert_eq!(pins.iter().count(), 3);
+
+        let exclusions = vec![CloudExclusions::for_root(&Config::default().privacy, root).unwrap()];
+        // Edits show up on the next turn
+        fs::write(root.join("src/model.rs"), "pub struct Order {\n    pub id: u64,\n    pub cents: u64,\n}\n\nimpl Order {\n    pub fn total(&self) -> u64 {\n        self.cents\n    }\n}\n").unwrap();
+        let rendered = pins.render(&exclusions).unwrap();
+        assert!(rendered.contains("pub cents: u64"));
+        assert!(rendered.contains("### #2 Order::total"));
+        assert!(rendered.contains("    pub fn total(&self) -> u64 {\n        self.cents\n    }\n</repository_file>"));
+        assert!(rendered.contains("always use u64 cents"));
+
+        assert!(pins.remove("#2").is_some());
+        assert!(pins.remove("src/model.rs").is_some());
+        assert!(pins.remove("9").is_none());
+        assert_eq!(pins.iter().count(), 1);
+
+        // After switching projects, new pins resolve there and old ones
+        // keep reading from their own project
+        let other_dir = tempfile::tempdir().unwrap();
+        fs::write(other_dir.path().join("README.md"), "# Billing service\n").unwrap();
+        pins.add("src/model.rs").unwrap();
+        pins.set_root(other_dir.path());
+        assert!(pins.add("src/model.rs").is_err());
+        pins.add("README.md").unwrap();
+        let mut exclusions = exclusions;
+        exclusions.push(CloudExclusions::for_root(&Config::default().privacy, other_dir.path()).unwrap());
+        let rendered = pins.render(&exclusions).unwrap();
+        assert!(rendered.contains("pub cents: u64"));
+        assert!(rendered.contains("# Billing service"));
+    }
+}
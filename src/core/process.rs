@@ -0,0 +1,85 @@
+//! Child processes with a time limit
+//!
+//! Hooks, graders and eval checks run programs that read a document on
+//! stdin and answer on stdout, and shrink reruns a crashing command. Stdin
+//! is written and the outputs are read on their own threads, so neither
+//! side can block on a full pipe, and the child is killed once it runs past
+//! its time limit.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::io::{Read, Write};
+use std::process::{Command, Output, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Run `command` with `stdin` as its input; `None` when it ran past
+/// `timeout` and was killed
+pub fn run_with_timeout(command: &mut Command, stdin: Vec<u8>, timeout: Duration) -> Result<Option<Output>> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let mut pipe = child.stdin.take();
+    let writer = std::thread::spawn(move || {
+        if let Some(pipe) = pipe.as_mut() {
+            pipe.write_all(&stdin).ok();
+        }
+    });
+    let reader = read_pipe(child.stdout.take());
+    let errors = read_pipe(child.stderr.take());
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            child.kill().ok();
+            child.wait().ok();
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    writer.join().ok();
+
+    Ok(Some(Output {
+        status,
+        stdout: reader.join().unwrap_or_default(),
+        stderr: errors.join().unwrap_or_default(),
+    }))
+}
+
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buf).ok();
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_with_timeout() {
+        // More than a pipe buffer each way
+        let input = "x".repeat(1 << 20).into_bytes();
+        let output = run_with_timeout(Command::new("sh").args(["-c", "cat; echo done >&2"]), input.clone(), Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, input);
+        assert_eq!(output.stderr, b"done\n");
+
+        let slow = run_with_timeout(Command::new("sh").args(["-c", "sleep 5"]), Vec::new(), Duration::from_millis(100)).unwrap();
+        assert!(slow.is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Provenance tracking for generated code
+//!
+//! Flags generated code that is a near-verbatim copy of existing code,
+//! either in the local project or (when configured) in public repositories
+//! found through a code search API. The result is stored with the applied
+//! change in the journal so license questions can be audited later.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::permissions::{self, Permission};
+use crate::config::ComplianceConfig;
+use crate::index::{self, similarity};
+
+/// Distinctive lines queried against the public code search
+const MAX_SEARCH_QUERIES: usize = 3;
+
+/// Shortest line considered distinctive enough to search for
+const MIN_QUERY_LENGTH: usize = 30;
+
+/// Results kept per public search query
+const MAX_RESULTS_PER_QUERY: usize = 5;
+
+/// Near-copy found in the local project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalMatch {
+    pub path: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub score: f32,
+}
+
+/// Exact line match found through the public code search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicMatch {
+    pub repository: String,
+    pub path: String,
+    pub url: String,
+}
+
+/// Where a piece of generated code came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Provenance {
+    /// AI provider that produced the code
+    pub provider: String,
+    /// Request the code was generated from
+    pub prompt: String,
+    /// Whether a similarity check was run
+    #[serde(default)]
+    pub checked: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_matches: Vec<LocalMatch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub public_matches: Vec<PublicMatch>,
+}
+
+impl Provenance {
+    pub fn new(provider: &str, prompt: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            prompt: prompt.to_string(),
+            checked: false,
+            local_matches: Vec::new(),
+            public_matches: Vec::new(),
+        }
+    }
+
+    /// Whether the code looks copied from somewhere
+    pub fn is_flagged(&self) -> bool {
+        !self.local_matches.is_empty() || !self.public_matches.is_empty()
+    }
+
+    /// Run the configured similarity checks against `code`
+    ///
+    /// `exclude` is the file about to be written, which would otherwise
+    /// match itself when overwritten.
+    pub async fn check(&mut self, code: &str, root: &Path, exclude: Option<&Path>, config: &ComplianceConfig) -> Result<()> {
+        self.local_matches = local_matches(code, root, exclude, config.similarity_threshold)?;
+
+        if let Some(url) = &config.code_search_url {
+            let token = std::env::var(&config.code_search_token_env).ok();
+            self.public_matches = public_matches(code, url, token.as_deref()).await?;
+        }
+
+        self.checked = true;
+        Ok(())
+    }
+}
+
+/// Indexed files sharing most of their code with `code`
+pub fn local_matches(code: &str, root: &Path, exclude: Option<&Path>, threshold: f32) -> Result<Vec<LocalMatch>> {
+    let excluded = exclude.and_then(|p| p.canonicalize().ok());
+    let files: Vec<_> = index::collect_files(root, false)?
+        .into_iter()
+        .filter(|f| excluded.is_none() || f.canonicalize().ok() != excluded)
+        .collect();
+
+    Ok(similarity::find_similar(code, &files, threshold)
+        .into_iter()
+        .map(|m| LocalMatch {
+            path: m.path.strip_prefix(root).unwrap_or(&m.path).display().to_string(),
+            line_start: m.line_start,
+            line_end: m.line_end,
+            score: m.score,
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    items: Vec<SearchItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchItem {
+    path: String,
+    html_url: String,
+    repository: SearchRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRepository {
+    full_name: String,
+}
+
+/// Search a GitHub-compatible code search API for the most distinctive lines
+pub async fn public_matches(code: &str, url: &str, token: Option<&str>) -> Result<Vec<PublicMatch>> {
+    permissions::request(Permission::Network, &format!("search {} for copies of the generated code", url))?;
+    let client = reqwest::Client::new();
+    let mut seen = HashSet::new();
+    let mut matches = Vec::new();
+
+    for line in distinctive_lines(code) {
+        let mut request = client
+            .get(url)
+            .query(&[("q", format!("\"{}\"", line)), ("per_page", MAX_RESULTS_PER_QUERY.to_string())])
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "nexus-forge");
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await
+            .with_context(|| format!("Failed to reach code search at {}", url))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Code search returned {}", response.status());
+        }
+
+        let results: SearchResponse = response.json().await
+            .context("Failed to parse code search response")?;
+
+        for item in results.items {
+            if seen.insert(item.html_url.clone()) {
+                matches.push(PublicMatch {
+                    repository: item.repository.full_name,
+                    path: item.path,
+                    url: item.html_url,
+                });
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Longest lines of code that are not comments, imports or boilerplate
+fn distinctive_lines(code: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = code
+        .lines()
+        .map(str::trim)
+        .filter(|l| l.len() >= MIN_QUERY_LENGTH)
+        .filter(|l| !["//", "#", "/*", "*", "use ", "import ", "from ", "\"\"\""].iter().any(|p| l.starts_with(p)))
+        .collect();
+
+    lines.sort_by_key(|l| std::cmp::Reverse(l.len()));
+    lines.dedup();
+    lines.truncate(MAX_SEARCH_QUERIES);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distinctive_lines() {
+        let code = "use std::collections::HashMap;\n// a fairly long comment that should be ignored\nfn short() {}\nlet total = items.iter().map(|i| i.price * i.quantity).sum::<f64>();";
+        assert_eq!(distinctive_lines(code), vec!["let total = items.iter().map(|i| i.price * i.quantity).sum::<f64>();"]);
+    }
+
+    #[test]
+    fn test_local_matches_excludes_target() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let code = "pub fn checksum(data: &[u8]) -> u32 {\n    data.iter().fold(0u32, |acc, b| acc.rotate_left(5) ^ *b as u32)\n}\n";
+        std::fs::write(temp_dir.path().join("a.rs"), code).unwrap();
+        std::fs::write(temp_dir.path().join("b.rs"), code).unwrap();
+
+        let target = temp_dir.path().join("b.rs");
+        let matches = local_matches(code, temp_dir.path(), Some(&target), 0.8).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "a.rs");
+    }
+}
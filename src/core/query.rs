@@ -0,0 +1,275 @@
+//! Structured symbol queries
+//!
+//! A small filter language over parsed symbols and the call graph:
+//!
+//! ```text
+//! kind:fn name:parse* lang:rust path:src/ai/** calls:send_message
+//! ```
+//!
+//! Terms are ANDed. `kind:` takes alternatives (`kind:struct,enum`), `name:`
+//! and `path:` take globs, `calls:` / `calledby:` take a function name or
+//! `Owner::name`, and a leading `-` negates a term. Bare words must appear
+//! in the symbol name.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::callgraph::CallGraph;
+use super::parser::{Language, ParsedFile, Symbol, SymbolKind};
+
+/// Keys the query language understands
+pub const KEYS: &[&str] = &["kind", "name", "lang", "path", "calls", "calledby"];
+
+#[derive(Debug)]
+enum Filter {
+    Kind(Vec<SymbolKind>),
+    Name(GlobMatcher),
+    Lang(Language),
+    Path(GlobMatcher),
+    Calls(Pattern),
+    CalledBy(Pattern),
+    Word(String),
+}
+
+/// A function name pattern for `calls:` and `calledby:`
+#[derive(Debug)]
+struct Pattern {
+    owner: Option<String>,
+    name: GlobMatcher,
+}
+
+#[derive(Debug)]
+struct Term {
+    filter: Filter,
+    negated: bool,
+}
+
+/// A parsed query
+#[derive(Debug)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+/// A symbol matching a query
+#[derive(Debug, Clone)]
+pub struct QueryHit {
+    pub path: PathBuf,
+    pub language: Language,
+    pub symbol: Symbol,
+}
+
+impl Query {
+    /// Whether `text` uses the query syntax rather than plain search words
+    pub fn is_structured(text: &str) -> bool {
+        text.split_whitespace().any(|token| {
+            let token = token.strip_prefix('-').unwrap_or(token);
+            token.split_once(':').is_some_and(|(key, _)| KEYS.contains(&key.to_lowercase().as_str()))
+        })
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut terms = Vec::new();
+        for token in text.split_whitespace() {
+            let (negated, token) = match token.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, token),
+            };
+            let filter = match token.split_once(':') {
+                Some((key, value)) if KEYS.contains(&key.to_lowercase().as_str()) => {
+                    if value.is_empty() {
+                        bail!("`{}:` needs a value", key);
+                    }
+                    parse_filter(&key.to_lowercase(), value)?
+                }
+                _ => Filter::Word(token.to_lowercase()),
+            };
+            terms.push(Term { filter, negated });
+        }
+        if terms.is_empty() {
+            bail!("Empty query");
+        }
+        Ok(Self { terms })
+    }
+
+    fn needs_call_graph(&self) -> bool {
+        self.terms.iter().any(|t| matches!(t.filter, Filter::Calls(_) | Filter::CalledBy(_)))
+    }
+
+    /// Symbols in `files` matching every term, in file order. Paths are
+    /// matched relative to `root`.
+    pub fn run(&self, files: &[ParsedFile], root: &Path) -> Vec<QueryHit> {
+        let graph = self.needs_call_graph().then(|| CallGraph::from_parsed(files));
+        let functions: HashMap<(&Path, usize), usize> = graph
+            .as_ref()
+            .map(|g| {
+                g.functions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| ((f.path.as_path(), f.line_start), i))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut hits = Vec::new();
+        for file in files {
+            let relative = file.path.strip_prefix(root).unwrap_or(&file.path);
+            for symbol in &file.symbols {
+                let function = functions.get(&(file.path.as_path(), symbol.line_start)).copied();
+                let matched = self.terms.iter().all(|term| {
+                    let is_match = match &term.filter {
+                        Filter::Kind(kinds) => kinds.contains(&symbol.kind),
+                        Filter::Name(glob) => glob.is_match(&symbol.name),
+                        Filter::Lang(language) => file.language == *language,
+                        Filter::Path(glob) => glob.is_match(relative),
+                        Filter::Word(word) => symbol.name.to_lowercase().contains(word),
+                        Filter::Calls(pattern) => match (&graph, function) {
+                            (Some(graph), Some(caller)) => graph
+                                .calls_from(caller)
+                                .iter()
+                                .any(|call| pattern.matches(graph, call.callee)),
+                            _ => false,
+                        },
+                        Filter::CalledBy(pattern) => match (&graph, function) {
+                            (Some(graph), Some(callee)) => (0..graph.functions.len()).any(|caller| {
+                                pattern.matches(graph, caller)
+                                    && graph.calls_from(caller).iter().any(|call| call.callee == callee)
+                            }),
+                            _ => false,
+                        },
+                    };
+                    is_match != term.negated
+                });
+
+                if matched {
+                    hits.push(QueryHit {
+                        path: file.path.clone(),
+                        language: file.language,
+                        symbol: symbol.clone(),
+                    });
+                }
+            }
+        }
+        hits
+    }
+}
+
+impl Pattern {
+    fn parse(value: &str) -> Result<Self> {
+        let (owner, name) = match value.rsplit_once("::").or_else(|| value.rsplit_once('.')) {
+            Some((owner, name)) => (Some(owner.to_string()), name),
+            None => (None, value),
+        };
+        Ok(Self { owner, name: glob(name, false)? })
+    }
+
+    fn matches(&self, graph: &CallGraph, function: usize) -> bool {
+        let function = &graph.functions[function];
+        self.name.is_match(&function.name)
+            && self.owner.as_deref().is_none_or(|o| function.owner.as_deref() == Some(o))
+    }
+}
+
+fn parse_filter(key: &str, value: &str) -> Result<Filter> {
+    Ok(match key {
+        "kind" => Filter::Kind(
+            value
+                .split(',')
+                .map(|k| parse_kind(k).with_context(|| format!("Unknown kind `{}` (try fn, struct, class, enum, trait, interface, mod, const, impl, type)", k)))
+                .collect::<Result<_>>()?,
+        ),
+        "name" => Filter::Name(glob(value, false)?),
+        "lang" => Filter::Lang(parse_language(value).with_context(|| format!("Unknown language `{}` (try rust, python, js, ts)", value))?),
+        "path" => {
+            // A plain directory matches everything below it
+            let pattern = if value.contains(['*', '?', '[', '{']) {
+                value.to_string()
+            } else {
+                format!("{}/**", value.trim_end_matches('/'))
+            };
+            Filter::Path(glob(&pattern, true)?)
+        }
+        "calls" => Filter::Calls(Pattern::parse(value)?),
+        "calledby" => Filter::CalledBy(Pattern::parse(value)?),
+        _ => bail!("Unknown key `{}`", key),
+    })
+}
+
+fn glob(pattern: &str, literal_separator: bool) -> Result<GlobMatcher> {
+    Ok(GlobBuilder::new(pattern)
+        .case_insensitive(!literal_separator)
+        .literal_separator(literal_separator)
+        .build()
+        .with_context(|| format!("Invalid pattern `{}`", pattern))?
+        .compile_matcher())
+}
+
+fn parse_kind(kind: &str) -> Option<SymbolKind> {
+    Some(match kind.to_lowercase().as_str() {
+        "fn" | "func" | "function" | "method" | "def" => SymbolKind::Function,
+        "struct" => SymbolKind::Struct,
+        "class" => SymbolKind::Class,
+        "enum" => SymbolKind::Enum,
+        "trait" => SymbolKind::Trait,
+        "interface" => SymbolKind::Interface,
+        "mod" | "module" => SymbolKind::Module,
+        "const" | "constant" => SymbolKind::Constant,
+        "impl" => SymbolKind::Impl,
+        "type" | "alias" => SymbolKind::TypeAlias,
+        _ => return None,
+    })
+}
+
+pub fn parse_language(language: &str) -> Option<Language> {
+    Some(match language.to_lowercase().as_str() {
+        "rust" | "rs" => Language::Rust,
+        "python" | "py" => Language::Python,
+        "javascript" | "js" => Language::JavaScript,
+        "typescript" | "ts" => Language::TypeScript,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+    use std::fs;
+
+    #[test]
+    fn test_structured_query() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/ai")).unwrap();
+        fs::write(
+            root.join("src/ai/client.rs"),
+            "pub struct Client;\n\nimpl Client {\n    pub fn send_message(&self) {}\n\n    pub fn parse_reply(&self) {\n        self.send_message();\n    }\n}\n\npub fn parse_config() {}\n",
+        )
+        .unwrap();
+        fs::write(root.join("src/main.rs"), "fn parse_args() {}\n\nenum Mode { A }\n").unwrap();
+
+        let mut parser = CodeParser::new().unwrap();
+        let files: Vec<ParsedFile> = ["src/ai/client.rs", "src/main.rs"]
+            .iter()
+            .map(|f| parser.parse_file(&root.join(f)).unwrap())
+            .collect();
+        let names = |text: &str| -> Vec<String> {
+            Query::parse(text).unwrap().run(&files, root).into_iter().map(|h| h.symbol.name).collect()
+        };
+
+        assert_eq!(names("kind:fn name:parse* lang:rust path:src/ai/** calls:send_message"), ["parse_reply"]);
+        assert_eq!(names("kind:fn name:parse*"), ["parse_reply", "parse_config", "parse_args"]);
+        assert_eq!(names("kind:fn name:parse* -path:src/ai"), ["parse_args"]);
+        assert_eq!(names("kind:struct,enum"), ["Client", "Mode"]);
+        assert_eq!(names("calledby:Client::parse_reply"), ["send_message"]);
+        assert_eq!(names("kind:fn config"), ["parse_config"]);
+
+        assert!(Query::is_structured("kind:fn parse"));
+        assert!(!Query::is_structured("error handling: retries"));
+        assert!(Query::parse("kind:lambda").is_err());
+        assert!(Query::parse("lang:").is_err());
+    }
+}
@@ -0,0 +1,541 @@
+//! HTTP traffic recording
+//!
+//! A small HTTP/1.1 reverse proxy that forwards requests to a service under
+//! test and records each exchange as JSON lines in `.nexus/recordings/`.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+use super::journal::{create_project_dir, PROJECT_DIR};
+use super::permissions::{self, Permission};
+use crate::index::endpoints::Endpoint;
+
+const RECORDINGS_DIR: &str = "recordings";
+
+/// Body bytes kept per request or response
+const MAX_RECORDED_BODY: usize = 16 * 1024;
+
+/// Largest request body the proxy will buffer
+const MAX_REQUEST_BODY: usize = 32 * 1024 * 1024;
+
+/// Headers that carry credentials and are never written to disk
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie", "proxy-authorization", "x-api-key"];
+
+/// JSON and form fields whose values are never written to disk; matched as
+/// parts of the field name, so `access_token` and `userPassword` count
+const SENSITIVE_FIELDS: &[&str] = &[
+    "password", "passwd", "token", "secret", "api_key", "apikey", "credential", "private_key", "session",
+];
+
+/// Connection-level headers that are not forwarded
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection", "keep-alive", "proxy-connection", "transfer-encoding", "te", "trailer", "upgrade",
+    "host", "content-length",
+];
+
+/// Header name/value pairs in wire order
+pub type Headers = Vec<(String, String)>;
+
+/// One recorded request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+    pub timestamp: DateTime<Utc>,
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    pub request_headers: Headers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub response_headers: Headers,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl Exchange {
+    /// Path without the query string
+    pub fn route(&self) -> &str {
+        self.path.split('?').next().unwrap_or(&self.path)
+    }
+
+    pub fn response_header(&self, name: &str) -> Option<&str> {
+        self.response_headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// This exchange with credentials in headers, query strings and bodies
+    /// replaced, for recordings made before bodies were redacted
+    pub fn redacted(&self) -> Exchange {
+        Exchange {
+            path: redact_path(&self.path),
+            request_headers: redact_headers(self.request_headers.clone()),
+            request_body: self.request_body.as_deref().map(redact_body),
+            response_headers: redact_headers(self.response_headers.clone()),
+            response_body: self.response_body.as_deref().map(redact_body),
+            ..self.clone()
+        }
+    }
+
+    /// Structure of a JSON response body, if it has one
+    pub fn response_shape(&self) -> Option<Value> {
+        let body = self.response_body.as_deref()?;
+        serde_json::from_str(body).ok().map(|v| json_shape(&v))
+    }
+}
+
+/// A representative exchange for one endpoint and status
+pub struct Sample<'a> {
+    pub endpoint: Option<&'a Endpoint>,
+    pub exchange: &'a Exchange,
+    /// Recorded exchanges this sample stands for
+    pub count: usize,
+}
+
+/// Directory holding recordings for a project root
+pub fn recordings_dir(root: &Path) -> PathBuf {
+    root.join(PROJECT_DIR).join(RECORDINGS_DIR)
+}
+
+/// Path for a new recording
+pub fn new_recording_path(root: &Path) -> PathBuf {
+    recordings_dir(root).join(format!("{}.jsonl", Utc::now().format("%Y%m%d-%H%M%S")))
+}
+
+/// Most recent recording in a project, if any
+pub fn latest_recording(root: &Path) -> Option<PathBuf> {
+    fs::read_dir(recordings_dir(root))
+        .ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .max()
+}
+
+/// Read all exchanges from a recording
+pub fn load(path: &Path) -> Result<Vec<Exchange>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read recording {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect())
+}
+
+/// Pick one exchange per endpoint and status, in first-seen order
+pub fn representatives<'a>(exchanges: &'a [Exchange], endpoints: &'a [Endpoint]) -> Vec<Sample<'a>> {
+    let mut samples: Vec<Sample<'a>> = Vec::new();
+    let mut index: HashMap<(String, String, u16), usize> = HashMap::new();
+
+    for exchange in exchanges {
+        let endpoint = endpoints.iter().find(|e| e.matches(&exchange.method, exchange.route()));
+        let route = endpoint.map(|e| e.path.clone()).unwrap_or_else(|| exchange.route().to_string());
+        let key = (exchange.method.clone(), route, exchange.status);
+
+        match index.get(&key) {
+            Some(&i) => {
+                samples[i].count += 1;
+                // Prefer an example that shows the response structure
+                if samples[i].exchange.response_shape().is_none() && exchange.response_shape().is_some() {
+                    samples[i].exchange = exchange;
+                }
+            }
+            None => {
+                index.insert(key, samples.len());
+                samples.push(Sample { endpoint, exchange, count: 1 });
+            }
+        }
+    }
+
+    samples
+}
+
+/// Replace JSON values by their types, keeping keys and nesting
+pub fn json_shape(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), json_shape(v))).collect()),
+        Value::Array(items) => Value::Array(items.first().map(json_shape).into_iter().collect()),
+        Value::String(_) => Value::String("string".to_string()),
+        Value::Number(_) => Value::String("number".to_string()),
+        Value::Bool(_) => Value::String("boolean".to_string()),
+        Value::Null => Value::String("null".to_string()),
+    }
+}
+
+/// A body with the values of sensitive JSON or form fields replaced;
+/// other bodies are returned as they are
+pub fn redact_body(body: &str) -> String {
+    if let Ok(mut value) = serde_json::from_str::<Value>(body) {
+        redact_json(&mut value);
+        return serde_json::to_string(&value).unwrap_or_default();
+    }
+    let trimmed = body.trim();
+    let is_form = trimmed.contains('=') && !trimmed.contains(char::is_whitespace) && !trimmed.starts_with(['{', '[', '<']);
+    if is_form {
+        redact_form(trimmed)
+    } else {
+        body.to_string()
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive_field(key) {
+                    *value = Value::String("<redacted>".to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// `a=1&password=x` with the sensitive values replaced
+fn redact_form(form: &str) -> String {
+    form.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if is_sensitive_field(key) => format!("{}=<redacted>", key),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// A request path with sensitive query parameters replaced
+fn redact_path(path: &str) -> String {
+    match path.split_once('?') {
+        Some((route, query)) => format!("{}?{}", route, redact_form(query)),
+        None => path.to_string(),
+    }
+}
+
+fn is_sensitive_field(name: &str) -> bool {
+    let name = name.to_lowercase().replace('-', "_");
+    SENSITIVE_FIELDS.iter().any(|field| name.contains(field))
+}
+
+/// Normalize `:8080` to a loopback listen address
+pub fn listen_address(proxy: &str) -> String {
+    if proxy.starts_with(':') {
+        format!("127.0.0.1{}", proxy)
+    } else if proxy.chars().all(|c| c.is_ascii_digit()) {
+        format!("127.0.0.1:{}", proxy)
+    } else {
+        proxy.to_string()
+    }
+}
+
+/// Proxy `listen` to `target`, appending exchanges to `output` until
+/// Ctrl+C. `on_exchange` is called for each recorded exchange.
+pub async fn serve(
+    listen: &str,
+    target: &str,
+    output: &Path,
+    mut on_exchange: impl FnMut(&Exchange),
+) -> Result<usize> {
+    permissions::request(Permission::Network, &format!("forward requests to {}", target))?;
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+
+    if let Some(parent) = output.parent() {
+        create_project_dir(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(output)
+        .with_context(|| format!("Failed to open {}", output.display()))?;
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let target = target.trim_end_matches('/').to_string();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Exchange>();
+    let mut recorded = 0;
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let client = client.clone();
+                let target = target.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    if let Ok(Some(exchange)) = handle_connection(stream, &client, &target).await {
+                        tx.send(exchange).ok();
+                    }
+                });
+            }
+            Some(exchange) = rx.recv() => {
+                writeln!(file, "{}", serde_json::to_string(&exchange)?)
+                    .context("Failed to write recording")?;
+                recorded += 1;
+                on_exchange(&exchange);
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    Ok(recorded)
+}
+
+/// Forward a single request (one per connection) and record it
+async fn handle_connection(stream: TcpStream, client: &reqwest::Client, target: &str) -> Result<Option<Exchange>> {
+    let mut reader = BufReader::new(stream);
+
+    let mut head = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        if line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push_str(&line);
+    }
+
+    let (method, path, headers) = parse_request_head(&head)?;
+    let body = read_body(&mut reader, &headers).await?;
+
+    let started = Instant::now();
+    let mut request = client
+        .request(reqwest::Method::from_bytes(method.as_bytes())?, format!("{}{}", target, path))
+        .body(body.clone());
+    for (name, value) in &headers {
+        // Ask for identity encoding so recorded bodies stay readable
+        if !is_hop_by_hop(name) && !name.eq_ignore_ascii_case("accept-encoding") {
+            request = request.header(name.as_str(), value.as_str());
+        }
+    }
+
+    let (status, response_headers, response_body) = match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            let headers: Headers = response
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.to_string(), String::from_utf8_lossy(v.as_bytes()).to_string()))
+                .collect();
+            let body = response.bytes().await.map(|b| b.to_vec()).unwrap_or_default();
+            (status.as_u16(), headers, body)
+        }
+        Err(e) => (502, vec![("content-type".to_string(), "text/plain".to_string())], format!("nexus record: {}\n", e).into_bytes()),
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let reason = reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let mut reply = format!("HTTP/1.1 {} {}\r\n", status, reason);
+    for (name, value) in &response_headers {
+        if !is_hop_by_hop(name) {
+            reply.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    reply.push_str(&format!("content-length: {}\r\nconnection: close\r\n\r\n", response_body.len()));
+
+    let stream = reader.get_mut();
+    stream.write_all(reply.as_bytes()).await?;
+    stream.write_all(&response_body).await?;
+    stream.shutdown().await.ok();
+
+    Ok(Some(Exchange {
+        timestamp: Utc::now(),
+        method,
+        path: redact_path(&path),
+        request_headers: redact_headers(headers),
+        request_body: capture_body(&body),
+        status,
+        response_headers: redact_headers(response_headers),
+        response_body: capture_body(&response_body),
+        duration_ms,
+    }))
+}
+
+/// Split an HTTP/1.1 request head into method, path and headers
+pub fn parse_request_head(head: &str) -> Result<(String, String, Headers)> {
+    let mut lines = head.lines();
+    let request_line = lines.next().context("Empty request")?;
+    let mut parts = request_line.split_whitespace();
+
+    let method = parts.next().context("Missing request method")?.to_string();
+    let mut path = parts.next().context("Missing request path")?.to_string();
+
+    // Absolute-form targets, as sent to a configured HTTP proxy
+    if let Some(rest) = path.strip_prefix("http://").or_else(|| path.strip_prefix("https://")) {
+        path = rest.find('/').map(|i| rest[i..].to_string()).unwrap_or_else(|| "/".to_string());
+    }
+
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+
+    Ok((method, path, headers))
+}
+
+async fn read_body(reader: &mut BufReader<TcpStream>, headers: &[(String, String)]) -> Result<Vec<u8>> {
+    let header = |name: &str| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str());
+
+    if header("transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+        let mut body = Vec::new();
+        loop {
+            let mut size_line = String::new();
+            reader.read_line(&mut size_line).await?;
+            let size = usize::from_str_radix(size_line.trim().split(';').next().unwrap_or(""), 16)
+                .context("Malformed chunk size")?;
+            if size == 0 {
+                // Skip trailers up to the final empty line
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                }
+                return Ok(body);
+            }
+            if body.len() + size > MAX_REQUEST_BODY {
+                anyhow::bail!("Request body too large");
+            }
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).await?;
+            body.extend_from_slice(&chunk[..size]);
+        }
+    }
+
+    let length: usize = header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if length > MAX_REQUEST_BODY {
+        anyhow::bail!("Request body too large");
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+fn is_hop_by_hop(name: &str) -> bool {
+    HOP_BY_HOP_HEADERS.iter().any(|h| name.eq_ignore_ascii_case(h))
+}
+
+fn redact_headers(headers: Headers) -> Headers {
+    headers
+        .into_iter()
+        .map(|(k, v)| {
+            if SENSITIVE_HEADERS.iter().any(|s| k.eq_ignore_ascii_case(s)) {
+                (k, "<redacted>".to_string())
+            } else {
+                (k, v)
+            }
+        })
+        .collect()
+}
+
+/// Text bodies are kept, redacted and truncated; binary bodies are summarized
+fn capture_body(body: &[u8]) -> Option<String> {
+    if body.is_empty() {
+        return None;
+    }
+
+    match std::str::from_utf8(body).map(redact_body) {
+        Ok(text) if text.len() <= MAX_RECORDED_BODY => Some(text),
+        Ok(text) => {
+            let mut end = MAX_RECORDED_BODY;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            Some(format!("{}…", &text[..end]))
+        }
+        Err(_) => Some(format!("<binary {} bytes>", body.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(method: &str, path: &str, status: u16, body: Option<&str>) -> Exchange {
+        Exchange {
+            timestamp: Utc::now(),
+            method: method.to_string(),
+            path: path.to_string(),
+            request_headers: Vec::new(),
+            request_body: None,
+            status,
+            response_headers: Vec::new(),
+            response_body: body.map(String::from),
+            duration_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_parse_request_head() {
+        let head = "POST http://localhost:8080/users?x=1 HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer abc\r\n";
+        let (method, path, headers) = parse_request_head(head).unwrap();
+
+        assert_eq!(method, "POST");
+        assert_eq!(path, "/users?x=1");
+        assert_eq!(headers.len(), 2);
+        assert_eq!(redact_headers(headers)[1].1, "<redacted>");
+
+        let body = capture_body(br#"{"user": {"email": "a@b.c", "password": "hunter2"}, "items": [{"api_key": "k1"}]}"#).unwrap();
+        assert!(body.contains("a@b.c") && !body.contains("hunter2") && !body.contains("k1"), "{}", body);
+        // A sensitive key hides its whole value, nested fields included
+        let nested = capture_body(br#"{"credentials": {"user": "ann", "pass": "p4ss"}, "tokens": ["t1"]}"#).unwrap();
+        let nested: Value = serde_json::from_str(&nested).unwrap();
+        assert_eq!(nested, serde_json::json!({"credentials": "<redacted>", "tokens": "<redacted>"}));
+        assert_eq!(redact_body("name=ann&refresh_token=abc"), "name=ann&refresh_token=<redacted>");
+        assert_eq!(redact_body("plain text with token=abc"), "plain text with token=abc");
+        assert_eq!(redact_path("/login?user=ann&Secret=x"), "/login?user=ann&Secret=<redacted>");
+        assert_eq!(listen_address(":8080"), "127.0.0.1:8080");
+    }
+
+    #[test]
+    fn test_representatives_group_by_endpoint_and_status() {
+        let endpoints = vec![Endpoint {
+            method: "GET".to_string(),
+            path: "/users/:id".to_string(),
+            handler: "show_user".to_string(),
+            file: PathBuf::from("src/routes.rs"),
+            line: 3,
+        }];
+        let exchanges = vec![
+            exchange("GET", "/users/1", 200, None),
+            exchange("GET", "/users/2", 200, Some(r#"{"id": 2, "tags": ["a"], "admin": null}"#)),
+            exchange("GET", "/users/9", 404, None),
+            exchange("GET", "/health", 200, Some("ok")),
+        ];
+
+        let samples = representatives(&exchanges, &endpoints);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].count, 2);
+        assert_eq!(samples[0].endpoint.unwrap().handler, "show_user");
+        assert_eq!(samples[0].exchange.path, "/users/2");
+        assert_eq!(
+            samples[0].exchange.response_shape().unwrap(),
+            serde_json::json!({"id": "number", "tags": ["string"], "admin": "null"})
+        );
+        assert_eq!(samples[1].exchange.status, 404);
+        assert!(samples[2].endpoint.is_none());
+    }
+}
@@ -0,0 +1,291 @@
+//! Reference checks for AI answers
+//!
+//! Models sometimes cite files and functions that do not exist. Before an
+//! answer is shown, the file paths and symbol names it mentions are looked
+//! up in the project: paths among its files, symbols among the identifiers
+//! in its source. Unknown ones get a marker so they stand out.
+//!
+//! Only inline code spans and bare paths in prose are checked. Fenced code
+//! blocks hold suggested code, where new names are expected.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::parser::Language;
+use crate::index;
+
+/// Placed after a reference that is not in the project
+pub const MARKER: &str = "⚠";
+
+/// Extensions that make a word read as a file path
+const PATH_EXTENSIONS: &[&str] = &[
+    "rs", "py", "pyi", "js", "jsx", "mjs", "ts", "tsx", "go", "java", "kt", "rb", "swift", "cs", "c", "h",
+    "cpp", "hpp", "toml", "json", "yaml", "yml", "md", "sql", "sh", "html", "css", "lock", "cfg", "ini",
+];
+
+/// Shortest identifier worth checking; shorter ones are too generic
+const MIN_IDENTIFIER_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Path,
+    Symbol,
+}
+
+/// A reference the project does not contain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownReference {
+    /// As written in the answer, e.g. `` `src/auth.rs:42` ``
+    pub span: String,
+    /// The path or name looked up
+    pub name: String,
+    pub kind: ReferenceKind,
+}
+
+/// File paths and identifiers of one or more project roots
+#[derive(Debug, Default)]
+pub struct ReferenceIndex {
+    /// Relative paths with `/` separators
+    paths: HashSet<String>,
+    identifiers: HashSet<String>,
+    /// Labels answers may put before paths, as in `api:src/main.rs:12`
+    labels: Vec<String>,
+    /// Absolute roots, for citations that use full paths
+    roots: Vec<PathBuf>,
+}
+
+impl ReferenceIndex {
+    /// Index the files under `root`
+    pub fn build(root: &Path) -> Result<Self> {
+        let mut index = Self::default();
+        index.add_root(root, None)?;
+        Ok(index)
+    }
+
+    /// Add the files under another root, cited as `label:path`
+    pub fn add_root(&mut self, root: &Path, label: Option<&str>) -> Result<()> {
+        for file in index::collect_matching(root, |_| true)? {
+            let relative = file.strip_prefix(root).unwrap_or(&file);
+            self.paths.insert(relative.to_string_lossy().replace('\\', "/"));
+            if Language::from_path(&file) == Language::Unknown {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(&file) {
+                self.add_identifiers(&content);
+            }
+        }
+        if let Some(label) = label {
+            self.labels.push(label.to_string());
+        }
+        self.roots.push(root.canonicalize().unwrap_or_else(|_| root.to_path_buf()));
+        Ok(())
+    }
+
+    fn add_identifiers(&mut self, content: &str) {
+        for word in content.split(|c: char| !is_identifier_char(c)) {
+            if word.len() >= MIN_IDENTIFIER_LEN {
+                self.identifiers.insert(word.to_string());
+            }
+        }
+    }
+
+    /// Whether a cited path is one of the project's files. Partial paths
+    /// (`auth/login.rs`) match the end of a full one.
+    pub fn knows_path(&self, path: &str) -> bool {
+        let mut path = strip_location(path).trim_start_matches("./");
+        if let Some((label, rest)) = path.split_once(':') {
+            if self.labels.iter().any(|l| l == label) {
+                path = rest;
+            }
+        }
+        if self.paths.contains(path) {
+            return true;
+        }
+        if Path::new(path).is_absolute() {
+            return self.roots.iter().any(|root| {
+                Path::new(path).strip_prefix(root).is_ok_and(|p| self.paths.contains(&p.to_string_lossy().replace('\\', "/")))
+            });
+        }
+        let suffix = format!("/{}", path);
+        self.paths.iter().any(|p| p.ends_with(&suffix))
+    }
+
+    /// Whether each part of a name (`Config::load`, `cache.get`) occurs in
+    /// the project's source
+    pub fn knows_symbol(&self, name: &str) -> bool {
+        name.split(|c: char| !is_identifier_char(c))
+            .filter(|part| part.len() >= MIN_IDENTIFIER_LEN && part != &"self")
+            .all(|part| self.identifiers.contains(part))
+    }
+
+    /// The references in `answer` that the project does not contain
+    pub fn check(&self, answer: &str) -> Vec<UnknownReference> {
+        let mut unknown: Vec<UnknownReference> = Vec::new();
+        for (span, name, kind) in candidates(answer) {
+            let known = match kind {
+                ReferenceKind::Path => self.knows_path(&name),
+                ReferenceKind::Symbol => self.knows_symbol(&name),
+            };
+            if !known && !unknown.iter().any(|u| u.span == span) {
+                unknown.push(UnknownReference { span, name, kind });
+            }
+        }
+        unknown
+    }
+}
+
+/// `answer` with a marker after each unknown reference, outside code blocks
+pub fn annotate(answer: &str, unknown: &[UnknownReference]) -> String {
+    if unknown.is_empty() {
+        return answer.to_string();
+    }
+    let mut in_block = false;
+    let mut lines = Vec::new();
+    for line in answer.lines() {
+        if line.trim_start().starts_with("```") {
+            in_block = !in_block;
+        }
+        if in_block {
+            lines.push(line.to_string());
+            continue;
+        }
+        let mut marked = line.to_string();
+        for reference in unknown {
+            marked = marked.replace(&reference.span, &format!("{} {}", reference.span, MARKER));
+        }
+        lines.push(marked);
+    }
+    let mut annotated = lines.join("\n");
+    if answer.ends_with('\n') {
+        annotated.push('\n');
+    }
+    annotated
+}
+
+/// Paths and names mentioned outside code blocks: (span, name, kind)
+fn candidates(answer: &str) -> Vec<(String, String, ReferenceKind)> {
+    let mut found = Vec::new();
+    let mut in_block = false;
+    for line in answer.lines() {
+        if line.trim_start().starts_with("```") {
+            in_block = !in_block;
+            continue;
+        }
+        if in_block {
+            continue;
+        }
+
+        // Inline code spans sit between odd and even backticks
+        let parts: Vec<&str> = line.split('`').collect();
+        for (i, part) in parts.iter().enumerate() {
+            let is_span = i % 2 == 1 && i + 1 < parts.len();
+            if is_span {
+                let span = format!("`{}`", part);
+                let text = part.trim();
+                if is_path(text) {
+                    found.push((span, text.to_string(), ReferenceKind::Path));
+                } else if is_checked_symbol(text) {
+                    found.push((span, text.trim_end_matches("()").to_string(), ReferenceKind::Symbol));
+                }
+                continue;
+            }
+            // Bare paths in prose
+            for word in part.split_whitespace() {
+                let word = word.trim_matches(|c: char| "()[]{}<>\"',;*".contains(c)).trim_end_matches(['.', ':']);
+                if word.contains('/') && is_path(word) && !word.contains("://") {
+                    found.push((word.to_string(), word.to_string(), ReferenceKind::Path));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// `src/a.rs`, `a.rs:12`, `api:src/a.rs:3-9`
+fn is_path(text: &str) -> bool {
+    let path = strip_location(text);
+    if path.contains(char::is_whitespace) || path.contains("://") || path.starts_with('-') {
+        return false;
+    }
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => !stem.is_empty() && PATH_EXTENSIONS.contains(&ext.to_lowercase().as_str()),
+        None => false,
+    }
+}
+
+/// Names that read as project symbols: called (`load()`), qualified
+/// (`Config::load`, `cache.get`), snake_case or CamelCase. Plain words such
+/// as `config` or `Result` are too generic to check.
+fn is_checked_symbol(text: &str) -> bool {
+    let called = text.ends_with("()");
+    let name = text.trim_end_matches("()");
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.split("::").flat_map(|p| p.split('.')).all(|p| !p.is_empty() && p.chars().all(is_identifier_char));
+    if !valid {
+        return false;
+    }
+    let qualified = name.contains("::") || name.contains('.');
+    let snake = name.trim_matches('_').contains('_');
+    let humps = name.chars().skip(1).filter(|c| c.is_ascii_uppercase()).count();
+    let camel = humps > 0 && name.chars().any(|c| c.is_ascii_lowercase());
+    called || qualified || snake || camel
+}
+
+/// The path without a trailing `:line`, `:line-line` or `:line:column`
+fn strip_location(text: &str) -> &str {
+    let mut path = text;
+    while let Some((head, tail)) = path.rsplit_once(':') {
+        if !tail.is_empty() && tail.chars().all(|c| c.is_ascii_digit() || c == '-') {
+            path = head;
+        } else {
+            break;
+        }
+    }
+    path.split_once("#L").map_or(path, |(head, _)| head)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_references_are_marked() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/auth")).unwrap();
+        fs::write(root.join("src/auth/login.rs"), "pub fn verify_password(hash: &str) -> bool {\n    hash.is_empty()\n}\n").unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        let index = ReferenceIndex::build(root).unwrap();
+
+        let answer = "Passwords are checked by `verify_password()` in `src/auth/login.rs:1`, \
+                      which `SessionStore::refresh` calls; see src/auth/tokens.rs for details.\n\n\
+                      ```rust\nfn new_helper() {}\n```\n\
+                      Dependencies live in `Cargo.toml` and the check returns a `bool`.\n";
+        let unknown = index.check(answer);
+        let names: Vec<&str> = unknown.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, ["SessionStore::refresh", "src/auth/tokens.rs"]);
+
+        let annotated = annotate(answer, &unknown);
+        assert!(annotated.contains("`SessionStore::refresh` ⚠ calls"));
+        assert!(annotated.contains("src/auth/tokens.rs ⚠ for"));
+        assert!(annotated.contains("`src/auth/login.rs:1`,"));
+        assert!(annotated.contains("fn new_helper() {}\n"));
+
+        // Partial paths and labelled citations
+        assert!(index.knows_path("auth/login.rs"));
+        assert!(!index.knows_path("api:src/auth/login.rs"));
+        let mut labelled = ReferenceIndex::default();
+        labelled.add_root(root, Some("api")).unwrap();
+        assert!(labelled.knows_path("api:src/auth/login.rs:1-2"));
+    }
+}
@@ -0,0 +1,127 @@
+//! Saved searches
+//!
+//! Named queries for recurring investigations, kept in the user data
+//! directory so they work in every checkout:
+//! `nexus search --save auth "authentication login session"`, then
+//! `nexus search --saved auth`.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SAVED_SEARCHES_FILE: &str = "saved_searches.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<String>,
+    pub saved: DateTime<Utc>,
+}
+
+/// The saved searches file
+#[derive(Debug)]
+pub struct SavedSearches {
+    path: PathBuf,
+    searches: BTreeMap<String, SavedSearch>,
+}
+
+impl SavedSearches {
+    /// Searches in the user data directory
+    pub fn open() -> Result<Self> {
+        let dir = directories::ProjectDirs::from("com", "nexus", "forge")
+            .context("Failed to determine data directory")?
+            .data_dir()
+            .to_path_buf();
+        Self::load(&dir.join(SAVED_SEARCHES_FILE))
+    }
+
+    /// Searches stored at `path`, empty when it does not exist yet
+    pub fn load(path: &Path) -> Result<Self> {
+        let searches = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Self { path: path.to_path_buf(), searches })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.searches.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &SavedSearch)> {
+        self.searches.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.searches.is_empty()
+    }
+
+    /// Save `query` under `name`, replacing any search with that name.
+    /// Returns whether one was replaced.
+    pub fn insert(&mut self, name: &str, query: &str, group_by: Option<&str>) -> Result<bool> {
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            bail!("Invalid name `{}`: use letters, digits, `-`, `_` and `.`", name);
+        }
+        if query.trim().is_empty() {
+            bail!("Nothing to save: the query is empty");
+        }
+
+        let search = SavedSearch {
+            query: query.trim().to_string(),
+            group_by: group_by.map(str::to_string),
+            saved: Utc::now(),
+        };
+        Ok(self.searches.insert(name.to_string(), search).is_some())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<SavedSearch> {
+        self.searches.remove(name)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.searches)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_and_remove() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("data").join(SAVED_SEARCHES_FILE);
+
+        let mut searches = SavedSearches::load(&path).unwrap();
+        assert!(searches.is_empty());
+        assert!(!searches.insert("auth-queries", " authentication login session ", Some("file")).unwrap());
+        assert!(searches.insert("auth-queries", "authentication login", None).unwrap());
+        assert!(searches.insert("bad name", "x", None).is_err());
+        assert!(searches.insert("empty", "  ", None).is_err());
+        searches.insert("db", "database connection", Some("package")).unwrap();
+        searches.save().unwrap();
+
+        let mut loaded = SavedSearches::load(&path).unwrap();
+        assert_eq!(loaded.get("auth-queries").unwrap().query, "authentication login");
+        assert_eq!(loaded.get("db").unwrap().group_by.as_deref(), Some("package"));
+        assert_eq!(loaded.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>(), ["auth-queries", "db"]);
+
+        assert!(loaded.remove("db").is_some());
+        assert!(loaded.remove("db").is_none());
+    }
+}
@@ -0,0 +1,277 @@
+//! Static safety checks for generated scripts
+//!
+//! A line-based pass over shell scripts, PowerShell and CI pipeline configs
+//! that flags commands which are destructive or run untrusted code, so they
+//! can be reviewed before the script is written or executed.
+
+#![allow(dead_code)]
+
+use std::path::Path;
+
+/// Kind of script being checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptKind {
+    Shell,
+    PowerShell,
+    /// CI pipelines and other YAML with embedded shell steps
+    CiConfig,
+}
+
+impl ScriptKind {
+    /// Detect the script kind from a file path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        let full = path.to_string_lossy().replace('\\', "/").to_lowercase();
+
+        match name.as_str() {
+            ".gitlab-ci.yml" | ".travis.yml" | "azure-pipelines.yml" | "bitbucket-pipelines.yml"
+            | "jenkinsfile" | "cloudbuild.yaml" => return Some(ScriptKind::CiConfig),
+            _ => {}
+        }
+
+        let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        match ext.as_deref() {
+            Some("sh" | "bash" | "zsh" | "ksh") => Some(ScriptKind::Shell),
+            Some("ps1" | "psm1") => Some(ScriptKind::PowerShell),
+            Some("yml" | "yaml")
+                if full.contains(".github/workflows/") || full.contains(".circleci/") =>
+            {
+                Some(ScriptKind::CiConfig)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How dangerous a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+        }
+    }
+}
+
+/// A potentially dangerous line
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetyFinding {
+    /// 1-based line number
+    pub line: usize,
+    pub severity: Severity,
+    /// Short rule identifier, e.g. `pipe-to-shell`
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// Shells and interpreters that execute whatever is piped into them
+const PIPE_TARGETS: &[&str] = &["sh", "bash", "zsh", "ksh", "dash", "python", "python3", "perl", "ruby", "node", "iex", "invoke-expression"];
+
+/// Commands that download content
+const DOWNLOADERS: &[&str] = &["curl", "wget", "iwr", "irm", "invoke-webrequest", "invoke-restmethod", "downloadstring"];
+
+/// Run the safety pass over a script
+pub fn scan(code: &str, kind: ScriptKind) -> Vec<SafetyFinding> {
+    let mut findings = Vec::new();
+
+    for (idx, raw) in code.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        let lower = line.to_lowercase();
+        let mut push = |severity, rule, message: &str| {
+            findings.push(SafetyFinding { line: idx + 1, severity, rule, message: message.to_string() });
+        };
+
+        if pipes_download_to_shell(&lower) {
+            push(Severity::High, "pipe-to-shell", "Downloads and executes remote code without verification");
+        }
+
+        for segment in split_commands(&lower) {
+            if let Some(message) = check_recursive_delete(&segment) {
+                push(Severity::High, "rm-rf", &message);
+            }
+        }
+
+        if lower.contains(":(){") && lower.contains(":|:") {
+            push(Severity::High, "fork-bomb", "Fork bomb");
+        }
+        if lower.contains("mkfs") || (lower.contains("dd ") && lower.contains("of=/dev/")) || lower.contains("> /dev/sd") {
+            push(Severity::High, "disk-write", "Writes directly to a block device");
+        }
+        if lower.contains("chmod") && lower.contains("777") {
+            push(Severity::Medium, "world-writable", "Makes files world-writable (chmod 777)");
+        }
+        if lower.contains("curl") && (lower.contains(" -k") || lower.contains("--insecure"))
+            || lower.contains("--no-check-certificate")
+            || lower.contains("sslverify false")
+            || lower.contains("-skipcertificatecheck")
+        {
+            push(Severity::Medium, "tls-disabled", "Disables TLS certificate verification");
+        }
+        if lower.starts_with("eval ") && lower.contains('$') {
+            push(Severity::Medium, "eval", "Evaluates a dynamically built command");
+        }
+        if lower.contains("git push") && (lower.contains(" --force") || lower.contains(" -f")) {
+            push(Severity::Medium, "force-push", "Force-pushes, which can discard remote history");
+        }
+        if lower.contains("set-executionpolicy") && (lower.contains("bypass") || lower.contains("unrestricted")) {
+            push(Severity::Medium, "execution-policy", "Disables the PowerShell execution policy");
+        }
+        if kind == ScriptKind::CiConfig && lower.contains("pull_request_target") {
+            push(Severity::Medium, "pull-request-target", "pull_request_target runs untrusted PR code with repository secrets");
+        }
+        if lower.starts_with("sudo ") || lower.contains("&& sudo ") {
+            push(Severity::Low, "sudo", "Runs with elevated privileges");
+        }
+    }
+
+    findings
+}
+
+/// `curl ... | sh`, `bash <(curl ...)`, `sh -c "$(curl ...)"`, `iex (iwr ...)`
+fn pipes_download_to_shell(line: &str) -> bool {
+    if !DOWNLOADERS.iter().any(|d| line.contains(d)) {
+        return false;
+    }
+
+    if ["<(curl", "<(wget", "\"$(curl", "\"$(wget", "$(curl", "$(wget"].iter().any(|p| line.contains(p))
+        && PIPE_TARGETS.iter().any(|t| line.contains(&format!("{} ", t)))
+    {
+        return true;
+    }
+    if (line.starts_with("iex") || line.contains("invoke-expression")) && line.contains('(') {
+        return true;
+    }
+
+    let mut downloaded = false;
+    for segment in line.split('|') {
+        let mut words = segment.split_whitespace().filter(|w| *w != "sudo" && !w.starts_with('-'));
+        let first = words.next().unwrap_or("");
+
+        if downloaded && PIPE_TARGETS.contains(&first) {
+            return true;
+        }
+        if DOWNLOADERS.iter().any(|d| segment.contains(d)) {
+            downloaded = true;
+        }
+    }
+
+    false
+}
+
+/// Split a line into individual commands
+fn split_commands(line: &str) -> Vec<String> {
+    line.replace("&&", ";")
+        .replace("||", ";")
+        .split([';', '|'])
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Flag `rm -rf` on roots, globs and variables that may be empty
+fn check_recursive_delete(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace().skip_while(|w| *w == "sudo");
+    let program = words.next()?;
+
+    let (recursive_flags, force_flags): (&[&str], &[&str]) = match program {
+        "rm" => (&["--recursive"], &["--force"]),
+        "remove-item" | "ri" => (&["-recurse"], &["-force"]),
+        _ => return None,
+    };
+
+    let mut recursive = false;
+    let mut force = false;
+    let mut targets = Vec::new();
+
+    for word in words {
+        if recursive_flags.contains(&word) {
+            recursive = true;
+        } else if force_flags.contains(&word) {
+            force = true;
+        } else if word.starts_with("--") || (program != "rm" && word.starts_with('-')) {
+            continue;
+        } else if let Some(flags) = word.strip_prefix('-') {
+            recursive |= flags.contains('r');
+            force |= flags.contains('f');
+        } else {
+            targets.push(word.trim_matches(['"', '\'']));
+        }
+    }
+
+    if !recursive {
+        return None;
+    }
+
+    for target in targets {
+        let target = target.trim_end_matches('/');
+        if matches!(target, "" | "/*" | "*" | "~" | "." | "..") || target == "$home" {
+            return Some(format!("Recursively deletes `{}`", if target.is_empty() { "/" } else { target }));
+        }
+        // `${VAR:?}` aborts when unset, so it is safe
+        if target.starts_with('$') && !target.contains(":?") {
+            let how = if force { "force-deletes" } else { "deletes" };
+            return Some(format!(
+                "Recursively {} `{}`; if the variable is empty this targets the wrong path (use ${{VAR:?}})",
+                how, target
+            ));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(code: &str, kind: ScriptKind) -> Vec<&'static str> {
+        scan(code, kind).into_iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_flags_dangerous_shell() {
+        let script = "#!/bin/bash\n\
+                      # curl https://example.com/install.sh | sh\n\
+                      curl -fsSL https://example.com/install.sh | sudo bash\n\
+                      rm -rf \"$BUILD_DIR/\"\n\
+                      rm -rf \"${BUILD_DIR:?}/\"\n\
+                      cd /tmp && rm -fr /\n\
+                      rm -r ./build\n\
+                      chmod -R 777 /srv";
+
+        let findings = scan(script, ScriptKind::Shell);
+        let lines: Vec<(usize, &str)> = findings.iter().map(|f| (f.line, f.rule)).collect();
+
+        assert_eq!(lines, vec![(3, "pipe-to-shell"), (4, "rm-rf"), (6, "rm-rf"), (8, "world-writable")]);
+        assert!(findings.iter().take(3).all(|f| f.severity == Severity::High));
+    }
+
+    #[test]
+    fn test_flags_ci_and_powershell() {
+        let workflow = "on:\n  pull_request_target:\njobs:\n  build:\n    steps:\n      - run: bash <(curl -s https://codecov.io/bash)\n      - run: git push --force origin main";
+        assert_eq!(rules(workflow, ScriptKind::CiConfig), vec!["pull-request-target", "pipe-to-shell", "force-push"]);
+
+        let ps = "iex (New-Object Net.WebClient).DownloadString('https://x.io/a.ps1')\nRemove-Item -Recurse -Force $env:TEMP_DIR";
+        assert_eq!(rules(ps, ScriptKind::PowerShell), vec!["pipe-to-shell", "rm-rf"]);
+    }
+
+    #[test]
+    fn test_script_kind_from_path() {
+        assert_eq!(ScriptKind::from_path(Path::new("scripts/deploy.sh")), Some(ScriptKind::Shell));
+        assert_eq!(ScriptKind::from_path(Path::new(".github/workflows/ci.yml")), Some(ScriptKind::CiConfig));
+        assert_eq!(ScriptKind::from_path(Path::new(".gitlab-ci.yml")), Some(ScriptKind::CiConfig));
+        assert_eq!(ScriptKind::from_path(Path::new("config/app.yml")), None);
+        assert_eq!(ScriptKind::from_path(Path::new("src/main.rs")), None);
+    }
+}
@@ -0,0 +1,908 @@
+//! Service dependency map
+//!
+//! Reads docker-compose files and Kubernetes manifests for the services of a
+//! system, then infers who talks to whom from URLs in their configuration,
+//! HTTP calls and queue topics in their code. Dependencies the code relies on
+//! but the manifests do not declare, and targets no service answers to, are
+//! reported as issues.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::diagram::mermaid_id;
+use crate::index::{self, endpoints};
+
+/// File names docker compose reads
+const COMPOSE_FILES: &[&str] = &[
+    "docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml",
+    "docker-compose.override.yml", "docker-compose.override.yaml",
+];
+
+/// Kubernetes kinds that run containers
+const WORKLOAD_KINDS: &[&str] = &["Deployment", "StatefulSet", "DaemonSet", "Job", "CronJob", "Pod"];
+
+/// Words that make a line about a message broker
+const QUEUE_HINTS: &[&str] = &[
+    "topic", "queue", "subject", "routing_key", "producer", "consumer", "kafka", "nats", "sqs", "pubsub",
+    "rabbit", "amqp", "channel", "stream",
+];
+
+const PUBLISH_VERBS: &[&str] = &["publish", "produce", "send", "emit", "enqueue", "put_message"];
+const SUBSCRIBE_VERBS: &[&str] = &["subscribe", "consume", "basic_consume", "listen", "on_message"];
+
+/// A service declared in a manifest
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub name: String,
+    /// Manifest declaring it, and the line
+    pub manifest: PathBuf,
+    pub line: usize,
+    /// Source directory (compose `build` context), scanned for calls
+    pub dir: Option<PathBuf>,
+    /// Whether the manifest format can declare dependencies (compose `depends_on`)
+    pub declares: bool,
+    /// Declared dependencies (`depends_on`, `links`)
+    pub declared: BTreeSet<String>,
+    /// Environment variables with their line in the manifest
+    pub env: Vec<(String, String, usize)>,
+}
+
+impl Service {
+    fn new(name: &str, manifest: &Path, line: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            manifest: manifest.to_path_buf(),
+            line,
+            dir: None,
+            declares: false,
+            declared: BTreeSet::new(),
+            env: Vec::new(),
+        }
+    }
+}
+
+/// How a dependency was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EdgeKind {
+    /// `depends_on` / `links` in a manifest
+    Declared,
+    /// A URL or host in the service's environment
+    Config,
+    /// An HTTP call in the service's code
+    Http,
+    /// Publisher and subscriber of the same queue topic
+    Queue,
+}
+
+impl EdgeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EdgeKind::Declared => "declared",
+            EdgeKind::Config => "config",
+            EdgeKind::Http => "http",
+            EdgeKind::Queue => "queue",
+        }
+    }
+}
+
+/// Where a dependency shows up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evidence {
+    pub kind: EdgeKind,
+    /// The URL, variable, call or topic
+    pub detail: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Something wrong with the system's wiring
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// `from` calls `to` but its manifest does not declare it
+    Undeclared { from: String, to: String },
+    /// `from` points at a host no service answers to
+    Unreachable { from: String, target: String, reason: String, evidence: Evidence },
+    /// A topic with publishers but no subscribers, or the reverse
+    OrphanTopic { topic: String, services: Vec<String>, published: bool },
+}
+
+impl Issue {
+    pub fn describe(&self) -> String {
+        match self {
+            Issue::Undeclared { from, to } => format!("{} uses {} without declaring it in depends_on", from, to),
+            Issue::Unreachable { from, target, reason, .. } => format!("{} points at {}: {}", from, target, reason),
+            Issue::OrphanTopic { topic, services, published: true } => {
+                format!("{} publish to `{}` but no service subscribes", services.join(", "), topic)
+            }
+            Issue::OrphanTopic { topic, services, published: false } => {
+                format!("{} subscribe to `{}` but no service publishes", services.join(", "), topic)
+            }
+        }
+    }
+}
+
+/// Services and the dependencies between them
+#[derive(Debug, Default)]
+pub struct ServiceMap {
+    pub services: Vec<Service>,
+    /// Kubernetes Service names routing to a workload
+    pub aliases: BTreeMap<String, String>,
+    /// (from, to) with everything that links them
+    pub edges: BTreeMap<(String, String), Vec<Evidence>>,
+    pub issues: Vec<Issue>,
+}
+
+impl ServiceMap {
+    /// Map the services declared under `root`
+    pub fn discover(root: &Path) -> Result<Self> {
+        let manifests = index::collect_matching(root, |p| {
+            p.extension().is_some_and(|e| e == "yml" || e == "yaml")
+        })?;
+        Ok(Self::build(&manifests))
+    }
+
+    /// Map the services declared in a set of YAML files
+    pub fn build(manifests: &[PathBuf]) -> Self {
+        let mut map = Self::default();
+        let mut labels: BTreeMap<String, String> = BTreeMap::new();
+        let mut selectors: Vec<(String, String, PathBuf, usize)> = Vec::new();
+
+        for path in manifests {
+            let Ok(content) = fs::read_to_string(path) else {
+                continue;
+            };
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            if COMPOSE_FILES.contains(&name.as_str()) {
+                map.services.extend(compose_services(path, &content));
+            } else {
+                for doc in documents(&content) {
+                    read_kubernetes(&doc, path, &mut map.services, &mut labels, &mut selectors);
+                }
+            }
+        }
+
+        // A Kubernetes Service routes to the workload its selector matches
+        for (name, app, path, line) in selectors {
+            match labels.get(&app) {
+                Some(workload) if *workload != name => {
+                    map.aliases.insert(name, workload.clone());
+                }
+                Some(_) => {}
+                None if !map.services.iter().any(|s| s.name == name) => {
+                    map.services.push(Service::new(&name, &path, line));
+                }
+                None => {}
+            }
+        }
+
+        map.services.sort_by(|a, b| a.name.cmp(&b.name));
+        map.services.dedup_by(|a, b| a.name == b.name);
+
+        map.link_declared();
+        map.link_config();
+        map.link_code();
+        map.check_declarations();
+        map
+    }
+
+    /// The service a hostname reaches
+    pub fn resolve(&self, host: &str) -> Option<&str> {
+        let host = self.aliases.get(host).map(String::as_str).unwrap_or(host);
+        self.services.iter().find(|s| s.name == host).map(|s| s.name.as_str())
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, evidence: Evidence) {
+        let entry = self.edges.entry((from.to_string(), to.to_string())).or_default();
+        if !entry.iter().any(|e| e.kind == evidence.kind && e.file == evidence.file && e.line == evidence.line) {
+            entry.push(evidence);
+        }
+    }
+
+    fn link_declared(&mut self) {
+        let mut found = Vec::new();
+        for service in &self.services {
+            for target in &service.declared {
+                let evidence = Evidence {
+                    kind: EdgeKind::Declared,
+                    detail: format!("depends_on {}", target),
+                    file: service.manifest.clone(),
+                    line: service.line,
+                };
+                match self.resolve(target) {
+                    Some(to) => found.push((service.name.clone(), to.to_string(), evidence)),
+                    None => self.issues.push(Issue::Unreachable {
+                        from: service.name.clone(),
+                        target: target.clone(),
+                        reason: "depends on a service that is not defined".to_string(),
+                        evidence,
+                    }),
+                }
+            }
+        }
+        for (from, to, evidence) in found {
+            self.add_edge(&from, &to, evidence);
+        }
+    }
+
+    fn link_config(&mut self) {
+        let mut found = Vec::new();
+        for service in &self.services {
+            for (key, value, line) in &service.env {
+                for (host, scheme) in hosts(key, value) {
+                    let evidence = Evidence {
+                        kind: EdgeKind::Config,
+                        detail: format!("{}={}", key, value),
+                        file: service.manifest.clone(),
+                        line: *line,
+                    };
+                    if is_loopback(&host) {
+                        self.issues.push(Issue::Unreachable {
+                            from: service.name.clone(),
+                            target: value.clone(),
+                            reason: "localhost inside a container is the container itself".to_string(),
+                            evidence,
+                        });
+                    } else if let Some(to) = self.resolve(&host) {
+                        if to != service.name {
+                            found.push((service.name.clone(), to.to_string(), evidence));
+                        }
+                    } else if scheme && is_internal(&host) {
+                        self.issues.push(Issue::Unreachable {
+                            from: service.name.clone(),
+                            target: value.clone(),
+                            reason: format!("no service named `{}`", host),
+                            evidence,
+                        });
+                    }
+                }
+            }
+        }
+        for (from, to, evidence) in found {
+            self.add_edge(&from, &to, evidence);
+        }
+    }
+
+    fn link_code(&mut self) {
+        let sources: Vec<(String, Vec<PathBuf>)> = self
+            .services
+            .iter()
+            .filter_map(|s| {
+                let dir = s.dir.as_ref().filter(|d| d.is_dir())?;
+                let files = index::collect_files(dir, false).ok()?;
+                Some((s.name.clone(), files.into_iter().filter(|f| !index::is_test_path(f)).collect()))
+            })
+            .collect();
+
+        let routes: Vec<(String, Vec<endpoints::Endpoint>)> = sources
+            .iter()
+            .map(|(name, files)| (name.clone(), endpoints::inventory(files)))
+            .collect();
+
+        let mut found = Vec::new();
+        let mut unreachable = Vec::new();
+        let mut publishers: BTreeMap<String, BTreeMap<String, Evidence>> = BTreeMap::new();
+        let mut subscribers: BTreeMap<String, BTreeMap<String, Evidence>> = BTreeMap::new();
+
+        for (name, files) in &sources {
+            for file in files {
+                let Ok(content) = fs::read_to_string(file) else {
+                    continue;
+                };
+
+                for (idx, line) in content.lines().enumerate() {
+                    let evidence = |kind: EdgeKind, detail: &str| Evidence {
+                        kind,
+                        detail: detail.to_string(),
+                        file: file.clone(),
+                        line: idx + 1,
+                    };
+
+                    for literal in endpoints::string_literals(line) {
+                        let Some(host) = url_host(&literal) else {
+                            continue;
+                        };
+                        match self.resolve(&host) {
+                            Some(to) if to != name => found.push((name.clone(), to.to_string(), evidence(EdgeKind::Http, &literal))),
+                            Some(_) => {}
+                            None if is_internal(&host) && !is_loopback(&host) => unreachable.push((
+                                name.clone(),
+                                literal.clone(),
+                                format!("no service named `{}`", host),
+                                evidence(EdgeKind::Http, &literal),
+                            )),
+                            None => {}
+                        }
+                    }
+
+                    if let Some((publish, topic)) = topic_usage(line) {
+                        let side = if publish { &mut publishers } else { &mut subscribers };
+                        side.entry(topic.clone()).or_default().entry(name.clone()).or_insert_with(|| evidence(EdgeKind::Queue, &topic));
+                    }
+                }
+
+                // Relative calls (`${API}/payments/${id}`) reach whichever
+                // other service serves the route
+                for call in endpoints::scan_calls(file, &content) {
+                    let own = routes.iter().any(|(n, r)| n == name && r.iter().any(|e| call.reaches(e)));
+                    let owners: Vec<&String> = routes
+                        .iter()
+                        .filter(|(n, r)| n != name && r.iter().any(|e| call.reaches(e)))
+                        .map(|(n, _)| n)
+                        .collect();
+                    if let [owner] = owners.as_slice() {
+                        if !own {
+                            let detail = format!("{} {}", call.method.as_deref().unwrap_or("?"), call.path);
+                            found.push((name.clone(), owner.to_string(), evidence_at(EdgeKind::Http, &detail, &call.file, call.line)));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (from, to, evidence) in found {
+            self.add_edge(&from, &to, evidence);
+        }
+        for (from, target, reason, evidence) in unreachable {
+            self.issues.push(Issue::Unreachable { from, target, reason, evidence });
+        }
+
+        let topics: BTreeSet<&String> = publishers.keys().chain(subscribers.keys()).collect();
+        let mut queued = Vec::new();
+        for topic in topics {
+            match (publishers.get(topic), subscribers.get(topic)) {
+                (Some(producing), Some(consuming)) => {
+                    for from in producing.keys() {
+                        for (to, evidence) in consuming {
+                            if from != to {
+                                queued.push((from.clone(), to.clone(), evidence.clone()));
+                            }
+                        }
+                    }
+                }
+                (Some(producing), None) => self.issues.push(Issue::OrphanTopic {
+                    topic: topic.clone(),
+                    services: producing.keys().cloned().collect(),
+                    published: true,
+                }),
+                (None, Some(consuming)) => self.issues.push(Issue::OrphanTopic {
+                    topic: topic.clone(),
+                    services: consuming.keys().cloned().collect(),
+                    published: false,
+                }),
+                (None, None) => {}
+            }
+        }
+        for (from, to, evidence) in queued {
+            self.add_edge(&from, &to, evidence);
+        }
+    }
+
+    /// Flag config and HTTP dependencies missing from `depends_on`. Queue
+    /// peers are decoupled through the broker, so they need no declaration.
+    fn check_declarations(&mut self) {
+        for ((from, to), evidence) in &self.edges {
+            let Some(service) = self.services.iter().find(|s| &s.name == from) else {
+                continue;
+            };
+            let inferred = evidence.iter().any(|e| matches!(e.kind, EdgeKind::Config | EdgeKind::Http));
+            let declared = evidence.iter().any(|e| e.kind == EdgeKind::Declared);
+            if service.declares && inferred && !declared {
+                self.issues.push(Issue::Undeclared { from: from.clone(), to: to.clone() });
+            }
+        }
+    }
+
+    /// Whether `from` → `to` is used but undeclared
+    pub fn is_undeclared(&self, from: &str, to: &str) -> bool {
+        self.issues.iter().any(|i| matches!(i, Issue::Undeclared { from: f, to: t } if f == from && t == to))
+    }
+
+    /// Render as a Mermaid `graph`: solid edges are used and declared, thick
+    /// edges are used but undeclared, dotted edges are declared only
+    pub fn render_mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+
+        for service in &self.services {
+            out.push_str(&format!("    {}[\"{}\"]\n", mermaid_id(&service.name), service.name));
+        }
+
+        for ((from, to), evidence) in &self.edges {
+            let kinds: BTreeSet<EdgeKind> = evidence.iter().map(|e| e.kind).collect();
+            let topics: BTreeSet<&str> = evidence.iter().filter(|e| e.kind == EdgeKind::Queue).map(|e| e.detail.as_str()).collect();
+            let mut labels: Vec<String> = kinds
+                .iter()
+                .filter(|k| !matches!(k, EdgeKind::Declared | EdgeKind::Queue))
+                .map(|k| k.label().to_string())
+                .collect();
+            labels.extend(topics.iter().map(|t| t.to_string()));
+
+            let arrow = if kinds.len() == 1 && kinds.contains(&EdgeKind::Declared) {
+                "-.->"
+            } else if self.is_undeclared(from, to) {
+                labels.push("undeclared".to_string());
+                "==>"
+            } else {
+                "-->"
+            };
+            let label = if labels.is_empty() { String::new() } else { format!("|{}|", labels.join(", ")) };
+            out.push_str(&format!("    {} {}{} {}\n", mermaid_id(from), arrow, label, mermaid_id(to)));
+        }
+
+        let mut missing = BTreeSet::new();
+        for issue in &self.issues {
+            if let Issue::Unreachable { from, target, .. } = issue {
+                let id = format!("missing_{}", mermaid_id(target));
+                if missing.insert(id.clone()) {
+                    out.push_str(&format!("    {}[\"{} ?\"]:::missing\n", id, target.replace('"', "'")));
+                }
+                out.push_str(&format!("    {} -.->|unreachable| {}\n", mermaid_id(from), id));
+            }
+        }
+        if !missing.is_empty() {
+            out.push_str("    classDef missing stroke:#EF9A9A,stroke-dasharray: 5 5\n");
+        }
+
+        out
+    }
+}
+
+fn evidence_at(kind: EdgeKind, detail: &str, file: &Path, line: usize) -> Evidence {
+    Evidence { kind, detail: detail.to_string(), file: file.to_path_buf(), line }
+}
+
+// ============================================
+// Manifests
+// ============================================
+
+/// Services in a docker-compose file
+fn compose_services(path: &Path, content: &str) -> Vec<Service> {
+    let Some(doc) = documents(content).into_iter().next() else {
+        return Vec::new();
+    };
+    let Some(services) = doc.get("services") else {
+        return Vec::new();
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+
+    services
+        .children
+        .iter()
+        .map(|node| {
+            let mut service = Service::new(&node.key, path, node.line);
+            service.declares = true;
+
+            let context = node
+                .get("build")
+                .map(|b| if b.value.is_empty() { b.get("context").map(|c| c.value.clone()).unwrap_or_default() } else { b.value.clone() })
+                .filter(|c| !c.is_empty());
+            service.dir = context.map(|c| base.join(c.trim_start_matches("./")));
+
+            for key in ["depends_on", "links"] {
+                if let Some(list) = node.get(key) {
+                    service.declared.extend(list.list().into_iter().map(|d| d.split(':').next().unwrap_or(&d).to_string()));
+                }
+            }
+
+            if let Some(environment) = node.get("environment") {
+                for var in &environment.children {
+                    if var.key == "-" {
+                        if let Some((k, v)) = var.value.split_once('=') {
+                            service.env.push((k.to_string(), v.to_string(), var.line));
+                        }
+                    } else {
+                        service.env.push((var.key.clone(), var.value.clone(), var.line));
+                    }
+                }
+            }
+            service
+        })
+        .collect()
+}
+
+/// Workloads and Services in one Kubernetes document
+fn read_kubernetes(
+    doc: &Node,
+    path: &Path,
+    services: &mut Vec<Service>,
+    labels: &mut BTreeMap<String, String>,
+    selectors: &mut Vec<(String, String, PathBuf, usize)>,
+) {
+    let Some(kind) = doc.get("kind").map(|k| k.value.as_str()) else {
+        return;
+    };
+    let Some(name) = doc.path(&["metadata", "name"]) else {
+        return;
+    };
+    if doc.get("apiVersion").is_none() || name.value.is_empty() {
+        return;
+    }
+
+    if kind == "Service" {
+        if let Some(app) = doc.path(&["spec", "selector", "app"]).or_else(|| doc.path(&["spec", "selector", "app.kubernetes.io/name"])) {
+            selectors.push((name.value.clone(), app.value.clone(), path.to_path_buf(), name.line));
+        }
+        return;
+    }
+    if !WORKLOAD_KINDS.contains(&kind) {
+        return;
+    }
+
+    let mut service = Service::new(&name.value, path, name.line);
+    for app in [
+        doc.path(&["metadata", "labels", "app"]),
+        doc.path(&["spec", "template", "metadata", "labels", "app"]),
+        doc.path(&["spec", "selector", "matchLabels", "app"]),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        labels.insert(app.value.clone(), name.value.clone());
+    }
+
+    let mut envs = Vec::new();
+    doc.find_all("env", &mut envs);
+    for env in envs {
+        for var in &env.children {
+            if let (Some(k), Some(v)) = (var.get("name"), var.get("value")) {
+                service.env.push((k.value.clone(), v.value.clone(), v.line));
+            }
+        }
+    }
+    services.push(service);
+}
+
+/// Hosts named by an environment variable, with whether each had a scheme:
+/// `http://payments:8080`, `kafka:9092,kafka-2:9092`, or a bare `DB_HOST=db`
+fn hosts(key: &str, value: &str) -> Vec<(String, bool)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter_map(|part| {
+            if part.contains("://") {
+                return url_host(part).map(|h| (h, true));
+            }
+            let (host, port) = part.split_once(':').unwrap_or((part, ""));
+            let bare = !host.is_empty() && host.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.');
+            let is_port = !port.is_empty() && port.chars().all(|c| c.is_ascii_digit());
+            (bare && (is_port || key.to_uppercase().ends_with("HOST"))).then(|| (normalize_host(host), false))
+        })
+        .collect()
+}
+
+/// Host of a URL, with cluster DNS suffixes reduced to the service name
+fn url_host(url: &str) -> Option<String> {
+    let rest = &url[url.find("://")? + 3..];
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    let host = authority.split(':').next()?;
+    if host.is_empty() || host.contains(['$', '{', '}', '%', '<']) {
+        return None;
+    }
+    Some(normalize_host(host))
+}
+
+/// `payments.prod.svc.cluster.local` → `payments`
+fn normalize_host(host: &str) -> String {
+    let host = host.to_lowercase();
+    if host.ends_with(".svc") || host.ends_with(".svc.cluster.local") || host.ends_with(".cluster.local") {
+        return host.split('.').next().unwrap_or(&host).to_string();
+    }
+    host
+}
+
+fn is_loopback(host: &str) -> bool {
+    matches!(host, "localhost" | "127.0.0.1" | "0.0.0.0" | "::1")
+}
+
+/// A single-label hostname, as services use inside a cluster or network
+fn is_internal(host: &str) -> bool {
+    !host.contains('.') && !host.chars().all(|c| c.is_ascii_digit())
+}
+
+/// A queue topic published (`true`) or subscribed to (`false`) on a line
+fn topic_usage(line: &str) -> Option<(bool, String)> {
+    let lower = line.to_lowercase();
+    if !QUEUE_HINTS.iter().any(|h| lower.contains(h)) {
+        return None;
+    }
+
+    let called = |verb: &str| {
+        lower.match_indices(verb).any(|(pos, _)| {
+            let before = lower[..pos].chars().last();
+            let after = lower[pos + verb.len()..].chars().next();
+            !before.is_some_and(|c| c.is_alphanumeric()) && matches!(after, Some('(' | '_' | 's' | 'e'))
+        })
+    };
+    let publish = if SUBSCRIBE_VERBS.iter().any(|v| called(v)) {
+        false
+    } else if PUBLISH_VERBS.iter().any(|v| called(v)) {
+        true
+    } else {
+        return None;
+    };
+
+    let topic = endpoints::string_literals(line).into_iter().find(|s| {
+        s.len() > 1 && !s.contains(char::is_whitespace) && !s.contains('/') && !s.contains("://")
+            && s.chars().any(|c| c.is_alphabetic())
+    })?;
+    Some((publish, topic))
+}
+
+// ============================================
+// YAML
+// ============================================
+
+/// A YAML line with the lines nested under it. List items have the key `-`.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    key: String,
+    value: String,
+    /// 1-based line
+    line: usize,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn get(&self, key: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.key == key)
+    }
+
+    fn path(&self, keys: &[&str]) -> Option<&Node> {
+        keys.iter().try_fold(self, |node, key| node.get(key))
+    }
+
+    /// Items of a `[a, b]` flow list, a `- a` block list or a mapping's keys
+    fn list(&self) -> Vec<String> {
+        if self.value.starts_with('[') {
+            return self
+                .value
+                .trim_matches(['[', ']'])
+                .split(',')
+                .map(unquote)
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        self.children
+            .iter()
+            .map(|c| if c.key == "-" { c.value.clone() } else { c.key.clone() })
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    fn find_all<'a>(&'a self, key: &str, found: &mut Vec<&'a Node>) {
+        for child in &self.children {
+            if child.key == key {
+                found.push(child);
+            }
+            child.find_all(key, found);
+        }
+    }
+}
+
+/// One flattened YAML line
+struct Entry {
+    indent: usize,
+    key: String,
+    value: String,
+    line: usize,
+}
+
+/// The documents of a YAML stream, each as a root node. This reads the
+/// block mappings and lists manifests use, not the whole YAML spec.
+fn documents(content: &str) -> Vec<Node> {
+    let mut docs = Vec::new();
+    let mut entries = Vec::new();
+
+    for (idx, raw) in content.lines().enumerate() {
+        let text = raw.trim();
+        if text.starts_with("---") {
+            docs.push(std::mem::take(&mut entries));
+            continue;
+        }
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut indent = raw.len() - raw.trim_start().len();
+        let mut text = text;
+        loop {
+            if let Some(rest) = text.strip_prefix('-').filter(|r| r.is_empty() || r.starts_with(' ')) {
+                let item = rest.trim_start();
+                if split_key(item).is_some() {
+                    entries.push(Entry { indent, key: "-".to_string(), value: String::new(), line: idx + 1 });
+                    indent += 1 + rest.len() - item.len();
+                    text = item;
+                    continue;
+                }
+                entries.push(Entry { indent, key: "-".to_string(), value: unquote(item), line: idx + 1 });
+            } else if let Some((key, value)) = split_key(text) {
+                entries.push(Entry { indent, key, value, line: idx + 1 });
+            }
+            break;
+        }
+    }
+    docs.push(entries);
+
+    docs.into_iter()
+        .filter(|e| !e.is_empty())
+        .map(|entries| {
+            let mut pos = 0;
+            Node { children: build(&entries, &mut pos, 0, false), ..Node::default() }
+        })
+        .collect()
+}
+
+/// Nodes at `indent`; with `items_only`, stop at the first non-list entry
+fn build(entries: &[Entry], pos: &mut usize, indent: usize, items_only: bool) -> Vec<Node> {
+    let mut nodes = Vec::new();
+
+    while let Some(entry) = entries.get(*pos) {
+        if entry.indent < indent || (items_only && entry.indent == indent && entry.key != "-") {
+            break;
+        }
+        *pos += 1;
+
+        let mut node = Node { key: entry.key.clone(), value: entry.value.clone(), line: entry.line, children: Vec::new() };
+        if let Some(next) = entries.get(*pos) {
+            if next.indent > entry.indent {
+                node.children = build(entries, pos, next.indent, false);
+            } else if next.indent == entry.indent && next.key == "-" && entry.key != "-" && entry.value.is_empty() {
+                // Compose allows list items level with their key
+                node.children = build(entries, pos, next.indent, true);
+            }
+        }
+        nodes.push(node);
+    }
+
+    nodes
+}
+
+/// `key: value` or `key:`; scalars such as `- 8080:80` or URLs are not keys
+fn split_key(text: &str) -> Option<(String, String)> {
+    let (key, value) = match text.strip_suffix(':') {
+        Some(key) if !key.contains(": ") => (key, ""),
+        _ => text.split_once(": ")?,
+    };
+    if key.is_empty() || key.starts_with(['{', '[', '"', '\'']) && !key.ends_with(['"', '\'']) {
+        return None;
+    }
+    let value = match value.find(" #") {
+        Some(pos) => &value[..pos],
+        None => value,
+    };
+    Some((unquote(key), unquote(value)))
+}
+
+fn unquote(text: &str) -> String {
+    text.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_compose_map_with_code_and_queues() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let compose = write(root, "docker-compose.yml", r#"
+services:
+  web:
+    build: ./web
+    depends_on:
+    - db
+    environment:
+      - PAYMENTS_URL=http://payments:8080
+      - AUDIT_URL=http://audit-svc:9000
+  payments:
+    build:
+      context: ./payments
+    depends_on: [db, kafka]
+    environment:
+      DATABASE_URL: "postgres://app@db:5432/app"
+      CACHE_URL: http://localhost:6379
+  mailer:
+    build: ./mailer
+  db:
+    image: postgres:16
+  kafka:
+    image: bitnami/kafka
+"#);
+        write(root, "web/src/api.ts", "export const pay = (id) => fetch(`${API}/payments/${id}`, { method: 'POST' });\n");
+        write(root, "payments/src/main.rs", "let app = Router::new().route(\"/payments/:id\", post(create_payment));\nproducer.send(\"payment.completed\", payload);\nproducer.send(\"payment.audit\", payload);\n");
+        write(root, "mailer/app.py", "consumer.subscribe([\"payment.completed\"])\n");
+
+        let map = ServiceMap::build(&[compose]);
+        let names: Vec<&str> = map.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["db", "kafka", "mailer", "payments", "web"]);
+
+        let edge = |from: &str, to: &str| map.edges.get(&(from.to_string(), to.to_string()));
+        let kinds = |from: &str, to: &str| edge(from, to).map(|e| e.iter().map(|e| e.kind).collect::<BTreeSet<_>>()).unwrap_or_default();
+        assert_eq!(kinds("web", "payments"), BTreeSet::from([EdgeKind::Config, EdgeKind::Http]));
+        assert_eq!(kinds("payments", "db"), BTreeSet::from([EdgeKind::Declared, EdgeKind::Config]));
+        assert_eq!(kinds("payments", "mailer"), BTreeSet::from([EdgeKind::Queue]));
+        assert_eq!(kinds("web", "db"), BTreeSet::from([EdgeKind::Declared]));
+
+        assert!(map.is_undeclared("web", "payments"));
+        assert!(!map.is_undeclared("payments", "mailer"));
+        let unreachable: Vec<&str> = map
+            .issues
+            .iter()
+            .filter_map(|i| match i {
+                Issue::Unreachable { target, .. } => Some(target.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(unreachable, vec!["http://localhost:6379", "http://audit-svc:9000"]);
+        assert!(map.issues.iter().any(|i| matches!(i, Issue::OrphanTopic { topic, published: true, .. } if topic == "payment.audit")));
+
+        let mermaid = map.render_mermaid();
+        assert!(mermaid.contains("web ==>|config, http, undeclared| payments"));
+        assert!(mermaid.contains("web -.-> db"));
+        assert!(mermaid.contains("payments -->|payment.completed| mailer"));
+    }
+
+    #[test]
+    fn test_kubernetes_services_and_aliases() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let manifest = write(temp_dir.path(), "k8s/app.yaml", r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: orders
+spec:
+  template:
+    metadata:
+      labels:
+        app: orders
+    spec:
+      containers:
+        - name: orders
+          env:
+            - name: INVENTORY_URL
+              value: "http://inventory-api.shop.svc.cluster.local:80/v1"
+            - name: LOG_LEVEL
+              value: debug
+---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: inventory
+spec:
+  template:
+    metadata:
+      labels:
+        app: inventory
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: inventory-api
+spec:
+  selector:
+    app: inventory
+"#);
+
+        let map = ServiceMap::build(&[manifest]);
+        let names: Vec<&str> = map.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["inventory", "orders"]);
+        assert_eq!(map.resolve("inventory-api"), Some("inventory"));
+
+        let evidence = &map.edges[&("orders".to_string(), "inventory".to_string())];
+        assert_eq!(evidence[0].kind, EdgeKind::Config);
+        assert_eq!(evidence[0].line, 16);
+        // Kubernetes has no depends_on, so nothing is undeclared
+        assert!(map.issues.is_empty());
+    }
+}
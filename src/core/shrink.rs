@@ -0,0 +1,258 @@
+//! Crash input minimization
+//!
+//! Delta debugging (ddmin) over a crashing input: first by lines, then by
+//! bytes, keeping only reductions that still reproduce the same crash.
+
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use super::permissions::{self, Permission};
+use super::process;
+
+/// Placeholder in the reproduce command replaced by the input path
+pub const INPUT_PLACEHOLDER: &str = "{}";
+
+/// Stderr lines kept from a crashing run
+const STDERR_TAIL_LINES: usize = 40;
+
+/// Minimize `items` while `test` keeps returning true (Zeller's ddmin)
+pub fn ddmin<T: Clone>(items: &[T], test: &mut impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut items = items.to_vec();
+    let mut n = 2;
+
+    while items.len() >= 2 {
+        let chunk = items.len().div_ceil(n);
+        let mut reduced = false;
+
+        // Try each chunk on its own
+        for start in (0..items.len()).step_by(chunk) {
+            let end = (start + chunk).min(items.len());
+            if test(&items[start..end]) {
+                items = items[start..end].to_vec();
+                n = 2;
+                reduced = true;
+                break;
+            }
+        }
+
+        // Then try removing each chunk
+        if !reduced {
+            for start in (0..items.len()).step_by(chunk) {
+                let end = (start + chunk).min(items.len());
+                let complement: Vec<T> = items[..start].iter().chain(&items[end..]).cloned().collect();
+                if test(&complement) {
+                    items = complement;
+                    n = (n - 1).max(2);
+                    reduced = true;
+                    break;
+                }
+            }
+        }
+
+        if !reduced {
+            if n >= items.len() {
+                break;
+            }
+            n = (n * 2).min(items.len());
+        }
+    }
+
+    items
+}
+
+/// Minimize an input by lines, then by bytes
+pub fn shrink_bytes(input: &[u8], test: &mut impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+    let lines: Vec<&[u8]> = input.split_inclusive(|b| *b == b'\n').collect();
+    let by_lines = ddmin(&lines, &mut |subset: &[&[u8]]| test(&subset.concat())).concat();
+
+    ddmin(&by_lines, test)
+}
+
+/// Result of running the reproduce command once
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub timed_out: bool,
+    pub stderr: String,
+}
+
+impl Outcome {
+    pub fn crashed(&self) -> bool {
+        !self.timed_out && (self.signal.is_some() || self.exit_code.is_some_and(|c| c != 0))
+    }
+
+    /// Identifies the crash, so shrinking does not drift to a different bug
+    pub fn signature(&self) -> String {
+        let location = self.stderr.lines().find_map(|line| {
+            let line = line.trim();
+            // Rust panics: "thread 'main' panicked at src/lib.rs:10:5:"
+            if let Some(idx) = line.find("panicked at ") {
+                return Some(line[idx..].trim_end_matches(':').to_string());
+            }
+            // Sanitizers: "ERROR: AddressSanitizer: heap-buffer-overflow ..."
+            if line.starts_with("ERROR: ") && line.contains("Sanitizer") {
+                return Some(line.split(" on ").next().unwrap_or(line).to_string());
+            }
+            None
+        });
+
+        // Python: the exception type on the last traceback line
+        let exception = if self.stderr.contains("Traceback (most recent call last)") {
+            self.stderr
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .and_then(|l| l.split(':').next())
+                .map(|t| t.trim().to_string())
+        } else {
+            None
+        };
+
+        let status = match (self.signal, self.exit_code) {
+            (Some(signal), _) => format!("signal {}", signal),
+            (None, Some(code)) => format!("exit {}", code),
+            _ => "unknown".to_string(),
+        };
+
+        [Some(status), location, exception].into_iter().flatten().collect::<Vec<_>>().join(" | ")
+    }
+
+    /// Last lines of stderr
+    pub fn stderr_tail(&self) -> String {
+        let lines: Vec<&str> = self.stderr.lines().collect();
+        lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n")
+    }
+}
+
+/// Runs a command against candidate inputs
+pub struct Reproducer {
+    command: Vec<String>,
+    timeout: Duration,
+    /// Private directory holding `scratch`, removed on drop
+    _scratch_dir: TempDir,
+    scratch: PathBuf,
+    pub runs: usize,
+}
+
+impl Reproducer {
+    /// `command` may contain `{}` where the input path goes; otherwise the
+    /// path is appended as the last argument
+    pub fn new(command: Vec<String>, timeout: Duration, input: &Path) -> Result<Self> {
+        if command.is_empty() {
+            anyhow::bail!("No reproduce command given");
+        }
+        permissions::request(Permission::Run, &format!("run `{}` on candidate inputs", command.join(" ")))?;
+
+        // Candidates keep the input's file name, so its extension still applies
+        let scratch_dir = tempfile::Builder::new()
+            .prefix("nexus-shrink-")
+            .tempdir()
+            .context("Failed to create a scratch directory")?;
+        let scratch = scratch_dir.path().join(input.file_name().unwrap_or("input".as_ref()));
+
+        Ok(Self { command, timeout, _scratch_dir: scratch_dir, scratch, runs: 0 })
+    }
+
+    /// Run the command on an input
+    pub fn run(&mut self, input: &[u8]) -> Result<Outcome> {
+        fs::write(&self.scratch, input)
+            .with_context(|| format!("Failed to write {}", self.scratch.display()))?;
+        self.runs += 1;
+
+        let scratch = self.scratch.display().to_string();
+        let mut args: Vec<String> = self.command.iter().map(|a| a.replace(INPUT_PLACEHOLDER, &scratch)).collect();
+        if !self.command.iter().any(|a| a.contains(INPUT_PLACEHOLDER)) {
+            args.push(scratch);
+        }
+
+        let output = process::run_with_timeout(Command::new(&args[0]).args(&args[1..]), Vec::new(), self.timeout)
+            .with_context(|| format!("Failed to run {}", args[0]))?;
+        let (status, stderr) = match &output {
+            Some(output) => (Some(output.status), String::from_utf8_lossy(&output.stderr).to_string()),
+            None => (None, String::new()),
+        };
+
+        Ok(Outcome {
+            exit_code: status.and_then(|s| s.code()),
+            signal: status.and_then(exit_signal),
+            timed_out: output.is_none(),
+            stderr,
+        })
+    }
+
+    /// Whether an input reproduces the crash with the given signature
+    pub fn reproduces(&mut self, input: &[u8], signature: &str) -> bool {
+        self.run(input)
+            .map(|o| o.crashed() && o.signature() == signature)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ddmin_finds_minimal_trigger() {
+        // Crashes when both `<` and `>` are present
+        let input = b"header\nbody <tag with attrs>\nfooter\n";
+        let mut runs = 0;
+        let minimized = shrink_bytes(input, &mut |candidate: &[u8]| {
+            runs += 1;
+            candidate.contains(&b'<') && candidate.contains(&b'>')
+        });
+
+        assert_eq!(minimized, b"<>");
+        assert!(runs < 200);
+    }
+
+    #[test]
+    fn test_signature_ignores_message_details() {
+        let outcome = |stderr: &str| Outcome { exit_code: Some(101), signal: None, timed_out: false, stderr: stderr.to_string() };
+
+        let a = outcome("thread 'main' panicked at src/parse.rs:42:9:\nindex out of bounds: the len is 3 but the index is 7");
+        let b = outcome("thread 'main' panicked at src/parse.rs:42:9:\nindex out of bounds: the len is 0 but the index is 1");
+        let c = outcome("thread 'main' panicked at src/lex.rs:3:1:\nunwrap on None");
+
+        assert_eq!(a.signature(), "exit 101 | panicked at src/parse.rs:42:9");
+        assert_eq!(a.signature(), b.signature());
+        assert_ne!(a.signature(), c.signature());
+
+        let py = outcome("Traceback (most recent call last):\n  File \"x.py\", line 1\nKeyError: 'name'\n");
+        assert_eq!(py.signature(), "exit 101 | KeyError");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reproducer_runs_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let input = temp_dir.path().join("crash.txt");
+        let command = vec!["sh".to_string(), "-c".to_string(), "grep -q BOOM {} && exit 3 || exit 0".to_string()];
+        let mut reproducer = Reproducer::new(command, Duration::from_secs(5), &input).unwrap();
+
+        let crash = reproducer.run(b"xx BOOM xx").unwrap();
+        assert!(crash.crashed());
+        assert!(!reproducer.run(b"fine").unwrap().crashed());
+
+        let minimized = shrink_bytes(b"xx BOOM xx", &mut |c: &[u8]| reproducer.reproduces(c, &crash.signature()));
+        assert_eq!(minimized, b"BOOM");
+    }
+}
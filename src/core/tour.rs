@@ -0,0 +1,246 @@
+//! Code tours
+//!
+//! Retrieves the symbols most relevant to a topic and assembles them into a
+//! VS Code CodeTour (`.tours/*.tour`) that can be replayed step by step.
+
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::parser::{identifier_words, ParsedFile, SymbolKind};
+use crate::index::is_test_path;
+
+/// Directory CodeTour reads tours from
+pub const TOURS_DIR: &str = ".tours";
+
+const CODETOUR_SCHEMA: &str = "https://aka.ms/codetour-schema";
+
+/// Words that carry no meaning in a topic
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "how", "what", "does", "with", "from", "into", "this", "that", "are",
+    "works", "work", "code", "flow", "when", "where", "which", "about",
+];
+
+/// A tour in CodeTour's JSON format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tour {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub steps: Vec<TourStep>,
+}
+
+impl Tour {
+    pub fn new(title: &str, description: &str, steps: Vec<TourStep>) -> Self {
+        Self {
+            schema: CODETOUR_SCHEMA.to_string(),
+            title: title.to_string(),
+            description: description.to_string(),
+            steps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TourStep {
+    /// Path relative to the workspace root, with forward slashes
+    pub file: String,
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    /// Markdown shown next to the line
+    pub description: String,
+}
+
+/// A symbol retrieved for a topic
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub signature: Option<String>,
+    pub score: f64,
+}
+
+/// Meaningful lower-case words of a topic
+pub fn topic_words(topic: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    topic
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .flat_map(identifier_words)
+        .filter(|w| w.len() > 2 && !STOP_WORDS.contains(&w.as_str()))
+        .filter(|w| seen.insert(w.clone()))
+        .collect()
+}
+
+/// Symbols ranked by how much of the topic their name, signature and body mention
+pub fn rank_candidates(files: &[ParsedFile], topic: &str, limit: usize) -> Vec<Candidate> {
+    let words = topic_words(topic);
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut candidates = Vec::new();
+
+    // Tests explain how code is checked, not how it works
+    for file in files.iter().filter(|f| !is_test_path(&f.path)) {
+        let lines: Vec<&str> = file.content.lines().collect();
+
+        for symbol in &file.symbols {
+            if matches!(symbol.kind, SymbolKind::Impl | SymbolKind::Module) || symbol.name.starts_with("test_") {
+                continue;
+            }
+
+            let name_words = identifier_words(&symbol.name);
+            let signature = symbol.signature.as_deref().unwrap_or("").to_lowercase();
+            let body = lines
+                .get(symbol.line_start.saturating_sub(1)..symbol.line_end.min(lines.len()))
+                .map(|l| l.join("\n").to_lowercase())
+                .unwrap_or_default();
+
+            let mut score = 0.0;
+            for word in &words {
+                if name_words.iter().any(|w| same_stem(w, word)) {
+                    score += 3.0;
+                } else if signature.contains(word.as_str()) {
+                    score += 1.5;
+                } else if body.contains(word.as_str()) {
+                    score += 1.0;
+                }
+            }
+
+            if score == 0.0 {
+                continue;
+            }
+
+            // Types and entry points anchor a tour better than helpers
+            score *= match symbol.kind {
+                SymbolKind::Struct | SymbolKind::Class | SymbolKind::Trait | SymbolKind::Interface => 1.2,
+                SymbolKind::Function => 1.1,
+                _ => 1.0,
+            };
+
+            candidates.push(Candidate {
+                path: file.path.clone(),
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                line_start: symbol.line_start,
+                line_end: symbol.line_end,
+                signature: symbol.signature.clone(),
+                score,
+            });
+        }
+    }
+
+    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    candidates.truncate(limit);
+    candidates
+}
+
+/// Whether two words are variants of each other ("validate", "validation")
+fn same_stem(a: &str, b: &str) -> bool {
+    let common = a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count();
+    common == a.len().min(b.len()) && common >= 3 || common >= 5
+}
+
+/// File name for a tour about `topic`
+pub fn tour_file_name(topic: &str) -> String {
+    let slug: String = topic
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!("{}.tour", if slug.is_empty() { "tour" } else { &slug })
+}
+
+/// Workspace-relative path in CodeTour's form
+pub fn tour_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Keep only steps that point at a retrieved file and a line inside it,
+/// so a tour never sends readers to code that does not exist
+pub fn validate_steps(steps: Vec<TourStep>, candidates: &[Candidate], files: &[ParsedFile], root: &Path) -> Vec<TourStep> {
+    let mut seen = HashSet::new();
+
+    steps
+        .into_iter()
+        .filter_map(|mut step| {
+            step.file = step.file.trim_start_matches("./").to_string();
+            let file = files.iter().find(|f| tour_path(&f.path, root) == step.file)?;
+            let retrieved = candidates.iter().any(|c| tour_path(&c.path, root) == step.file);
+            if !retrieved || step.line == 0 || step.line > file.line_count.max(1) {
+                return None;
+            }
+            seen.insert((step.file.clone(), step.line)).then_some(step)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::parser::CodeParser;
+
+    fn parsed(path: &str, content: &str) -> ParsedFile {
+        CodeParser::new().unwrap().parse_source(Path::new(path), content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_rank_candidates_prefers_name_matches() {
+        let files = vec![
+            parsed("./src/auth.rs", "pub struct SessionToken;\n\npub fn validate_session(token: &SessionToken) -> bool {\n    true\n}\n\nfn unrelated() {\n    let session = 1;\n}\n"),
+            parsed("./src/db.rs", "fn connect() {}\n"),
+        ];
+
+        assert_eq!(topic_words("How does session validation work?"), vec!["session", "validation"]);
+
+        let ranked = rank_candidates(&files, "How does session validation work?", 10);
+        let names: Vec<&str> = ranked.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["validate_session", "SessionToken", "unrelated"]);
+    }
+
+    #[test]
+    fn test_validate_steps_and_serialize() {
+        let files = vec![parsed("./src/auth.rs", "fn login() {\n    check();\n}\n\nfn check() {}\n")];
+        let root = Path::new(".");
+        let candidates = rank_candidates(&files, "login", 5);
+
+        let step = |file: &str, line: usize| TourStep {
+            file: file.to_string(),
+            line,
+            title: String::new(),
+            description: "Step".to_string(),
+        };
+        let steps = validate_steps(
+            vec![step("src/auth.rs", 1), step("src/auth.rs", 99), step("src/missing.rs", 1), step("./src/auth.rs", 1)],
+            &candidates,
+            &files,
+            root,
+        );
+        assert_eq!(steps.len(), 1);
+
+        let json = serde_json::to_string(&Tour::new("Login", "", steps)).unwrap();
+        assert_eq!(json, r#"{"$schema":"https://aka.ms/codetour-schema","title":"Login","steps":[{"file":"src/auth.rs","line":1,"description":"Step"}]}"#);
+        assert_eq!(tour_file_name("How does Login work?"), "how-does-login-work.tour");
+    }
+}
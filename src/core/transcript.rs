@@ -0,0 +1,331 @@
+//! Chat transcripts
+//!
+//! Every chat session is saved to `.nexus/history/<id>.json` as it goes, so
+//! it can be exported later as Markdown, standalone HTML or JSON for sharing
+//! design discussions.
+
+#![allow(dead_code)]
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::journal::{create_project_dir, PROJECT_DIR};
+
+const HISTORY_DIR: &str = "history";
+
+/// Who wrote a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Speaker {
+    User,
+    Assistant,
+}
+
+impl Speaker {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Speaker::User => "You",
+            Speaker::Assistant => "Nexus AI",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptMessage {
+    pub speaker: Speaker,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A chat session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    /// `YYYYMMDD-HHMMSS` of the session start
+    pub id: String,
+    pub started: DateTime<Utc>,
+    pub provider: String,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+/// Transcript export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "markdown" | "md" => Some(ExportFormat::Markdown),
+            "html" | "htm" => Some(ExportFormat::Html),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl Transcript {
+    pub fn new(provider: &str) -> Self {
+        let started = Utc::now();
+        Self {
+            id: started.with_timezone(&Local).format("%Y%m%d-%H%M%S").to_string(),
+            started,
+            provider: provider.to_string(),
+            messages: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, speaker: Speaker, content: &str) {
+        self.messages.push(TranscriptMessage {
+            speaker,
+            content: content.to_string(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// First user message, shortened, as a title
+    pub fn title(&self) -> String {
+        let first = self
+            .messages
+            .iter()
+            .find(|m| m.speaker == Speaker::User)
+            .and_then(|m| m.content.lines().find(|l| !l.trim().is_empty()))
+            .unwrap_or("Chat")
+            .trim();
+        let mut title: String = first.chars().take(60).collect();
+        if title.chars().count() < first.chars().count() {
+            title.push('…');
+        }
+        title
+    }
+
+    fn dir(root: &Path) -> PathBuf {
+        root.join(PROJECT_DIR).join(HISTORY_DIR)
+    }
+
+    /// Write the session under `root`, replacing the previous save
+    pub fn save(&self, root: &Path) -> Result<PathBuf> {
+        let dir = Self::dir(root);
+        create_project_dir(&dir)?;
+        let path = dir.join(format!("{}.json", self.id));
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Saved sessions under `root`, newest first
+    pub fn list(root: &Path) -> Result<Vec<Transcript>> {
+        let dir = Self::dir(root);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut transcripts: Vec<Transcript> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+            .filter_map(|e| fs::read_to_string(e.path()).ok())
+            .filter_map(|c| serde_json::from_str(&c).ok())
+            .collect();
+        transcripts.sort_by_key(|t| std::cmp::Reverse(t.started));
+        Ok(transcripts)
+    }
+
+    /// A saved session by id, unique id prefix, or `latest`
+    pub fn load(root: &Path, id: &str) -> Result<Transcript> {
+        let transcripts = Self::list(root)?;
+        if id == "latest" {
+            return transcripts.into_iter().next().context("No saved chats yet");
+        }
+
+        let mut matches: Vec<Transcript> = transcripts.into_iter().filter(|t| t.id.starts_with(id)).collect();
+        match matches.len() {
+            0 => bail!("No chat with id `{}` (see `nexus history list`)", id),
+            1 => Ok(matches.remove(0)),
+            n => bail!("`{}` matches {} chats; use more of the id", id, n),
+        }
+    }
+
+    /// File name for an export, e.g. `chat-20240512-141503.md`
+    pub fn file_name(&self, format: ExportFormat) -> String {
+        format!("chat-{}.{}", self.id, format.extension())
+    }
+
+    pub fn render(&self, format: ExportFormat) -> Result<String> {
+        Ok(match format {
+            ExportFormat::Markdown => self.render_markdown(),
+            ExportFormat::Html => self.render_html(),
+            ExportFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = format!(
+            "# {}\n\n*{} · {} · {} messages*\n",
+            self.title(),
+            local_time(&self.started, "%Y-%m-%d %H:%M"),
+            self.provider,
+            self.messages.len()
+        );
+        for message in &self.messages {
+            out.push_str(&format!(
+                "\n---\n\n### {} · {}\n\n{}\n",
+                message.speaker.label(),
+                local_time(&message.timestamp, "%H:%M:%S"),
+                message.content.trim_end()
+            ));
+        }
+        out
+    }
+
+    fn render_html(&self) -> String {
+        let mut body = String::new();
+        for message in &self.messages {
+            let class = match message.speaker {
+                Speaker::User => "user",
+                Speaker::Assistant => "assistant",
+            };
+            body.push_str(&format!(
+                "<section class=\"{}\">\n<header><strong>{}</strong> <time datetime=\"{}\">{}</time></header>\n{}</section>\n",
+                class,
+                message.speaker.label(),
+                message.timestamp.to_rfc3339(),
+                local_time(&message.timestamp, "%H:%M:%S"),
+                markdown_to_html(&message.content)
+            ));
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, "Segoe UI", sans-serif; max-width: 860px; margin: 2rem auto; padding: 0 1rem; color: #212121; }}
+section {{ border-left: 3px solid #64B5F6; padding: 0.25rem 1rem; margin: 1.5rem 0; }}
+section.assistant {{ border-color: #FFCA28; }}
+header {{ color: #546E7A; font-size: 0.9rem; }}
+time {{ margin-left: 0.5rem; }}
+pre {{ background: #263238; color: #EEFFFF; padding: 0.75rem; overflow-x: auto; border-radius: 4px; }}
+code {{ font-family: "JetBrains Mono", Menlo, monospace; font-size: 0.9em; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="meta">{started} · {provider} · {count} messages</p>
+{body}</body>
+</html>
+"#,
+            title = escape_html(&self.title()),
+            started = local_time(&self.started, "%Y-%m-%d %H:%M"),
+            provider = escape_html(&self.provider),
+            count = self.messages.len(),
+            body = body
+        )
+    }
+}
+
+fn local_time(time: &DateTime<Utc>, format: &str) -> String {
+    time.with_timezone(&Local).format(format).to_string()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// `inline code` spans as `<code>`, everything else escaped
+fn inline_html(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 1 { format!("<code>{}</code>", escape_html(part)) } else { escape_html(part) })
+        .collect()
+}
+
+/// Fenced code blocks as `<pre><code>`, other text as paragraphs
+fn markdown_to_html(text: &str) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    let flush = |paragraph: &mut Vec<String>, out: &mut String| {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", paragraph.join("<br>\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in text.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+        match (&mut code, fence) {
+            (Some((language, lines)), Some(_)) => {
+                let class = if language.is_empty() { String::new() } else { format!(" class=\"language-{}\"", escape_html(language)) };
+                out.push_str(&format!("<pre><code{}>{}</code></pre>\n", class, escape_html(&lines.join("\n"))));
+                code = None;
+            }
+            (Some((_, lines)), None) => lines.push(line),
+            (None, Some(language)) => {
+                flush(&mut paragraph, &mut out);
+                code = Some((language.trim().to_string(), Vec::new()));
+            }
+            (None, None) if line.trim().is_empty() => flush(&mut paragraph, &mut out),
+            (None, None) => paragraph.push(inline_html(line)),
+        }
+    }
+
+    // An unterminated fence still renders as code
+    if let Some((_, lines)) = code {
+        out.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(&lines.join("\n"))));
+    }
+    flush(&mut paragraph, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_load_and_export() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut transcript = Transcript::new("Claude");
+        transcript.push(Speaker::User, "Should the cache be write-through?");
+        transcript.push(Speaker::Assistant, "Yes, use `put` then:\n\n```rust\ncache.put(k, v);\nstore.write(k, v) < 1\n```\n\nDone.");
+        transcript.save(temp_dir.path()).unwrap();
+
+        let loaded = Transcript::load(temp_dir.path(), &transcript.id[..8]).unwrap();
+        assert_eq!(loaded.messages.len(), 2);
+        assert_eq!(Transcript::load(temp_dir.path(), "latest").unwrap().id, transcript.id);
+        assert!(Transcript::load(temp_dir.path(), "1999").is_err());
+
+        let markdown = loaded.render(ExportFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("# Should the cache be write-through?"));
+        assert!(markdown.contains("### Nexus AI · "));
+        assert!(markdown.contains("```rust\ncache.put(k, v);"));
+
+        let html = loaded.render(ExportFormat::Html).unwrap();
+        assert!(html.contains("<pre><code class=\"language-rust\">cache.put(k, v);\nstore.write(k, v) &lt; 1</code></pre>"));
+        assert!(html.contains("<p>Yes, use <code>put</code> then:</p>"));
+        assert!(html.contains("<p>Done.</p>"));
+
+        assert_eq!(ExportFormat::parse("MD"), Some(ExportFormat::Markdown));
+        assert_eq!(loaded.file_name(ExportFormat::Html), format!("chat-{}.html", loaded.id));
+    }
+}
@@ -0,0 +1,442 @@
+//! HTTP endpoint inventory
+//!
+//! Finds route definitions (axum, actix/rocket, Flask/FastAPI, Express)
+//! and maps them to the handler symbols that serve them, plus the HTTP
+//! client calls that reach them from other services.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::parser::Language;
+
+const HTTP_METHODS: &[&str] = &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Text that marks a line as an outgoing HTTP request
+const CLIENT_MARKERS: &[&str] = &[
+    "fetch(", "axios", "requests.", "httpx.", "reqwest", "http.Get", "http.Post", "http.NewRequest",
+    "client.", "$http", "ky.", "got(", "superagent", "HttpClient", "urlopen(", ".request(",
+];
+
+/// A route served by the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// Upper-case HTTP method, or `*` for any
+    pub method: String,
+    /// Route template as written, e.g. `/users/:id`
+    pub path: String,
+    /// Handler function name
+    pub handler: String,
+    pub file: PathBuf,
+    /// 1-based line of the route definition
+    pub line: usize,
+}
+
+impl Endpoint {
+    /// Whether a concrete request is served by this route
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        if self.method != "*" && !self.method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+
+        let path = path.split('?').next().unwrap_or(path);
+        let template: Vec<&str> = self.path.split('/').filter(|s| !s.is_empty()).collect();
+        let actual: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        for (i, segment) in template.iter().enumerate() {
+            if *segment == "*" || segment.starts_with("{*") || segment.starts_with("*") {
+                return true;
+            }
+            let Some(value) = actual.get(i) else {
+                return false;
+            };
+            if !is_param(segment) && segment != value {
+                return false;
+            }
+        }
+
+        template.len() == actual.len()
+    }
+}
+
+/// An outgoing HTTP request found in source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// Upper-case HTTP method, when the call names one
+    pub method: Option<String>,
+    /// Request path with interpolated segments as `:param`, e.g. `/payments/:param`
+    pub path: String,
+    pub file: PathBuf,
+    /// 1-based line of the call
+    pub line: usize,
+}
+
+impl CallSite {
+    /// Whether this call can reach `endpoint`; calls without a method match any
+    pub fn reaches(&self, endpoint: &Endpoint) -> bool {
+        let method = self.method.as_deref().unwrap_or(&endpoint.method);
+        endpoint.matches(if method == "*" { "GET" } else { method }, &self.path)
+    }
+
+    /// Routes in `endpoints` this call can reach
+    pub fn targets<'a>(&self, endpoints: &'a [Endpoint]) -> Vec<&'a Endpoint> {
+        endpoints.iter().filter(|e| self.reaches(e)).collect()
+    }
+}
+
+fn is_param(segment: &str) -> bool {
+    segment.starts_with(':') || segment.starts_with('{') || segment.starts_with('<')
+}
+
+/// Build the endpoint inventory for a set of files
+pub fn inventory(files: &[PathBuf]) -> Vec<Endpoint> {
+    files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok().map(|c| scan_source(path, &c)))
+        .flatten()
+        .collect()
+}
+
+/// Find outgoing HTTP calls in a set of files
+pub fn call_sites(files: &[PathBuf]) -> Vec<CallSite> {
+    files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok().map(|c| scan_calls(path, &c)))
+        .flatten()
+        .collect()
+}
+
+/// Find outgoing HTTP calls in one source file
+pub fn scan_calls(path: &Path, content: &str) -> Vec<CallSite> {
+    if Language::from_path(path) == Language::Unknown {
+        return Vec::new();
+    }
+
+    // Route definitions look like client calls (`app.get('/x', ...)`)
+    let routes: Vec<usize> = scan_source(path, content).iter().map(|e| e.line).collect();
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(idx, line)| !routes.contains(&(idx + 1)) && CLIENT_MARKERS.iter().any(|m| line.contains(m)))
+        .filter_map(|(idx, line)| {
+            let path_part = string_literals(line).into_iter().find_map(|s| request_path(&s))?;
+            Some(CallSite {
+                method: request_method(line),
+                path: path_part,
+                file: path.to_path_buf(),
+                line: idx + 1,
+            })
+        })
+        .collect()
+}
+
+/// Every quoted string on a line
+pub fn string_literals(line: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'', '`']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let body = &rest[start + 1..];
+        let Some(end) = body.find(quote) else {
+            break;
+        };
+        found.push(body[..end].to_string());
+        rest = &body[end + 1..];
+    }
+    found
+}
+
+/// The path of a URL literal, dropping scheme, host and a leading base-URL
+/// placeholder; interpolations become `:param`
+fn request_path(literal: &str) -> Option<String> {
+    let mut text = literal.trim();
+    if let Some(pos) = text.find("://") {
+        let after = &text[pos + 3..];
+        text = &after[after.find('/')?..];
+    } else if !text.starts_with('/') {
+        // `${API}/payments`, `{}/payments`, `{base}/payments`, `%s/payments`
+        let slash = text.find('/')?;
+        let prefix = &text[..slash];
+        let placeholder = (prefix.starts_with("${") || prefix.starts_with('{')) && prefix.ends_with('}') || prefix == "%s";
+        if !placeholder {
+            return None;
+        }
+        text = &text[slash..];
+    }
+
+    let text = text.split(['?', '#']).next().unwrap_or(text);
+    if text.contains(char::is_whitespace) || !text.chars().any(|c| c.is_alphabetic()) {
+        return None;
+    }
+
+    let segments: Vec<String> = text
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if s.contains('{') || s.contains("%s") || s.contains("%d") || s.starts_with(':') {
+                ":param".to_string()
+            } else {
+                s.to_string()
+            }
+        })
+        .collect();
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// HTTP method named by a client call: `.post(`, `requests.put(`, `method: "DELETE"`
+fn request_method(line: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    HTTP_METHODS
+        .iter()
+        .find(|m| {
+            lower.contains(&format!(".{}(", m))
+                || lower.contains(&format!("method: '{}'", m))
+                || lower.contains(&format!("method: \"{}\"", m))
+                || lower.contains(&format!("method=\"{}\"", m))
+                || lower.contains(&format!("method::{}", m))
+        })
+        .map(|m| m.to_uppercase())
+}
+
+/// Find route definitions in one source file
+pub fn scan_source(path: &Path, content: &str) -> Vec<Endpoint> {
+    let language = Language::from_path(path);
+    let lines: Vec<&str> = content.lines().collect();
+    let mut endpoints = Vec::new();
+
+    for (idx, raw) in lines.iter().enumerate() {
+        let line = raw.trim();
+        let mut push = |method: &str, route: String, handler: String| {
+            endpoints.push(Endpoint {
+                method: method.to_uppercase(),
+                path: route,
+                handler,
+                file: path.to_path_buf(),
+                line: idx + 1,
+            });
+        };
+
+        match language {
+            Language::Rust => {
+                // axum: .route("/users/:id", get(show_user).delete(remove_user))
+                if let Some(pos) = line.find(".route(") {
+                    let rest = &line[pos + 7..];
+                    if let Some(route) = first_string(rest) {
+                        for (method, handler) in method_calls(rest) {
+                            push(&method, route.clone(), handler);
+                        }
+                    }
+                }
+                // actix / rocket: #[get("/users/{id}")]
+                if let Some((method, route)) = attribute_route(line, "#[") {
+                    if let Some(handler) = next_definition(&lines[idx + 1..], "fn ") {
+                        push(&method, route, handler);
+                    }
+                }
+            }
+            Language::Python => {
+                // Flask / FastAPI: @app.get("/items/{id}"), @bp.route("/x", methods=["POST"])
+                if line.starts_with('@') {
+                    if let Some((method, route)) = python_decorator(line) {
+                        if let Some(handler) = next_definition(&lines[idx + 1..], "def ") {
+                            for m in method {
+                                push(&m, route.clone(), handler.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            Language::JavaScript | Language::TypeScript => {
+                // Express: app.get('/users/:id', auth, showUser)
+                for method in HTTP_METHODS.iter().chain(&["all"]) {
+                    for receiver in ["app.", "router.", "server."] {
+                        let call = format!("{}{}(", receiver, method);
+                        if let Some(pos) = line.find(&call) {
+                            let rest = &line[pos + call.len()..];
+                            if let Some(route) = first_string(rest).filter(|r| r.starts_with('/')) {
+                                let method = if *method == "all" { "*" } else { method };
+                                push(method, route, express_handler(rest));
+                            }
+                        }
+                    }
+                }
+            }
+            Language::Unknown => {}
+        }
+    }
+
+    endpoints
+}
+
+/// First quoted string in some text
+fn first_string(text: &str) -> Option<String> {
+    let start = text.find(['"', '\'', '`'])?;
+    let quote = text[start..].chars().next()?;
+    let body = &text[start + 1..];
+    Some(body[..body.find(quote)?].to_string())
+}
+
+/// `get(handler).post(other)` pairs in an axum method router
+fn method_calls(text: &str) -> Vec<(String, String)> {
+    let mut calls = Vec::new();
+
+    for method in HTTP_METHODS.iter().chain(&["any"]) {
+        let mut search = 0;
+        while let Some(pos) = text[search..].find(&format!("{}(", method)) {
+            let at = search + pos;
+            search = at + method.len() + 1;
+
+            // Must be a whole word: `get(` but not `target(`
+            let before = text[..at].chars().last();
+            if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+
+            let handler: String = text[search..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':')
+                .collect();
+            if !handler.is_empty() {
+                let name = handler.rsplit("::").next().unwrap_or(&handler).to_string();
+                let method = if *method == "any" { "*" } else { method };
+                calls.push((method.to_string(), name));
+            }
+        }
+    }
+
+    calls
+}
+
+/// `#[get("/path")]` style route attributes
+fn attribute_route(line: &str, prefix: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix(prefix)?;
+    let method: String = rest.chars().take_while(|c| c.is_alphabetic()).collect();
+    if !HTTP_METHODS.contains(&method.as_str()) {
+        return None;
+    }
+    let route = first_string(&rest[method.len()..])?;
+    Some((method, route))
+}
+
+/// Methods and route from a Flask/FastAPI decorator
+fn python_decorator(line: &str) -> Option<(Vec<String>, String)> {
+    let call = line.trim_start_matches('@');
+    let (target, rest) = call.split_once('(')?;
+    let verb = target.rsplit('.').next()?;
+    let route = first_string(rest)?;
+
+    if HTTP_METHODS.contains(&verb) {
+        return Some((vec![verb.to_string()], route));
+    }
+    if verb != "route" && verb != "api_route" {
+        return None;
+    }
+
+    // methods=["GET", "POST"]; Flask defaults to GET
+    let methods = match rest.find("methods") {
+        Some(pos) => rest[pos..]
+            .split(['[', ']'])
+            .nth(1)
+            .unwrap_or("")
+            .split(',')
+            .filter_map(first_string)
+            .collect(),
+        None => vec!["GET".to_string()],
+    };
+    Some((methods, route))
+}
+
+/// Last identifier argument of an Express route call
+fn express_handler(args: &str) -> String {
+    let inner = args.rsplit_once(')').map(|(a, _)| a).unwrap_or(args);
+    let last = inner.rsplit(',').next().unwrap_or("").trim();
+
+    if last.contains("=>") || last.starts_with("function") || last.starts_with("async") || last.is_empty() {
+        return "<anonymous>".to_string();
+    }
+    last.rsplit('.').next().unwrap_or(last).trim_matches(|c: char| !c.is_alphanumeric() && c != '_').to_string()
+}
+
+/// Name of the next `fn`/`def` after a decorator or attribute
+fn next_definition(lines: &[&str], keyword: &str) -> Option<String> {
+    for line in lines.iter().take(8) {
+        if let Some(pos) = line.find(keyword) {
+            let name: String = line[pos + keyword.len()..]
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            if !name.is_empty() {
+                return Some(name);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_rust_routes() {
+        let axum = "let app = Router::new()\n    .route(\"/users/:id\", get(handlers::show_user).delete(remove_user))\n    .route(\"/health\", get(health));";
+        let endpoints = scan_source(Path::new("src/main.rs"), axum);
+        let found: Vec<(&str, &str, &str)> = endpoints.iter().map(|e| (e.method.as_str(), e.path.as_str(), e.handler.as_str())).collect();
+        assert_eq!(found, vec![("GET", "/users/:id", "show_user"), ("DELETE", "/users/:id", "remove_user"), ("GET", "/health", "health")]);
+
+        let actix = "#[post(\"/orders\")]\nasync fn create_order(body: Json<Order>) -> impl Responder {}";
+        let endpoints = scan_source(Path::new("src/api.rs"), actix);
+        assert_eq!(endpoints[0].handler, "create_order");
+        assert_eq!(endpoints[0].line, 1);
+    }
+
+    #[test]
+    fn test_scan_python_and_express_routes() {
+        let flask = "@app.route('/login', methods=['GET', 'POST'])\ndef login():\n    pass\n@router.get(\"/items/{item_id}\")\nasync def read_item(item_id: int):\n    pass";
+        let endpoints = scan_source(Path::new("app.py"), flask);
+        let found: Vec<(&str, &str)> = endpoints.iter().map(|e| (e.method.as_str(), e.handler.as_str())).collect();
+        assert_eq!(found, vec![("GET", "login"), ("POST", "login"), ("GET", "read_item")]);
+
+        let express = "router.post('/users', auth, usersController.create);\napp.get(\"/ping\", (req, res) => res.send('ok'));";
+        let endpoints = scan_source(Path::new("server.js"), express);
+        assert_eq!(endpoints[0].handler, "create");
+        assert_eq!(endpoints[1].handler, "<anonymous>");
+    }
+
+    #[test]
+    fn test_endpoint_matches_request() {
+        let endpoint = |method: &str, path: &str| Endpoint {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: "h".to_string(),
+            file: PathBuf::new(),
+            line: 1,
+        };
+
+        assert!(endpoint("GET", "/users/:id").matches("GET", "/users/42?full=1"));
+        assert!(endpoint("GET", "/items/{item_id}").matches("get", "/items/abc"));
+        assert!(endpoint("*", "/static/*").matches("HEAD", "/static/css/app.css"));
+        assert!(!endpoint("GET", "/users/:id").matches("POST", "/users/42"));
+        assert!(!endpoint("GET", "/users/:id").matches("GET", "/users/42/posts"));
+        assert!(!endpoint("GET", "/users").matches("GET", "/orders"));
+    }
+
+    #[test]
+    fn test_scan_client_calls() {
+        let web = "const res = await fetch(`${API_URL}/payments/${id}`, { method: 'POST' });\naxios.get('https://api.example.com/users/42?full=1');\nrouter.get('/local', handler);\nconst label = 'not/a/call';";
+        let calls = scan_calls(Path::new("src/api.ts"), web);
+        let found: Vec<(Option<&str>, &str, usize)> = calls.iter().map(|c| (c.method.as_deref(), c.path.as_str(), c.line)).collect();
+        assert_eq!(found, vec![(Some("POST"), "/payments/:param", 1), (Some("GET"), "/users/42", 2)]);
+
+        let rust = "let resp = client.post(format!(\"{}/payments/{}\", base, id)).send().await?;";
+        let calls = scan_calls(Path::new("src/client.rs"), rust);
+        assert_eq!(calls[0].path, "/payments/:param");
+
+        let routes = scan_source(Path::new("src/main.rs"), ".route(\"/payments/:id\", post(create_payment).get(show_payment))");
+        let targets = calls[0].targets(&routes);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].handler, "create_payment");
+    }
+}
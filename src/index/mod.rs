@@ -4,7 +4,10 @@
 
 #![allow(dead_code)]
 
+pub mod endpoints;
 pub mod semantic;
+pub mod similarity;
+pub mod snapshot;
 
 use std::path::{Path, PathBuf};
 use std::time::Instant;
@@ -14,6 +17,8 @@ use walkdir::WalkDir;
 use ignore::gitignore::Gitignore;
 
 use crate::core::parser::{CodeParser, Language, ParsedFile, SymbolCounts};
+use crate::ui::text;
+use snapshot::IndexSnapshot;
 
 // ANSI color codes from design system
 mod colors {
@@ -62,6 +67,7 @@ pub async fn index_directory(path: &Path, _force: bool, verbose: bool) -> Result
 
     // Parse all files
     let mut parsed_files: Vec<ParsedFile> = Vec::new();
+    let mut indexed: Vec<String> = Vec::new();
     let mut errors: Vec<(PathBuf, String)> = Vec::new();
     let mut total_symbols = SymbolCounts::default();
 
@@ -80,6 +86,7 @@ pub async fn index_directory(path: &Path, _force: bool, verbose: bool) -> Result
                 total_symbols.constants += counts.constants;
                 total_symbols.impls += counts.impls;
                 parsed_files.push(parsed);
+                indexed.push(snapshot::relative(&abs_path, file_path));
             }
             Err(e) => {
                 if verbose {
@@ -105,6 +112,18 @@ pub async fn index_directory(path: &Path, _force: bool, verbose: bool) -> Result
         errors,
     };
 
+    // Record the run for index health checks
+    let snapshot = IndexSnapshot {
+        indexed_at: chrono::Utc::now(),
+        files: indexed,
+        files_skipped: files.len() - result.files_indexed,
+        total_lines: result.total_lines,
+        symbols: result.symbols.total(),
+    };
+    if let Err(e) = snapshot.save(&abs_path) {
+        print_warning(&format!("Index snapshot not saved: {}", e));
+    }
+
     // Print summary
     print_summary(&result, &abs_path);
 
@@ -112,7 +131,12 @@ pub async fn index_directory(path: &Path, _force: bool, verbose: bool) -> Result
 }
 
 /// Collect all supported source files in directory
-fn collect_files(path: &Path, _verbose: bool) -> Result<Vec<PathBuf>> {
+pub fn collect_files(path: &Path, _verbose: bool) -> Result<Vec<PathBuf>> {
+    collect_matching(path, |p| Language::from_path(p) != Language::Unknown)
+}
+
+/// Collect files accepted by `include`, skipping ignored and build directories
+pub fn collect_matching(path: &Path, include: impl Fn(&Path) -> bool) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
     // Try to load .gitignore
@@ -130,6 +154,9 @@ fn collect_files(path: &Path, _verbose: bool) -> Result<Vec<PathBuf>> {
             let path = e.path();
             let name = e.file_name().to_string_lossy();
 
+            // The root itself is always walked, even when hidden
+            if e.depth() == 0 { return true; }
+
             // Skip hidden directories and common non-source dirs
             if name.starts_with('.') { return false; }
             if name == "node_modules" { return false; }
@@ -151,17 +178,23 @@ fn collect_files(path: &Path, _verbose: bool) -> Result<Vec<PathBuf>> {
         let entry = entry?;
         let file_path = entry.path();
 
-        if file_path.is_file() {
-            let language = Language::from_path(file_path);
-            if language != Language::Unknown {
-                files.push(file_path.to_path_buf());
-            }
+        if file_path.is_file() && include(file_path) {
+            files.push(file_path.to_path_buf());
         }
     }
 
     Ok(files)
 }
 
+/// Whether a path holds tests rather than the code under test
+pub fn is_test_path(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    path.components().any(|c| matches!(c.as_os_str().to_str(), Some("tests" | "test" | "__tests__")))
+        || name.starts_with("test_")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
 /// Create a styled progress bar
 fn create_progress_bar(total: u64) -> ProgressBar {
     let pb = ProgressBar::new(total);
@@ -299,11 +332,7 @@ fn print_warning(message: &str) {
 /// Truncate a path for display
 fn truncate_path(path: &Path, max_len: usize) -> String {
     let s = path.display().to_string();
-    if s.len() <= max_len {
-        format!("{:<width$}", s, width = max_len)
-    } else {
-        format!("...{}", &s[s.len() - max_len + 3..])
-    }
+    text::pad(&text::truncate_start(&s, max_len), max_len)
 }
 
 /// Result of indexing operation
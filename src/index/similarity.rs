@@ -0,0 +1,414 @@
+//! Code similarity detection
+//!
+//! Token shingle fingerprints for finding near-verbatim copies of code
+//! among the indexed files, and TF-IDF embeddings of identifier words for
+//! finding code that does similar things in different words.
+
+#![allow(dead_code)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::core::parser::{CodeParser, SymbolKind};
+
+/// Tokens per shingle
+const SHINGLE_SIZE: usize = 8;
+
+/// Lines per indexed chunk
+const CHUNK_LINES: usize = 40;
+
+/// Distance between the start of consecutive chunks
+const CHUNK_STEP: usize = 20;
+
+/// Set of hashed token shingles for a piece of code
+#[derive(Debug, Clone, Default)]
+pub struct Fingerprint {
+    shingles: HashSet<u64>,
+}
+
+impl Fingerprint {
+    /// Fingerprint code, ignoring whitespace and formatting differences
+    pub fn new(code: &str) -> Self {
+        let tokens = tokenize(code);
+        let mut shingles = HashSet::new();
+
+        if tokens.len() < SHINGLE_SIZE {
+            if !tokens.is_empty() {
+                shingles.insert(hash_tokens(&tokens));
+            }
+        } else {
+            for window in tokens.windows(SHINGLE_SIZE) {
+                shingles.insert(hash_tokens(window));
+            }
+        }
+
+        Self { shingles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shingles.is_empty()
+    }
+
+    /// Shared shingles relative to the smaller fingerprint (1.0 = one contains the other)
+    pub fn overlap(&self, other: &Fingerprint) -> f32 {
+        let smaller = self.shingles.len().min(other.shingles.len());
+        if smaller == 0 {
+            return 0.0;
+        }
+        self.shingles.intersection(&other.shingles).count() as f32 / smaller as f32
+    }
+
+    /// Jaccard similarity of the two shingle sets
+    pub fn jaccard(&self, other: &Fingerprint) -> f32 {
+        let union = self.shingles.union(&other.shingles).count();
+        if union == 0 {
+            return 0.0;
+        }
+        self.shingles.intersection(&other.shingles).count() as f32 / union as f32
+    }
+}
+
+/// A fingerprinted range of lines in a file
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub path: PathBuf,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub fingerprint: Fingerprint,
+}
+
+/// A chunk similar to the queried code
+#[derive(Debug, Clone)]
+pub struct SimilarMatch {
+    pub path: PathBuf,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub score: f32,
+}
+
+/// Split a file into overlapping fingerprinted chunks
+pub fn chunk_file(path: &Path, content: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let fingerprint = Fingerprint::new(&lines[start..end].join("\n"));
+
+        if !fingerprint.is_empty() {
+            chunks.push(Chunk {
+                path: path.to_path_buf(),
+                line_start: start + 1,
+                line_end: end,
+                fingerprint,
+            });
+        }
+
+        if end == lines.len() {
+            break;
+        }
+        start += CHUNK_STEP;
+    }
+
+    chunks
+}
+
+/// Find chunks of the given files that share most of their code with `code`
+///
+/// Matches scoring at least `threshold` (0.0-1.0) are returned, best first,
+/// keeping only the strongest chunk per file.
+pub fn find_similar(code: &str, files: &[PathBuf], threshold: f32) -> Vec<SimilarMatch> {
+    let query = Fingerprint::new(code);
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<SimilarMatch> = Vec::new();
+
+    for path in files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+
+        let best = chunk_file(path, &content)
+            .into_iter()
+            .map(|chunk| (query.overlap(&chunk.fingerprint), chunk))
+            .filter(|(score, _)| *score >= threshold)
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some((score, chunk)) = best {
+            matches.push(SimilarMatch {
+                path: chunk.path,
+                line_start: chunk.line_start,
+                line_end: chunk.line_end,
+                score,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    matches
+}
+
+/// Distinct words a definition needs to be ranked; tiny ones match anything
+const MIN_CHUNK_WORDS: usize = 4;
+
+/// Words that say nothing about what code does
+const STOP_WORDS: &[&str] = &[
+    "fn", "pub", "let", "mut", "self", "return", "if", "else", "for", "in", "def", "function",
+    "const", "var", "the", "and", "or", "not", "to", "of", "is", "str", "string", "new", "ok",
+    "some", "none", "true", "false", "null", "this", "await", "async", "use", "from", "import",
+];
+
+/// Term weights for a piece of code, compared by cosine similarity
+#[derive(Debug, Clone, Default)]
+pub struct Embedding {
+    weights: HashMap<String, f32>,
+}
+
+impl Embedding {
+    /// Term frequencies of the code's identifier words
+    pub fn new(code: &str) -> Self {
+        let mut weights: HashMap<String, f32> = HashMap::new();
+        for word in words(code) {
+            *weights.entry(word).or_default() += 1.0;
+        }
+        // Dampen repetition so one noisy name does not dominate
+        for weight in weights.values_mut() {
+            *weight = 1.0 + weight.ln();
+        }
+        Self { weights }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Scale each term by its inverse document frequency
+    fn weighted(&self, idf: &HashMap<String, f32>) -> Self {
+        let weights = self
+            .weights
+            .iter()
+            .map(|(word, weight)| (word.clone(), weight * idf.get(word).copied().unwrap_or(1.0)))
+            .collect();
+        Self { weights }
+    }
+
+    /// Cosine similarity, 0.0-1.0
+    pub fn cosine(&self, other: &Embedding) -> f32 {
+        let dot: f32 = self
+            .weights
+            .iter()
+            .filter_map(|(word, weight)| other.weights.get(word).map(|w| w * weight))
+            .sum();
+        let norm = |e: &Embedding| e.weights.values().map(|w| w * w).sum::<f32>().sqrt();
+        let norms = norm(self) * norm(other);
+        if norms == 0.0 {
+            0.0
+        } else {
+            dot / norms
+        }
+    }
+}
+
+/// A function, class or type ranked against queried code
+#[derive(Debug, Clone)]
+pub struct RankedChunk {
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub line_start: usize,
+    pub line_end: usize,
+    /// Embedding similarity, 0.0-1.0
+    pub score: f32,
+    /// Shared shingles; near 1.0 means a near-verbatim copy
+    pub overlap: f32,
+}
+
+/// Definitions in `files` most like `code`, best first. Chunks overlapping
+/// `exclude` (the queried range itself) are skipped.
+pub fn rank_similar(code: &str, files: &[PathBuf], exclude: Option<(&Path, usize, usize)>, limit: usize) -> Vec<RankedChunk> {
+    let query = Embedding::new(code);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let Ok(mut parser) = CodeParser::new() else {
+        return Vec::new();
+    };
+
+    let mut chunks: Vec<(RankedChunk, String, Embedding)> = Vec::new();
+    for path in files {
+        let Ok(parsed) = parser.parse_file(path) else {
+            continue;
+        };
+        let lines: Vec<&str> = parsed.content.lines().collect();
+        for symbol in &parsed.symbols {
+            // Impl blocks and modules only repeat their members; constants are
+            // too small to compare
+            if matches!(symbol.kind, SymbolKind::Impl | SymbolKind::Module | SymbolKind::Constant) {
+                continue;
+            }
+            let overlaps_query = exclude.is_some_and(|(p, start, end)| {
+                p == path.as_path() && symbol.line_start <= end && start <= symbol.line_end
+            });
+            if overlaps_query {
+                continue;
+            }
+
+            let start = symbol.line_start.saturating_sub(1).min(lines.len());
+            let source = lines[start..symbol.line_end.min(lines.len())].join("\n");
+            let embedding = Embedding::new(&source);
+            if embedding.weights.len() < MIN_CHUNK_WORDS {
+                continue;
+            }
+            let chunk = RankedChunk {
+                path: path.clone(),
+                name: Some(symbol.name.clone()),
+                line_start: symbol.line_start,
+                line_end: symbol.line_end,
+                score: 0.0,
+                overlap: 0.0,
+            };
+            chunks.push((chunk, source, embedding));
+        }
+    }
+
+    // Words common across the codebase count for less
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for (_, _, embedding) in &chunks {
+        for word in embedding.weights.keys() {
+            *document_frequency.entry(word.as_str()).or_default() += 1;
+        }
+    }
+    let total = chunks.len() as f32 + 1.0;
+    let idf: HashMap<String, f32> = document_frequency
+        .into_iter()
+        .map(|(word, count)| (word.to_string(), (total / (count as f32 + 1.0)).ln() + 1.0))
+        .collect();
+
+    let query = query.weighted(&idf);
+    let fingerprint = Fingerprint::new(code);
+    let mut ranked: Vec<RankedChunk> = chunks
+        .into_iter()
+        .map(|(mut chunk, source, embedding)| {
+            chunk.score = query.cosine(&embedding.weighted(&idf));
+            chunk.overlap = fingerprint.overlap(&Fingerprint::new(&source));
+            chunk
+        })
+        .filter(|c| c.score > 0.0)
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Lowercase identifier words, splitting `snake_case` and `camelCase`
+fn words(code: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for identifier in code.split(|c: char| !(c.is_alphanumeric() || c == '_')) {
+        let mut word = String::new();
+        let mut previous_lower = false;
+        for c in identifier.chars() {
+            let boundary = c == '_' || (c.is_uppercase() && previous_lower);
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            if c != '_' {
+                word.extend(c.to_lowercase());
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words.retain(|w| w.len() > 1 && !w.chars().all(|c| c.is_ascii_digit()) && !STOP_WORDS.contains(&w.as_str()));
+    words
+}
+
+/// Split code into identifier/number runs and single punctuation tokens
+fn tokenize(code: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in code.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            current.push(c);
+        } else {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+fn hash_tokens(tokens: &[String]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tokens.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_ignores_formatting() {
+        let a = Fingerprint::new("fn add(a: i32, b: i32) -> i32 { a + b }");
+        let b = Fingerprint::new("fn add(a: i32,\n       b: i32) -> i32 {\n    a + b\n}");
+        let c = Fingerprint::new("def greet(name):\n    return f'hello {name}'");
+
+        assert_eq!(a.overlap(&b), 1.0);
+        assert_eq!(a.jaccard(&b), 1.0);
+        assert!(a.overlap(&c) < 0.1);
+    }
+
+    #[test]
+    fn test_find_similar_reports_copied_chunk() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("lib.rs");
+        std::fs::write(&source, "fn parse_header(input: &str) -> Option<(String, String)> {\n    let (key, value) = input.split_once(':')?;\n    Some((key.trim().to_string(), value.trim().to_string()))\n}\n").unwrap();
+
+        let copied = "fn parse_header(input: &str) -> Option<(String, String)> {\n  let (key, value) = input.split_once(':')?;\n  Some((key.trim().to_string(), value.trim().to_string()))\n}";
+        let matches = find_similar(copied, std::slice::from_ref(&source), 0.8);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, source);
+        assert!(find_similar("print('unrelated')", &[source], 0.8).is_empty());
+    }
+
+    #[test]
+    fn test_rank_similar_finds_reworded_logic() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("lib.rs");
+        std::fs::write(
+            &source,
+            "fn parse_header_line(raw: &str) -> Option<(String, String)> {\n    let (name, value) = raw.split_once(':')?;\n    Some((name.trim().to_lowercase(), value.trim().to_string()))\n}\n\nfn render_table(rows: &[Vec<String>]) -> String {\n    rows.iter().map(|r| r.join(\" | \")).collect::<Vec<_>>().join(\"\\n\")\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(words("parseHeader HTTP_status x1"), ["parse", "header", "http", "status", "x1"]);
+
+        // Same idea, different names and layout
+        let query = "def read_header(line):\n    name, value = line.split(':', 1)\n    return name.strip().lower(), value.strip()";
+        let ranked = rank_similar(query, std::slice::from_ref(&source), None, 5);
+        assert_eq!(ranked[0].name.as_deref(), Some("parse_header_line"));
+        assert!(ranked.get(1).is_none_or(|r| r.score < ranked[0].score));
+
+        // The queried range itself is not a result
+        let ranked = rank_similar(query, std::slice::from_ref(&source), Some((&source, 1, 4)), 5);
+        assert!(ranked.iter().all(|r| r.name.as_deref() != Some("parse_header_line")));
+    }
+}
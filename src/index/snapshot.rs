@@ -0,0 +1,142 @@
+//! Record of the last index run
+//!
+//! `nexus index` saves what it indexed to `.nexus/index.json`, so index
+//! health (files changed, added or removed since) can be checked without
+//! indexing again.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::journal::{create_project_dir, PROJECT_DIR};
+
+const SNAPSHOT_FILE: &str = "index.json";
+
+/// What the last index run covered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    pub indexed_at: DateTime<Utc>,
+    /// Indexed files, relative to the root
+    pub files: Vec<String>,
+    pub files_skipped: usize,
+    pub total_lines: usize,
+    pub symbols: usize,
+}
+
+/// How the project differs from its last index
+#[derive(Debug, Clone)]
+pub struct IndexHealth {
+    pub snapshot: IndexSnapshot,
+    /// Indexed files modified since
+    pub stale: Vec<String>,
+    /// Source files added since
+    pub added: Vec<String>,
+    /// Indexed files since deleted
+    pub removed: Vec<String>,
+}
+
+impl IndexHealth {
+    pub fn is_fresh(&self) -> bool {
+        self.stale.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl IndexSnapshot {
+    fn path(root: &Path) -> PathBuf {
+        root.join(PROJECT_DIR).join(SNAPSHOT_FILE)
+    }
+
+    /// The last snapshot under `root`, if the project was indexed
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Write the snapshot under `root`, replacing the previous one
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::path(root);
+        if let Some(parent) = path.parent() {
+            create_project_dir(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Compare the snapshot under `root` with the source files there now
+    pub fn health(root: &Path) -> Result<Option<IndexHealth>> {
+        let Some(snapshot) = Self::load(root)? else {
+            return Ok(None);
+        };
+        let current = super::collect_files(root, false)?;
+        let indexed: BTreeSet<&str> = snapshot.files.iter().map(String::as_str).collect();
+
+        let mut stale = Vec::new();
+        let mut added = Vec::new();
+        let mut seen = BTreeSet::new();
+        for path in &current {
+            let relative = relative(root, path);
+            if indexed.contains(relative.as_str()) {
+                let modified = fs::metadata(path).and_then(|m| m.modified()).map(DateTime::<Utc>::from);
+                if modified.is_ok_and(|m| m > snapshot.indexed_at) {
+                    stale.push(relative.clone());
+                }
+                seen.insert(relative);
+            } else {
+                added.push(relative);
+            }
+        }
+        let removed = snapshot.files.iter().filter(|f| !seen.contains(*f)).cloned().collect();
+        Ok(Some(IndexHealth { snapshot, stale, added, removed }))
+    }
+}
+
+/// `path` relative to `root`, with `/` separators
+pub fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_compares_snapshot_with_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        assert!(IndexSnapshot::health(root).unwrap().is_none());
+
+        fs::write(root.join("lib.rs"), "fn a() {}").unwrap();
+        fs::write(root.join("old.rs"), "fn b() {}").unwrap();
+        let snapshot = IndexSnapshot {
+            indexed_at: Utc::now() + chrono::Duration::seconds(5),
+            files: vec!["lib.rs".to_string(), "old.rs".to_string()],
+            files_skipped: 0,
+            total_lines: 2,
+            symbols: 2,
+        };
+        snapshot.save(root).unwrap();
+        assert_eq!(IndexSnapshot::load(root).unwrap(), Some(snapshot.clone()));
+        assert!(IndexSnapshot::health(root).unwrap().unwrap().is_fresh());
+
+        fs::remove_file(root.join("old.rs")).unwrap();
+        fs::write(root.join("new.py"), "def c(): pass").unwrap();
+        IndexSnapshot { indexed_at: Utc::now() - chrono::Duration::hours(1), ..snapshot }.save(root).unwrap();
+        let health = IndexSnapshot::health(root).unwrap().unwrap();
+        assert_eq!(health.stale, ["lib.rs"]);
+        assert_eq!(health.added, ["new.py"]);
+        assert_eq!(health.removed, ["old.rs"]);
+    }
+}
@@ -4,7 +4,7 @@
 //! built in Rust for maximum performance.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -30,6 +30,15 @@ struct Cli {
     #[arg(short, long, global = true)]
     config: Option<String>,
 
+    /// Allow permissions without asking for this run, e.g. `--grant write,run`
+    /// (read, write, run, network)
+    #[arg(long, global = true, value_delimiter = ',')]
+    grant: Vec<String>,
+
+    /// Refuse permissions for this run, e.g. `--deny-permission network`
+    #[arg(long, global = true, value_delimiter = ',')]
+    deny_permission: Vec<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -46,6 +55,14 @@ enum Commands {
     Ask {
         /// The question to ask
         question: String,
+
+        /// Registered roots to search together, e.g. `api,web`
+        #[arg(long, value_delimiter = ',')]
+        roots: Vec<String>,
+
+        /// Have a second request check the answer against the context
+        #[arg(long)]
+        verify: bool,
     },
 
     /// Fix bugs with AI assistance
@@ -78,7 +95,7 @@ enum Commands {
     /// Generate documentation for code
     Doc {
         /// File to document
-        file: String,
+        file: Option<String>,
 
         /// Output file for documentation
         #[arg(short, long)]
@@ -87,6 +104,10 @@ enum Commands {
         /// Generate inline doc comments instead of separate docs
         #[arg(long)]
         inline: bool,
+
+        /// Document the whole project, with module and data model diagrams
+        #[arg(long, conflicts_with_all = ["file", "inline"])]
+        project: bool,
     },
 
     /// Refactor code with AI assistance
@@ -103,11 +124,32 @@ enum Commands {
     /// Search your codebase semantically
     Search {
         /// Search query
-        query: String,
+        #[arg(required_unless_present_any = ["saved", "list_saved", "delete_saved"])]
+        query: Option<String>,
 
-        /// Maximum results
+        /// Maximum results (groups, with --group-by)
         #[arg(short, long, default_value = "10")]
         limit: usize,
+
+        /// Group results by file, symbol kind or package
+        #[arg(long, value_name = "BY", value_parser = ["file", "kind", "package"])]
+        group_by: Option<String>,
+
+        /// Save the query under a name, then run it
+        #[arg(long, value_name = "NAME")]
+        save: Option<String>,
+
+        /// Run a saved search instead of a query
+        #[arg(long, value_name = "NAME", conflicts_with = "save")]
+        saved: Option<String>,
+
+        /// List saved searches
+        #[arg(long)]
+        list_saved: bool,
+
+        /// Delete a saved search
+        #[arg(long, value_name = "NAME")]
+        delete_saved: Option<String>,
     },
 
     /// Index your codebase for faster operations
@@ -132,6 +174,10 @@ enum Commands {
         /// Language to generate
         #[arg(short, long)]
         language: Option<String>,
+
+        /// Check the output for near-verbatim copies of existing code
+        #[arg(long)]
+        license_check: bool,
     },
 
     /// Review code for issues and improvements
@@ -143,6 +189,22 @@ enum Commands {
         /// Focus areas (e.g., security, performance)
         #[arg(short, long)]
         focus: Option<Vec<String>>,
+
+        /// Grader plugin (executable or .wasm) to post-process findings; repeatable
+        #[arg(long = "grader")]
+        graders: Vec<String>,
+
+        /// Skip the graders in .nexus/graders/
+        #[arg(long)]
+        no_graders: bool,
+
+        /// Browse the findings afterwards: `e` explains one, `a` applies a fix
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Several AI passes (outline, deep dives, synthesis) within the [deep] budget
+        #[arg(long)]
+        deep: bool,
     },
 
     /// Explain code
@@ -153,6 +215,10 @@ enum Commands {
         /// Explanation depth (brief, detailed, expert)
         #[arg(short, long, default_value = "detailed")]
         depth: String,
+
+        /// Have a second request check the explanation against the code
+        #[arg(long)]
+        verify: bool,
     },
 
     /// Show configuration
@@ -183,6 +249,18 @@ enum Commands {
         force: bool,
     },
 
+    /// Release packaging helpers
+    Package {
+        #[command(subcommand)]
+        command: PackageCommands,
+    },
+
+    /// Offline bundles for air-gapped machines
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommands,
+    },
+
     /// AI-powered git diff analysis
     Diff {
         /// Analyze staged changes only
@@ -215,6 +293,423 @@ enum Commands {
         /// Focus area (time, memory, io, all)
         #[arg(short, long)]
         focus: Option<String>,
+
+        /// Several AI passes (outline, deep dives, synthesis) within the [deep] budget
+        #[arg(long)]
+        deep: bool,
+    },
+
+    /// Record HTTP traffic through a proxy and generate contract tests
+    Record {
+        /// Address to listen on (e.g. :8080)
+        #[arg(long, default_value = ":8080")]
+        proxy: String,
+
+        /// Service to forward requests to
+        #[arg(long, default_value = "http://localhost:3000")]
+        target: String,
+
+        /// Generate tests from an existing recording instead of capturing
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Where to write the generated tests
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Minimize a crashing input and explain the trigger
+    Shrink {
+        /// Input file that triggers the crash
+        input: String,
+
+        /// Command that reproduces the crash; `{}` is replaced by the input path
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+
+        /// Where to write the minimized input (default: <input>.min)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Per-run timeout in seconds
+        #[arg(long, default_value = "10")]
+        timeout: u64,
+
+        /// Skip the AI explanation
+        #[arg(long)]
+        no_explain: bool,
+    },
+
+    /// Set up fuzzing for hot parsing code
+    Fuzz {
+        #[command(subcommand)]
+        command: FuzzCommands,
+    },
+
+    /// Generate Mermaid diagrams of the codebase
+    Diagram {
+        #[command(subcommand)]
+        command: DiagramCommands,
+    },
+
+    /// Generate guided code tours
+    Tour {
+        #[command(subcommand)]
+        command: TourCommands,
+    },
+
+    /// Build a glossary of the codebase's domain terms
+    Glossary {
+        /// Maximum number of mined terms to define
+        #[arg(long, default_value = "40")]
+        limit: usize,
+
+        /// Only consider terms used in at least this many files
+        #[arg(long, default_value = "2")]
+        min_files: usize,
+
+        /// Output file
+        #[arg(short, long, default_value = "GLOSSARY.md")]
+        output: String,
+    },
+
+    /// Check naming consistency
+    Naming {
+        #[command(subcommand)]
+        command: NamingCommands,
+    },
+
+    /// Find comments that no longer match their code
+    Comments {
+        #[command(subcommand)]
+        command: CommentsCommands,
+    },
+
+    /// Inspect a Rust crate's public API
+    Api {
+        #[command(subcommand)]
+        command: ApiCommands,
+    },
+
+    /// List packages and tests affected by changes since a base branch
+    Affected {
+        /// Branch or revision to compare against
+        #[arg(long, default_value = "origin/main")]
+        base: String,
+
+        /// Print JSON for CI pipelines
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Map the services of a system and their dependencies
+    Services {
+        #[command(subcommand)]
+        command: ServicesCommands,
+    },
+
+    /// Index health, AI activity, pending changes, findings and token spend
+    Dashboard {
+        /// Write a standalone HTML page instead of printing
+        #[arg(long, value_name = "FILE")]
+        html: Option<String>,
+    },
+
+    /// Browse and export saved chat sessions
+    History {
+        #[command(subcommand)]
+        command: HistoryCommands,
+    },
+
+    /// Facts and decisions remembered across sessions on this project
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommands,
+    },
+
+    /// Find code similar to a file range or snippet
+    Similar {
+        /// `file:10-40`, `file:25`, a file, `-` for stdin, or a code snippet
+        target: String,
+
+        /// Maximum results
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Usage guide for a function or type from its real call sites
+    Examples {
+        /// Function or type name (`load_config`, `Config::load`, `Order`)
+        symbol: String,
+
+        /// Maximum examples sent to the AI
+        #[arg(short = 'n', long, default_value = "6")]
+        max: usize,
+
+        /// Write the guide to a file instead of printing it
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Evaluate providers on YAML task suites
+    Eval {
+        #[command(subcommand)]
+        command: EvalCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum PackageCommands {
+    /// Generate the Homebrew formula or Scoop manifest of a release
+    Manifest {
+        /// Manifest to generate
+        #[arg(value_parser = ["homebrew", "scoop"])]
+        format: String,
+
+        /// Release version (defaults to this build's version)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// `sha256sum` output covering the release binaries
+        #[arg(long, default_value = core::packaging::CHECKSUMS_ASSET)]
+        checksums: String,
+
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleCommands {
+    /// Pack this binary, Ollama models and the docs into one archive
+    Create {
+        /// Archive to write
+        #[arg(short, long, default_value = "nexus-bundle.tar.gz")]
+        output: String,
+
+        /// Ollama model to include, repeatable (defaults to the configured local model)
+        #[arg(short, long = "model")]
+        models: Vec<String>,
+
+        /// Binary to bundle (defaults to this one)
+        #[arg(long)]
+        binary: Option<String>,
+
+        /// Leave out the README and command reference
+        #[arg(long)]
+        no_docs: bool,
+    },
+
+    /// Install a bundle without network access
+    Install {
+        /// Bundle archive
+        archive: String,
+
+        /// Directory for the binary (defaults to ~/.local/bin)
+        #[arg(long)]
+        bin_dir: Option<String>,
+
+        /// Ollama model store (defaults to $OLLAMA_MODELS or ~/.ollama/models)
+        #[arg(long)]
+        models_dir: Option<String>,
+
+        /// Keep the configuration instead of switching to the local provider
+        #[arg(long)]
+        no_config: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FuzzCommands {
+    /// Generate a cargo-fuzz or atheris harness for a function
+    Init {
+        /// Function to fuzz
+        target: String,
+
+        /// File defining the function (skips the project-wide search)
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DiagramCommands {
+    /// ER diagram from SQL schemas and ORM models
+    Er {
+        /// Output file (.md for a Markdown block, otherwise raw Mermaid)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Module dependency graph from imports
+    Modules {
+        /// Directory levels grouped into one module
+        #[arg(long, default_value = "2")]
+        depth: usize,
+
+        /// Output file (.md for a Markdown block, otherwise raw Mermaid)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Call flow from an entry point as a sequence diagram or flowchart
+    Flow {
+        /// Entry function (`name` or `Type::name`)
+        entry: String,
+
+        /// File defining the entry function, when the name is ambiguous
+        #[arg(short, long)]
+        file: Option<String>,
+
+        /// How many calls deep to follow
+        #[arg(long, default_value = "3")]
+        depth: usize,
+
+        /// Diagram style (sequence, flowchart)
+        #[arg(long, default_value = "sequence")]
+        format: String,
+
+        /// Have the AI write human-readable step labels
+        #[arg(long)]
+        labels: bool,
+
+        /// Output file (.md for a Markdown block, otherwise raw Mermaid)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TourCommands {
+    /// Write a CodeTour (.tours/*.tour) walking through a topic
+    Generate {
+        /// What the tour should explain (e.g. "how authentication works")
+        topic: String,
+
+        /// Maximum number of steps
+        #[arg(long, default_value = "8")]
+        steps: usize,
+
+        /// Output file (default: .tours/<topic>.tour)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum NamingCommands {
+    /// Find inconsistent casing and synonym verbs, and plan renames
+    Check {
+        /// Directory to check
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Write the rename plan (.json, otherwise Markdown)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CommentsCommands {
+    /// Have the AI flag doc comments that contradict the implementation
+    Audit {
+        /// File or directory to audit
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Lowest confidence reported (low, medium, high)
+        #[arg(long, default_value = "low")]
+        min_confidence: String,
+
+        /// Maximum number of comments to audit
+        #[arg(long, default_value = "100")]
+        limit: usize,
+
+        /// Write the findings (.json, otherwise Markdown)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ApiCommands {
+    /// Public API changes between revisions, with semver advice
+    Diff {
+        /// Revision range (`v1.4.0..HEAD`); a single revision compares against the working tree
+        range: String,
+
+        /// Exit non-zero on breaking changes without a major version bump (for CI)
+        #[arg(long, value_name = "KIND", value_parser = ["breaking"])]
+        deny: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServicesCommands {
+    /// Service dependency graph from compose/Kubernetes manifests and code
+    Map {
+        /// Output file (.md for a Markdown block, otherwise raw Mermaid)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EvalCommands {
+    /// Run suites against providers and print a scoreboard
+    Run {
+        /// Suite files, directories or globs (`evals/*.yaml`)
+        #[arg(required = true)]
+        suites: Vec<String>,
+
+        /// Providers to compare (claude, proxy, local); defaults to the suite's or all available
+        #[arg(short, long, value_delimiter = ',')]
+        providers: Vec<String>,
+
+        /// Write a shareable JSON report (names, verdicts and timings; no prompts or answers)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MemoryCommands {
+    /// List the remembered facts
+    Show,
+
+    /// Edit the memory file in $EDITOR
+    Edit,
+
+    /// Remove remembered facts
+    Forget {
+        /// Text the facts to remove contain
+        pattern: Option<String>,
+
+        /// Remove everything remembered
+        #[arg(long, conflicts_with = "pattern")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommands {
+    /// List saved chats, newest first
+    List,
+
+    /// Export a chat transcript
+    Export {
+        /// Chat id (or a unique prefix, or `latest`)
+        id: String,
+
+        /// Transcript format
+        #[arg(short, long, default_value = "markdown", value_parser = ["markdown", "md", "html", "json"])]
+        format: String,
+
+        /// Output file (defaults to chat-<id>.<ext>)
+        #[arg(short, long)]
+        output: Option<String>,
     },
 }
 
@@ -227,11 +722,20 @@ async fn main() -> Result<()> {
     let subscriber = FmtSubscriber::builder()
         .with_max_level(level)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .finish();
     tracing::subscriber::set_global_default(subscriber)?;
 
     // Load configuration
     let config = config::load_config(cli.config.as_deref())?;
+    ai::router::AiRouter::install(config.clone());
+    core::hooks::Hooks::install(std::path::Path::new("."));
+    core::memory::ProjectMemory::install(std::path::Path::new("."));
+    core::permissions::Permissions::install(
+        std::path::Path::new("."),
+        &core::permissions::parse_list(&cli.grant)?,
+        &core::permissions::parse_list(&cli.deny_permission)?,
+    );
 
     info!("NEXUS AI Forge v{}", env!("CARGO_PKG_VERSION"));
 
@@ -239,8 +743,8 @@ async fn main() -> Result<()> {
         Some(Commands::Chat { prompt }) => {
             cli::chat::run(config, prompt).await?;
         }
-        Some(Commands::Ask { question }) => {
-            cli::ask::run(config, &question).await?;
+        Some(Commands::Ask { question, roots, verify }) => {
+            cli::ask::run(config, &question, &roots, verify).await?;
         }
         Some(Commands::Fix { file, error }) => {
             cli::fix::run(config, &file, error.as_deref()).await?;
@@ -251,26 +755,38 @@ async fn main() -> Result<()> {
         Some(Commands::Commit { execute }) => {
             cli::commit::run(config, execute).await?;
         }
-        Some(Commands::Doc { file, output, inline }) => {
-            cli::doc::run(config, &file, output.as_deref(), inline).await?;
+        Some(Commands::Doc { file, output, inline, project }) => {
+            cli::doc::run(config, file.as_deref(), output.as_deref(), inline, project).await?;
         }
         Some(Commands::Refactor { paths, description }) => {
             cli::refactor::run(config, &paths, &description).await?;
         }
-        Some(Commands::Search { query, limit }) => {
-            cli::search::run(config, &query, limit).await?;
+        Some(Commands::Search { query, limit, group_by, save, saved, list_saved, delete_saved }) => {
+            if list_saved {
+                cli::search::list_saved(config).await?;
+            } else if let Some(name) = delete_saved {
+                cli::search::delete_saved(config, &name).await?;
+            } else if let Some(name) = saved {
+                cli::search::run_saved(config, &name, limit, group_by.as_deref()).await?;
+            } else {
+                let query = query.unwrap_or_default();
+                if let Some(name) = save {
+                    cli::search::save(&name, &query, group_by.as_deref())?;
+                }
+                cli::search::run(config, &query, limit, group_by.as_deref()).await?;
+            }
         }
         Some(Commands::Index { path, force }) => {
             cli::index::run(config, path.as_deref(), force).await?;
         }
-        Some(Commands::Generate { description, output, language }) => {
-            cli::generate::run(config, &description, output.as_deref(), language.as_deref()).await?;
+        Some(Commands::Generate { description, output, language, license_check }) => {
+            cli::generate::run(config, &description, output.as_deref(), language.as_deref(), license_check).await?;
         }
-        Some(Commands::Review { paths, focus }) => {
-            cli::review::run(config, &paths, focus.as_deref()).await?;
+        Some(Commands::Review { paths, focus, graders, no_graders, interactive, deep }) => {
+            cli::review::run(config, &paths, focus.as_deref(), &graders, no_graders, interactive, deep).await?;
         }
-        Some(Commands::Explain { target, depth }) => {
-            cli::explain::run(config, &target, &depth).await?;
+        Some(Commands::Explain { target, depth, verify }) => {
+            cli::explain::run(config, &target, &depth, verify).await?;
         }
         Some(Commands::Config { show, init }) => {
             if init {
@@ -292,15 +808,114 @@ async fn main() -> Result<()> {
                 cli::update::run(false, force).await?;
             }
         }
+        Some(Commands::Package { command }) => match command {
+            PackageCommands::Manifest { format, version, checksums, output } => {
+                cli::package::manifest(&format, version.as_deref(), &checksums, output.as_deref())?;
+            }
+        },
+        Some(Commands::Bundle { command }) => match command {
+            BundleCommands::Create { output, models, binary, no_docs } => {
+                cli::bundle::create(&config, &output, &models, binary.as_deref(), no_docs, command_reference())?;
+            }
+            BundleCommands::Install { archive, bin_dir, models_dir, no_config } => {
+                cli::bundle::install(&config, &archive, bin_dir.as_deref(), models_dir.as_deref(), no_config)?;
+            }
+        },
         Some(Commands::Diff { staged, file }) => {
             cli::diff::run(config, staged, file.as_deref()).await?;
         }
         Some(Commands::Convert { file, to, output }) => {
             cli::convert::run(config, &file, &to, output.as_deref()).await?;
         }
-        Some(Commands::Optimize { file, focus }) => {
-            cli::optimize::run(config, &file, focus.as_deref()).await?;
+        Some(Commands::Optimize { file, focus, deep }) => {
+            cli::optimize::run(config, &file, focus.as_deref(), deep).await?;
+        }
+        Some(Commands::Record { proxy, target, from, output }) => {
+            cli::record::run(config, &proxy, &target, from.as_deref(), output.as_deref()).await?;
+        }
+        Some(Commands::Shrink { input, command, output, timeout, no_explain }) => {
+            cli::shrink::run(config, &input, &command, output.as_deref(), timeout, !no_explain).await?;
+        }
+        Some(Commands::Fuzz { command }) => match command {
+            FuzzCommands::Init { target, file } => {
+                cli::fuzz::init(config, &target, file.as_deref()).await?;
+            }
+        },
+        Some(Commands::Diagram { command }) => match command {
+            DiagramCommands::Er { output } => {
+                cli::diagram::er(config, output.as_deref()).await?;
+            }
+            DiagramCommands::Modules { depth, output } => {
+                cli::diagram::modules(config, depth, output.as_deref()).await?;
+            }
+            DiagramCommands::Flow { entry, file, depth, format, labels, output } => {
+                cli::diagram::flow(config, &entry, file.as_deref(), depth, &format, labels, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Tour { command }) => match command {
+            TourCommands::Generate { topic, steps, output } => {
+                cli::tour::generate(config, &topic, steps, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Glossary { limit, min_files, output }) => {
+            cli::glossary::run(config, limit, min_files, &output).await?;
+        }
+        Some(Commands::Naming { command }) => match command {
+            NamingCommands::Check { path, output } => {
+                cli::naming::check(config, &path, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Comments { command }) => match command {
+            CommentsCommands::Audit { path, min_confidence, limit, output } => {
+                cli::comments::audit(config, &path, &min_confidence, limit, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Api { command }) => match command {
+            ApiCommands::Diff { range, deny } => {
+                cli::api::diff(config, &range, deny.as_deref()).await?;
+            }
+        },
+        Some(Commands::Affected { base, json }) => {
+            cli::affected::run(config, &base, json).await?;
+        }
+        Some(Commands::Services { command }) => match command {
+            ServicesCommands::Map { output } => {
+                cli::services::map(config, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Dashboard { html }) => {
+            cli::dashboard::run(config, html.as_deref()).await?;
+        }
+        Some(Commands::History { command }) => match command {
+            HistoryCommands::List => {
+                cli::history::list(config).await?;
+            }
+            HistoryCommands::Export { id, format, output } => {
+                cli::history::export(config, &id, &format, output.as_deref()).await?;
+            }
+        },
+        Some(Commands::Memory { command }) => match command {
+            MemoryCommands::Show => {
+                cli::memory::show(config).await?;
+            }
+            MemoryCommands::Edit => {
+                cli::memory::edit(config).await?;
+            }
+            MemoryCommands::Forget { pattern, all } => {
+                cli::memory::forget(config, pattern.as_deref(), all).await?;
+            }
+        },
+        Some(Commands::Similar { target, limit }) => {
+            cli::similar::run(config, &target, limit).await?;
+        }
+        Some(Commands::Examples { symbol, max, output }) => {
+            cli::examples::run(config, &symbol, max, output.as_deref()).await?;
         }
+        Some(Commands::Eval { command }) => match command {
+            EvalCommands::Run { suites, providers, output } => {
+                cli::eval::run(config, &suites, &providers, output.as_deref()).await?;
+            }
+        },
         None => {
             // Default: Start interactive chat
             cli::chat::run(config, None).await?;
@@ -309,3 +924,34 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Markdown reference of every command's help, bundled as offline docs
+fn command_reference() -> String {
+    let mut cli = Cli::command();
+    let mut reference = format!("# nexus command reference\n\n```text\n{}\n```\n", cli.render_long_help());
+    for command in cli.get_subcommands_mut() {
+        let name = command.get_name().to_string();
+        reference.push_str(&format!("\n## nexus {}\n\n```text\n{}\n```\n", name, command.render_long_help()));
+    }
+    reference
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_permission_flags_leave_subcommand_flags_alone() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "nexus", "--deny-permission", "network", "api", "diff", "HEAD", "--deny", "breaking",
+        ])
+        .unwrap();
+        assert_eq!(cli.deny_permission, ["network"]);
+        match cli.command {
+            Some(Commands::Api { command: ApiCommands::Diff { deny, .. } }) => assert_eq!(deny.as_deref(), Some("breaking")),
+            _ => panic!("expected api diff"),
+        }
+    }
+}
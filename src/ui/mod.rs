@@ -3,6 +3,7 @@
 //! Provides Claude Code style interactive forms and prompts.
 
 pub mod form;
+pub mod text;
 pub mod theme;
 
 pub use form::{FormOption, NexusForm, FormResult};
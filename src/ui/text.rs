@@ -0,0 +1,120 @@
+//! Text fitting for terminal output
+//!
+//! Truncation and padding by display width and grapheme cluster, so
+//! multi-byte and wide characters are never split or miscounted in paths,
+//! signatures and table cells.
+
+#![allow(dead_code)]
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Marker for removed text
+pub const ELLIPSIS: &str = "…";
+
+/// Columns `text` takes in a terminal
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Keep the start of `text` within `max` columns, ending in `…` when cut
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    let mut used = width(ELLIPSIS);
+    for grapheme in text.graphemes(true) {
+        used += width(grapheme);
+        if used > max {
+            break;
+        }
+        out.push_str(grapheme);
+    }
+    out + ELLIPSIS
+}
+
+/// Keep the end of `text` within `max` columns, starting with `…` when cut.
+/// For paths, where the file name matters most.
+pub fn truncate_start(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut kept: Vec<&str> = Vec::new();
+    let mut used = width(ELLIPSIS);
+    for grapheme in text.graphemes(true).rev() {
+        used += width(grapheme);
+        if used > max {
+            break;
+        }
+        kept.push(grapheme);
+    }
+    kept.reverse();
+    let tail = kept.concat();
+    // Start at a path component when one begins in the kept part
+    let tail = match tail.find(['/', '\\']) {
+        Some(pos) if pos > 0 => tail[pos..].to_string(),
+        _ => tail,
+    };
+    format!("{}{}", ELLIPSIS, tail)
+}
+
+/// Truncate code such as a signature at a token boundary, so the preview
+/// never ends mid-identifier
+pub fn truncate_code(code: &str, max: usize) -> String {
+    let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+    if width(&code) <= max {
+        return code;
+    }
+    let cut = truncate(&code, max);
+    let cut = cut.strip_suffix(ELLIPSIS).unwrap_or(&cut);
+
+    // Back up to the last separator when the cut lands inside a token
+    let next = code[cut.len()..].chars().next();
+    let inside_token = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let end = if inside_token(cut.chars().last()) && inside_token(next) {
+        cut.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map(|i| i + 1).unwrap_or(cut.len())
+    } else {
+        cut.len()
+    };
+    format!("{}{}", cut[..end].trim_end(), ELLIPSIS)
+}
+
+/// Pad `text` with spaces to `columns` wide
+pub fn pad(text: &str, columns: usize) -> String {
+    let fill = columns.saturating_sub(width(text));
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+/// Truncate and pad `text` to exactly `columns` wide, for table cells
+pub fn cell(text: &str, columns: usize) -> String {
+    pad(&truncate(text, columns), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_wide_and_combining() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("héllo wörld", 6), "héllo…");
+        // Wide characters take two columns each
+        assert_eq!(truncate("日本語のテキスト", 7), "日本語…");
+        // A combining accent stays with its letter
+        assert_eq!(truncate("e\u{301}e\u{301}e\u{301}", 2), "e\u{301}…");
+        assert_eq!(cell("日本", 6), "日本  ");
+        assert_eq!(width(&cell("日本語のテキスト", 8)), 8);
+    }
+
+    #[test]
+    fn test_truncate_path_and_code() {
+        assert_eq!(truncate_start("src/ünïcode/modules/parser.rs", 20), "…/modules/parser.rs");
+        assert_eq!(truncate_start("a/b.rs", 20), "a/b.rs");
+        assert_eq!(
+            truncate_code("pub fn load_configuration(path: &Path, overrides: Vec<String>) -> Result<Config>", 40),
+            "pub fn load_configuration(path: &Path,…"
+        );
+        assert_eq!(truncate_code("fn f(a: u8)", 40), "fn f(a: u8)");
+    }
+}